@@ -23,6 +23,15 @@ pub enum AccountError {
 
     #[error("invalid public key: {0:?}")]
     InvalidPublicKey(starcoin_crypto::CryptoMaterialError),
+
+    #[error("invalid mnemonic: {0:?}")]
+    InvalidMnemonic(anyhow::Error),
+    #[error("mnemonic already exists in wallet")]
+    MnemonicAlreadyExist,
+    #[error("no mnemonic in wallet, create or import one first")]
+    MnemonicNotExist,
+    #[error("invalid password, cannot decrypt mnemonic")]
+    InvalidMnemonicPassword,
     // logic error
     #[error("transaction sign error, {0:?}")]
     TransactionSignError(anyhow::Error),
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::AccountInfo;
+use crate::{AccountInfo, MnemonicAccountInfo};
 use anyhow::Result;
 use starcoin_service_registry::ServiceRequest;
 use starcoin_types::account_address::AccountAddress;
@@ -48,6 +48,16 @@ pub enum AccountRequest {
         address: AccountAddress,
         new_password: String,
     },
+    CreateAccountFromMnemonic(String),
+    ImportMnemonic {
+        mnemonic: String,
+        password: String,
+    },
+    DeriveAccount {
+        index: u32,
+        password: String,
+    },
+    ExportMnemonic(String),
 }
 
 impl ServiceRequest for AccountRequest {
@@ -64,5 +74,7 @@ pub enum AccountResponse {
     ExportAccountResponse(Vec<u8>),
     AcceptedTokens(Vec<TokenCode>),
     SignedMessage(Box<SignedMessage>),
+    MnemonicAccountInfo(Box<MnemonicAccountInfo>),
+    ExportMnemonicResponse(String),
     None,
 }
@@ -1,4 +1,4 @@
-use crate::AccountInfo;
+use crate::{AccountInfo, MnemonicAccountInfo};
 use anyhow::Result;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::token_code::TokenCode;
@@ -10,6 +10,7 @@ pub enum AccountProviderStrategy {
     RPC,
     Local,
     PrivateKey,
+    ExternalSigner,
 }
 
 impl Default for AccountProviderStrategy {
@@ -75,4 +76,16 @@ pub trait AccountProvider {
         address: AccountAddress,
         password: Option<String>,
     ) -> Result<AccountInfo>;
+
+    /// Create a hd wallet with a new mnemonic, return the mnemonic and the first account.
+    fn create_account_from_mnemonic(&self, password: String) -> Result<MnemonicAccountInfo>;
+
+    /// Restore the hd wallet from mnemonic, return the first account.
+    fn import_mnemonic(&self, mnemonic: String, password: String) -> Result<AccountInfo>;
+
+    /// Derive the account at `index` from the hd wallet.
+    fn derive_account(&self, index: u32, password: String) -> Result<AccountInfo>;
+
+    /// Return the mnemonic of the hd wallet.
+    fn export_mnemonic(&self, password: String) -> Result<String>;
 }
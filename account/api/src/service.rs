@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::message::{AccountRequest, AccountResponse};
-use crate::AccountInfo;
+use crate::{AccountInfo, MnemonicAccountInfo};
 use anyhow::Result;
 use starcoin_service_registry::{ActorService, ServiceHandler, ServiceRef};
 use starcoin_types::account_address::AccountAddress;
@@ -71,6 +71,18 @@ pub trait AccountAsyncService:
         address: AccountAddress,
         password: Option<String>,
     ) -> Result<AccountInfo>;
+
+    /// Create a hd wallet with a new mnemonic, return the mnemonic and the first account.
+    async fn create_account_from_mnemonic(&self, password: String) -> Result<MnemonicAccountInfo>;
+
+    /// Restore the hd wallet from mnemonic, return the first account.
+    async fn import_mnemonic(&self, mnemonic: String, password: String) -> Result<AccountInfo>;
+
+    /// Derive the account at `index` from the hd wallet.
+    async fn derive_account(&self, index: u32, password: String) -> Result<AccountInfo>;
+
+    /// Return the mnemonic of the hd wallet.
+    async fn export_mnemonic(&self, password: String) -> Result<String>;
 }
 
 #[async_trait::async_trait]
@@ -278,4 +290,47 @@ where
             panic!("Unexpect response type.")
         }
     }
+    async fn create_account_from_mnemonic(&self, password: String) -> Result<MnemonicAccountInfo> {
+        let response = self
+            .send(AccountRequest::CreateAccountFromMnemonic(password))
+            .await??;
+        if let AccountResponse::MnemonicAccountInfo(account) = response {
+            Ok(*account)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
+    async fn import_mnemonic(&self, mnemonic: String, password: String) -> Result<AccountInfo> {
+        let response = self
+            .send(AccountRequest::ImportMnemonic { mnemonic, password })
+            .await??;
+        if let AccountResponse::AccountInfo(account) = response {
+            Ok(*account)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
+    async fn derive_account(&self, index: u32, password: String) -> Result<AccountInfo> {
+        let response = self
+            .send(AccountRequest::DeriveAccount { index, password })
+            .await??;
+        if let AccountResponse::AccountInfo(account) = response {
+            Ok(*account)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
+    async fn export_mnemonic(&self, password: String) -> Result<String> {
+        let response = self
+            .send(AccountRequest::ExportMnemonic(password))
+            .await??;
+        if let AccountResponse::ExportMnemonicResponse(mnemonic) = response {
+            Ok(mnemonic)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
 }
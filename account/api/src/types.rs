@@ -72,6 +72,14 @@ impl From<&Account> for AccountInfo {
     }
 }
 
+/// The account created with a new mnemonic, the mnemonic should be backed up by user.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MnemonicAccountInfo {
+    pub mnemonic: String,
+    #[serde(flatten)]
+    pub account: AccountInfo,
+}
+
 #[derive(Clone, Debug)]
 pub struct DefaultAccountChangeEvent {
     pub new_account: AccountInfo,
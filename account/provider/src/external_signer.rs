@@ -0,0 +1,315 @@
+use anyhow::{bail, ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_account_api::{AccountInfo, AccountProvider, AccountPublicKey, MnemonicAccountInfo};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_config::token_code::TokenCode;
+use starcoin_types::account_config::G_STC_TOKEN_CODE;
+use starcoin_types::genesis_config::ChainId;
+use starcoin_types::sign_message::{SignedMessage, SigningMessage};
+use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// The max size of a signer response line.
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+/// The request to external signer, every request is a json line on a new connection,
+/// and the signer replies a json line of `SignerResponse`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    Accounts,
+    SignTxn {
+        address: AccountAddress,
+        raw_txn: RawUserTransaction,
+    },
+    SignMessage {
+        address: AccountAddress,
+        message: SigningMessage,
+        chain_id: ChainId,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Accounts(Vec<SignerAccount>),
+    SignedTxn(SignedUserTransaction),
+    SignedMessage(SignedMessage),
+    Error(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignerAccount {
+    pub address: AccountAddress,
+    pub public_key: AccountPublicKey,
+}
+
+/// Forward the signing to an external signer process by unix socket.
+/// Only the accounts in allowlist can sign, and any error or timeout of the signer fails the signing.
+pub struct AccountExternalSignerProvider {
+    socket: PathBuf,
+    allowlist: Vec<AccountAddress>,
+    timeout: Duration,
+    chain_id: ChainId,
+}
+
+impl AccountExternalSignerProvider {
+    pub fn create(
+        socket: PathBuf,
+        allowlist: Vec<AccountAddress>,
+        timeout: Duration,
+        chain_id: ChainId,
+    ) -> Result<Self> {
+        if allowlist.is_empty() {
+            bail!("The allowlist of external signer should not be empty.")
+        }
+        Ok(Self {
+            socket,
+            allowlist,
+            timeout,
+            chain_id,
+        })
+    }
+
+    /// The timeout bounds the whole exchange, a signer trickling bytes can not hold the signing.
+    fn call(&self, request: &SignerRequest) -> Result<SignerResponse> {
+        let deadline = Instant::now() + self.timeout;
+        let stream = connect(self.socket.as_path())?;
+        stream.set_write_timeout(Some(remaining(deadline)?))?;
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        (&stream).write_all(&line)?;
+        let mut response = vec![];
+        let mut buf = [0u8; 4096];
+        loop {
+            stream.set_read_timeout(Some(remaining(deadline)?))?;
+            let n = (&stream)
+                .read(&mut buf)
+                .map_err(|e| format_err!("External signer has no response: {}", e))?;
+            ensure!(
+                n > 0,
+                "External signer closed the connection without response"
+            );
+            if let Some(pos) = buf[..n].iter().position(|b| *b == b'\n') {
+                response.extend_from_slice(&buf[..pos]);
+                break;
+            }
+            response.extend_from_slice(&buf[..n]);
+            ensure!(
+                response.len() <= MAX_RESPONSE_SIZE,
+                "External signer response exceeds {} bytes",
+                MAX_RESPONSE_SIZE
+            );
+        }
+        match serde_json::from_slice(&response)? {
+            SignerResponse::Error(e) => bail!("External signer error: {}", e),
+            response => Ok(response),
+        }
+    }
+
+    fn check_allowed(&self, address: AccountAddress) -> Result<()> {
+        ensure!(
+            self.allowlist.contains(&address),
+            "Account {} is not allowed by external signer",
+            address
+        );
+        Ok(())
+    }
+
+    fn account_public_key(&self, address: AccountAddress) -> Result<AccountPublicKey> {
+        self.get_account(address)?
+            .map(|account| account.public_key)
+            .ok_or_else(|| format_err!("Account {} not found in external signer", address))
+    }
+}
+
+impl AccountProvider for AccountExternalSignerProvider {
+    fn create_account(&self, _password: String) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn get_default_account(&self) -> Result<Option<AccountInfo>> {
+        Ok(self.get_accounts()?.into_iter().find(|a| a.is_default))
+    }
+
+    fn set_default_account(&self, _address: AccountAddress) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn get_accounts(&self) -> Result<Vec<AccountInfo>> {
+        let accounts = match self.call(&SignerRequest::Accounts)? {
+            SignerResponse::Accounts(accounts) => accounts,
+            response => bail!("Unexpected external signer response: {:?}", response),
+        };
+        // keep the order of allowlist, the first one is default.
+        Ok(self
+            .allowlist
+            .iter()
+            .filter_map(|address| accounts.iter().find(|a| &a.address == address))
+            .enumerate()
+            .map(|(i, account)| {
+                AccountInfo::new(
+                    account.address,
+                    account.public_key.clone(),
+                    i == 0,
+                    false,
+                    false,
+                )
+            })
+            .collect())
+    }
+
+    fn get_account(&self, address: AccountAddress) -> Result<Option<AccountInfo>> {
+        Ok(self
+            .get_accounts()?
+            .into_iter()
+            .find(|a| a.address == address))
+    }
+
+    fn sign_message(
+        &self,
+        address: AccountAddress,
+        message: SigningMessage,
+    ) -> Result<SignedMessage> {
+        self.check_allowed(address)?;
+        let public_key = self.account_public_key(address)?;
+        let signed_message = match self.call(&SignerRequest::SignMessage {
+            address,
+            message: message.clone(),
+            chain_id: self.chain_id,
+        })? {
+            SignerResponse::SignedMessage(signed_message) => signed_message,
+            response => bail!("Unexpected external signer response: {:?}", response),
+        };
+        ensure!(
+            signed_message.account == address
+                && signed_message.message == message
+                && signed_message.chain_id == self.chain_id
+                && signed_message.authenticator.public_key() == public_key,
+            "External signer signed a mismatched message"
+        );
+        signed_message.check_signature()?;
+        Ok(signed_message)
+    }
+
+    fn sign_txn(
+        &self,
+        raw_txn: RawUserTransaction,
+        signer_address: AccountAddress,
+    ) -> Result<SignedUserTransaction> {
+        self.check_allowed(signer_address)?;
+        let public_key = self.account_public_key(signer_address)?;
+        let signed_txn = match self.call(&SignerRequest::SignTxn {
+            address: signer_address,
+            raw_txn: raw_txn.clone(),
+        })? {
+            SignerResponse::SignedTxn(signed_txn) => signed_txn,
+            response => bail!("Unexpected external signer response: {:?}", response),
+        };
+        ensure!(
+            signed_txn.raw_txn() == &raw_txn
+                && signed_txn.authenticator().public_key() == public_key,
+            "External signer signed a mismatched txn"
+        );
+        signed_txn.clone().check_signature()?;
+        Ok(signed_txn)
+    }
+
+    fn unlock_account(
+        &self,
+        address: AccountAddress,
+        _password: String,
+        _duration: Duration,
+    ) -> Result<AccountInfo> {
+        // the keys are kept by external signer, nothing to unlock.
+        self.check_allowed(address)?;
+        self.get_account(address)?
+            .ok_or_else(|| format_err!("Account {} not found in external signer", address))
+    }
+
+    fn lock_account(&self, _address: AccountAddress) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn import_account(
+        &self,
+        _address: AccountAddress,
+        _private_key: Vec<u8>,
+        _password: String,
+    ) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn import_readonly_account(
+        &self,
+        _address: AccountAddress,
+        _public_key: Vec<u8>,
+    ) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn export_account(&self, _address: AccountAddress, _password: String) -> Result<Vec<u8>> {
+        bail!("Unsupported")
+    }
+
+    fn accepted_tokens(&self, _address: AccountAddress) -> Result<Vec<TokenCode>> {
+        Ok(vec![G_STC_TOKEN_CODE.clone()])
+    }
+
+    fn change_account_password(
+        &self,
+        _address: AccountAddress,
+        _new_password: String,
+    ) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn remove_account(
+        &self,
+        _address: AccountAddress,
+        _password: Option<String>,
+    ) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn create_account_from_mnemonic(&self, _password: String) -> Result<MnemonicAccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn import_mnemonic(&self, _mnemonic: String, _password: String) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn derive_account(&self, _index: u32, _password: String) -> Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn export_mnemonic(&self, _password: String) -> Result<String> {
+        bail!("Unsupported")
+    }
+}
+
+fn remaining(deadline: Instant) -> Result<Duration> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+        .ok_or_else(|| format_err!("External signer timeout"))
+}
+
+#[cfg(unix)]
+fn connect(socket: &Path) -> Result<std::os::unix::net::UnixStream> {
+    std::os::unix::net::UnixStream::connect(socket).map_err(|e| {
+        format_err!(
+            "Connect to external signer {} error: {}",
+            socket.display(),
+            e
+        )
+    })
+}
+
+#[cfg(not(unix))]
+fn connect(_socket: &Path) -> Result<std::net::TcpStream> {
+    bail!("External signer only supports unix socket")
+}
@@ -1,3 +1,4 @@
+pub mod external_signer;
 mod local_provider;
 mod private_key_provider;
 mod provider;
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use starcoin_account::{account_storage::AccountStorage, AccountManager};
-use starcoin_account_api::{AccountInfo, AccountProvider};
+use starcoin_account_api::{AccountInfo, AccountProvider, MnemonicAccountInfo};
 use starcoin_config::RocksdbConfig;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::token_code::TokenCode;
@@ -135,4 +135,36 @@ impl AccountProvider for AccountLocalProvider {
             .remove_account(address, password)
             .map_err(|e| e.into())
     }
+    fn create_account_from_mnemonic(
+        &self,
+        password: String,
+    ) -> anyhow::Result<MnemonicAccountInfo> {
+        let (mnemonic, account) = self
+            .manager
+            .create_account_from_mnemonic(password.as_str())?;
+        Ok(MnemonicAccountInfo {
+            mnemonic,
+            account: account.info(),
+        })
+    }
+
+    fn import_mnemonic(&self, mnemonic: String, password: String) -> anyhow::Result<AccountInfo> {
+        self.manager
+            .import_mnemonic(mnemonic.as_str(), password.as_str())
+            .map_err(|e| e.into())
+            .map(|account| account.info())
+    }
+
+    fn derive_account(&self, index: u32, password: String) -> anyhow::Result<AccountInfo> {
+        self.manager
+            .derive_account(index, password.as_str())
+            .map_err(|e| e.into())
+            .map(|account| account.info())
+    }
+
+    fn export_mnemonic(&self, password: String) -> anyhow::Result<String> {
+        self.manager
+            .export_mnemonic(password.as_str())
+            .map_err(|e| e.into())
+    }
 }
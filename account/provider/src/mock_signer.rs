@@ -0,0 +1,126 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A reference external signer for test, holds the private keys in memory and signs every request.
+
+use anyhow::Result;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "starcoin-mock-signer",
+    about = "A mock external signer for test"
+)]
+pub struct MockSignerOpt {
+    /// The unix socket path to listen on.
+    #[clap(long = "socket", parse(from_os_str))]
+    socket: PathBuf,
+
+    /// Hex encoded private key of the signing account, multiple keys can be provided.
+    #[clap(long = "private-key", multiple_occurrences = true)]
+    private_keys: Vec<String>,
+
+    /// Delay in milliseconds before replying, to simulate a slow signer.
+    #[clap(long = "delay", default_value = "0")]
+    delay: u64,
+
+    /// Interval in milliseconds between every byte of the reply, to simulate a stalled signer.
+    #[clap(long = "trickle", default_value = "0")]
+    trickle: u64,
+}
+
+#[cfg(unix)]
+fn main() -> Result<()> {
+    use starcoin_account::account_storage::AccountStorage;
+    use starcoin_account::Account;
+    use starcoin_account_api::AccountPrivateKey;
+    use starcoin_account_provider::external_signer::SignerResponse;
+    use starcoin_crypto::ValidCryptoMaterialStringExt;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::time::Duration;
+
+    let opt: MockSignerOpt = MockSignerOpt::parse();
+    let storage = AccountStorage::mock();
+    let mut accounts = vec![];
+    for private_key in &opt.private_keys {
+        let private_key = AccountPrivateKey::from_encoded_string(private_key)?;
+        let address = private_key.public_key().derived_address();
+        accounts.push(Account::create(
+            address,
+            private_key,
+            "".to_string(),
+            storage.clone(),
+        )?);
+    }
+    let listener = UnixListener::bind(opt.socket.as_path())?;
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let mut line = String::new();
+        if BufReader::new(&stream).read_line(&mut line)? == 0 {
+            continue;
+        }
+        let response = handle_request(&accounts, line.as_str())
+            .unwrap_or_else(|e| SignerResponse::Error(e.to_string()));
+        if opt.delay > 0 {
+            std::thread::sleep(Duration::from_millis(opt.delay));
+        }
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        // the client may be gone because of timeout.
+        let _ = if opt.trickle > 0 {
+            response.iter().try_for_each(|b| {
+                std::thread::sleep(Duration::from_millis(opt.trickle));
+                (&stream).write_all(&[*b])
+            })
+        } else {
+            (&stream).write_all(&response)
+        };
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn handle_request(
+    accounts: &[starcoin_account::Account],
+    request: &str,
+) -> Result<starcoin_account_provider::external_signer::SignerResponse> {
+    use anyhow::format_err;
+    use starcoin_account_provider::external_signer::{
+        SignerAccount, SignerRequest, SignerResponse,
+    };
+    use starcoin_types::account_address::AccountAddress;
+
+    let find_account = |address: AccountAddress| {
+        accounts
+            .iter()
+            .find(|account| account.address() == &address)
+            .ok_or_else(|| format_err!("Account {} not found", address))
+    };
+    Ok(match serde_json::from_str::<SignerRequest>(request)? {
+        SignerRequest::Accounts => SignerResponse::Accounts(
+            accounts
+                .iter()
+                .map(|account| SignerAccount {
+                    address: *account.address(),
+                    public_key: account.public_key(),
+                })
+                .collect(),
+        ),
+        SignerRequest::SignTxn { address, raw_txn } => {
+            SignerResponse::SignedTxn(find_account(address)?.sign_txn(raw_txn)?)
+        }
+        SignerRequest::SignMessage {
+            address,
+            message,
+            chain_id,
+        } => SignerResponse::SignedMessage(find_account(address)?.sign_message(message, chain_id)?),
+    })
+}
+
+#[cfg(not(unix))]
+fn main() -> Result<()> {
+    let _opt: MockSignerOpt = MockSignerOpt::parse();
+    anyhow::bail!("The mock signer only supports unix socket")
+}
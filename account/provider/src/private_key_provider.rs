@@ -1,6 +1,6 @@
 use anyhow::{bail, Result};
 use starcoin_account::{account_storage::AccountStorage, AccountManager};
-use starcoin_account_api::{AccountInfo, AccountPrivateKey, AccountProvider};
+use starcoin_account_api::{AccountInfo, AccountPrivateKey, AccountProvider, MnemonicAccountInfo};
 use starcoin_config::account_provider_config::G_ENV_PRIVATE_KEY;
 use starcoin_crypto::{ValidCryptoMaterial, ValidCryptoMaterialStringExt};
 use starcoin_types::account_address::AccountAddress;
@@ -148,4 +148,23 @@ impl AccountProvider for AccountPrivateKeyProvider {
     ) -> anyhow::Result<AccountInfo> {
         bail!("Unsupported")
     }
+
+    fn create_account_from_mnemonic(
+        &self,
+        _password: String,
+    ) -> anyhow::Result<MnemonicAccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn import_mnemonic(&self, _mnemonic: String, _password: String) -> anyhow::Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn derive_account(&self, _index: u32, _password: String) -> anyhow::Result<AccountInfo> {
+        bail!("Unsupported")
+    }
+
+    fn export_mnemonic(&self, _password: String) -> anyhow::Result<String> {
+        bail!("Unsupported")
+    }
 }
@@ -1,3 +1,4 @@
+use crate::external_signer::AccountExternalSignerProvider;
 use crate::rpc_provider::AccountRpcProvider;
 use crate::{
     local_provider::AccountLocalProvider, private_key_provider::AccountPrivateKeyProvider,
@@ -8,6 +9,7 @@ use starcoin_config::account_provider_config::AccountProviderConfig;
 use starcoin_rpc_client::RpcClient;
 use starcoin_types::genesis_config::ChainId;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct ProviderFactory;
 
@@ -38,6 +40,18 @@ impl ProviderFactory {
                 Ok(p) => Ok(Box::new(p)),
                 Err(e) => Err(e),
             },
+            AccountProviderStrategy::ExternalSigner => match AccountExternalSignerProvider::create(
+                config
+                    .external_signer
+                    .clone()
+                    .ok_or_else(|| anyhow!("expect unix socket path for external signer"))?,
+                config.external_signer_allowlist.clone(),
+                Duration::from_secs(config.external_signer_timeout()),
+                chain_id,
+            ) {
+                Ok(p) => Ok(Box::new(p)),
+                Err(e) => Err(e),
+            },
         }
     }
 }
@@ -1,5 +1,6 @@
 use starcoin_account_api::AccountInfo;
 use starcoin_account_api::AccountProvider;
+use starcoin_account_api::MnemonicAccountInfo;
 use starcoin_rpc_client::RpcClient;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::token_code::TokenCode;
@@ -110,4 +111,22 @@ impl AccountProvider for AccountRpcProvider {
     ) -> anyhow::Result<AccountInfo> {
         self.rpc.account_remove(address, password)
     }
+    fn create_account_from_mnemonic(
+        &self,
+        password: String,
+    ) -> anyhow::Result<MnemonicAccountInfo> {
+        self.rpc.account_create_from_mnemonic(password)
+    }
+
+    fn import_mnemonic(&self, mnemonic: String, password: String) -> anyhow::Result<AccountInfo> {
+        self.rpc.account_import_mnemonic(mnemonic, password)
+    }
+
+    fn derive_account(&self, index: u32, password: String) -> anyhow::Result<AccountInfo> {
+        self.rpc.account_derive(index, password)
+    }
+
+    fn export_mnemonic(&self, password: String) -> anyhow::Result<String> {
+        self.rpc.account_export_mnemonic(password)
+    }
 }
@@ -0,0 +1,144 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(unix)]
+
+use anyhow::Result;
+use starcoin_account_api::{AccountPrivateKey, AccountProvider};
+use starcoin_account_provider::external_signer::AccountExternalSignerProvider;
+use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::genesis_config::ChainId;
+use starcoin_types::sign_message::SigningMessage;
+use starcoin_types::transaction::RawUserTransaction;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+fn gen_private_key() -> AccountPrivateKey {
+    AccountPrivateKey::Single(KeyGen::from_os_rng().generate_keypair().0)
+}
+
+#[derive(Clone, Copy)]
+enum Reply {
+    /// Reply after the delay.
+    Delay(Duration),
+    /// Send the reply one byte per interval.
+    Trickle(Duration),
+}
+
+/// The reference `starcoin-mock-signer` process, killed on drop.
+struct MockSigner(Child);
+
+impl MockSigner {
+    fn start(socket: &Path, private_key: &AccountPrivateKey, reply: Reply) -> Result<Self> {
+        let (reply_arg, interval) = match reply {
+            Reply::Delay(delay) => ("--delay", delay),
+            Reply::Trickle(interval) => ("--trickle", interval),
+        };
+        let child = Command::new(env!("CARGO_BIN_EXE_starcoin-mock-signer"))
+            .arg("--socket")
+            .arg(socket)
+            .arg("--private-key")
+            .arg(private_key.to_encoded_string()?)
+            .arg(reply_arg)
+            .arg(interval.as_millis().to_string())
+            .spawn()?;
+        for _ in 0..100 {
+            if socket.exists() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Ok(Self(child))
+    }
+}
+
+impl Drop for MockSigner {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[test]
+fn test_external_signer() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let socket = dir.path().join("signer.sock");
+    let private_key = gen_private_key();
+    let public_key = private_key.public_key();
+    let address = public_key.derived_address();
+    let _signer = MockSigner::start(socket.as_path(), &private_key, Reply::Delay(Duration::ZERO))?;
+
+    let provider = AccountExternalSignerProvider::create(
+        socket.clone(),
+        vec![address],
+        Duration::from_secs(5),
+        ChainId::test(),
+    )?;
+    let default_account = provider.get_default_account()?.expect("default account");
+    assert_eq!(default_account.address, address);
+    assert_eq!(default_account.public_key, public_key);
+
+    let signed_txn = provider.sign_txn(RawUserTransaction::mock_by_sender(address), address)?;
+    assert!(signed_txn.check_signature().is_ok());
+    let signed_message = provider.sign_message(address, SigningMessage(b"hello".to_vec()))?;
+    assert!(signed_message.check_signature().is_ok());
+
+    // the account not in allowlist should be refused.
+    let other = AccountAddress::random();
+    assert!(provider
+        .sign_txn(RawUserTransaction::mock_by_sender(other), other)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_external_signer_timeout() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let socket = dir.path().join("signer.sock");
+    let private_key = gen_private_key();
+    let address = private_key.public_key().derived_address();
+    let _signer = MockSigner::start(
+        socket.as_path(),
+        &private_key,
+        Reply::Delay(Duration::from_secs(2)),
+    )?;
+
+    let provider = AccountExternalSignerProvider::create(
+        socket,
+        vec![address],
+        Duration::from_millis(200),
+        ChainId::test(),
+    )?;
+    assert!(provider
+        .sign_txn(RawUserTransaction::mock_by_sender(address), address)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_external_signer_trickle_timeout() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let socket = dir.path().join("signer.sock");
+    let private_key = gen_private_key();
+    let address = private_key.public_key().derived_address();
+    // every read gets a byte in time, but the whole response takes far longer than the timeout.
+    let _signer = MockSigner::start(
+        socket.as_path(),
+        &private_key,
+        Reply::Trickle(Duration::from_millis(50)),
+    )?;
+
+    let provider = AccountExternalSignerProvider::create(
+        socket,
+        vec![address],
+        Duration::from_millis(500),
+        ChainId::test(),
+    )?;
+    let now = Instant::now();
+    assert!(provider.get_default_account().is_err());
+    assert!(now.elapsed() < Duration::from_secs(2));
+    Ok(())
+}
@@ -39,6 +39,10 @@ impl EventHandler<Self, ContractEventNotification> for AccountEventService {
         item: ContractEventNotification,
         _ctx: &mut ServiceContext<AccountEventService>,
     ) {
+        // the accepted tokens are kept if the block is retracted.
+        if item.0.removed {
+            return;
+        }
         let addrs = match self.storage.list_addresses() {
             Ok(addresses) => addresses,
             Err(e) => {
@@ -56,7 +60,7 @@ impl EventHandler<Self, ContractEventNotification> for AccountEventService {
             return;
         }
 
-        for i in item.0 .1.as_ref() {
+        for i in item.0.events.as_ref() {
             if watched_keys.contains(i.contract_event.key()) {
                 if let Err(e) = self.handle_contract_event(&i.contract_event) {
                     error!(
@@ -4,7 +4,7 @@
 use anyhow::Result;
 use starcoin_account::{account_storage::AccountStorage, AccountManager};
 use starcoin_account_api::message::{AccountRequest, AccountResponse};
-use starcoin_account_api::DefaultAccountChangeEvent;
+use starcoin_account_api::{DefaultAccountChangeEvent, MnemonicAccountInfo};
 use starcoin_config::NodeConfig;
 use starcoin_crypto::ValidCryptoMaterial;
 use starcoin_logger::prelude::*;
@@ -188,6 +188,28 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
             } => AccountResponse::AccountInfo(Box::new(
                 self.manager.change_password(address, new_password)?,
             )),
+            AccountRequest::CreateAccountFromMnemonic(password) => {
+                let (mnemonic, account) = self
+                    .manager
+                    .create_account_from_mnemonic(password.as_str())?;
+                AccountResponse::MnemonicAccountInfo(Box::new(MnemonicAccountInfo {
+                    mnemonic,
+                    account: account.info(),
+                }))
+            }
+            AccountRequest::ImportMnemonic { mnemonic, password } => {
+                let account = self
+                    .manager
+                    .import_mnemonic(mnemonic.as_str(), password.as_str())?;
+                AccountResponse::AccountInfo(Box::new(account.info()))
+            }
+            AccountRequest::DeriveAccount { index, password } => {
+                let account = self.manager.derive_account(index, password.as_str())?;
+                AccountResponse::AccountInfo(Box::new(account.info()))
+            }
+            AccountRequest::ExportMnemonic(password) => AccountResponse::ExportMnemonicResponse(
+                self.manager.export_mnemonic(password.as_str())?,
+            ),
         };
         Ok(response)
     }
@@ -3,6 +3,7 @@
 
 use crate::account::Account;
 use crate::account_storage::AccountStorage;
+use crate::hd_wallet::HdWallet;
 use anyhow::format_err;
 use parking_lot::RwLock;
 use rand::prelude::*;
@@ -84,6 +85,74 @@ impl AccountManager {
         )
     }
 
+    /// Create a hd wallet with a new mnemonic, and the account at index 0.
+    /// Return the mnemonic and the account.
+    pub fn create_account_from_mnemonic(&self, password: &str) -> AccountResult<(String, Account)> {
+        let wallet = HdWallet::generate();
+        let account = self.save_hd_wallet(&wallet, password)?;
+        Ok((wallet.phrase(), account))
+    }
+
+    /// Restore the hd wallet from `mnemonic`, and the account at index 0.
+    pub fn import_mnemonic(&self, mnemonic: &str, password: &str) -> AccountResult<Account> {
+        let wallet = HdWallet::from_phrase(mnemonic).map_err(AccountError::InvalidMnemonic)?;
+        self.save_hd_wallet(&wallet, password)
+    }
+
+    /// Derive the account at `index` from the hd wallet,
+    /// the mnemonic is decrypted by `password`, and the account is encrypted by it too.
+    pub fn derive_account(&self, index: u32, password: &str) -> AccountResult<Account> {
+        let wallet = self.load_hd_wallet(password)?;
+        self.save_hd_account(&wallet, index, password)
+    }
+
+    pub fn export_mnemonic(&self, password: &str) -> AccountResult<String> {
+        Ok(self.load_hd_wallet(password)?.phrase())
+    }
+
+    fn save_hd_wallet(&self, wallet: &HdWallet, password: &str) -> AccountResult<Account> {
+        if self.store.contain_mnemonic()? {
+            return Err(AccountError::MnemonicAlreadyExist);
+        }
+        let account = self.save_hd_account(wallet, 0, password)?;
+        self.store
+            .update_mnemonic(wallet.phrase().as_str(), password)?;
+        Ok(account)
+    }
+
+    fn load_hd_wallet(&self, password: &str) -> AccountResult<HdWallet> {
+        let mnemonic = self
+            .store
+            .decrypt_mnemonic(password)
+            .map_err(|e| {
+                warn!(
+                    "Try to decrypt mnemonic with a invalid password, err: {:?}",
+                    e
+                );
+                AccountError::InvalidMnemonicPassword
+            })?
+            .ok_or(AccountError::MnemonicNotExist)?;
+        HdWallet::from_phrase(mnemonic.as_str()).map_err(AccountError::InvalidMnemonic)
+    }
+
+    fn save_hd_account(
+        &self,
+        wallet: &HdWallet,
+        index: u32,
+        password: &str,
+    ) -> AccountResult<Account> {
+        let private_key = wallet
+            .derive_private_key(index)
+            .map_err(AccountError::InvalidMnemonic)?;
+        let private_key = AccountPrivateKey::Single(private_key);
+        let address = private_key.public_key().derived_address();
+        self.save_account(
+            address,
+            private_key.public_key(),
+            Some((private_key, password.to_string())),
+        )
+    }
+
     pub fn unlock_account(
         &self,
         address: AccountAddress,
@@ -26,6 +26,7 @@ pub const ENCRYPTED_PRIVATE_KEY_PREFIX_NAME: ColumnFamilyName = "encrypted_priva
 pub const PUBLIC_KEY_PREFIX_NAME: ColumnFamilyName = "public_key";
 pub const ACCEPTED_TOKEN_PREFIX_NAME: ColumnFamilyName = "accepted_token";
 pub const GLOBAL_PREFIX_NAME: ColumnFamilyName = "global";
+pub const ENCRYPTED_MNEMONIC_PREFIX_NAME: ColumnFamilyName = "encrypted_mnemonic";
 
 define_storage!(
     AccountSettingStore,
@@ -61,6 +62,13 @@ define_storage!(
     ACCEPTED_TOKEN_PREFIX_NAME
 );
 
+define_storage!(
+    MnemonicStore,
+    GlobalSettingKey,
+    EncryptedMnemonic,
+    ENCRYPTED_MNEMONIC_PREFIX_NAME
+);
+
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct AcceptedTokens(pub Vec<TokenCode>);
 
@@ -79,6 +87,8 @@ pub enum GlobalSettingKey {
     DefaultAddress,
     /// FIXME: once db support iter, remove this.
     AllAddresses,
+    /// The mnemonic of the hd wallet.
+    Mnemonic,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -161,6 +171,19 @@ impl ValueCodec for EncryptedPrivateKey {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedMnemonic(pub Vec<u8>);
+
+impl ValueCodec for EncryptedMnemonic {
+    fn encode_value(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.0.clone())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self, Error> {
+        Ok(EncryptedMnemonic(data.to_vec()))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PublicKeyWrapper(AccountPublicKey);
 impl From<AccountPublicKey> for PublicKeyWrapper {
@@ -186,6 +209,7 @@ pub struct AccountStorage {
     public_key_store: PublicKeyStore,
     global_value_store: GlobalSettingStore,
     accepted_token_store: AcceptedTokenStore,
+    mnemonic_store: MnemonicStore,
 }
 
 impl AccountStorage {
@@ -198,6 +222,7 @@ impl AccountStorage {
                 PUBLIC_KEY_PREFIX_NAME,
                 ACCEPTED_TOKEN_PREFIX_NAME,
                 GLOBAL_PREFIX_NAME,
+                ENCRYPTED_MNEMONIC_PREFIX_NAME,
             ],
             false,
             rocksdb_config,
@@ -214,6 +239,7 @@ impl AccountStorage {
             private_key_store: PrivateKeyStore::new(store.clone()),
             public_key_store: PublicKeyStore::new(store.clone()),
             accepted_token_store: AcceptedTokenStore::new(store.clone()),
+            mnemonic_store: MnemonicStore::new(store.clone()),
             global_value_store: GlobalSettingStore::new(store),
         }
     }
@@ -334,6 +360,30 @@ impl AccountStorage {
         Ok(())
     }
 
+    pub fn contain_mnemonic(&self) -> Result<bool> {
+        self.mnemonic_store
+            .get(GlobalSettingKey::Mnemonic)
+            .map(|m| m.is_some())
+    }
+
+    pub fn decrypt_mnemonic(&self, password: impl AsRef<str>) -> Result<Option<String>> {
+        match self.mnemonic_store.get(GlobalSettingKey::Mnemonic)? {
+            None => Ok(None),
+            Some(encrypted_mnemonic) => {
+                let plain_data = decrypt(password.as_ref().as_bytes(), &encrypted_mnemonic.0)?;
+                Ok(Some(String::from_utf8(plain_data)?))
+            }
+        }
+    }
+
+    pub fn update_mnemonic(&self, mnemonic: &str, password: impl AsRef<str>) -> Result<()> {
+        let encrypted_mnemonic = encrypt(password.as_ref().as_bytes(), mnemonic.as_bytes());
+        self.mnemonic_store.put(
+            GlobalSettingKey::Mnemonic,
+            EncryptedMnemonic(encrypted_mnemonic),
+        )
+    }
+
     pub fn update_setting(&self, address: AccountAddress, setting: Setting) -> Result<()> {
         self.setting_store.put(address.into(), setting.into())
     }
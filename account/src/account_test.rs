@@ -6,10 +6,12 @@ use crate::Account;
 use crate::AccountManager;
 use anyhow::Result;
 use starcoin_account_api::error::AccountError;
-use starcoin_account_api::AccountPublicKey;
+use starcoin_account_api::{AccountPrivateKey, AccountPublicKey};
 use starcoin_config::RocksdbConfig;
 use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::multi_ed25519::multi_shard::MultiEd25519KeyShard;
 use starcoin_crypto::{SigningKey, ValidCryptoMaterial};
+use starcoin_decrypt::keystore::{decrypt_keystore, encrypt_keystore, KeystoreKdf};
 use starcoin_types::access_path::AccessPath;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::genesis_config::ChainId;
@@ -45,6 +47,80 @@ pub fn test_import_account() -> Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn test_mnemonic_account() -> Result<()> {
+    let manager = AccountManager::new(AccountStorage::mock(), ChainId::test())?;
+    assert!(matches!(
+        manager.derive_account(1, "hello").err().unwrap(),
+        AccountError::MnemonicNotExist
+    ));
+    let (mnemonic, account) = manager.create_account_from_mnemonic("hello")?;
+    let derived = manager.derive_account(1, "hello")?;
+    assert!(matches!(
+        manager.derive_account(2, "abc").err().unwrap(),
+        AccountError::InvalidMnemonicPassword
+    ));
+    assert!(matches!(
+        manager
+            .import_mnemonic(mnemonic.as_str(), "hello")
+            .err()
+            .unwrap(),
+        AccountError::MnemonicAlreadyExist
+    ));
+    assert_eq!(manager.export_mnemonic("hello")?, mnemonic);
+
+    // restore from mnemonic should regenerate the same addresses.
+    let restored_manager = AccountManager::new(AccountStorage::mock(), ChainId::test())?;
+    let restored = restored_manager.import_mnemonic(mnemonic.as_str(), "abc")?;
+    assert_eq!(restored.address(), account.address());
+    let restored_derived = restored_manager.derive_account(1, "abc")?;
+    assert_eq!(restored_derived.address(), derived.address());
+    assert_eq!(restored_derived.public_key(), derived.public_key());
+    Ok(())
+}
+
+#[test]
+pub fn test_multi_key_keystore() -> Result<()> {
+    let manager = AccountManager::new(AccountStorage::mock(), ChainId::test())?;
+    let mut key_gen = KeyGen::from_os_rng();
+    let keypairs = (0..3)
+        .map(|_| key_gen.generate_keypair())
+        .collect::<Vec<_>>();
+    let private_key = AccountPrivateKey::Multi(MultiEd25519KeyShard::new_multi(
+        keypairs
+            .iter()
+            .map(|(_, public_key)| public_key.clone())
+            .collect(),
+        2,
+        keypairs
+            .iter()
+            .take(2)
+            .map(|(private_key, _)| private_key.clone())
+            .collect(),
+    )?);
+    let address = private_key.public_key().derived_address();
+    manager.import_account(address, private_key.to_bytes(), "hello")?;
+
+    // export the multi key shard to a keystore, and import it to another wallet.
+    let exported = manager.export_account(address, "hello")?;
+    let keystore = encrypt_keystore(
+        b"keystore",
+        exported.as_slice(),
+        Some(address.to_string()),
+        KeystoreKdf::Pbkdf2 { c: 1000 },
+    )?;
+    let keystore = serde_json::from_str(serde_json::to_string(&keystore)?.as_str())?;
+    let imported =
+        AccountPrivateKey::try_from(decrypt_keystore(b"keystore", &keystore)?.as_slice())?;
+    assert_eq!(imported.to_bytes(), private_key.to_bytes());
+    assert_eq!(imported.public_key().derived_address(), address);
+
+    let restored_manager = AccountManager::new(AccountStorage::mock(), ChainId::test())?;
+    let account = restored_manager.import_account(address, imported.to_bytes(), "abc")?;
+    assert_eq!(account.public_key(), private_key.public_key());
+    Ok(())
+}
+
 #[test]
 pub fn test_wallet() -> Result<()> {
     let tempdir = tempfile::tempdir()?;
@@ -0,0 +1,119 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha512;
+use starcoin_crypto::ed25519::Ed25519PrivateKey;
+use std::convert::TryFrom;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// The SLIP-0044 coin type of Starcoin.
+pub const STARCOIN_COIN_TYPE: u32 = 101010;
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+/// 256 bits entropy, 24 words mnemonic.
+const MNEMONIC_ENTROPY_SIZE: usize = 32;
+
+/// A BIP39 mnemonic backed wallet, accounts are derived along the SLIP-0010 Ed25519 path
+/// `m/44'/101010'/{index}'/0'/0'`.
+pub struct HdWallet {
+    mnemonic: Mnemonic,
+}
+
+impl HdWallet {
+    pub fn generate() -> Self {
+        let entropy: [u8; MNEMONIC_ENTROPY_SIZE] = rand::rngs::OsRng.gen();
+        let mnemonic =
+            Mnemonic::from_entropy(&entropy).expect("Mnemonic from 32 bytes entropy should ok.");
+        Self { mnemonic }
+    }
+
+    pub fn from_phrase(phrase: &str) -> Result<Self> {
+        let phrase = phrase
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        let mnemonic = Mnemonic::parse_normalized(phrase.as_str())
+            .map_err(|e| format_err!("Invalid mnemonic: {}", e))?;
+        Ok(Self { mnemonic })
+    }
+
+    pub fn phrase(&self) -> String {
+        self.mnemonic.to_string()
+    }
+
+    pub fn derive_private_key(&self, index: u32) -> Result<Ed25519PrivateKey> {
+        ensure!(
+            index < HARDENED_OFFSET,
+            "Account index should less than {}",
+            HARDENED_OFFSET
+        );
+        let seed = self.mnemonic.to_seed_normalized("");
+        let key = derive_ed25519_key(&seed, &[44, STARCOIN_COIN_TYPE, index, 0, 0]);
+        Ed25519PrivateKey::try_from(&key[..]).map_err(|e| format_err!("{:?}", e))
+    }
+}
+
+/// SLIP-0010 Ed25519 derivation, Ed25519 only supports hardened child keys,
+/// so every index in `path` is hardened.
+fn derive_ed25519_key(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let mut mac =
+        HmacSha512::new_from_slice(ED25519_SEED_KEY).expect("HMAC can take key of any size");
+    mac.update(seed);
+    let mut node = mac.finalize().into_bytes();
+    for index in path {
+        let mut mac =
+            HmacSha512::new_from_slice(&node[32..]).expect("HMAC can take key of any size");
+        mac.update(&[0u8]);
+        mac.update(&node[..32]);
+        mac.update(&(index | HARDENED_OFFSET).to_be_bytes());
+        node = mac.finalize().into_bytes();
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&node[..32]);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::ValidCryptoMaterial;
+
+    #[test]
+    fn test_slip10_vector() {
+        // Test vector 1 for ed25519 of SLIP-0010.
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(derive_ed25519_key(&seed, &[])),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(derive_ed25519_key(&seed, &[0])),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+    }
+
+    #[test]
+    fn test_restore_from_phrase() {
+        let wallet = HdWallet::generate();
+        assert_eq!(wallet.phrase().split(' ').count(), 24);
+        let restored = HdWallet::from_phrase(format!(" {}\n", wallet.phrase()).as_str()).unwrap();
+        for index in 0..3 {
+            assert_eq!(
+                wallet.derive_private_key(index).unwrap().to_bytes(),
+                restored.derive_private_key(index).unwrap().to_bytes()
+            );
+        }
+        assert_ne!(
+            wallet.derive_private_key(0).unwrap().to_bytes(),
+            wallet.derive_private_key(1).unwrap().to_bytes()
+        );
+        assert!(wallet.derive_private_key(HARDENED_OFFSET).is_err());
+        assert!(HdWallet::from_phrase("abandon abandon").is_err());
+    }
+}
@@ -3,8 +3,12 @@
 
 use crate::metrics::BlockRelayerMetrics;
 use anyhow::{ensure, format_err, Result};
+use futures::future::BoxFuture;
 use futures::FutureExt;
-use network_api::messages::{CompactBlockMessage, NotificationMessage, PeerCompactBlockMessage};
+use network_api::messages::{
+    CompactBlockMessage, NotificationMessage, PeerCompactBlockMessage,
+    PeerSaltedCompactBlockMessage,
+};
 use network_api::{NetworkService, PeerId, PeerProvider, PeerSelector, PeerStrategy};
 use starcoin_chain::verifier::StaticVerifier;
 use starcoin_config::NodeConfig;
@@ -12,7 +16,7 @@ use starcoin_config::G_CRATE_VERSION;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network::NetworkServiceRef;
-use starcoin_network_rpc_api::GetTxnsWithHash;
+use starcoin_network_rpc_api::{GetBlockTxns, GetTxnsWithHash, MAX_TXN_REQUEST_SIZE};
 use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
 use starcoin_sync::block_connector::BlockConnectorService;
 use starcoin_sync::verified_rpc_client::VerifiedRpcClient;
@@ -24,8 +28,8 @@ use starcoin_types::block::ExecutedBlock;
 use starcoin_types::sync_status::SyncStatus;
 use starcoin_types::system_events::{NewBranch, SyncStatusChangeEvent};
 use starcoin_types::{
-    block::{Block, BlockBody},
-    compact_block::{CompactBlock, ShortId},
+    block::{Block, BlockBody, BlockHeader},
+    compact_block::{CompactBlock, SaltedCompactBlock, SaltedShortId, ShortId},
     system_events::NewHeadBlock,
     transaction::SignedUserTransaction,
 };
@@ -33,6 +37,41 @@ use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::sync::Arc;
 
+/// The compact block received from peer, the salted compact block is relayed by the peers which
+/// support the salted compact block protocol.
+enum RelayedCompactBlock {
+    Legacy(CompactBlock),
+    Salted(SaltedCompactBlock),
+}
+
+impl RelayedCompactBlock {
+    fn header(&self) -> &BlockHeader {
+        match self {
+            Self::Legacy(compact_block) => &compact_block.header,
+            Self::Salted(compact_block) => &compact_block.header,
+        }
+    }
+}
+
+/// Fetch the txns of a block by indexes from the peer which relayed the compact block.
+trait BlockTxnsFetcher: Send + Sync {
+    fn fetch_block_txns(
+        &self,
+        peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> BoxFuture<Result<Vec<Option<SignedUserTransaction>>>>;
+}
+
+impl BlockTxnsFetcher for VerifiedRpcClient {
+    fn fetch_block_txns(
+        &self,
+        peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> BoxFuture<Result<Vec<Option<SignedUserTransaction>>>> {
+        self.get_block_txns(Some(peer_id), req).boxed()
+    }
+}
+
 pub struct BlockRelayer {
     txpool: TxPoolService,
     sync_status: Option<SyncStatus>,
@@ -170,22 +209,13 @@ impl BlockRelayer {
             );
 
             if let Some(metrics) = metrics {
-                metrics
-                    .txns_filled_total
-                    .with_label_values(&["expect"])
-                    .inc_by(expect_txn_len as u64);
-                metrics
-                    .txns_filled_total
-                    .with_label_values(&["txpool"])
-                    .inc_by(filled_from_txpool);
-                metrics
-                    .txns_filled_total
-                    .with_label_values(&["network"])
-                    .inc_by(filled_from_network);
-                metrics
-                    .txns_filled_total
-                    .with_label_values(&["prefilled"])
-                    .inc_by(filled_from_prefilled);
+                Self::report_filled_txns(
+                    &metrics,
+                    expect_txn_len as u64,
+                    filled_from_txpool,
+                    filled_from_prefilled,
+                    filled_from_network,
+                );
             }
             collect_txns
         };
@@ -197,20 +227,199 @@ impl BlockRelayer {
         Ok(block)
     }
 
+    /// Reconstruct the block of the salted compact block, the prefilled txns are filled first, then
+    /// the txns in txpool which match the short ids, and the missing txns are fetched from the peer
+    /// by the indexes in one round trip.
+    async fn fill_salted_compact_block<P, F>(
+        txpool: P,
+        fetcher: F,
+        compact_block: SaltedCompactBlock,
+        peer_id: PeerId,
+        now: u64,
+        metrics: Option<BlockRelayerMetrics>,
+    ) -> Result<Block>
+    where
+        P: TxPoolSyncService,
+        F: BlockTxnsFetcher,
+    {
+        let block_id = compact_block.header.id();
+        let key = compact_block.short_id_key();
+        let expect_txn_len = compact_block.txn_len();
+        let mut filled_from_txpool: u64 = 0;
+        let mut filled_from_prefilled: u64 = 0;
+        let mut filled_from_network: u64 = 0;
+
+        let mut txns: Vec<Option<SignedUserTransaction>> = vec![None; expect_txn_len];
+        // Fill the block txns by prefilled txn
+        for prefilled_txn in compact_block.prefilled_txn {
+            let index = prefilled_txn.index as usize;
+            ensure!(
+                index < expect_txn_len && txns[index].is_none(),
+                "Invalid prefilled txn index {} of compact block {}",
+                index,
+                block_id
+            );
+            txns[index] = Some(prefilled_txn.tx);
+            filled_from_prefilled += 1;
+        }
+        // The short ids are the txns not prefilled, in the order of the block.
+        let short_id_indexes = txns
+            .iter()
+            .enumerate()
+            .filter(|(_, txn)| txn.is_none())
+            .map(|(index, _)| index as u64)
+            .collect::<Vec<_>>();
+
+        // Fill the block txns by tx pool, the txns with colliding short ids are ignored.
+        let mut pool_txns: HashMap<SaltedShortId, Option<SignedUserTransaction>> = HashMap::new();
+        if !short_id_indexes.is_empty() {
+            for txn in txpool.get_pending_txns(None, Some(now)) {
+                pool_txns
+                    .entry(key.short_id(&txn.id()))
+                    .and_modify(|exist| *exist = None)
+                    .or_insert(Some(txn));
+            }
+        }
+        let mut missing_indexes = vec![];
+        for (index, short_id) in short_id_indexes.iter().zip(compact_block.short_ids.iter()) {
+            match pool_txns.get(short_id) {
+                Some(Some(txn)) => {
+                    txns[*index as usize] = Some(txn.clone());
+                    filled_from_txpool += 1;
+                }
+                _ => missing_indexes.push(*index),
+            }
+        }
+
+        // Fetch the missing txns from peer
+        if !missing_indexes.is_empty() {
+            filled_from_network = Self::fetch_block_txns(
+                &fetcher,
+                peer_id.clone(),
+                block_id,
+                missing_indexes,
+                &mut txns,
+            )
+            .await?;
+        }
+
+        let header = compact_block.header;
+        let uncles = compact_block.uncles;
+        let mut block = Block::new(
+            header.clone(),
+            BlockBody::new(txns.iter().flatten().cloned().collect(), uncles.clone()),
+        );
+        if let Err(e) = StaticVerifier::verify_body_hash(&block) {
+            if filled_from_txpool == 0 {
+                return Err(e);
+            }
+            // A txn in txpool collides with the short id of a block txn, fetch all the txns of
+            // short ids from the peer.
+            warn!(
+                "[block-relay] Short id collision in compact block {}, fetch all txns from peer {}",
+                block_id, peer_id
+            );
+            filled_from_network =
+                Self::fetch_block_txns(&fetcher, peer_id, block_id, short_id_indexes, &mut txns)
+                    .await?;
+            filled_from_txpool = 0;
+            block = Block::new(
+                header,
+                BlockBody::new(txns.into_iter().flatten().collect(), uncles),
+            );
+            StaticVerifier::verify_body_hash(&block)?;
+        }
+
+        if let Some(metrics) = metrics.as_ref() {
+            Self::report_filled_txns(
+                metrics,
+                expect_txn_len as u64,
+                filled_from_txpool,
+                filled_from_prefilled,
+                filled_from_network,
+            );
+        }
+        Ok(block)
+    }
+
+    /// Fetch the txns of the block by indexes from peer, return the count of fetched txns.
+    /// Fetch the txns at `indexes` of the block, split into requests the peer accepts.
+    async fn fetch_block_txns<F: BlockTxnsFetcher>(
+        fetcher: &F,
+        peer_id: PeerId,
+        block_id: HashValue,
+        indexes: Vec<u64>,
+        txns: &mut [Option<SignedUserTransaction>],
+    ) -> Result<u64> {
+        for chunk in indexes.chunks(MAX_TXN_REQUEST_SIZE as usize) {
+            let fetched_txns = fetcher
+                .fetch_block_txns(
+                    peer_id.clone(),
+                    GetBlockTxns {
+                        block_id,
+                        indexes: chunk.to_vec(),
+                    },
+                )
+                .await?;
+            for (index, txn) in chunk.iter().zip(fetched_txns) {
+                let txn = txn.ok_or_else(|| {
+                    format_err!(
+                        "Peer {} does not return the txn at index {} of block {}",
+                        peer_id,
+                        index,
+                        block_id
+                    )
+                })?;
+                txns[*index as usize] = Some(txn);
+            }
+        }
+        Ok(indexes.len() as u64)
+    }
+
+    fn report_filled_txns(
+        metrics: &BlockRelayerMetrics,
+        expect: u64,
+        filled_from_txpool: u64,
+        filled_from_prefilled: u64,
+        filled_from_network: u64,
+    ) {
+        metrics
+            .txns_filled_total
+            .with_label_values(&["expect"])
+            .inc_by(expect);
+        metrics
+            .txns_filled_total
+            .with_label_values(&["txpool"])
+            .inc_by(filled_from_txpool);
+        metrics
+            .txns_filled_total
+            .with_label_values(&["network"])
+            .inc_by(filled_from_network);
+        metrics
+            .txns_filled_total
+            .with_label_values(&["prefilled"])
+            .inc_by(filled_from_prefilled);
+        if expect > 0 {
+            metrics
+                .txns_reconstruction_hit_rate
+                .observe((filled_from_txpool + filled_from_prefilled) as f64 / expect as f64);
+        }
+    }
+
     fn handle_block_event(
         &self,
-        compact_block_msg: PeerCompactBlockMessage,
+        peer_id: PeerId,
+        compact_block: RelayedCompactBlock,
         ctx: &mut ServiceContext<BlockRelayer>,
     ) -> Result<()> {
         let network = ctx.get_shared::<NetworkServiceRef>()?;
         let block_connector_service = ctx.service_ref::<BlockConnectorService>()?.clone();
         let txpool = self.txpool.clone();
         let metrics = self.metrics.clone();
+        let now = self.time_service.now_secs();
         let fut = async move {
-            let compact_block = compact_block_msg.message.compact_block;
-            let peer_id = compact_block_msg.peer_id;
             debug!("Receive peer compact block event from peer id:{}", peer_id);
-            let block_id = compact_block.header.id();
+            let block_id = compact_block.header().id();
             if let Ok(Some((_, _, _, version))) =
                 txpool.get_store().get_failed_block_by_id(block_id)
             {
@@ -229,14 +438,29 @@ impl BlockRelayer {
                 let _timer = metrics
                     .as_ref()
                     .map(|metrics| metrics.txns_filled_time.start_timer());
-                let block = BlockRelayer::fill_compact_block(
-                    txpool.clone(),
-                    rpc_client,
-                    compact_block,
-                    peer_id.clone(),
-                    metrics,
-                )
-                .await?;
+                let block = match compact_block {
+                    RelayedCompactBlock::Legacy(compact_block) => {
+                        BlockRelayer::fill_compact_block(
+                            txpool.clone(),
+                            rpc_client,
+                            compact_block,
+                            peer_id.clone(),
+                            metrics,
+                        )
+                        .await?
+                    }
+                    RelayedCompactBlock::Salted(compact_block) => {
+                        BlockRelayer::fill_salted_compact_block(
+                            txpool.clone(),
+                            rpc_client,
+                            compact_block,
+                            peer_id.clone(),
+                            now,
+                            metrics,
+                        )
+                        .await?
+                    }
+                };
 
                 block_connector_service.notify(PeerNewBlock::new(peer_id, block))?;
             }
@@ -249,6 +473,38 @@ impl BlockRelayer {
         }));
         Ok(())
     }
+
+    fn handle_compact_block(
+        &mut self,
+        peer_id: PeerId,
+        compact_block: RelayedCompactBlock,
+        ctx: &mut ServiceContext<BlockRelayer>,
+    ) {
+        let block_timestamp = compact_block.header().timestamp();
+        let current_timestamp = self.time_service.now_millis();
+        let time = current_timestamp.saturating_sub(block_timestamp);
+        let time_sec: f64 = (time as f64) / 1000_f64;
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.block_relay_time.observe(time_sec);
+        }
+        sl_info!(
+            "{action} {hash} {time_sec}",
+            time_sec = time_sec,
+            hash = compact_block.header().id().to_hex(),
+            action = "block_relay_time",
+        );
+        //TODO should filter too old block?
+
+        if let Err(e) = self.handle_block_event(peer_id, compact_block, ctx) {
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.txns_filled_failed_total.inc();
+            }
+            error!(
+                "[block-relay] handle PeerCompactBlockMessage error: {:?}",
+                e
+            );
+        }
+    }
 }
 
 impl ActorService for BlockRelayer {
@@ -313,29 +569,218 @@ impl EventHandler<Self, PeerCompactBlockMessage> for BlockRelayer {
         compact_block_msg: PeerCompactBlockMessage,
         ctx: &mut ServiceContext<BlockRelayer>,
     ) {
-        let block_timestamp = compact_block_msg.message.compact_block.header.timestamp();
-        let current_timestamp = self.time_service.now_millis();
-        let time = current_timestamp.saturating_sub(block_timestamp);
-        let time_sec: f64 = (time as f64) / 1000_f64;
-        if let Some(metrics) = self.metrics.as_ref() {
-            metrics.block_relay_time.observe(time_sec);
-        }
-        sl_info!(
-            "{action} {hash} {time_sec}",
-            time_sec = time_sec,
-            hash = compact_block_msg.message.compact_block.header.id().to_hex(),
-            action = "block_relay_time",
+        self.handle_compact_block(
+            compact_block_msg.peer_id,
+            RelayedCompactBlock::Legacy(compact_block_msg.message.compact_block),
+            ctx,
         );
-        //TODO should filter too old block?
+    }
+}
 
-        if let Err(e) = self.handle_block_event(compact_block_msg, ctx) {
-            if let Some(metrics) = self.metrics.as_ref() {
-                metrics.txns_filled_failed_total.inc();
+impl EventHandler<Self, PeerSaltedCompactBlockMessage> for BlockRelayer {
+    fn handle_event(
+        &mut self,
+        compact_block_msg: PeerSaltedCompactBlockMessage,
+        ctx: &mut ServiceContext<BlockRelayer>,
+    ) {
+        self.handle_compact_block(
+            compact_block_msg.peer_id,
+            RelayedCompactBlock::Salted(compact_block_msg.message.compact_block),
+            ctx,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_metrics::Registry;
+    use starcoin_network_rpc_api::RpcRequest;
+    use starcoin_txpool_mock_service::MockTxPoolService;
+    use starcoin_types::block::BlockHeaderBuilder;
+    use starcoin_types::compact_block::PrefilledTxn;
+    use std::sync::Mutex;
+
+    #[derive(Clone)]
+    struct MockBlockTxnsFetcher {
+        block: Block,
+        requests: Arc<Mutex<Vec<GetBlockTxns>>>,
+    }
+
+    impl MockBlockTxnsFetcher {
+        fn new(block: Block) -> Self {
+            Self {
+                block,
+                requests: Arc::new(Mutex::new(vec![])),
             }
-            error!(
-                "[block-relay] handle PeerCompactBlockMessage error: {:?}",
-                e
-            );
         }
+
+        fn requested_indexes(&self) -> Vec<Vec<u64>> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|req| req.indexes.clone())
+                .collect()
+        }
+    }
+
+    impl BlockTxnsFetcher for MockBlockTxnsFetcher {
+        fn fetch_block_txns(
+            &self,
+            _peer_id: PeerId,
+            req: GetBlockTxns,
+        ) -> BoxFuture<Result<Vec<Option<SignedUserTransaction>>>> {
+            // reject the request as the peer does.
+            if let Err(e) = req.verify() {
+                return futures::future::ready(Err(e)).boxed();
+            }
+            let txns = req
+                .indexes
+                .iter()
+                .map(|index| self.block.transactions().get(*index as usize).cloned())
+                .collect();
+            self.requests.lock().unwrap().push(req);
+            futures::future::ready(Ok(txns)).boxed()
+        }
+    }
+
+    fn new_block(txns: Vec<SignedUserTransaction>) -> Block {
+        let body = BlockBody::new(txns, None);
+        let header = BlockHeaderBuilder::random()
+            .with_body_hash(body.hash())
+            .build();
+        Block::new(header, body)
+    }
+
+    fn filled_txns(metrics: &BlockRelayerMetrics, label: &str) -> u64 {
+        metrics.txns_filled_total.with_label_values(&[label]).get()
+    }
+
+    #[stest::test]
+    async fn test_fill_salted_compact_block() -> Result<()> {
+        let txns = (0..4)
+            .map(|_| SignedUserTransaction::mock())
+            .collect::<Vec<_>>();
+        let block = new_block(txns.clone());
+        let mut compact_block = CompactBlock::new(block.clone());
+        compact_block.prefilled_txn = vec![PrefilledTxn {
+            index: 3,
+            tx: txns[3].clone(),
+        }];
+        let compact_block = compact_block.into_salted(1);
+
+        // the pool has the txns at index 0 and 2, and a txn not in the block.
+        let txpool = MockTxPoolService::new_with_txns(vec![
+            txns[0].clone(),
+            SignedUserTransaction::mock(),
+            txns[2].clone(),
+        ]);
+        let fetcher = MockBlockTxnsFetcher::new(block.clone());
+        let metrics = BlockRelayerMetrics::register(&Registry::new())?;
+        let filled_block = BlockRelayer::fill_salted_compact_block(
+            txpool,
+            fetcher.clone(),
+            compact_block.clone(),
+            PeerId::random(),
+            0,
+            Some(metrics.clone()),
+        )
+        .await?;
+        assert_eq!(filled_block, block);
+        // only the txn at index 1 is fetched, in one round trip.
+        assert_eq!(fetcher.requested_indexes(), vec![vec![1]]);
+        assert_eq!(filled_txns(&metrics, "expect"), 4);
+        assert_eq!(filled_txns(&metrics, "txpool"), 2);
+        assert_eq!(filled_txns(&metrics, "prefilled"), 1);
+        assert_eq!(filled_txns(&metrics, "network"), 1);
+        assert_eq!(metrics.txns_reconstruction_hit_rate.get_sample_count(), 1);
+        assert_eq!(metrics.txns_reconstruction_hit_rate.get_sample_sum(), 0.75);
+
+        // the block is reconstructed without network round trip if the pool has all the txns.
+        let txpool = MockTxPoolService::new_with_txns(txns[0..3].to_vec());
+        let fetcher = MockBlockTxnsFetcher::new(block.clone());
+        let metrics = BlockRelayerMetrics::register(&Registry::new())?;
+        let filled_block = BlockRelayer::fill_salted_compact_block(
+            txpool,
+            fetcher.clone(),
+            compact_block,
+            PeerId::random(),
+            0,
+            Some(metrics.clone()),
+        )
+        .await?;
+        assert_eq!(filled_block, block);
+        assert!(fetcher.requested_indexes().is_empty());
+        assert_eq!(metrics.txns_reconstruction_hit_rate.get_sample_sum(), 1.0);
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_fill_salted_compact_block_with_collision() -> Result<()> {
+        let txns = (0..2)
+            .map(|_| SignedUserTransaction::mock())
+            .collect::<Vec<_>>();
+        let block = new_block(txns.clone());
+        let mut compact_block = SaltedCompactBlock::new(block.clone(), 1);
+        // a txn in the pool collides with the short id of the block txn at index 1.
+        let colliding_txn = SignedUserTransaction::mock();
+        compact_block.short_ids[1] = compact_block.short_id_key().short_id(&colliding_txn.id());
+
+        let txpool = MockTxPoolService::new_with_txns(vec![txns[0].clone(), colliding_txn]);
+        let fetcher = MockBlockTxnsFetcher::new(block.clone());
+        let metrics = BlockRelayerMetrics::register(&Registry::new())?;
+        let filled_block = BlockRelayer::fill_salted_compact_block(
+            txpool,
+            fetcher.clone(),
+            compact_block,
+            PeerId::random(),
+            0,
+            Some(metrics.clone()),
+        )
+        .await?;
+        assert_eq!(filled_block, block);
+        // the body hash mismatches, so all the txns of short ids are fetched from the peer.
+        assert_eq!(fetcher.requested_indexes(), vec![vec![0, 1]]);
+        assert_eq!(filled_txns(&metrics, "txpool"), 0);
+        assert_eq!(filled_txns(&metrics, "network"), 2);
+        assert_eq!(metrics.txns_reconstruction_hit_rate.get_sample_sum(), 0.0);
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_fill_salted_compact_block_with_large_collision() -> Result<()> {
+        let txn_len = MAX_TXN_REQUEST_SIZE as usize + 1;
+        let txns = (0..txn_len)
+            .map(|_| SignedUserTransaction::mock())
+            .collect::<Vec<_>>();
+        let block = new_block(txns);
+        let mut compact_block = SaltedCompactBlock::new(block.clone(), 1);
+        let colliding_txn = SignedUserTransaction::mock();
+        compact_block.short_ids[0] = compact_block.short_id_key().short_id(&colliding_txn.id());
+
+        let txpool = MockTxPoolService::new_with_txns(vec![colliding_txn]);
+        let fetcher = MockBlockTxnsFetcher::new(block.clone());
+        let filled_block = BlockRelayer::fill_salted_compact_block(
+            txpool,
+            fetcher.clone(),
+            compact_block,
+            PeerId::random(),
+            0,
+            None,
+        )
+        .await?;
+        assert_eq!(filled_block, block);
+        // both the missing txns and the refetch after collision are split by the request limit.
+        let request_sizes = fetcher
+            .requested_indexes()
+            .iter()
+            .map(Vec::len)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            request_sizes,
+            vec![txn_len - 1, MAX_TXN_REQUEST_SIZE as usize, 1]
+        );
+        Ok(())
     }
 }
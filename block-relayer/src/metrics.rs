@@ -10,6 +10,7 @@ use starcoin_metrics::{
 pub struct BlockRelayerMetrics {
     pub txns_filled_total: UIntCounterVec,
     pub txns_filled_time: Histogram,
+    pub txns_reconstruction_hit_rate: Histogram,
     pub block_relay_time: Histogram,
     pub txns_filled_failed_total: UIntCounter,
 }
@@ -31,6 +32,16 @@ impl BlockRelayerMetrics {
             Histogram::with_opts(HistogramOpts::new("txns_filled_time", "txns filled time"))?,
             registry,
         )?;
+        let txns_reconstruction_hit_rate = register(
+            Histogram::with_opts(
+                HistogramOpts::new(
+                    "txns_reconstruction_hit_rate",
+                    "rate of the block txns filled from local txpool and prefilled txns, the block is reconstructed without network round trip if the rate is 1",
+                )
+                .buckets(vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]),
+            )?,
+            registry,
+        )?;
         let block_relay_time = register(
             Histogram::with_opts(HistogramOpts::new(
                 "block_relay_time",
@@ -50,6 +61,7 @@ impl BlockRelayerMetrics {
         Ok(Self {
             txns_filled_total,
             txns_filled_time,
+            txns_reconstruction_hit_rate,
             block_relay_time,
             txns_filled_failed_total,
         })
@@ -5,6 +5,7 @@ use crate::TransactionInfoWithProof;
 use anyhow::Result;
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::ServiceRequest;
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::RichTransactionInfo;
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
@@ -53,6 +54,12 @@ pub enum ChainRequest {
         reverse: bool,
         max_size: u64,
     },
+    GetTransactionInfosBySender {
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    },
     GetTransactionProof {
         block_id: HashValue,
         transaction_global_index: u64,
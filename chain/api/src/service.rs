@@ -6,6 +6,7 @@ use crate::TransactionInfoWithProof;
 use anyhow::{bail, Result};
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::{ActorService, ServiceHandler, ServiceRef};
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::contract_event::{ContractEvent, ContractEventInfo};
 use starcoin_types::filter::Filter;
 use starcoin_types::startup_info::ChainStatus;
@@ -63,6 +64,16 @@ pub trait ReadableChainService {
         max_size: u64,
     ) -> Result<Vec<RichTransactionInfo>>;
 
+    /// Get the main chain transaction infos of `sender` from sequence number `start_seq`,
+    /// require the account transaction index.
+    fn get_transaction_infos_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<RichTransactionInfo>>;
+
     fn get_transaction_proof(
         &self,
         block_id: HashValue,
@@ -129,6 +140,13 @@ pub trait ChainAsyncService:
         reverse: bool,
         max_size: u64,
     ) -> Result<Vec<RichTransactionInfo>>;
+    async fn get_transaction_infos_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<RichTransactionInfo>>;
 
     async fn get_transaction_proof(
         &self,
@@ -406,6 +424,28 @@ where
         }
     }
 
+    async fn get_transaction_infos_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<RichTransactionInfo>> {
+        let response = self
+            .send(ChainRequest::GetTransactionInfosBySender {
+                sender,
+                start_seq,
+                reverse,
+                max_size,
+            })
+            .await??;
+        if let ChainResponse::TransactionInfos(tx_infos) = response {
+            Ok(tx_infos)
+        } else {
+            bail!("get txn infos by sender error")
+        }
+    }
+
     async fn get_transaction_proof(
         &self,
         block_id: HashValue,
@@ -3,25 +3,36 @@
 
 pub mod message;
 
-use crate::message::{ContractEventNotification, Event, Notification, ThinBlock};
+use crate::message::{
+    BlockEvents, ContractEventNotification, Event, Notification, ReorgTooDeep, ThinBlock,
+};
 use anyhow::{format_err, Result};
+use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
 use starcoin_storage::{Storage, Store};
-use starcoin_types::block::Block;
+use starcoin_types::block::{Block, BlockHeader};
 use starcoin_types::system_events::NewHeadBlock;
 use std::sync::Arc;
 
+/// The max depth of reorg to notify the retracted blocks.
+pub const MAX_REORG_DEPTH: usize = 256;
+
 /// ChainNotify watch `NewHeadBlock` message from bus,
-/// and then reproduce `Notification<ThinBlock>` and `Notification<Arc<[Event]>>` message to bus.
+/// and then reproduce `Notification<ThinBlock>` and `Notification<BlockEvents>` message to bus.
 /// User can subscribe the two notification to watch onchain events.
+/// When the main chain switches to another branch, the blocks retracted from the main chain
+/// are notified with `removed` first, then the enacted blocks in ascending order.
+/// If the reorg is deeper than `MAX_REORG_DEPTH`, a `ReorgTooDeep` notification is sent
+/// instead of the retracted blocks, followed by the new head.
 pub struct ChainNotifyHandlerService {
     store: Arc<dyn Store>,
+    head: Option<BlockHeader>,
 }
 
 impl ChainNotifyHandlerService {
     pub fn new(store: Arc<dyn Store>) -> Self {
-        Self { store }
+        Self { store, head: None }
     }
 }
 
@@ -37,6 +48,9 @@ impl ServiceFactory<Self> for ChainNotifyHandlerService {
 impl ActorService for ChainNotifyHandlerService {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.subscribe::<NewHeadBlock>();
+        if let Some(startup_info) = self.store.get_startup_info()? {
+            self.head = self.store.get_block_header_by_hash(startup_info.main)?;
+        }
         Ok(())
     }
 
@@ -54,21 +68,85 @@ impl EventHandler<Self, NewHeadBlock> for ChainNotifyHandlerService {
     ) {
         let NewHeadBlock(block_detail) = item;
         let block = block_detail.block();
-        // notify header.
-        self.notify_new_block(block, ctx);
+        let (retracted, enacted) = match self.head.take() {
+            Some(head) if head.id() != block.header().parent_hash() => {
+                match self.find_reorg(&head, block) {
+                    Ok(Some(reorg)) => reorg,
+                    Ok(None) => {
+                        warn!(target: "pubsub", "reorg from head {} to {} is deeper than {} blocks, notify subscribers to resync", head.id(), block.id(), MAX_REORG_DEPTH);
+                        ctx.broadcast(Notification(ReorgTooDeep {
+                            old_head: head,
+                            new_head: block.header().clone(),
+                            max_depth: MAX_REORG_DEPTH as u64,
+                        }));
+                        (vec![], vec![block.clone()])
+                    }
+                    Err(e) => {
+                        warn!(target: "pubsub", "fail to find the retracted blocks from head {}, err: {}", head.id(), e);
+                        (vec![], vec![block.clone()])
+                    }
+                }
+            }
+            _ => (vec![], vec![block.clone()]),
+        };
+        self.head = Some(block.header().clone());
 
-        // notify events
-        if let Err(e) = self.notify_events(block, self.store.clone(), ctx) {
-            error!(target: "pubsub", "fail to notify events to client, err: {}", &e);
+        for (blocks, removed) in [(retracted, true), (enacted, false)] {
+            for block in blocks {
+                // notify header.
+                self.notify_new_block(&block, removed, ctx);
+
+                // notify events
+                if let Err(e) = self.notify_events(&block, removed, self.store.clone(), ctx) {
+                    error!(target: "pubsub", "fail to notify events to client, err: {}", &e);
+                }
+            }
         }
     }
 }
 
 impl ChainNotifyHandlerService {
-    pub fn notify_new_block(&self, block: &Block, ctx: &mut ServiceContext<Self>) {
+    /// Find the blocks retracted from the main chain in descending order,
+    /// and the blocks enacted in ascending order, when the head switches to `new_head`.
+    /// Return `None` if the reorg is deeper than `MAX_REORG_DEPTH`.
+    fn find_reorg(
+        &self,
+        old_head: &BlockHeader,
+        new_head: &Block,
+    ) -> Result<Option<(Vec<Block>, Vec<Block>)>> {
+        let mut retracted = vec![];
+        let mut enacted = vec![];
+        let mut old = self.get_block(old_head.id())?;
+        let mut new = new_head.clone();
+        while old.id() != new.id() {
+            if retracted.len() >= MAX_REORG_DEPTH || enacted.len() >= MAX_REORG_DEPTH {
+                return Ok(None);
+            }
+            if new.header().number() >= old.header().number() {
+                let parent = self.get_block(new.header().parent_hash())?;
+                enacted.push(new);
+                new = parent;
+            } else {
+                let parent = self.get_block(old.header().parent_hash())?;
+                retracted.push(old);
+                old = parent;
+            }
+        }
+        enacted.reverse();
+        Ok(Some((retracted, enacted)))
+    }
+
+    fn get_block(&self, block_id: HashValue) -> Result<Block> {
+        self.store
+            .get_block_by_hash(block_id)?
+            .ok_or_else(|| format_err!("cannot find block by it's id {}", block_id))
+    }
+
+    pub fn notify_new_block(&self, block: &Block, removed: bool, ctx: &mut ServiceContext<Self>) {
         let thin_block = ThinBlock::new(
             block.header().clone(),
             block.transactions().iter().map(|t| t.id()).collect(),
+            removed,
         );
         ctx.broadcast(Notification(thin_block));
     }
@@ -76,34 +154,44 @@ impl ChainNotifyHandlerService {
     pub fn notify_events(
         &self,
         block: &Block,
+        removed: bool,
         store: Arc<dyn Store>,
         ctx: &mut ServiceContext<Self>,
     ) -> Result<()> {
-        let block_number = block.header().number();
-        let block_id = block.id();
-        let txn_info_ids = store.get_block_txn_info_ids(block_id)?;
-        let mut all_events: Vec<Event> = vec![];
-        for txn_info_id in txn_info_ids.into_iter().rev() {
-            let txn_info = store
-                .get_transaction_info(txn_info_id)?
-                .ok_or_else(|| format_err!("cannot find txn info by it's id {}", &txn_info_id))?;
-            // get events directly by txn_info_id
-            let events = store.get_contract_events(txn_info_id)?.unwrap_or_default();
-            all_events.extend(events.into_iter().enumerate().map(|(idx, evt)| {
-                Event::new(
-                    block_id,
-                    block_number,
-                    txn_info.transaction_hash(),
-                    Some(txn_info.transaction_index),
-                    Some(txn_info.transaction_global_index),
-                    Some(idx as u32),
-                    evt,
-                )
-            }));
-        }
         let events_notification: ContractEventNotification =
-            Notification((block.header.state_root(), all_events.into()));
+            Notification(load_block_events(store.as_ref(), block, removed)?);
         ctx.broadcast(events_notification);
         Ok(())
     }
 }
+
+/// Load the events of `block` from storage.
+pub fn load_block_events(store: &dyn Store, block: &Block, removed: bool) -> Result<BlockEvents> {
+    let block_number = block.header().number();
+    let block_id = block.id();
+    let txn_info_ids = store.get_block_txn_info_ids(block_id)?;
+    let mut all_events: Vec<Event> = vec![];
+    for txn_info_id in txn_info_ids.into_iter().rev() {
+        let txn_info = store
+            .get_transaction_info(txn_info_id)?
+            .ok_or_else(|| format_err!("cannot find txn info by it's id {}", &txn_info_id))?;
+        // get events directly by txn_info_id
+        let events = store.get_contract_events(txn_info_id)?.unwrap_or_default();
+        all_events.extend(events.into_iter().enumerate().map(|(idx, evt)| {
+            Event::new(
+                block_id,
+                block_number,
+                txn_info.transaction_hash(),
+                Some(txn_info.transaction_index),
+                Some(txn_info.transaction_global_index),
+                Some(idx as u32),
+                evt,
+            )
+        }));
+    }
+    Ok(BlockEvents::new(
+        block.header().clone(),
+        all_events.into(),
+        removed,
+    ))
+}
@@ -9,8 +9,9 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct Notification<T>(pub T);
 
-pub type ContractEventNotification = Notification<(HashValue, Arc<[Event]>)>;
+pub type ContractEventNotification = Notification<BlockEvents>;
 pub type NewHeadEventNotification = Notification<ThinBlock>;
+pub type ReorgTooDeepNotification = Notification<ReorgTooDeep>;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Event {
@@ -46,17 +47,37 @@ impl Event {
     }
 }
 
-/// Block with only txn hashes.
+/// Events of a block, `removed` is true if the block is retracted from the main chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockEvents {
+    pub header: BlockHeader,
+    pub events: Arc<[Event]>,
+    pub removed: bool,
+}
+
+impl BlockEvents {
+    pub fn new(header: BlockHeader, events: Arc<[Event]>, removed: bool) -> Self {
+        Self {
+            header,
+            events,
+            removed,
+        }
+    }
+}
+
+/// Block with only txn hashes, `removed` is true if the block is retracted from the main chain.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ThinBlock {
     pub header: BlockHeader,
     pub body: Vec<HashValue>,
+    pub removed: bool,
 }
 impl ThinBlock {
-    pub fn new(header: BlockHeader, txn_hashes: Vec<HashValue>) -> Self {
+    pub fn new(header: BlockHeader, txn_hashes: Vec<HashValue>, removed: bool) -> Self {
         Self {
             header,
             body: txn_hashes,
+            removed,
         }
     }
     pub fn header(&self) -> &BlockHeader {
@@ -66,3 +87,13 @@ impl ThinBlock {
         &self.body
     }
 }
+
+/// The main chain switches from `old_head` to `new_head` by a reorg deeper than `max_depth`,
+/// the retracted blocks are not notified, only the new head is notified after it,
+/// the subscribers should resync from the new head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorgTooDeep {
+    pub old_head: BlockHeader,
+    pub new_head: BlockHeader,
+    pub max_depth: u64,
+}
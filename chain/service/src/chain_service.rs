@@ -14,6 +14,7 @@ use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler,
 };
 use starcoin_storage::{BlockStore, Storage, Store};
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::block::ExecutedBlock;
 use starcoin_types::contract_event::ContractEventInfo;
 use starcoin_types::filter::Filter;
@@ -216,6 +217,15 @@ impl ServiceHandler<Self, ChainRequest> for ChainReaderService {
                 self.inner
                     .get_transaction_infos(start_index, reverse, max_size)?,
             )),
+            ChainRequest::GetTransactionInfosBySender {
+                sender,
+                start_seq,
+                reverse,
+                max_size,
+            } => Ok(ChainResponse::TransactionInfos(
+                self.inner
+                    .get_transaction_infos_by_sender(sender, start_seq, reverse, max_size)?,
+            )),
             ChainRequest::GetTransactionProof {
                 block_id,
                 transaction_global_index,
@@ -398,6 +408,48 @@ impl ReadableChainService for ChainReaderServiceInner {
             .get_transaction_infos(start_index, reverse, max_size)
     }
 
+    fn get_transaction_infos_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<RichTransactionInfo>> {
+        let mut txn_infos = vec![];
+        let mut start_seq = start_seq;
+        loop {
+            let entries = self
+                .storage
+                .get_account_txn_index(sender, start_seq, reverse, max_size)?;
+            let (last_seq, exhausted) = match entries.last() {
+                Some((key, _)) => (key.sequence_number, (entries.len() as u64) < max_size),
+                None => break,
+            };
+            for (key, value) in entries {
+                // skip the entries of other branches, the txn info of main chain must in the same block.
+                match self.main.get_transaction_info(value.transaction_hash)? {
+                    Some(txn_info) if txn_info.block_id() == key.block_id => {
+                        txn_infos.push(txn_info);
+                    }
+                    _ => continue,
+                }
+                if txn_infos.len() as u64 >= max_size {
+                    return Ok(txn_infos);
+                }
+            }
+            let next_seq = if reverse {
+                last_seq.checked_sub(1)
+            } else {
+                last_seq.checked_add(1)
+            };
+            match next_seq {
+                Some(seq) if !exhausted => start_seq = Some(seq),
+                _ => break,
+            }
+        }
+        Ok(txn_infos)
+    }
+
     fn get_transaction_proof(
         &self,
         block_id: HashValue,
@@ -0,0 +1,91 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_config::NodeConfig;
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_storage::{Storage, Store};
+use starcoin_types::block::ExecutedBlock;
+use starcoin_types::system_events::NewHeadBlock;
+use std::sync::Arc;
+
+/// Avoid blocking the service too long when catching up after restart,
+/// the rest blocks are pruned on the next head block.
+const MAX_PRUNE_BLOCKS_PER_HEAD: u64 = 32;
+
+/// Delete the state nodes which are no longer reachable from the state of
+/// the latest `retention` main chain blocks.
+pub struct StatePruneService {
+    storage: Arc<dyn Store>,
+    retention: u64,
+}
+
+impl StatePruneService {
+    pub fn new(storage: Arc<dyn Store>, retention: u64) -> Result<Self> {
+        ensure!(
+            storage.state_pruning_enabled(),
+            "State pruning is not enabled in storage."
+        );
+        Ok(Self { storage, retention })
+    }
+
+    fn prune(&self, head: &ExecutedBlock) -> Result<()> {
+        let target = head.header().number().saturating_sub(self.retention);
+        let pruned = self.storage.get_state_pruned_block()?.unwrap_or(0);
+        if target <= pruned {
+            return Ok(());
+        }
+        let block_accumulator = MerkleAccumulator::new_with_info(
+            head.block_info().get_block_accumulator_info().clone(),
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Block),
+        );
+        let end = target.min(pruned + MAX_PRUNE_BLOCKS_PER_HEAD);
+        let mut deleted = 0;
+        for number in (pruned + 1)..=end {
+            let block_id = block_accumulator
+                .get_leaf(number)?
+                .ok_or_else(|| format_err!("Can not find main block by number {}", number))?;
+            deleted += self.storage.prune_block_state(number, block_id)?;
+            self.storage.save_state_pruned_block(number)?;
+        }
+        debug!(
+            "Prune state of blocks [{}, {}], deleted {} state nodes.",
+            pruned + 1,
+            end,
+            deleted
+        );
+        Ok(())
+    }
+}
+
+impl ServiceFactory<Self> for StatePruneService {
+    fn create(ctx: &mut ServiceContext<StatePruneService>) -> Result<StatePruneService> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        Self::new(storage, config.storage.state_prune_retention())
+    }
+}
+
+impl ActorService for StatePruneService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        ctx.subscribe::<NewHeadBlock>();
+        Ok(())
+    }
+
+    fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        ctx.unsubscribe::<NewHeadBlock>();
+        Ok(())
+    }
+}
+
+impl EventHandler<Self, NewHeadBlock> for StatePruneService {
+    fn handle_event(&mut self, event: NewHeadBlock, _ctx: &mut ServiceContext<StatePruneService>) {
+        if let Err(e) = self.prune(event.0.as_ref()) {
+            error!("StatePruneService prune state err: {:?}", e);
+        }
+    }
+}
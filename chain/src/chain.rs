@@ -21,6 +21,9 @@ use starcoin_logger::prelude::*;
 use starcoin_open_block::OpenedBlock;
 use starcoin_state_api::{AccountStateReader, ChainStateReader, ChainStateWriter};
 use starcoin_statedb::ChainStateDB;
+use starcoin_storage::event_index::{
+    address_index, event_key_index, type_tag_index, EventIndexType,
+};
 use starcoin_storage::Store;
 use starcoin_time_service::TimeService;
 use starcoin_types::block::BlockIdAndNumber;
@@ -45,7 +48,7 @@ use std::iter::Extend;
 use std::option::Option::{None, Some};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     sync::Arc,
 };
 
@@ -575,6 +578,9 @@ static MAIN_DIRECT_SAVE_BLOCK_HASH_MAP: Lazy<BTreeMap<HashValue, (BlockExecutedD
 
 static OUTPUT_BLOCK: AtomicBool = AtomicBool::new(false);
 
+/// Block count of a single event index scan in `filter_events`.
+const EVENT_INDEX_SCAN_WINDOW: BlockNumber = 10000;
+
 pub struct ChainStatusWithBlock {
     pub status: ChainStatus,
     pub head: Block,
@@ -718,7 +724,12 @@ impl BlockChain {
                 epoch
             ));
         }
-        for block_number in epoch.start_block_number()..epoch.end_block_number() {
+        // the blocks before the fork block of a forked chain are not in local storage.
+        let start_block_number = match self.storage.get_fork_block()? {
+            Some(fork_block) => epoch.start_block_number().max(fork_block.number),
+            None => epoch.start_block_number(),
+        };
+        for block_number in start_block_number..epoch.end_block_number() {
             let block_uncles = if block_number == head_number {
                 head_block.uncle_ids()
             } else {
@@ -968,9 +979,10 @@ impl BlockChain {
         );
 
         watch(CHAIN_WATCH_NAME, "n23");
-        statedb
+        let state_seqs = statedb
             .flush()
             .map_err(BlockExecutorError::BlockChainStateErr)?;
+        storage.save_block_state_journal(header.number(), block_id, state_seqs)?;
         // If chain state is matched, and accumulator is matched,
         // then, we save flush states, and save block data.
         watch(CHAIN_WATCH_NAME, "n24");
@@ -1013,27 +1025,26 @@ impl BlockChain {
             "events' length should be equal to txn infos' length"
         );
         let txn_info_ids: Vec<_> = txn_infos.iter().map(|info| info.id()).collect();
-        for (info_id, events) in txn_info_ids.iter().zip(txn_events.into_iter()) {
-            storage.save_contract_events(*info_id, events)?;
+        let rich_txn_infos: Vec<_> = txn_infos
+            .into_iter()
+            .enumerate()
+            .map(|(transaction_index, info)| {
+                RichTransactionInfo::new(
+                    block_id,
+                    block.header().number(),
+                    info,
+                    transaction_index as u32,
+                    transaction_global_index
+                        .checked_add(transaction_index as u64)
+                        .expect("transaction_global_index overflow."),
+                )
+            })
+            .collect();
+        for (txn_info, events) in rich_txn_infos.iter().zip(txn_events.into_iter()) {
+            storage.save_contract_events_with_index(txn_info, events)?;
         }
 
-        storage.save_transaction_infos(
-            txn_infos
-                .into_iter()
-                .enumerate()
-                .map(|(transaction_index, info)| {
-                    RichTransactionInfo::new(
-                        block_id,
-                        block.header().number(),
-                        info,
-                        transaction_index as u32,
-                        transaction_global_index
-                            .checked_add(transaction_index as u64)
-                            .expect("transaction_global_index overflow."),
-                    )
-                })
-                .collect(),
-        )?;
+        storage.save_transaction_infos(rich_txn_infos)?;
 
         let txn_id_vec = transactions
             .iter()
@@ -1123,9 +1134,10 @@ impl BlockChain {
             "verify block: txn accumulator root mismatch"
         );
 
-        statedb
+        let state_seqs = statedb
             .flush()
             .map_err(BlockExecutorError::BlockChainStateErr)?;
+        storage.save_block_state_journal(header.number(), block_id, state_seqs)?;
         // If chain state is matched, and accumulator is matched,
         // then, we save flush states, and save block data.
         txn_accumulator
@@ -1158,27 +1170,26 @@ impl BlockChain {
 
         // save block's transaction relationship and save transaction
         let txn_info_ids: Vec<_> = txn_infos.iter().map(|info| info.id()).collect();
-        for (info_id, events) in txn_info_ids.iter().zip(txn_events.into_iter()) {
-            storage.save_contract_events(*info_id, events)?;
+        let rich_txn_infos: Vec<_> = txn_infos
+            .into_iter()
+            .enumerate()
+            .map(|(transaction_index, info)| {
+                RichTransactionInfo::new(
+                    block_id,
+                    block.header().number(),
+                    info,
+                    transaction_index as u32,
+                    transaction_global_index
+                        .checked_add(transaction_index as u64)
+                        .expect("transaction_global_index overflow."),
+                )
+            })
+            .collect();
+        for (txn_info, events) in rich_txn_infos.iter().zip(txn_events.into_iter()) {
+            storage.save_contract_events_with_index(txn_info, events)?;
         }
 
-        storage.save_transaction_infos(
-            txn_infos
-                .into_iter()
-                .enumerate()
-                .map(|(transaction_index, info)| {
-                    RichTransactionInfo::new(
-                        block_id,
-                        block.header().number(),
-                        info,
-                        transaction_index as u32,
-                        transaction_global_index
-                            .checked_add(transaction_index as u64)
-                            .expect("transaction_global_index overflow."),
-                    )
-                })
-                .collect(),
-        )?;
+        storage.save_transaction_infos(rich_txn_infos)?;
 
         let txn_id_vec = transactions
             .iter()
@@ -1698,6 +1709,175 @@ impl ChainReader for BlockChain {
 
 impl BlockChain {
     pub fn filter_events(&self, filter: Filter) -> Result<Vec<ContractEventInfo>> {
+        if let Some(event_with_infos) = self.filter_events_by_index(&filter)? {
+            return Ok(event_with_infos);
+        }
+        self.filter_events_by_scan(filter)
+    }
+
+    /// Filter events through the event index column families.
+    /// Return `None` if the index can not serve the filter, the caller should scan blocks instead.
+    fn filter_events_by_index(&self, filter: &Filter) -> Result<Option<Vec<ContractEventInfo>>> {
+        // prefer the most selective index.
+        let (index_type, indexes): (EventIndexType, BTreeSet<Vec<u8>>) =
+            if !filter.event_keys.is_empty() {
+                (
+                    EventIndexType::EventKey,
+                    filter.event_keys.iter().map(event_key_index).collect(),
+                )
+            } else if !filter.type_tags.is_empty() {
+                (
+                    EventIndexType::TypeTag,
+                    filter.type_tags.iter().map(type_tag_index).collect(),
+                )
+            } else if !filter.addrs.is_empty() {
+                (
+                    EventIndexType::Address,
+                    filter.addrs.iter().map(address_index).collect(),
+                )
+            } else {
+                return Ok(None);
+            };
+        match self.storage.get_event_index_start()? {
+            Some(index_start) if index_start <= filter.from_block => {}
+            _ => return Ok(None),
+        }
+
+        let reverse = filter.reverse;
+        let chain_header = self.current_header();
+        let max_block_number = chain_header.number().min(filter.to_block);
+        if filter.from_block > max_block_number {
+            return Ok(Some(vec![]));
+        }
+        let num_leaves = self.txn_accumulator.num_leaves();
+
+        let mut event_with_infos = vec![];
+        // txn info and events of the last matched txn.
+        let mut last_txn: Option<(RichTransactionInfo, Vec<ContractEvent>)> = None;
+        let mut window_begin = filter.from_block;
+        let mut window_end = max_block_number;
+        loop {
+            // scan the index window by window, to respect the limit without loading all entries.
+            let (from_block, to_block) = if reverse {
+                (
+                    window_end
+                        .saturating_sub(EVENT_INDEX_SCAN_WINDOW - 1)
+                        .max(window_begin),
+                    window_end,
+                )
+            } else {
+                (
+                    window_begin,
+                    window_begin
+                        .saturating_add(EVENT_INDEX_SCAN_WINDOW - 1)
+                        .min(window_end),
+                )
+            };
+            let mut keys = vec![];
+            for index in indexes.iter() {
+                match self.storage.get_contract_event_index(
+                    index_type,
+                    index.as_slice(),
+                    from_block,
+                    to_block,
+                    reverse,
+                )? {
+                    Some(index_keys) => keys.extend(index_keys),
+                    None => return Ok(None),
+                }
+            }
+            keys.sort_by(|a, b| {
+                (a.block_number, a.transaction_global_index, a.event_index)
+                    .cmp(&(b.block_number, b.transaction_global_index, b.event_index))
+                    .then_with(|| a.txn_info_id.cmp(&b.txn_info_id))
+            });
+            keys.dedup_by(|a, b| {
+                a.transaction_global_index == b.transaction_global_index
+                    && a.event_index == b.event_index
+                    && a.txn_info_id == b.txn_info_id
+            });
+            if reverse {
+                keys.reverse();
+            }
+
+            for key in keys {
+                // skip the index entries of other chain branches.
+                if key.transaction_global_index >= num_leaves
+                    || self
+                        .txn_accumulator
+                        .get_leaf(key.transaction_global_index)?
+                        != Some(key.txn_info_id)
+                {
+                    continue;
+                }
+                let cached =
+                    matches!(&last_txn, Some((txn_info, _)) if txn_info.id() == key.txn_info_id);
+                if !cached {
+                    let txn_info = self
+                        .storage
+                        .get_transaction_info(key.txn_info_id)?
+                        .ok_or_else(|| {
+                            format_err!(
+                                "cannot find txn info with txn_info_id {} on main chain(head: {})",
+                                key.txn_info_id,
+                                chain_header.id()
+                            )
+                        })?;
+                    let events = self
+                        .storage
+                        .get_contract_events(key.txn_info_id)?
+                        .ok_or_else(|| {
+                            format_err!(
+                                "cannot find events of txn with txn_info_id {} on main chain(header: {})",
+                                key.txn_info_id,
+                                chain_header.id()
+                            )
+                        })?;
+                    last_txn = Some((txn_info, events));
+                }
+                let (txn_info, events) = last_txn.as_ref().expect("last txn should exist");
+                let event = events.get(key.event_index as usize).ok_or_else(|| {
+                    format_err!(
+                        "event index {} out of range, events len: {}",
+                        key.event_index,
+                        events.len()
+                    )
+                })?;
+                if !filter.matching(txn_info.block_number, event) {
+                    continue;
+                }
+                event_with_infos.push(ContractEventInfo {
+                    block_hash: txn_info.block_id(),
+                    block_number: txn_info.block_number,
+                    transaction_hash: txn_info.transaction_hash(),
+                    transaction_index: txn_info.transaction_index,
+                    transaction_global_index: txn_info.transaction_global_index,
+                    event_index: key.event_index,
+                    event: event.clone(),
+                });
+                if let Some(limit) = filter.limit {
+                    if event_with_infos.len() >= limit {
+                        return Ok(Some(event_with_infos));
+                    }
+                }
+            }
+
+            if reverse {
+                if from_block <= window_begin {
+                    break;
+                }
+                window_end = from_block - 1;
+            } else {
+                if to_block >= window_end {
+                    break;
+                }
+                window_begin = to_block + 1;
+            }
+        }
+        Ok(Some(event_with_infos))
+    }
+
+    fn filter_events_by_scan(&self, filter: Filter) -> Result<Vec<ContractEventInfo>> {
         let reverse = filter.reverse;
         let chain_header = self.current_header();
         let max_block_number = chain_header.number().min(filter.to_block);
@@ -1807,6 +1987,7 @@ impl ChainWriter for BlockChain {
     fn connect(&mut self, executed_block: ExecutedBlock) -> Result<ExecutedBlock> {
         let (block, block_info) = (executed_block.block(), executed_block.block_info());
         debug_assert!(block.header().parent_hash() == self.status.status.head().id());
+        self.storage.save_account_txn_index(block)?;
         //TODO try reuse accumulator and state db.
         let txn_accumulator_info = block_info.get_txn_accumulator_info();
         let block_accumulator_info = block_info.get_block_accumulator_info();
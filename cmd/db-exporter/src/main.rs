@@ -30,10 +30,12 @@ use starcoin_storage::{
     block_info::BlockInfoStore,
     cache_storage::CacheStorage,
     db_storage::DBStorage,
+    event_index::ContractEventIndexStore,
     storage::{ColumnFamilyName, InnerStore, StorageInstance, ValueCodec},
-    BlockStore, Storage, StorageVersion, Store, BLOCK_ACCUMULATOR_NODE_PREFIX_NAME,
-    BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME, BLOCK_PREFIX_NAME, FAILED_BLOCK_PREFIX_NAME,
-    STATE_NODE_PREFIX_NAME, STATE_NODE_PREFIX_NAME_PREV, TRANSACTION_ACCUMULATOR_NODE_PREFIX_NAME,
+    BlockStore, ContractEventStore, Storage, StorageVersion, Store,
+    BLOCK_ACCUMULATOR_NODE_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME,
+    BLOCK_PREFIX_NAME, FAILED_BLOCK_PREFIX_NAME, STATE_NODE_PREFIX_NAME,
+    STATE_NODE_PREFIX_NAME_PREV, TRANSACTION_ACCUMULATOR_NODE_PREFIX_NAME,
 };
 use starcoin_transaction_builder::{
     build_signed_empty_txn, create_signed_txn_with_association_account, DEFAULT_MAX_GAS_AMOUNT,
@@ -244,6 +246,8 @@ enum Cmd {
     ApplyBlockOutput(ApplyBlockOutputOptions),
     SaveStartupInfo(SaveStartupInfoOptions),
     TokenSupply(TokenSupplyOptions),
+    BackfillEventIndex(BackfillEventIndexOptions),
+    ExportChunkedSnapshot(ExportChunkedSnapshotOptions),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -535,6 +539,40 @@ pub struct TokenSupplyOptions {
     resource_type: StrView<StructTag>,
 }
 
+#[derive(Debug, Parser)]
+#[clap(
+    name = "backfill-event-index",
+    about = "index the events of history blocks, for db upgraded from old version"
+)]
+pub struct BackfillEventIndexOptions {
+    #[clap(long, short = 'n')]
+    /// Chain Network, like main, barnard
+    pub net: BuiltinNetworkID,
+    #[clap(long, short = 'o', parse(from_os_str))]
+    /// starcoin node db path. like ~/.starcoin/main
+    pub to_path: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "export-chunked-snapshot",
+    about = "export the verifiable snapshot for node --bootstrap-from-snapshot"
+)]
+pub struct ExportChunkedSnapshotOptions {
+    #[clap(long, short = 'n')]
+    /// Chain Network, like main, barnard
+    pub net: BuiltinNetworkID,
+    #[clap(long, short = 'o', parse(from_os_str))]
+    /// output dir, manifest.json and the chunks will write in output dir
+    pub output: PathBuf,
+    #[clap(long, short = 'i', parse(from_os_str))]
+    /// starcoin node db path. like ~/.starcoin/main
+    pub db_path: PathBuf,
+    #[clap(long, short = 'b')]
+    /// the pivot is the last block of the epoch before this block, default is 64 blocks behind the head
+    pub block_number: Option<BlockNumber>,
+}
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
     let opt = Opt::parse();
@@ -731,6 +769,19 @@ async fn main() -> anyhow::Result<()> {
             );
             return result;
         }
+        Cmd::BackfillEventIndex(option) => {
+            let result = backfill_event_index(option.to_path, option.net);
+            return result;
+        }
+        Cmd::ExportChunkedSnapshot(option) => {
+            let result = export_chunked_snapshot(
+                option.db_path,
+                option.output,
+                option.net,
+                option.block_number,
+            );
+            return result;
+        }
     }
     Ok(())
 }
@@ -2510,3 +2561,107 @@ fn token_supply(
     file.flush()?;
     Ok(())
 }
+
+pub fn backfill_event_index(to_dir: PathBuf, network: BuiltinNetworkID) -> anyhow::Result<()> {
+    ::starcoin_logger::init();
+    let net = ChainNetwork::new_builtin(network);
+    let db_storage = DBStorage::new(to_dir.join("starcoindb/db"), RocksdbConfig::default(), None)?;
+    let mut instance =
+        StorageInstance::new_cache_and_db_instance(CacheStorage::new(None), db_storage);
+    // make sure the event index column families exist.
+    instance.check_upgrade()?;
+    let storage = Arc::new(Storage::new(instance)?);
+    let (chain_info, _) = Genesis::init_and_check_storage(&net, storage.clone(), to_dir.as_ref())?;
+    let chain = BlockChain::new(
+        net.time_service(),
+        chain_info.head().id(),
+        storage.clone(),
+        None,
+    )
+    .expect("create block chain should success.");
+    let index_start = storage
+        .get_event_index_start()?
+        .unwrap_or_else(|| chain_info.head().number().saturating_add(1));
+    if index_start == 0 {
+        println!("event index is already complete");
+        return Ok(());
+    }
+
+    let start_time = SystemTime::now();
+    let bar = ProgressBar::new(index_start);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:100.cyan/blue} {percent}% {msg}"),
+    );
+    // index from high to low, so the index start can move down after every batch,
+    // and an interrupted backfill can continue.
+    for block_number in (0..index_start).rev() {
+        let block = chain
+            .get_block_by_number(block_number)?
+            .ok_or_else(|| format_err!("{} get block error", block_number))?;
+        for txn_info in storage.get_block_transaction_infos(block.id())? {
+            let events = storage
+                .get_contract_events(txn_info.id())?
+                .unwrap_or_default();
+            storage.save_contract_event_index(&txn_info, events.as_slice())?;
+        }
+        if block_number % BATCH_SIZE == 0 {
+            storage.save_event_index_start(block_number)?;
+        }
+        bar.set_message(format!("index block {}", block_number));
+        bar.inc(1);
+    }
+    bar.finish();
+    let use_time = SystemTime::now().duration_since(start_time)?;
+    println!("backfill event index use time: {:?}", use_time.as_secs());
+    Ok(())
+}
+
+pub fn export_chunked_snapshot(
+    from_dir: PathBuf,
+    output: PathBuf,
+    network: BuiltinNetworkID,
+    block_number: Option<BlockNumber>,
+) -> anyhow::Result<()> {
+    ::starcoin_logger::init();
+    let start_time = SystemTime::now();
+    let net = ChainNetwork::new_builtin(network);
+    let db_storage = DBStorage::open_with_cfs(
+        from_dir.join("starcoindb/db/starcoindb"),
+        StorageVersion::current_version()
+            .get_column_family_names()
+            .to_vec(),
+        true,
+        Default::default(),
+        None,
+    )?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        db_storage,
+    ))?);
+    let (chain_info, _) =
+        Genesis::init_and_check_storage(&net, storage.clone(), from_dir.as_ref())?;
+    let chain = BlockChain::new(
+        net.time_service(),
+        chain_info.head().id(),
+        storage.clone(),
+        None,
+    )?;
+    let checkpoint_id = starcoin_sync::snapshot::snapshot_checkpoint(&chain, block_number)?;
+    let manifest = starcoin_sync::snapshot::export_snapshot(
+        storage,
+        net.time_service(),
+        checkpoint_id,
+        output.as_path(),
+    )?;
+    println!(
+        "export snapshot of pivot block ({:?},{}) with {} chunks, bootstrap with --snapshot-trusted-pivot {:?}",
+        manifest.pivot.id,
+        manifest.pivot.number,
+        manifest.chunks.len(),
+        manifest.pivot.id
+    );
+    let use_time = SystemTime::now().duration_since(start_time)?;
+    println!("export chunked snapshot use time: {:?}", use_time.as_secs());
+    Ok(())
+}
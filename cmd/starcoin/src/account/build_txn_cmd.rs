@@ -0,0 +1,184 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::env::current_dir;
+use std::path::PathBuf;
+
+use anyhow::{ensure, format_err, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::hash::PlainCryptoHash;
+use starcoin_rpc_api::types::{FunctionIdView, RawUserTransactionView};
+use starcoin_types::transaction::{
+    parse_transaction_argument_advance, RawUserTransaction, TransactionArgument,
+};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::transaction::{ScriptFunction, TransactionPayload};
+use starcoin_vm_types::transaction_argument::convert_txn_args;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+
+#[derive(Debug, Parser)]
+#[clap(name = "build-txn")]
+/// Build a unsigned txn running a script function, and output the raw txn to file.
+/// In offline mode, the sequence_number, chain_id, gas price and expiration time should be provided,
+/// and the raw txn can be signed by `sign-txn-file` on a machine without network.
+pub struct BuildTxnOpt {
+    #[clap(short = 's', long)]
+    /// the sender address of the txn.
+    sender: AccountAddress,
+
+    #[clap(long = "function", name = "script-function")]
+    /// script function to execute, example: 0x1::TransferScripts::peer_to_peer_v2
+    script_function: FunctionIdView,
+
+    #[clap(
+    short = 't',
+    long = "type_tag",
+    name = "type-tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the script
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[clap(long = "arg", name = "transaction-args", parse(try_from_str = parse_transaction_argument_advance))]
+    /// args for the script.
+    args: Option<Vec<TransactionArgument>>,
+
+    #[clap(long = "offline")]
+    /// build the txn without fetching anything from node.
+    offline: bool,
+
+    #[clap(long = "sequence-number")]
+    /// the txn's sequence_number, required in offline mode, otherwise get from txpool or chain.
+    sequence_number: Option<u64>,
+
+    #[clap(long = "chain-id")]
+    /// the chain id of the txn, required in offline mode, otherwise use the id of current network.
+    chain_id: Option<ChainId>,
+
+    #[clap(long = "max-gas-amount")]
+    /// max gas used to execute the script
+    max_gas_amount: Option<u64>,
+
+    #[clap(long = "gas-unit-price", alias = "gas-price")]
+    /// gas price used to execute the script, required in offline mode.
+    gas_unit_price: Option<u64>,
+
+    #[clap(long = "expiration-timestamp-secs")]
+    /// the unix timestamp(in seconds) the txn expired, required in offline mode, otherwise default is one hour from now.
+    expiration_timestamp_secs: Option<u64>,
+
+    #[clap(long = "gas-token")]
+    /// token code of gas to pay, for example: 0x1::STC::STC, default is STC.
+    gas_token: Option<String>,
+
+    #[clap(short = 'o', long = "output", parse(from_os_str))]
+    /// file to save the raw txn, default is `<txn hash>.raw-txn` in current dir.
+    output_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildTxnData {
+    pub raw_txn: RawUserTransactionView,
+    pub output_file: PathBuf,
+}
+
+pub struct BuildTxnCommand;
+
+impl CommandAction for BuildTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = BuildTxnOpt;
+    type ReturnItem = BuildTxnData;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let state = ctx.state();
+        let script_function = opt.script_function.clone().0;
+        let payload = TransactionPayload::ScriptFunction(ScriptFunction::new(
+            script_function.module,
+            script_function.function,
+            opt.type_tags.clone().unwrap_or_default(),
+            convert_txn_args(&opt.args.clone().unwrap_or_default()),
+        ));
+
+        let (sequence_number, chain_id, gas_unit_price, expiration_timestamp_secs) = if opt.offline
+        {
+            let required = |value: Option<u64>, name: &str| {
+                value.ok_or_else(|| format_err!("--{} is required in offline mode", name))
+            };
+            (
+                required(opt.sequence_number, "sequence-number")?,
+                opt.chain_id
+                    .ok_or_else(|| format_err!("--chain-id is required in offline mode"))?,
+                required(opt.gas_unit_price, "gas-unit-price")?,
+                required(opt.expiration_timestamp_secs, "expiration-timestamp-secs")?,
+            )
+        } else {
+            let chain_id = state.net().chain_id();
+            if let Some(id) = opt.chain_id {
+                ensure!(
+                    id == chain_id,
+                    "The chain id {} mismatch with current network {}",
+                    id,
+                    chain_id
+                );
+            }
+            let sequence_number = match opt.sequence_number {
+                Some(sequence_number) => sequence_number,
+                None => state.next_sequence_number(opt.sender)?.0,
+            };
+            let expiration_timestamp_secs = match opt.expiration_timestamp_secs {
+                Some(timestamp) => timestamp,
+                None => {
+                    state.client().node_info()?.now_seconds + CliState::DEFAULT_EXPIRATION_TIME_SECS
+                }
+            };
+            (
+                sequence_number,
+                chain_id,
+                opt.gas_unit_price.unwrap_or(CliState::DEFAULT_GAS_PRICE),
+                expiration_timestamp_secs,
+            )
+        };
+
+        let raw_txn = RawUserTransaction::new(
+            opt.sender,
+            sequence_number,
+            payload,
+            opt.max_gas_amount
+                .unwrap_or(CliState::DEFAULT_MAX_GAS_AMOUNT),
+            gas_unit_price,
+            expiration_timestamp_secs,
+            chain_id,
+            opt.gas_token
+                .clone()
+                .unwrap_or_else(|| CliState::DEFAULT_GAS_TOKEN.to_string()),
+        );
+        let raw_txn_view = state.review_raw_txn(&raw_txn)?;
+
+        let output_file = match opt.output_file.clone() {
+            Some(output_file) => output_file,
+            None => {
+                let mut output_file = current_dir()?;
+                output_file.push(raw_txn.crypto_hash().to_hex());
+                output_file.set_extension("raw-txn");
+                output_file
+            }
+        };
+        std::fs::write(output_file.as_path(), bcs_ext::to_bytes(&raw_txn)?)?;
+        Ok(BuildTxnData {
+            raw_txn: raw_txn_view,
+            output_file,
+        })
+    }
+}
@@ -6,6 +6,7 @@ use crate::StarcoinOpt;
 use anyhow::Result;
 use clap::Parser;
 use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
 use starcoin_account_api::AccountInfo;
 
 /// Create a new account
@@ -14,6 +15,11 @@ use starcoin_account_api::AccountInfo;
 pub struct CreateOpt {
     #[clap(short = 'p')]
     password: String,
+
+    /// Create a hd wallet with a new mnemonic, and the first account derived from it.
+    /// Please back up the mnemonic, more accounts can be derived by `account derive`.
+    #[clap(long = "mnemonic")]
+    mnemonic: bool,
 }
 
 pub struct CreateCommand;
@@ -22,18 +28,39 @@ impl CommandAction for CreateCommand {
     type State = CliState;
     type GlobalOpt = StarcoinOpt;
     type Opt = CreateOpt;
-    type ReturnItem = AccountInfo;
+    type ReturnItem = CreateAccountData;
 
     fn run(
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
-    ) -> Result<AccountInfo> {
+    ) -> Result<CreateAccountData> {
         let account_client = ctx.state().account_client();
-        let account = account_client.create_account(ctx.opt().password.clone())?;
-        Ok(account)
+        let opt = ctx.opt();
+        if opt.mnemonic {
+            let mnemonic_account =
+                account_client.create_account_from_mnemonic(opt.password.clone())?;
+            Ok(CreateAccountData {
+                account: mnemonic_account.account,
+                mnemonic: Some(mnemonic_account.mnemonic),
+            })
+        } else {
+            let account = account_client.create_account(opt.password.clone())?;
+            Ok(CreateAccountData {
+                account,
+                mnemonic: None,
+            })
+        }
     }
 
     fn skip_history(&self, _ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>) -> bool {
         true
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAccountData {
+    #[serde(flatten)]
+    pub account: AccountInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mnemonic: Option<String>,
+}
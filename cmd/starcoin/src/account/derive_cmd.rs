@@ -0,0 +1,45 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+use starcoin_account_api::AccountInfo;
+
+/// Derive the account at index from the hd wallet of node wallet.
+#[derive(Debug, Parser)]
+#[clap(name = "derive")]
+pub struct DeriveOpt {
+    /// The password of the hd wallet, the derived account is encrypted by it too.
+    #[clap(short = 'p', default_value = "")]
+    password: String,
+
+    /// The account index in derivation path m/44'/101010'/{index}'/0'/0'.
+    #[clap(long = "index")]
+    index: u32,
+}
+
+pub struct DeriveCommand;
+
+impl CommandAction for DeriveCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = DeriveOpt;
+    type ReturnItem = AccountInfo;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt: &DeriveOpt = ctx.opt();
+        ctx.state()
+            .account_client()
+            .derive_account(opt.index, opt.password.clone())
+    }
+
+    fn skip_history(&self, _ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>) -> bool {
+        true
+    }
+}
@@ -8,10 +8,11 @@ use clap::Parser;
 use scmd::{CommandAction, ExecContext};
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_decrypt::keystore::{encrypt_keystore, KeystoreKdf};
 use starcoin_types::transaction::authenticator::AccountPrivateKey;
 use starcoin_vm_types::account_address::AccountAddress;
 use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Export account's private key.
 #[derive(Debug, Parser)]
@@ -23,6 +24,18 @@ pub struct ExportOpt {
     password: String,
     #[clap(short = 'o', parse(from_os_str))]
     output_file: Option<PathBuf>,
+
+    /// Export the private key to a web3 secret storage style json keystore file, instead of plain text.
+    #[clap(long = "keystore", parse(from_os_str), conflicts_with("output_file"))]
+    keystore: Option<PathBuf>,
+
+    /// The password to encrypt the keystore, default to the account password.
+    #[clap(long = "keystore-password")]
+    keystore_password: Option<String>,
+
+    /// The key derivation function of the keystore, scrypt or pbkdf2.
+    #[clap(long = "kdf", default_value = "scrypt")]
+    kdf: KeystoreKdf,
 }
 
 pub struct ExportCommand;
@@ -41,20 +54,37 @@ impl CommandAction for ExportCommand {
         let opt: &ExportOpt = ctx.opt();
         let data = client.export_account(opt.account_address, opt.password.clone())?;
         let private_key = AccountPrivateKey::try_from(data.as_slice())?;
+        if let Some(keystore_file) = &opt.keystore {
+            check_output_file(keystore_file)?;
+            let password = opt
+                .keystore_password
+                .as_ref()
+                .unwrap_or(&opt.password)
+                .as_bytes();
+            let keystore = encrypt_keystore(
+                password,
+                data.as_slice(),
+                Some(opt.account_address.to_string()),
+                opt.kdf,
+            )?;
+            std::fs::write(keystore_file, serde_json::to_string_pretty(&keystore)?)?;
+            eprintln!("keystore saved to {}", keystore_file.as_path().display());
+            return Ok(ExportData {
+                account: opt.account_address,
+                private_key: None,
+                keystore: Some(keystore_file.clone()),
+            });
+        }
         let encoded = private_key.to_encoded_string()?;
         if let Some(output_file) = &opt.output_file {
-            if output_file.exists() {
-                bail!(
-                    "the output_file {} is already exists, please change a name",
-                    output_file.display()
-                );
-            }
+            check_output_file(output_file)?;
             std::fs::write(output_file, encoded.clone())?;
             eprintln!("private key saved to {}", output_file.as_path().display());
         }
         Ok(ExportData {
             account: opt.account_address,
-            private_key: encoded,
+            private_key: Some(encoded),
+            keystore: None,
         })
     }
 
@@ -63,8 +93,21 @@ impl CommandAction for ExportCommand {
     }
 }
 
+fn check_output_file(output_file: &Path) -> Result<()> {
+    if output_file.exists() {
+        bail!(
+            "the output_file {} is already exists, please change a name",
+            output_file.display()
+        );
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct ExportData {
     pub account: AccountAddress,
-    pub private_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<PathBuf>,
 }
@@ -0,0 +1,54 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+use std::path::PathBuf;
+
+/// Export the mnemonic of the hd wallet.
+#[derive(Debug, Parser)]
+#[clap(name = "export-mnemonic")]
+pub struct ExportMnemonicOpt {
+    #[clap(short = 'p', default_value = "")]
+    password: String,
+    #[clap(short = 'o', parse(from_os_str))]
+    output_file: Option<PathBuf>,
+}
+
+pub struct ExportMnemonicCommand;
+
+impl CommandAction for ExportMnemonicCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ExportMnemonicOpt;
+    type ReturnItem = String;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt: &ExportMnemonicOpt = ctx.opt();
+        let mnemonic = ctx
+            .state()
+            .account_client()
+            .export_mnemonic(opt.password.clone())?;
+        if let Some(output_file) = &opt.output_file {
+            if output_file.exists() {
+                bail!(
+                    "the output_file {} is already exists, please change a name",
+                    output_file.display()
+                );
+            }
+            std::fs::write(output_file, mnemonic.clone())?;
+            eprintln!("mnemonic saved to {}", output_file.as_path().display());
+        }
+        Ok(mnemonic)
+    }
+
+    fn skip_history(&self, _ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>) -> bool {
+        true
+    }
+}
@@ -8,8 +8,11 @@ use clap::Parser;
 use scmd::{CommandAction, ExecContext};
 use starcoin_account_api::{AccountInfo, AccountPrivateKey};
 use starcoin_crypto::{ValidCryptoMaterial, ValidCryptoMaterialStringExt};
+use starcoin_decrypt::keystore::{decrypt_keystore, Keystore};
 use starcoin_vm_types::account_address::AccountAddress;
+use std::convert::TryFrom;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// Import account by private key to node wallet.
 #[derive(Debug, Parser)]
@@ -29,6 +32,18 @@ pub struct ImportOpt {
     )]
     from_file: Option<PathBuf>,
 
+    /// Import the private key from a web3 secret storage style json keystore file.
+    #[clap(
+        long = "keystore",
+        parse(from_os_str),
+        conflicts_with_all(&["input", "from_file"])
+    )]
+    keystore: Option<PathBuf>,
+
+    /// The password to decrypt the keystore, default to the account password.
+    #[clap(long = "keystore-password")]
+    keystore_password: Option<String>,
+
     /// if account_address is absent, generate address by public_key.
     #[clap(name = "account_address")]
     account_address: Option<AccountAddress>,
@@ -48,20 +63,52 @@ impl CommandAction for ImportCommand {
     ) -> Result<Self::ReturnItem> {
         let opt: &ImportOpt = ctx.opt();
         let client = ctx.state().account_client();
-        let private_key = match (opt.from_input.as_ref(), opt.from_file.as_ref()) {
-            (Some(p), _) => AccountPrivateKey::from_encoded_string(p)?,
-            (None, Some(p)) => {
+        let mut keystore_address = None;
+        let private_key = match (
+            opt.from_input.as_ref(),
+            opt.from_file.as_ref(),
+            opt.keystore.as_ref(),
+        ) {
+            (Some(p), _, _) => AccountPrivateKey::from_encoded_string(p)?,
+            (None, Some(p), _) => {
                 let data = std::fs::read_to_string(p)?.replace(['\n', '\r'], "");
                 AccountPrivateKey::from_encoded_string(data.as_str())?
             }
-            (None, None) => {
-                bail!("private key should be specified, use one of <input>, <from-file>")
+            (None, None, Some(p)) => {
+                let keystore: Keystore = serde_json::from_slice(&std::fs::read(p)?)?;
+                let password = opt
+                    .keystore_password
+                    .as_ref()
+                    .unwrap_or(&opt.password)
+                    .as_bytes();
+                let data = decrypt_keystore(password, &keystore)?;
+                keystore_address = keystore.address.clone();
+                AccountPrivateKey::try_from(data.as_slice())?
+            }
+            (None, None, None) => {
+                bail!(
+                    "private key should be specified, use one of <input>, <from-file>, <keystore>"
+                )
             }
         };
 
-        let address = opt
-            .account_address
-            .unwrap_or_else(|| private_key.public_key().derived_address());
+        let address = match opt.account_address {
+            Some(address) => address,
+            None => {
+                // the keystore address is not authenticated, derive it from the decrypted key.
+                let address = private_key.public_key().derived_address();
+                if let Some(keystore_address) = keystore_address {
+                    if AccountAddress::from_str(&keystore_address)? != address {
+                        bail!(
+                            "Keystore address {} mismatch with the address {} derived from the key, specify the account_address to import it",
+                            keystore_address,
+                            address
+                        );
+                    }
+                }
+                address
+            }
+        };
         let account = client.import_account(
             address,
             private_key.to_bytes().to_vec(),
@@ -0,0 +1,58 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+use starcoin_account_api::AccountInfo;
+use std::path::PathBuf;
+
+/// Restore the hd wallet by mnemonic to node wallet, and import the first account derived from it.
+#[derive(Debug, Parser)]
+#[clap(name = "import-mnemonic")]
+pub struct ImportMnemonicOpt {
+    #[clap(short = 'p', default_value = "")]
+    password: String,
+
+    #[clap(name = "input", short = 'i', help = "input of mnemonic")]
+    from_input: Option<String>,
+
+    #[clap(
+        short = 'f',
+        help = "file path of mnemonic",
+        parse(from_os_str),
+        conflicts_with("input")
+    )]
+    from_file: Option<PathBuf>,
+}
+
+pub struct ImportMnemonicCommand;
+
+impl CommandAction for ImportMnemonicCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ImportMnemonicOpt;
+    type ReturnItem = AccountInfo;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt: &ImportMnemonicOpt = ctx.opt();
+        let client = ctx.state().account_client();
+        let mnemonic = match (opt.from_input.as_ref(), opt.from_file.as_ref()) {
+            (Some(m), _) => m.clone(),
+            (None, Some(p)) => std::fs::read_to_string(p)?,
+            (None, None) => {
+                bail!("mnemonic should be specified, use one of <input>, <from-file>")
+            }
+        };
+        client.import_mnemonic(mnemonic, opt.password.clone())
+    }
+
+    fn skip_history(&self, _ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>) -> bool {
+        true
+    }
+}
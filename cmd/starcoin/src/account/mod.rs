@@ -19,15 +19,19 @@ pub use unlock_cmd::*;
 pub use verify_sign_cmd::*;
 
 mod accept_token_cmd;
+pub mod build_txn_cmd;
 mod change_password_cmd;
 mod create_cmd;
 mod default_cmd;
 mod derive_account_address_cmd;
+pub mod derive_cmd;
 mod execute_script_cmd;
 mod execute_script_function_cmd;
 mod export_cmd;
+pub mod export_mnemonic_cmd;
 pub mod generate_keypair;
 mod import_cmd;
+pub mod import_mnemonic_cmd;
 pub mod import_multisig_cmd;
 pub mod import_readonly_cmd;
 mod list_cmd;
@@ -39,6 +43,7 @@ pub mod rotate_auth_key_cmd;
 mod show_cmd;
 mod sign_cmd;
 pub mod sign_multisig_txn_cmd;
+pub mod sign_txn_file_cmd;
 pub mod submit_txn_cmd;
 mod transfer_cmd;
 mod unlock_cmd;
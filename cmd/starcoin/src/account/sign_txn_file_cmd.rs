@@ -0,0 +1,88 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use scmd::{CommandAction, ExecContext};
+use starcoin_account_api::AccountPublicKey;
+use starcoin_crypto::HashValue;
+use starcoin_vm_types::transaction::RawUserTransaction;
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+
+#[derive(Debug, Parser)]
+#[clap(name = "sign-txn-file")]
+/// Sign the raw txn file generated by `build-txn`, and output the signed txn to file.
+/// The signed txn file can be submitted by `submit-txn`.
+pub struct SignTxnFileOpt {
+    #[clap(name = "raw-txn-file", parse(from_os_str))]
+    /// the raw txn file generated by `build-txn`.
+    raw_txn_file: PathBuf,
+
+    #[clap(short = 'o', long = "output", parse(from_os_str))]
+    /// file to save the signed txn, default is `<txn id>.signed-txn` in the dir of raw txn file.
+    output_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignTxnFileData {
+    pub txn_id: HashValue,
+    pub output_file: PathBuf,
+}
+
+pub struct SignTxnFileCommand;
+
+impl CommandAction for SignTxnFileCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SignTxnFileOpt;
+    type ReturnItem = SignTxnFileData;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let state = ctx.state();
+        let raw_txn: RawUserTransaction =
+            bcs_ext::from_bytes(&std::fs::read(opt.raw_txn_file.as_path())?)?;
+        let chain_id = state.net().chain_id();
+        ensure!(
+            raw_txn.chain_id() == chain_id,
+            "The chain id {} of txn mismatch with current network {}",
+            raw_txn.chain_id(),
+            chain_id
+        );
+        state.review_raw_txn(&raw_txn)?;
+
+        let sender = state.get_account(raw_txn.sender())?;
+        if let AccountPublicKey::Multi(_) = sender.public_key {
+            bail!(
+                "sender {} is a multisig address, please use `sign-multisig-txn` instead",
+                sender.address
+            );
+        }
+        let signed_txn = state.account_client().sign_txn(raw_txn, sender.address)?;
+        let txn_id = signed_txn.id();
+
+        let output_file = match opt.output_file.clone() {
+            Some(output_file) => output_file,
+            None => {
+                let mut output_file = opt.raw_txn_file.clone();
+                output_file.set_file_name(txn_id.to_hex());
+                output_file.set_extension("signed-txn");
+                output_file
+            }
+        };
+        std::fs::write(output_file.as_path(), bcs_ext::to_bytes(&signed_txn)?)?;
+        Ok(SignTxnFileData {
+            txn_id,
+            output_file,
+        })
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::types::TransactionInfoView;
+use starcoin_vm_types::account_address::AccountAddress;
+
+/// List the transactions sent by an account, require the node to enable the account transaction index.
+#[derive(Debug, Parser)]
+#[clap(name = "list-txn", alias = "list_txn")]
+pub struct ListTransactionOpt {
+    /// The sender address of transactions.
+    #[clap(name = "sender", long)]
+    sender: AccountAddress,
+
+    /// The sequence number for start scan, if absent, start from the first transaction, or the last one if reverse.
+    #[clap(name = "start-seq", alias = "start_seq", long, short = 's')]
+    start_seq: Option<u64>,
+
+    #[clap(name = "reverse", long, short = 'r')]
+    reverse: Option<bool>,
+
+    #[clap(name = "count", long, short = 'c', default_value = "32")]
+    count: u64,
+}
+
+pub struct ListTransactionCommand;
+
+impl CommandAction for ListTransactionCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ListTransactionOpt;
+    type ReturnItem = Vec<TransactionInfoView>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let opt = ctx.opt();
+        let txn_infos = client.chain_get_transactions_by_sender(
+            opt.sender,
+            opt.start_seq,
+            opt.reverse.unwrap_or(false),
+            opt.count,
+        )?;
+        Ok(txn_infos)
+    }
+}
@@ -12,6 +12,7 @@ mod get_txn_infos_cmd;
 pub mod get_txn_proof_cmd;
 mod info_cmd;
 mod list_block_cmd;
+mod list_txn_cmd;
 
 pub use epoch_info::*;
 pub use get_block_cmd::*;
@@ -23,3 +24,4 @@ pub use get_txn_info_list_cmd::*;
 pub use get_txn_infos_cmd::*;
 pub use info_cmd::*;
 pub use list_block_cmd::*;
+pub use list_txn_cmd::*;
@@ -233,21 +233,7 @@ impl CliState {
         let sender = self.get_account_or_default(sender)?;
         let (sequence_number, future_transaction) = match sequence_number {
             Some(sequence_number) => (sequence_number, false),
-            None => match self.client.next_sequence_number_in_txpool(sender.address)? {
-                Some(sequence_number) => {
-                    eprintln!("get sequence_number {} from txpool", sequence_number);
-                    (sequence_number, true)
-                }
-                None => self
-                    .get_account_resource(*sender.address())?
-                    .map(|account| (account.sequence_number(), false))
-                    .ok_or_else(|| {
-                        format_err!(
-                            "Can not find account on chain by address:{}",
-                            sender.address()
-                        )
-                    })?,
-            },
+            None => self.next_sequence_number(sender.address)?,
         };
         let node_info = self.client.node_info()?;
         let expiration_timestamp_secs = expiration_time_secs
@@ -269,6 +255,21 @@ impl CliState {
         ))
     }
 
+    /// Get the next sequence_number of `sender` from txpool or chain state,
+    /// return true if there is pending transaction of `sender` in the txpool.
+    pub fn next_sequence_number(&self, sender: AccountAddress) -> Result<(u64, bool)> {
+        match self.client.next_sequence_number_in_txpool(sender)? {
+            Some(sequence_number) => {
+                eprintln!("get sequence_number {} from txpool", sequence_number);
+                Ok((sequence_number, true))
+            }
+            None => self
+                .get_account_resource(sender)?
+                .map(|account| (account.sequence_number(), false))
+                .ok_or_else(|| format_err!("Can not find account on chain by address:{}", sender)),
+        }
+    }
+
     pub fn dry_run_transaction(&self, txn: DryRunTransaction) -> Result<DryRunOutputView> {
         let state_reader = self.client().state_reader(StateRootOption::Latest)?;
         playground::dry_run_explain(&state_reader, txn, None)
@@ -359,6 +360,22 @@ impl CliState {
         decode_txn_payload(&chain_state_reader, payload)
     }
 
+    /// Print the raw txn with abi decoded payload to stderr for review.
+    /// The payload may not be decoded if the modules it used are absent in the connected node, such as on an offline node.
+    pub fn review_raw_txn(&self, raw_txn: &RawUserTransaction) -> Result<RawUserTransactionView> {
+        let mut raw_txn_view: RawUserTransactionView = raw_txn.clone().try_into()?;
+        match self.decode_txn_payload(raw_txn.payload()) {
+            Ok(decoded_payload) => raw_txn_view.decoded_payload = Some(decoded_payload.into()),
+            Err(e) => eprintln!("WARNING: can not decode the txn payload: {}", e),
+        }
+        // Use `eprintln` instead of `println`, for keep the cli stdout's format(such as json) is not broken by print.
+        eprintln!(
+            "Review the transaction: \n {}",
+            serde_json::to_string_pretty(&raw_txn_view)?
+        );
+        Ok(raw_txn_view)
+    }
+
     pub fn into_inner(self) -> (ChainNetworkID, Arc<RpcClient>, Option<NodeHandle>) {
         (self.net, self.client, self.node_handle)
     }
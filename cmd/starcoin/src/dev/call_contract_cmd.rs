@@ -3,11 +3,17 @@
 
 use crate::cli_state::CliState;
 use crate::StarcoinOpt;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use scmd::{CommandAction, ExecContext};
 use starcoin_abi_decoder::DecodedMoveValue;
-use starcoin_rpc_api::types::{ContractCall, FunctionIdView, TransactionArgumentView, TypeTagView};
+use starcoin_rpc_api::types::{
+    ArgumentsView, ByteCodeOrScriptFunction, ContractCall, DryRunTransactionRequest,
+    FunctionIdView, ScriptData, StrView, TransactionArgumentView, TransactionRequest,
+    TransactionStatusView, TypeTagView,
+};
+use starcoin_vm_types::account_address::AccountAddress;
+use std::path::PathBuf;
 
 /// Call Contract command
 ///  Some examples:
@@ -16,6 +22,8 @@ use starcoin_rpc_api::types::{ContractCall, FunctionIdView, TransactionArgumentV
 ///  dev call --function 0x1::Block::current_block_number
 ///  # 0x1::Account::balance<0x1::STC::STC>(0x726098b70ba8aa2cc172af19af8804)
 ///  dev call --function 0x1::Account::balance -t 0x1::STC::STC --arg 0x726098b70ba8aa2cc172af19af8804
+///  # profile the gas of an entry function, the output can be rendered by `flamegraph.pl gas.folded > gas.svg`
+///  dev call --function 0x1::TransferScripts::peer_to_peer_v2 -t 0x1::STC::STC --arg 0x726098b70ba8aa2cc172af19af8804 --arg 1000u128 --trace gas.folded
 ///  ```
 #[derive(Debug, Parser)]
 #[clap(name = "call")]
@@ -37,6 +45,14 @@ pub struct CallContractOpt {
         help = "can specify multi arg"
     )]
     args: Option<Vec<TransactionArgumentView>>,
+
+    #[clap(long = "trace", name = "trace-file")]
+    /// dry run the entry function as a transaction, and write the gas charged by the call stacks to the file in folded stacks format.
+    trace: Option<PathBuf>,
+
+    #[clap(short = 's', long)]
+    /// the sender of the traced transaction, default is the default account.
+    sender: Option<AccountAddress>,
 }
 
 pub struct CallContractCommand;
@@ -53,6 +69,40 @@ impl CommandAction for CallContractCommand {
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
 
+        if let Some(trace_file) = opt.trace.as_ref() {
+            let sender = ctx.state().get_account_or_default(opt.sender)?;
+            let request = DryRunTransactionRequest {
+                transaction: TransactionRequest {
+                    sender: Some(sender.address),
+                    script: Some(ScriptData {
+                        code: StrView(ByteCodeOrScriptFunction::ScriptFunction(
+                            opt.function.0.clone(),
+                        )),
+                        type_args: opt.type_tags.clone().unwrap_or_default(),
+                        args: ArgumentsView::HumanReadable(opt.args.clone().unwrap_or_default()),
+                    }),
+                    ..Default::default()
+                },
+                sender_public_key: StrView(sender.public_key),
+                trace: Some(true),
+            };
+            let output = ctx.state().client().dry_run(request)?;
+            let trace = match output.trace {
+                Some(trace) => trace,
+                None => bail!("The dry run output has no trace: {:?}", output.txn_output),
+            };
+            std::fs::write(trace_file, trace.to_folded_stacks())?;
+            if !matches!(output.txn_output.status, TransactionStatusView::Executed) {
+                eprintln!("txn dry run failed: {:?}", output.explained_status);
+            }
+            eprintln!(
+                "gas used {}, trace is written to {}",
+                output.txn_output.gas_used.0,
+                trace_file.display()
+            );
+            return Ok(vec![]);
+        }
+
         let call = ContractCall {
             function_id: opt.function.clone(),
             type_args: opt.type_tags.clone().unwrap_or_default(),
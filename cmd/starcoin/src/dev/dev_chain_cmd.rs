@@ -0,0 +1,132 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::HashValue;
+
+/// Mine blocks immediately, the empty block is also mined, only available in dev chain.
+#[derive(Debug, Parser)]
+#[clap(name = "mine")]
+pub struct MineOpt {
+    #[clap(short = 'n', long = "number", default_value = "1")]
+    /// the number of blocks to mine.
+    number: u64,
+}
+
+pub struct MineCommand;
+
+impl CommandAction for MineCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = MineOpt;
+    type ReturnItem = Vec<HashValue>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        ctx.state().client().dev_mine(ctx.opt().number)
+    }
+}
+
+/// Increase the node time, only available in dev chain.
+#[derive(Debug, Parser)]
+#[clap(name = "increase-time")]
+pub struct IncreaseTimeOpt {
+    #[clap(name = "millis")]
+    /// the time to increase, in milliseconds.
+    millis: u64,
+}
+
+pub struct IncreaseTimeCommand;
+
+impl CommandAction for IncreaseTimeCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = IncreaseTimeOpt;
+    type ReturnItem = u64;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        ctx.state().client().dev_increase_time(ctx.opt().millis)
+    }
+}
+
+/// Set the timestamp of the next block, only available in dev chain.
+#[derive(Debug, Parser)]
+#[clap(name = "set-next-block-timestamp")]
+pub struct SetNextBlockTimestampOpt {
+    #[clap(name = "timestamp")]
+    /// the timestamp in milliseconds.
+    timestamp: u64,
+}
+
+pub struct SetNextBlockTimestampCommand;
+
+impl CommandAction for SetNextBlockTimestampCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SetNextBlockTimestampOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        ctx.state()
+            .client()
+            .dev_set_next_block_timestamp(ctx.opt().timestamp)
+    }
+}
+
+/// Take a snapshot of the chain head and node time, only available in dev chain.
+#[derive(Debug, Parser)]
+#[clap(name = "snapshot")]
+pub struct SnapshotOpt {}
+
+pub struct SnapshotCommand;
+
+impl CommandAction for SnapshotCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SnapshotOpt;
+    type ReturnItem = u64;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        ctx.state().client().dev_snapshot()
+    }
+}
+
+/// Revert the chain head and node time to a snapshot, only available in dev chain.
+#[derive(Debug, Parser)]
+#[clap(name = "revert")]
+pub struct RevertOpt {
+    #[clap(name = "snapshot-id")]
+    /// the snapshot id returned by `dev snapshot`.
+    snapshot_id: u64,
+}
+
+pub struct RevertCommand;
+
+impl CommandAction for RevertCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = RevertOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        ctx.state().client().dev_revert(ctx.opt().snapshot_id)
+    }
+}
@@ -19,6 +19,7 @@ mod call_contract_cmd;
 mod compile_cmd;
 mod concurrency_level_cmd;
 mod deploy_cmd;
+pub(crate) mod dev_chain_cmd;
 pub mod dev_helper;
 pub(crate) mod gen_block_cmd;
 mod get_coin_cmd;
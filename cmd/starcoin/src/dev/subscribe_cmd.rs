@@ -4,7 +4,7 @@ use anyhow::Result;
 use clap::Parser;
 use futures::{TryStream, TryStreamExt};
 use scmd::{CommandAction, ExecContext};
-use starcoin_rpc_api::types::pubsub::EventFilter;
+use starcoin_rpc_api::types::pubsub::{EventCursor, EventFilter, EventParams};
 use starcoin_rpc_api::types::TypeTagView;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::event::EventKey;
@@ -43,6 +43,15 @@ pub struct SubscribeEventOpt {
     #[clap(long = "decode")]
     /// whether decode event
     decode: bool,
+    #[clap(long = "confirmations")]
+    /// notify the events of a block after it is `confirmations` blocks deep in the main chain
+    confirmations: Option<u64>,
+    #[clap(long = "replay")]
+    /// replay the matched events from `from_block` before the new events
+    replay: bool,
+    #[clap(long = "cursor", parse(try_from_str = serde_json::from_str))]
+    /// resume from the cursor of the last received event, as json, implies `--replay`
+    cursor: Option<EventCursor>,
 }
 
 pub struct SubscribeEventCommand;
@@ -64,10 +73,13 @@ impl CommandAction for SubscribeEventCommand {
             limit: ctx.opt().limit,
         };
 
-        let event_stream = ctx
-            .state()
-            .client()
-            .subscribe_events(filter, ctx.opt().decode)?;
+        let event_stream = ctx.state().client().subscribe_events(EventParams {
+            filter,
+            decode: ctx.opt().decode,
+            confirmations: ctx.opt().confirmations,
+            replay: ctx.opt().replay,
+            cursor: ctx.opt().cursor,
+        })?;
         println!("Subscribe successful, Press `q` and Enter to quit");
         blocking_display_notification(event_stream, |evt| {
             serde_json::to_string(&evt).expect("should never fail")
@@ -79,7 +91,11 @@ impl CommandAction for SubscribeEventCommand {
 
 #[derive(Debug, Parser)]
 #[clap(name = "new_block")]
-pub struct SubscribeBlockOpt {}
+pub struct SubscribeBlockOpt {
+    #[clap(long = "confirmations")]
+    /// notify a block after it is `confirmations` blocks deep in the main chain
+    confirmations: Option<u64>,
+}
 pub struct SubscribeBlockCommand;
 impl CommandAction for SubscribeBlockCommand {
     type State = CliState;
@@ -90,7 +106,10 @@ impl CommandAction for SubscribeBlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let event_stream = ctx.state().client().subscribe_new_blocks()?;
+        let event_stream = ctx
+            .state()
+            .client()
+            .subscribe_new_blocks(ctx.opt().confirmations)?;
         println!("Subscribe successful, Press `q` and Enter to quit");
         blocking_display_notification(event_stream, |evt| {
             serde_json::to_string(&evt).expect("should never fail")
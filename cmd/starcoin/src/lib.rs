@@ -38,9 +38,14 @@ pub fn add_command(
                 .subcommand(account::ExportCommand)
                 .subcommand(account::ImportCommand)
                 .subcommand(account::import_readonly_cmd::ImportReadonlyCommand)
+                .subcommand(account::import_mnemonic_cmd::ImportMnemonicCommand)
+                .subcommand(account::export_mnemonic_cmd::ExportMnemonicCommand)
+                .subcommand(account::derive_cmd::DeriveCommand)
                 .subcommand(account::ExecuteScriptFunctionCmd)
                 .subcommand(account::ExecuteScriptCommand)
                 .subcommand(account::sign_multisig_txn_cmd::GenerateMultisigTxnCommand)
+                .subcommand(account::build_txn_cmd::BuildTxnCommand)
+                .subcommand(account::sign_txn_file_cmd::SignTxnFileCommand)
                 .subcommand(account::submit_txn_cmd::SubmitSignedTxnCommand)
                 .subcommand(account::SignMessageCmd)
                 .subcommand(account::VerifySignMessageCmd)
@@ -101,6 +106,7 @@ pub fn add_command(
                 .subcommand(chain::GetEventsCommand)
                 .subcommand(chain::EpochInfoCommand)
                 .subcommand(chain::GetTransactionInfoListCommand)
+                .subcommand(chain::ListTransactionCommand)
                 .subcommand(chain::get_txn_proof_cmd::GetTransactionProofCommand)
                 .subcommand(chain::GetBlockInfoCommand),
         )
@@ -108,7 +114,8 @@ pub fn add_command(
             CustomCommand::with_name("txpool")
                 .subcommand(txpool::PendingTxnCommand)
                 .subcommand(txpool::PendingTxnsCommand)
-                .subcommand(txpool::TxPoolStatusCommand),
+                .subcommand(txpool::TxPoolStatusCommand)
+                .subcommand(txpool::CancelTxnCommand),
         )
         .command(
             CustomCommand::with_name("dev")
@@ -142,6 +149,11 @@ pub fn add_command(
                 .subcommand(dev::panic_cmd::PanicCommand)
                 .subcommand(dev::sleep_cmd::SleepCommand)
                 .subcommand(dev::gen_block_cmd::GenBlockCommand)
+                .subcommand(dev::dev_chain_cmd::MineCommand)
+                .subcommand(dev::dev_chain_cmd::IncreaseTimeCommand)
+                .subcommand(dev::dev_chain_cmd::SetNextBlockTimestampCommand)
+                .subcommand(dev::dev_chain_cmd::SnapshotCommand)
+                .subcommand(dev::dev_chain_cmd::RevertCommand)
                 .subcommand(dev::SetConcurrencyLevelCommand)
                 .subcommand(dev::GetConcurrencyLevelCommand),
         )
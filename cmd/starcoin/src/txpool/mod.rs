@@ -2,14 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::cli_state::CliState;
+use crate::view::{ExecuteResultView, TransactionOptions};
 use crate::StarcoinOpt;
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use clap::Parser;
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::HashValue;
 use starcoin_rpc_api::types::SignedUserTransactionView;
+use starcoin_transaction_builder::encode_transfer_script_by_token_code;
 use starcoin_txpool_api::TxPoolStatus;
 use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::token::stc::G_STC_TOKEN_CODE;
+use starcoin_vm_types::transaction::TransactionPayload;
 
 /// Get txn data by its hash
 #[derive(Debug, Parser)]
@@ -88,3 +92,69 @@ impl CommandAction for TxPoolStatusCommand {
         client.txpool_status()
     }
 }
+
+/// Cancel a pending txn by replacing it with a zero amount transfer to the sender self,
+/// the replacement has the same sequence number and a higher gas price.
+#[derive(Debug, Parser)]
+#[clap(name = "cancel")]
+pub struct CancelTxnOpt {
+    #[clap(name = "sender", help = "sender of the pending txn")]
+    sender: AccountAddress,
+    #[clap(name = "sequence-number", help = "sequence number of the pending txn")]
+    sequence_number: u64,
+    #[clap(
+        long = "gas-unit-price",
+        alias = "gas-price",
+        name = "price of gas unit"
+    )]
+    /// gas price of the replacement, default to twice the gas price of the pending txn.
+    gas_unit_price: Option<u64>,
+    #[clap(short = 'b', name = "blocking-mode", long = "blocking")]
+    /// blocking wait the replacement txn mined
+    blocking: bool,
+}
+
+pub struct CancelTxnCommand;
+
+impl CommandAction for CancelTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = CancelTxnOpt;
+    type ReturnItem = ExecuteResultView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let pending_txn = client
+            .get_pending_txns_of_sender(opt.sender, None)?
+            .into_iter()
+            .find(|txn| txn.raw_txn.sequence_number.0 == opt.sequence_number)
+            .ok_or_else(|| {
+                format_err!(
+                    "Can not find pending txn of sender {} with sequence number {} in txpool.",
+                    opt.sender,
+                    opt.sequence_number
+                )
+            })?;
+        let gas_unit_price = opt
+            .gas_unit_price
+            .unwrap_or_else(|| pending_txn.raw_txn.gas_unit_price.0.saturating_mul(2));
+        let script_function =
+            encode_transfer_script_by_token_code(opt.sender, 0, G_STC_TOKEN_CODE.clone());
+        let txn_opts = TransactionOptions {
+            sender: Some(opt.sender),
+            sequence_number: Some(opt.sequence_number),
+            gas_unit_price: Some(gas_unit_price),
+            blocking: opt.blocking,
+            gas_token: Some(pending_txn.raw_txn.gas_token_code),
+            ..Default::default()
+        };
+        ctx.state().build_and_execute_transaction(
+            txn_opts,
+            TransactionPayload::ScriptFunction(script_function),
+        )
+    }
+}
@@ -19,6 +19,8 @@ where
 {
     global_limiter: DirectRateLimiter,
     user_limiter: KeyedRateLimiter<User>,
+    /// limiters of the users with custom quota.
+    custom_user_limiters: HashMap<User, DirectRateLimiter>,
 }
 
 impl<User> ApiLimiter<User>
@@ -29,15 +31,28 @@ where
         Self {
             global_limiter: DirectRateLimiter::direct(global_quota),
             user_limiter: KeyedRateLimiter::keyed(user_quota),
+            custom_user_limiters: HashMap::new(),
         }
     }
 
+    /// Replace the user quota of the given users.
+    pub fn with_custom_user_quotas(mut self, user_quotas: &HashMap<User, Quota>) -> Self {
+        self.custom_user_limiters = user_quotas
+            .iter()
+            .map(|(user, quota)| (user.clone(), DirectRateLimiter::direct(*quota)))
+            .collect();
+        self
+    }
+
     pub fn check(
         &self,
         user: Option<&User>,
     ) -> Result<(), NotUntil<<DefaultClock as Clock>::Instant>> {
         if let Some(u) = user {
-            self.user_limiter.check_key(u)?;
+            match self.custom_user_limiters.get(u) {
+                Some(limiter) => limiter.check()?,
+                None => self.user_limiter.check_key(u)?,
+            }
         }
         self.global_limiter.check()?;
         Ok(())
@@ -58,6 +73,8 @@ where
     default_user_api_quota: Quota,
     /// custom user quota when calling a api.
     custom_user_api_quotas: HashMap<ApiName, Quota>,
+    /// quota of the specific users when calling a api, override the user api quotas.
+    custom_user_quotas: HashMap<User, Quota>,
 
     limiters: DashMap<ApiName, ApiLimiter<User>>,
 }
@@ -78,10 +95,17 @@ where
             custom_global_api_quotas,
             default_user_api_quota,
             custom_user_api_quotas,
+            custom_user_quotas: HashMap::new(),
             limiters: Default::default(),
         }
     }
 
+    /// Set the quota of the specific users when calling a api.
+    pub fn with_custom_user_quotas(mut self, custom_user_quotas: HashMap<User, Quota>) -> Self {
+        self.custom_user_quotas = custom_user_quotas;
+        self
+    }
+
     pub fn check(&self, api: &ApiName, user: Option<&User>) -> Result<(), anyhow::Error> {
         let elem = match self.limiters.entry(api.clone()) {
             Entry::Occupied(o) => o.into_ref(),
@@ -105,7 +129,7 @@ where
             .get(api)
             .cloned()
             .unwrap_or(self.default_user_api_quota);
-        ApiLimiter::new(global_quota, user_quota)
+        ApiLimiter::new(global_quota, user_quota).with_custom_user_quotas(&self.custom_user_quotas)
     }
 }
 
@@ -135,4 +159,22 @@ mod tests {
         let result = limiter.check(Some(&"abc".to_string()));
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_custom_user_limit() {
+        let global_quota = Quota::per_second(unsafe { NonZeroU32::new_unchecked(10) });
+        let user_quota = Quota::per_second(unsafe { NonZeroU32::new_unchecked(1) });
+        let custom_user_quota = Quota::per_second(unsafe { NonZeroU32::new_unchecked(3) });
+        let limiter = ApiLimiter::<String>::new(global_quota, user_quota).with_custom_user_quotas(
+            &vec![("abc".to_string(), custom_user_quota)]
+                .into_iter()
+                .collect(),
+        );
+        for _i in 0..3 {
+            assert!(limiter.check(Some(&"abc".to_string())).is_ok());
+        }
+        assert!(limiter.check(Some(&"abc".to_string())).is_err());
+        assert!(limiter.check(Some(&"abcd".to_string())).is_ok());
+        assert!(limiter.check(Some(&"abcd".to_string())).is_err());
+    }
 }
@@ -0,0 +1,414 @@
+//! Web3 secret storage (version 3) style keystore, the secret is encrypted by aes-128-ctr
+//! with a key derived by scrypt or pbkdf2, and authenticated by keccak256 mac.
+
+use crate::PBKDF2_SALT_SIZE;
+use aes::Aes128;
+use anyhow::{bail, ensure, format_err, Result};
+use ctr::cipher::{NewCipher, StreamCipher};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::str::FromStr;
+use subtle::ConstantTimeEq;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+pub const KEYSTORE_VERSION: u32 = 3;
+pub const SCRYPT_DEFAULT_LOG_N: u8 = 18;
+pub const SCRYPT_DEFAULT_R: u32 = 8;
+pub const SCRYPT_DEFAULT_P: u32 = 1;
+pub const KEYSTORE_PBKDF2_DEFAULT_ITERATIONS: u32 = 262144;
+/// The kdf params are read from an untrusted keystore file, bound the memory and time to derive
+/// the key before the mac is checked.
+pub const SCRYPT_MAX_LOG_N: u8 = 20;
+pub const SCRYPT_MAX_R_P: u32 = 64;
+const SCRYPT_MAX_MEMORY: u64 = 1 << 30;
+pub const KEYSTORE_PBKDF2_MAX_ITERATIONS: u32 = 10_000_000;
+const KEYSTORE_CIPHER: &str = "aes-128-ctr";
+const KEYSTORE_PRF: &str = "hmac-sha256";
+const KEYSTORE_DKLEN: u32 = 32;
+const KEYSTORE_IV_SIZE: usize = 16;
+
+/// The key derivation function used to encrypt a keystore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeystoreKdf {
+    Scrypt { log_n: u8, r: u32, p: u32 },
+    Pbkdf2 { c: u32 },
+}
+
+impl KeystoreKdf {
+    pub fn scrypt() -> Self {
+        KeystoreKdf::Scrypt {
+            log_n: SCRYPT_DEFAULT_LOG_N,
+            r: SCRYPT_DEFAULT_R,
+            p: SCRYPT_DEFAULT_P,
+        }
+    }
+
+    pub fn pbkdf2() -> Self {
+        KeystoreKdf::Pbkdf2 {
+            c: KEYSTORE_PBKDF2_DEFAULT_ITERATIONS,
+        }
+    }
+}
+
+impl Default for KeystoreKdf {
+    fn default() -> Self {
+        Self::scrypt()
+    }
+}
+
+impl FromStr for KeystoreKdf {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "scrypt" => Ok(Self::scrypt()),
+            "pbkdf2" => Ok(Self::pbkdf2()),
+            _ => bail!("Unsupported keystore kdf: {}, expect scrypt or pbkdf2", s),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub crypto: KeystoreCrypto,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    pub ciphertext: String,
+    pub kdf: String,
+    pub kdfparams: KdfParams,
+    pub mac: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub iv: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KdfParams {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        c: u32,
+        dklen: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+impl KdfParams {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KdfParams::Scrypt { .. } => "scrypt",
+            KdfParams::Pbkdf2 { .. } => "pbkdf2",
+        }
+    }
+
+    fn derive_key(&self, password: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                ensure!(
+                    *dklen == KEYSTORE_DKLEN,
+                    "Invalid keystore dklen: {}",
+                    dklen
+                );
+                ensure!(
+                    *n > 1 && n.is_power_of_two() && n.trailing_zeros() <= SCRYPT_MAX_LOG_N as u32,
+                    "Invalid scrypt param n: {}",
+                    n
+                );
+                // scrypt uses 128 * r * n bytes memory, and the time grows with r * p.
+                ensure!(
+                    *r > 0
+                        && *p > 0
+                        && (*r as u64) * (*p as u64) <= SCRYPT_MAX_R_P as u64
+                        && 128 * (*r as u64) * (*n as u64) <= SCRYPT_MAX_MEMORY,
+                    "Invalid scrypt params r: {}, p: {}",
+                    r,
+                    p
+                );
+                let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p)
+                    .map_err(|e| format_err!("Invalid scrypt params: {}", e))?;
+                let mut dk = vec![0u8; KEYSTORE_DKLEN as usize];
+                scrypt::scrypt(password, &hex::decode(salt)?, &params, &mut dk)
+                    .map_err(|e| format_err!("Scrypt error: {}", e))?;
+                Ok(dk)
+            }
+            KdfParams::Pbkdf2 {
+                c,
+                dklen,
+                prf,
+                salt,
+            } => {
+                ensure!(
+                    *dklen == KEYSTORE_DKLEN,
+                    "Invalid keystore dklen: {}",
+                    dklen
+                );
+                ensure!(prf == KEYSTORE_PRF, "Unsupported pbkdf2 prf: {}", prf);
+                ensure!(
+                    *c > 0 && *c <= KEYSTORE_PBKDF2_MAX_ITERATIONS,
+                    "Invalid pbkdf2 param c: {}",
+                    c
+                );
+                let mut dk = vec![0u8; KEYSTORE_DKLEN as usize];
+                pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha256>>(
+                    password,
+                    &hex::decode(salt)?,
+                    *c,
+                    &mut dk,
+                );
+                Ok(dk)
+            }
+        }
+    }
+}
+
+/// mac = keccak256(dk[16..32] ++ ciphertext)
+fn keystore_mac(dk: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut hasher = Keccak256::new();
+    hasher.update(&dk[16..32]);
+    hasher.update(ciphertext);
+    hasher.finalize().to_vec()
+}
+
+fn apply_cipher(dk: &[u8], iv: &[u8], data: &mut [u8]) -> Result<()> {
+    let mut cipher = Aes128Ctr::new_from_slices(&dk[..16], iv)
+        .map_err(|e| format_err!("Invalid keystore cipher params: {:?}", e))?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+fn random_uuid<R: RngCore>(rng: &mut R) -> String {
+    let mut id = [0u8; 16];
+    rng.fill_bytes(&mut id);
+    // uuid version 4, variant 1
+    id[6] = (id[6] & 0x0f) | 0x40;
+    id[8] = (id[8] & 0x3f) | 0x80;
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex::encode(&id[0..4]),
+        hex::encode(&id[4..6]),
+        hex::encode(&id[6..8]),
+        hex::encode(&id[8..10]),
+        hex::encode(&id[10..16])
+    )
+}
+
+/// Encrypt `plain` to a keystore with `password`.
+pub fn encrypt_keystore(
+    password: &[u8],
+    plain: &[u8],
+    address: Option<String>,
+    kdf: KeystoreKdf,
+) -> Result<Keystore> {
+    let mut rng = rand::thread_rng();
+    let mut salt = [0u8; PBKDF2_SALT_SIZE];
+    rng.fill_bytes(&mut salt);
+    let salt = hex::encode(salt);
+    let kdfparams = match kdf {
+        KeystoreKdf::Scrypt { log_n, r, p } => KdfParams::Scrypt {
+            dklen: KEYSTORE_DKLEN,
+            n: 1u32
+                .checked_shl(log_n as u32)
+                .ok_or_else(|| format_err!("Invalid scrypt param log_n: {}", log_n))?,
+            r,
+            p,
+            salt,
+        },
+        KeystoreKdf::Pbkdf2 { c } => KdfParams::Pbkdf2 {
+            c,
+            dklen: KEYSTORE_DKLEN,
+            prf: KEYSTORE_PRF.to_string(),
+            salt,
+        },
+    };
+    let dk = kdfparams.derive_key(password)?;
+    let mut iv = [0u8; KEYSTORE_IV_SIZE];
+    rng.fill_bytes(&mut iv);
+    let mut ciphertext = plain.to_vec();
+    apply_cipher(&dk, &iv, &mut ciphertext)?;
+    let mac = keystore_mac(&dk, &ciphertext);
+    Ok(Keystore {
+        version: KEYSTORE_VERSION,
+        id: random_uuid(&mut rng),
+        address,
+        crypto: KeystoreCrypto {
+            cipher: KEYSTORE_CIPHER.to_string(),
+            cipherparams: CipherParams {
+                iv: hex::encode(iv),
+            },
+            ciphertext: hex::encode(ciphertext),
+            kdf: kdfparams.name().to_string(),
+            kdfparams,
+            mac: hex::encode(mac),
+        },
+    })
+}
+
+/// Decrypt the secret of `keystore` with `password`.
+pub fn decrypt_keystore(password: &[u8], keystore: &Keystore) -> Result<Vec<u8>> {
+    ensure!(
+        keystore.version == KEYSTORE_VERSION,
+        "Unsupported keystore version: {}",
+        keystore.version
+    );
+    let crypto = &keystore.crypto;
+    ensure!(
+        crypto.cipher == KEYSTORE_CIPHER,
+        "Unsupported keystore cipher: {}",
+        crypto.cipher
+    );
+    ensure!(
+        crypto.kdf == crypto.kdfparams.name(),
+        "Keystore kdf {} mismatch with kdfparams",
+        crypto.kdf
+    );
+    let dk = crypto.kdfparams.derive_key(password)?;
+    let mut plain = hex::decode(&crypto.ciphertext)?;
+    let mac = hex::decode(&crypto.mac)?;
+    if !bool::from(keystore_mac(&dk, &plain).as_slice().ct_eq(&mac)) {
+        bail!("Invalid keystore password or corrupted keystore");
+    }
+    let iv = hex::decode(&crypto.cipherparams.iv)?;
+    apply_cipher(&dk, &iv, &mut plain)?;
+    Ok(plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_vector() {
+        // The pbkdf2 test vector of web3 secret storage definition.
+        let keystore: Keystore = serde_json::from_str(
+            r#"{
+                "crypto" : {
+                    "cipher" : "aes-128-ctr",
+                    "cipherparams" : {
+                        "iv" : "6087dab2f9fdbbfaddc31a909735c1e6"
+                    },
+                    "ciphertext" : "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                    "kdf" : "pbkdf2",
+                    "kdfparams" : {
+                        "c" : 262144,
+                        "dklen" : 32,
+                        "prf" : "hmac-sha256",
+                        "salt" : "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                    },
+                    "mac" : "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+                },
+                "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+                "version" : 3
+            }"#,
+        )
+        .unwrap();
+        let plain = decrypt_keystore(b"testpassword", &keystore).unwrap();
+        assert_eq!(
+            hex::encode(plain),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+        assert!(decrypt_keystore(b"wrongpassword", &keystore).is_err());
+    }
+
+    #[test]
+    fn test_keystore_encryption() {
+        let plain = b"hello world";
+        for kdf in [
+            KeystoreKdf::Scrypt {
+                log_n: 10,
+                r: 8,
+                p: 1,
+            },
+            KeystoreKdf::Pbkdf2 { c: 1000 },
+        ] {
+            let keystore =
+                encrypt_keystore(b"password", plain, Some("0x1".to_string()), kdf).unwrap();
+            let json = serde_json::to_string(&keystore).unwrap();
+            let keystore: Keystore = serde_json::from_str(json.as_str()).unwrap();
+            assert_eq!(keystore.address, Some("0x1".to_string()));
+            assert_eq!(
+                decrypt_keystore(b"password", &keystore).unwrap(),
+                plain.to_vec()
+            );
+            assert!(decrypt_keystore(b"wrong", &keystore).is_err());
+        }
+    }
+    #[test]
+    fn test_keystore_malicious_params() {
+        let keystore = encrypt_keystore(
+            b"password",
+            b"hello world",
+            None,
+            KeystoreKdf::Pbkdf2 { c: 1000 },
+        )
+        .unwrap();
+        let with_params = |kdfparams: KdfParams| {
+            let mut keystore = keystore.clone();
+            keystore.crypto.kdf = kdfparams.name().to_string();
+            keystore.crypto.kdfparams = kdfparams;
+            keystore
+        };
+        let salt = "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd".to_string();
+        let pbkdf2 = |c: u32, dklen: u32| KdfParams::Pbkdf2 {
+            c,
+            dklen,
+            prf: KEYSTORE_PRF.to_string(),
+            salt: salt.clone(),
+        };
+        let scrypt = |n: u32, r: u32, p: u32| KdfParams::Scrypt {
+            dklen: KEYSTORE_DKLEN,
+            n,
+            r,
+            p,
+            salt: salt.clone(),
+        };
+        for kdfparams in [
+            pbkdf2(1000, u32::MAX),
+            pbkdf2(1000, 64),
+            pbkdf2(u32::MAX, KEYSTORE_DKLEN),
+            pbkdf2(0, KEYSTORE_DKLEN),
+            scrypt(1 << 31, 8, 1),
+            scrypt(1 << 20, u32::MAX, 1),
+            scrypt(1 << 10, 8, u32::MAX),
+            scrypt(1 << 10, 0, 1),
+        ] {
+            assert!(decrypt_keystore(b"password", &with_params(kdfparams)).is_err());
+        }
+        assert!(encrypt_keystore(
+            b"password",
+            b"hello world",
+            None,
+            KeystoreKdf::Scrypt {
+                log_n: SCRYPT_MAX_LOG_N + 1,
+                r: 8,
+                p: 1
+            },
+        )
+        .is_err());
+        assert!(decrypt_keystore(b"password", &keystore).is_ok());
+    }
+}
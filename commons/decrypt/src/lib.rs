@@ -4,6 +4,8 @@ use byteorder::{ReadBytesExt, WriteBytesExt};
 use rand::RngCore;
 use std::io::{Cursor, Read, Write};
 
+pub mod keystore;
+
 pub const PBKDF2_DEFAULT_ITERATIONS: usize = 1000;
 pub const PBKDF2_SALT_SIZE: usize = 32;
 pub const AES_NONCE_SIZE: usize = 12;
@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 pub const G_ENV_PRIVATE_KEY: &str = "STARCOIN_PRIVATE_KEY";
+pub const G_DEFAULT_EXTERNAL_SIGNER_TIMEOUT: u64 = 10;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Parser)]
 pub struct AccountProviderConfig {
@@ -34,6 +35,21 @@ pub struct AccountProviderConfig {
     #[clap(skip)]
     pub account_address: Option<AccountAddress>,
 
+    /// Path to the unix socket of the external signer, sign txn and message by the external signer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "external-signer", parse(from_os_str))]
+    pub external_signer: Option<PathBuf>,
+
+    /// The accounts allowed to sign by the external signer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long = "external-signer-allow", multiple_occurrences = true)]
+    pub external_signer_allowlist: Vec<AccountAddress>,
+
+    /// Timeout in seconds of the external signer request, default is 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "external-signer-timeout")]
+    pub external_signer_timeout: Option<u64>,
+
     #[serde(skip)]
     #[clap(skip)]
     provider_strategy: AccountProviderStrategy,
@@ -44,6 +60,7 @@ impl ConfigModule for AccountProviderConfig {
         if (self.account_dir.is_some() as i32)
             + (self.secret_file.is_some() as i32)
             + (self.from_env as i32)
+            + (self.external_signer.is_some() as i32)
             > 1
         {
             bail!("Account provider conflicts")
@@ -56,10 +73,21 @@ impl ConfigModule for AccountProviderConfig {
             self.account_address = opt.account_provider.account_address;
         }
         self.from_env = opt.account_provider.from_env;
+        if opt.account_provider.external_signer.is_some() {
+            self.external_signer = opt.account_provider.external_signer.clone();
+        }
+        if !opt.account_provider.external_signer_allowlist.is_empty() {
+            self.external_signer_allowlist = opt.account_provider.external_signer_allowlist.clone();
+        }
+        if opt.account_provider.external_signer_timeout.is_some() {
+            self.external_signer_timeout = opt.account_provider.external_signer_timeout;
+        }
         if self.account_dir.is_some() {
             self.provider_strategy = AccountProviderStrategy::Local
         } else if self.secret_file.is_some() || self.from_env {
             self.provider_strategy = AccountProviderStrategy::PrivateKey
+        } else if self.external_signer.is_some() {
+            self.provider_strategy = AccountProviderStrategy::ExternalSigner
         } else {
             self.provider_strategy = AccountProviderStrategy::RPC
         }
@@ -74,6 +102,9 @@ impl AccountProviderConfig {
             secret_file: None,
             from_env: false,
             account_address: None,
+            external_signer: None,
+            external_signer_allowlist: vec![],
+            external_signer_timeout: None,
             provider_strategy: AccountProviderStrategy::Local,
         })
     }
@@ -91,21 +122,52 @@ impl AccountProviderConfig {
             secret_file,
             from_env,
             account_address,
+            external_signer: None,
+            external_signer_allowlist: vec![],
+            external_signer_timeout: None,
             provider_strategy: AccountProviderStrategy::PrivateKey,
         })
     }
 
+    pub fn new_external_signer_provider_config(
+        external_signer: PathBuf,
+        allowlist: Vec<AccountAddress>,
+        timeout: Option<u64>,
+    ) -> Result<Self> {
+        if allowlist.is_empty() {
+            bail!("The allowlist of external signer should not be empty.")
+        }
+        Ok(Self {
+            account_dir: None,
+            secret_file: None,
+            from_env: false,
+            account_address: None,
+            external_signer: Some(external_signer),
+            external_signer_allowlist: allowlist,
+            external_signer_timeout: timeout,
+            provider_strategy: AccountProviderStrategy::ExternalSigner,
+        })
+    }
+
+    pub fn external_signer_timeout(&self) -> u64 {
+        self.external_signer_timeout
+            .unwrap_or(G_DEFAULT_EXTERNAL_SIGNER_TIMEOUT)
+    }
+
     pub fn get_strategy(&self) -> AccountProviderStrategy {
         debug_assert!(
             (self.account_dir.is_some() as i32)
                 + (self.secret_file.is_some() as i32)
                 + (self.from_env as i32)
+                + (self.external_signer.is_some() as i32)
                 <= 1
         );
         if self.account_dir.is_some() {
             AccountProviderStrategy::Local
         } else if self.secret_file.is_some() || self.from_env {
             AccountProviderStrategy::PrivateKey
+        } else if self.external_signer.is_some() {
+            AccountProviderStrategy::ExternalSigner
         } else {
             AccountProviderStrategy::RPC
         }
@@ -119,6 +181,9 @@ impl Default for AccountProviderConfig {
             secret_file: None,
             account_address: None,
             from_env: false,
+            external_signer: None,
+            external_signer_allowlist: vec![],
+            external_signer_timeout: None,
             provider_strategy: AccountProviderStrategy::RPC,
         }
     }
@@ -8,6 +8,7 @@ pub enum Api {
     Account,
     Chain,
     Debug,
+    Dev,
     Miner,
     NetworkManager,
     NodeManager,
@@ -44,6 +45,7 @@ impl std::fmt::Display for Api {
             Self::Account => "account",
             Self::Chain => "chain",
             Self::Debug => "debug",
+            Self::Dev => "dev",
             Self::Miner => "miner",
             Self::NetworkManager => "network_manager",
             Self::NodeManager => "node_manager",
@@ -68,6 +70,7 @@ impl FromStr for Api {
             "account" => Ok(Account),
             "chain" => Ok(Chain),
             "debug" => Ok(Debug),
+            "dev" => Ok(Dev),
             "miner" => Ok(Miner),
             "network_manager" => Ok(NetworkManager),
             "node_manager" => Ok(NodeManager),
@@ -200,7 +203,7 @@ impl ApiSet {
             ApiSet::UnsafeContext => public_list,
             ApiSet::List(ref apis) => apis.iter().cloned().collect(),
 
-            ApiSet::IpcContext | ApiSet::All => {
+            ApiSet::IpcContext => {
                 public_list.insert(Api::PubSub);
                 public_list.insert(Api::Debug);
                 public_list.insert(Api::Account);
@@ -210,6 +213,12 @@ impl ApiSet {
                 public_list
             }
 
+            ApiSet::All => {
+                let mut apis = ApiSet::IpcContext.list_apis();
+                apis.insert(Api::Dev);
+                apis
+            }
+
             ApiSet::PubSub => {
                 public_list.insert(Api::PubSub);
                 public_list
@@ -25,6 +25,7 @@ use starcoin_vm_types::gas_schedule::{
     G_LATEST_GAS_COST_TABLE, G_TEST_GAS_CONSTANTS,
 };
 use starcoin_vm_types::genesis_config::{ChainId, ConsensusStrategy, StdlibVersion};
+use starcoin_vm_types::impersonation::ImpersonatedAccounts;
 use starcoin_vm_types::on_chain_config::{
     instruction_table_v1, instruction_table_v2, native_table_v1, native_table_v2, v4_native_table,
     ConsensusConfig, DaoConfig, GasSchedule, TransactionPublishOption, VMConfig, Version,
@@ -420,6 +421,7 @@ pub struct ChainNetwork {
     id: ChainNetworkID,
     genesis_config: GenesisConfig,
     time_service: Arc<dyn TimeService>,
+    impersonated_accounts: Option<ImpersonatedAccounts>,
 }
 
 impl Display for ChainNetwork {
@@ -437,10 +439,14 @@ impl PartialEq for ChainNetwork {
 impl ChainNetwork {
     pub fn new(id: ChainNetworkID, genesis_config: GenesisConfig) -> Self {
         let time_service = genesis_config.time_service_type.new_time_service();
+        // only the node of dev or test network can impersonate accounts.
+        let impersonated_accounts =
+            (id.is_dev() || id.is_test()).then(ImpersonatedAccounts::default);
         Self {
             id,
             genesis_config,
             time_service,
+            impersonated_accounts,
         }
     }
 
@@ -475,6 +481,11 @@ impl ChainNetwork {
         self.time_service.clone()
     }
 
+    /// The accounts impersonated by the node, None if the network can not impersonate accounts.
+    pub fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts> {
+        self.impersonated_accounts.clone()
+    }
+
     pub fn stdlib_version(&self) -> StdlibVersion {
         self.genesis_config().stdlib_version
     }
@@ -62,12 +62,13 @@ pub use metrics_config::MetricsConfig;
 pub use miner_config::{MinerClientConfig, MinerConfig};
 pub use network_config::{NetworkConfig, NetworkRpcQuotaConfiguration};
 pub use rpc_config::{
-    ApiQuotaConfiguration, HttpConfiguration, IpcConfiguration, RpcConfig, TcpConfiguration,
-    WsConfiguration,
+    ApiQuotaConfiguration, HttpConfiguration, IpcConfiguration, RpcAuthConfig, RpcConfig,
+    RpcTokenConfig, TcpConfiguration, WsConfiguration, MIN_RPC_AUTH_SECRET_LEN,
 };
 pub use starcoin_crypto::ed25519::genesis_key_pair;
 pub use starcoin_time_service::{MockTimeService, RealTimeService, TimeService};
-pub use storage_config::{RocksdbConfig, StorageConfig, DEFAULT_CACHE_SIZE};
+pub use storage_config::{RocksdbConfig, StatePruneMode, StorageConfig, DEFAULT_CACHE_SIZE};
+pub use sync_config::SyncMode;
 pub use txpool_config::TxPoolConfig;
 
 pub static G_CRATE_VERSION: &str = clap::crate_version!();
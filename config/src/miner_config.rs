@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -31,6 +31,12 @@ pub struct MinerConfig {
     /// Miner client thread number, not work for dev network, default is 1
     pub miner_thread: Option<u16>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "dev-instant-seal")]
+    /// Seal a block by the node as soon as the txpool has a transaction, without miner client.
+    /// Only support dev and test network.
+    pub dev_instant_seal: Option<bool>,
+
     #[serde(skip)]
     #[clap(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -46,9 +52,15 @@ impl MinerConfig {
             .unwrap_or_else(|| self.base().net.is_main())
     }
     pub fn is_disable_mint_empty_block(&self) -> bool {
+        if self.is_dev_instant_seal() {
+            return true;
+        }
         self.disable_mint_empty_block
             .unwrap_or_else(|| self.base().net().is_dev())
     }
+    pub fn is_dev_instant_seal(&self) -> bool {
+        self.dev_instant_seal.unwrap_or(false)
+    }
     pub fn miner_client_config(&self) -> Option<MinerClientConfig> {
         if self.disable_miner_client() {
             return None;
@@ -102,6 +114,15 @@ impl ConfigModule for MinerConfig {
         if opt.miner.block_gas_limit.is_some() {
             self.block_gas_limit = opt.miner.block_gas_limit;
         }
+        if opt.miner.dev_instant_seal.is_some() {
+            self.dev_instant_seal = opt.miner.dev_instant_seal;
+        }
+        ensure!(
+            !self.is_dev_instant_seal()
+                || self.base().net().is_test()
+                || self.base().net().is_dev(),
+            "The dev instant seal only support dev and test network."
+        );
 
         Ok(())
     }
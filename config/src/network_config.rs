@@ -6,7 +6,7 @@ use crate::{
     get_available_port_from, get_random_available_port, parse_key_val, ApiQuotaConfig, BaseConfig,
     ConfigModule, QuotaDuration, StarcoinOpt,
 };
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use clap::Parser;
 use network_api::messages::{NotificationMessage, BLOCK_PROTOCOL_NAME};
 use network_p2p_types::peer_id::PeerId;
@@ -31,6 +31,7 @@ use std::sync::Arc;
 
 pub static G_DEFAULT_NETWORK_PORT: u16 = 9840;
 static G_NETWORK_KEY_FILE: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("network_key"));
+static G_PEERS_FILE: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("network_peers.json"));
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, Parser)]
 pub struct NetworkRpcQuotaConfiguration {
@@ -186,6 +187,27 @@ pub struct NetworkConfig {
     /// P2P network seed, multi seed should use ',' as delimiter.
     pub seeds: Seeds,
 
+    #[serde(skip_serializing_if = "Seeds::is_empty")]
+    #[serde(default)]
+    #[clap(long = "trusted-peer", default_value = "")]
+    /// P2P network trusted peers which are always connected, multi peer should use ',' as delimiter.
+    pub trusted_peers: Seeds,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "reserved-only")]
+    /// Only connect with the trusted peers, other peers are denied. Default false.
+    reserved_only: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "allow-ip", long, use_value_delimiter = true)]
+    /// Only accept and dial the ip or CIDR network, such as 10.0.0.0/8, multi network should use ',' as delimiter. Default allow all.
+    allow_ips: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "deny-ip", long, use_value_delimiter = true)]
+    /// Never accept and dial the ip or CIDR network, it takes precedence over the allow-ip. multi network should use ',' as delimiter.
+    deny_ips: Option<Vec<String>>,
+
     /// Enable peer discovery on local networks.
     /// By default this option is `false`. only support cli option.
     #[serde(skip)]
@@ -211,6 +233,21 @@ pub struct NetworkConfig {
     ///max peers to propagate new block and new transactions. Default 128.
     max_peers_to_propagate: Option<u32>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    /// count of the best peers by total difficulty that always receive new block, in addition to the random propagated peers. Default 3, 0 disable the high bandwidth mode.
+    high_bandwidth_peers: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    /// disable persisting the known peers, their reputation and the banned peers across restarts. Default false.
+    disable_peer_persistence: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    /// interval(s) of persisting the known peers. Default 60.
+    peers_persist_interval: Option<u64>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     #[clap(long)]
     ///max count for incoming peers. Default 25.
@@ -279,6 +316,28 @@ impl NetworkConfig {
         seeds
     }
 
+    pub fn trusted_peers(&self) -> Vec<MultiaddrWithPeerId> {
+        let self_peer_id = self.self_peer_id();
+        self.trusted_peers
+            .clone()
+            .into_vec()
+            .into_iter()
+            .filter(|node| &node.peer_id != self_peer_id.origin())
+            .collect()
+    }
+
+    pub fn reserved_only(&self) -> bool {
+        self.reserved_only.unwrap_or(false)
+    }
+
+    pub fn allow_ips(&self) -> Vec<String> {
+        self.allow_ips.clone().unwrap_or_default()
+    }
+
+    pub fn deny_ips(&self) -> Vec<String> {
+        self.deny_ips.clone().unwrap_or_default()
+    }
+
     pub fn network_keypair(&self) -> &(Ed25519PrivateKey, Ed25519PublicKey) {
         self.network_keypair.as_ref().expect("Config should init.")
     }
@@ -314,6 +373,23 @@ impl NetworkConfig {
         self.min_peers_to_propagate.unwrap_or(8)
     }
 
+    pub fn high_bandwidth_peers(&self) -> u32 {
+        self.high_bandwidth_peers.unwrap_or(3)
+    }
+
+    pub fn peer_persistence_enabled(&self) -> bool {
+        !self.disable_peer_persistence.unwrap_or(false)
+    }
+
+    pub fn peers_persist_interval(&self) -> u64 {
+        self.peers_persist_interval.unwrap_or(60)
+    }
+
+    /// The file of the persisted peers, under the data dir.
+    pub fn peers_file(&self) -> PathBuf {
+        self.base().data_dir().join(G_PEERS_FILE.as_path())
+    }
+
     pub fn max_incoming_peers(&self) -> u32 {
         self.max_incoming_peers.unwrap_or(25)
     }
@@ -403,6 +479,18 @@ impl ConfigModule for NetworkConfig {
 
         self.seeds.merge(&opt.network.seeds);
 
+        self.trusted_peers.merge(&opt.network.trusted_peers);
+
+        if opt.network.reserved_only.is_some() {
+            self.reserved_only = opt.network.reserved_only;
+        }
+        if opt.network.allow_ips.is_some() {
+            self.allow_ips = opt.network.allow_ips.clone();
+        }
+        if opt.network.deny_ips.is_some() {
+            self.deny_ips = opt.network.deny_ips.clone();
+        }
+
         if opt.network.disable_seed {
             self.disable_seed = opt.network.disable_seed;
         }
@@ -431,10 +519,20 @@ impl ConfigModule for NetworkConfig {
         if let Some(m) = opt.network.min_peers_to_propagate {
             self.min_peers_to_propagate = Some(m);
         }
+        if let Some(m) = opt.network.high_bandwidth_peers {
+            self.high_bandwidth_peers = Some(m);
+        }
         if opt.network.discover_local.is_some() {
             self.discover_local = opt.network.discover_local;
         }
 
+        if opt.network.disable_peer_persistence.is_some() {
+            self.disable_peer_persistence = opt.network.disable_peer_persistence;
+        }
+        if opt.network.peers_persist_interval.is_some() {
+            self.peers_persist_interval = opt.network.peers_persist_interval;
+        }
+
         if opt.network.max_incoming_peers.is_some() {
             self.max_incoming_peers = opt.network.max_incoming_peers;
         }
@@ -464,7 +562,16 @@ impl ConfigModule for NetworkConfig {
             );
         }
 
+        ensure!(
+            self.peers_persist_interval() > 0,
+            "The peers persist interval should be greater than 0."
+        );
+
         self.load_or_generate_keypair()?;
+        ensure!(
+            !self.reserved_only() || !self.trusted_peers().is_empty(),
+            "The reserved only mode requires at least one trusted peer."
+        );
         self.generate_listen_address();
         Ok(())
     }
@@ -1,11 +1,12 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::helper::load_config;
 use crate::{
-    get_available_port_from, get_random_available_ports, parse_key_val, ApiQuotaConfig, ApiSet,
-    BaseConfig, ConfigModule, QuotaDuration, StarcoinOpt,
+    get_available_port_from, get_random_available_ports, parse_key_val, Api, ApiQuotaConfig,
+    ApiSet, BaseConfig, ConfigModule, QuotaDuration, StarcoinOpt,
 };
-use anyhow::Result;
+use anyhow::{ensure, format_err, Result};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use starcoin_logger::prelude::*;
@@ -13,7 +14,7 @@ use std::collections::HashSet;
 use std::fmt::Formatter;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::num::NonZeroU32;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 //10M
@@ -26,6 +27,8 @@ const DEFAULT_WEB_SOCKET_PORT: u16 = 9870;
 const DEFAULT_RPC_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
 const DEFAULT_BLOCK_QUERY_MAX_RANGE: u64 = 32;
 const DEFAULT_TXN_INFO_QUEYR_MAX_RANGE: u64 = 32;
+/// Min length of the rpc auth secret, a short secret makes the tokens easy to forge.
+pub const MIN_RPC_AUTH_SECRET_LEN: usize = 32;
 
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, Parser)]
 pub struct HttpConfiguration {
@@ -331,6 +334,71 @@ impl ApiQuotaConfiguration {
     }
 }
 
+/// The tokens to access the json rpc, loaded from the file of `--rpc-auth-config`.
+/// A token is `<id>.<hex encoded HMAC-SHA256 of id, keyed by secret>`, and is sent in the http
+/// `Authorization: Bearer <token>` header.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcAuthConfig {
+    /// The key to sign the tokens.
+    pub secret: String,
+    #[serde(default)]
+    pub tokens: Vec<RpcTokenConfig>,
+}
+
+impl RpcAuthConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let config: Self = load_config(path)
+            .map_err(|e| format_err!("Load rpc auth config {:?} failed: {}", path, e))?;
+        ensure!(
+            config.secret.len() >= MIN_RPC_AUTH_SECRET_LEN,
+            "Rpc auth secret should be at least {} bytes.",
+            MIN_RPC_AUTH_SECRET_LEN
+        );
+        let ids: HashSet<&str> = config.tokens.iter().map(|t| t.id.as_str()).collect();
+        ensure!(
+            ids.len() == config.tokens.len(),
+            "Duplicate token id in rpc auth config."
+        );
+        Ok(config)
+    }
+
+    pub fn token(&self, id: &str) -> Option<&RpcTokenConfig> {
+        self.tokens.iter().find(|t| t.id == id)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcTokenConfig {
+    /// Identity of the token, the user api quota is keyed on it.
+    pub id: String,
+    /// The apis allowed beyond the apiset of the transport.
+    #[serde(default)]
+    pub apis: HashSet<Api>,
+    /// The methods allowed beyond the apiset of the transport, a trailing `*` matches any suffix,
+    /// eg: `node_manager.*`.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Api quota of the token, default is the default user api quota.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<ApiQuotaConfig>,
+}
+
+impl RpcTokenConfig {
+    /// Check whether the token is allowed to call the `method` of `api`.
+    pub fn is_allowed(&self, api: Option<Api>, method: &str) -> bool {
+        api.map(|api| self.apis.contains(&api)).unwrap_or(false)
+            || self
+                .methods
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => method.starts_with(prefix),
+                    None => pattern == method,
+                })
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Deserialize, Serialize, Parser)]
 #[serde(deny_unknown_fields)]
 pub struct RpcConfig {
@@ -382,6 +450,26 @@ pub struct RpcConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[clap(long = "query-max-txn-info-range")]
     pub txn_info_query_max_range: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "rpc-auth-config")]
+    /// Path of the rpc token file, relative to the data dir, the tokens grant the http requests
+    /// apis beyond the http apiset.
+    pub auth_config: Option<PathBuf>,
+
+    #[serde(skip)]
+    #[clap(skip)]
+    auth: Option<Arc<RpcAuthConfig>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "graphql-port")]
+    /// Port of the GraphQL endpoint, the endpoint is disabled if not set.
+    /// If the rpc auth is configured, the endpoint requires a token allowed to call `graphql`.
+    pub graphql_port: Option<u16>,
+
+    #[serde(skip)]
+    #[clap(skip)]
+    graphql_address: Option<ListenAddress>,
 }
 
 #[derive(Clone, Eq, PartialEq)]
@@ -442,6 +530,10 @@ impl RpcConfig {
         self.ws_address.clone()
     }
 
+    pub fn get_graphql_address(&self) -> Option<ListenAddress> {
+        self.graphql_address.clone()
+    }
+
     pub fn block_query_max_range(&self) -> u64 {
         self.block_query_max_range
             .unwrap_or(DEFAULT_BLOCK_QUERY_MAX_RANGE)
@@ -452,6 +544,10 @@ impl RpcConfig {
             .unwrap_or(DEFAULT_TXN_INFO_QUEYR_MAX_RANGE)
     }
 
+    pub fn auth(&self) -> Option<Arc<RpcAuthConfig>> {
+        self.auth.clone()
+    }
+
     fn base(&self) -> &BaseConfig {
         self.base.as_ref().expect("Config should init.")
     }
@@ -499,6 +595,9 @@ impl RpcConfig {
         } else {
             Some(ListenAddress::new("ws", self.rpc_address(), ws_port))
         };
+        self.graphql_address = self
+            .graphql_port
+            .map(|port| ListenAddress::new("http", self.rpc_address(), port));
     }
 
     #[cfg(not(windows))]
@@ -527,6 +626,18 @@ impl ConfigModule for RpcConfig {
         if opt.rpc.txn_info_query_max_range.is_some() {
             self.txn_info_query_max_range = opt.rpc.txn_info_query_max_range;
         }
+        if opt.rpc.graphql_port.is_some() {
+            self.graphql_port = opt.rpc.graphql_port;
+        }
+        if opt.rpc.auth_config.is_some() {
+            self.auth_config = opt.rpc.auth_config.clone();
+        }
+        self.auth = match self.auth_config.as_ref() {
+            Some(path) => Some(Arc::new(RpcAuthConfig::load(
+                self.base().data_dir().join(path).as_path(),
+            )?)),
+            None => None,
+        };
         self.http.merge(&opt.rpc.http)?;
         self.tcp.merge(&opt.rpc.tcp)?;
         self.ws.merge(&opt.rpc.ws)?;
@@ -538,6 +649,7 @@ impl ConfigModule for RpcConfig {
         info!("Http rpc address: {:?}", self.get_http_address());
         info!("TCP rpc address: {:?}", self.get_tcp_address());
         info!("Websocket rpc address: {:?}", self.get_ws_address());
+        info!("GraphQL address: {:?}", self.get_graphql_address());
         info!("Ipc file path: {:?}", self.get_ipc_file());
 
         Ok(())
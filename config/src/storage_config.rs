@@ -2,11 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use clap::Parser;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use starcoin_types::block::BlockNumber;
+use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 
 /// Port selected RocksDB options for tuning underlying rocksdb instance of DiemDB.
@@ -67,6 +70,45 @@ impl Default for RocksdbConfig {
 
 static G_DEFAULT_DB_DIR: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("starcoindb/db"));
 pub const DEFAULT_CACHE_SIZE: usize = 20000;
+pub const DEFAULT_STATE_PRUNE_RETENTION: u64 = 10000;
+pub const MIN_STATE_PRUNE_RETENTION: u64 = 128;
+
+/// How the history state is kept.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatePruneMode {
+    /// Keep the state of all blocks.
+    Archive,
+    /// Only keep the state of the latest `state-prune-retention` blocks.
+    Pruned,
+}
+
+impl Default for StatePruneMode {
+    fn default() -> Self {
+        Self::Archive
+    }
+}
+
+impl Display for StatePruneMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Archive => write!(f, "archive"),
+            Self::Pruned => write!(f, "pruned"),
+        }
+    }
+}
+
+impl FromStr for StatePruneMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "archive" => Ok(Self::Archive),
+            "pruned" => Ok(Self::Pruned),
+            _ => anyhow::bail!("Unknown state prune mode: {}, expect archive or pruned", s),
+        }
+    }
+}
 
 #[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize, Parser)]
 #[serde(deny_unknown_fields)]
@@ -102,6 +144,47 @@ pub struct StorageConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[clap(name = "rocksdb-bytes-per-sync", long, help = "rocksdb bytes per sync")]
     pub bytes_per_sync: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "enable-account-txn-index",
+        long,
+        help = "index the transactions by sender, only the blocks connected while enabled are indexed"
+    )]
+    /// maintain the sender -> transaction index, this flag support both cli and config.
+    pub enable_account_txn_index: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "state-prune-mode",
+        long,
+        help = "archive or pruned, pruned mode only keeps the state of the latest blocks, and can only be set on a new database"
+    )]
+    pub state_prune_mode: Option<StatePruneMode>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "state-prune-retention",
+        long,
+        help = "how many latest blocks' state are kept in pruned mode, default 10000"
+    )]
+    pub state_prune_retention: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "fork-url",
+        long,
+        help = "the http rpc url of a remote node, fork the chain of the remote node and fetch the missing state from it, only support dev network"
+    )]
+    pub fork_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "fork-block",
+        long,
+        help = "the block number of the remote chain to fork at, default is the head block of the remote node"
+    )]
+    pub fork_block: Option<BlockNumber>,
 }
 
 impl StorageConfig {
@@ -129,6 +212,23 @@ impl StorageConfig {
     pub fn cache_size(&self) -> usize {
         self.cache_size.unwrap_or(DEFAULT_CACHE_SIZE)
     }
+    pub fn enable_account_txn_index(&self) -> bool {
+        self.enable_account_txn_index.unwrap_or(false)
+    }
+    pub fn state_prune_mode(&self) -> StatePruneMode {
+        self.state_prune_mode.unwrap_or_default()
+    }
+    pub fn state_prune_retention(&self) -> u64 {
+        self.state_prune_retention
+            .unwrap_or(DEFAULT_STATE_PRUNE_RETENTION)
+            .max(MIN_STATE_PRUNE_RETENTION)
+    }
+    pub fn fork_url(&self) -> Option<&str> {
+        self.fork_url.as_deref()
+    }
+    pub fn fork_block(&self) -> Option<BlockNumber> {
+        self.fork_block
+    }
 }
 
 impl ConfigModule for StorageConfig {
@@ -149,6 +249,36 @@ impl ConfigModule for StorageConfig {
         if opt.storage.wal_bytes_per_sync.is_some() {
             self.wal_bytes_per_sync = opt.storage.wal_bytes_per_sync;
         }
+        if opt.storage.enable_account_txn_index.is_some() {
+            self.enable_account_txn_index = opt.storage.enable_account_txn_index;
+        }
+        if opt.storage.state_prune_mode.is_some() {
+            self.state_prune_mode = opt.storage.state_prune_mode;
+        }
+        if opt.storage.state_prune_retention.is_some() {
+            self.state_prune_retention = opt.storage.state_prune_retention;
+        }
+        if opt.storage.fork_url.is_some() {
+            self.fork_url = opt.storage.fork_url.clone();
+        }
+        if opt.storage.fork_block.is_some() {
+            self.fork_block = opt.storage.fork_block;
+        }
+        if self.fork_url.is_some() {
+            ensure!(
+                self.base().net().is_dev(),
+                "Fork from a remote node only support dev network."
+            );
+            ensure!(
+                self.state_prune_mode() == StatePruneMode::Archive,
+                "Fork from a remote node only support archive state prune mode."
+            );
+        } else {
+            ensure!(
+                self.fork_block.is_none(),
+                "The fork block is set without the fork url."
+            );
+        }
         Ok(())
     }
 }
@@ -9,6 +9,8 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 
 const DEFAULT_STRATUM_PORT: u16 = 9880;
+const DEFAULT_SHARE_RATE: u32 = 20;
+const DEFAULT_MIN_SHARE_DIFFICULTY: u64 = 1;
 // UNSPECIFIED is 0.0.0.0
 const DEFAULT_STRATUM_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
 
@@ -28,6 +30,17 @@ pub struct StratumConfig {
     /// Stratum address, default is 0.0.0.0
     pub address: Option<IpAddr>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-share-rate")]
+    /// Target shares per minute of a worker, the share difficulty of every worker is retargeted
+    /// toward it, default is 20.
+    pub share_rate: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-min-share-difficulty")]
+    /// The initial and min share difficulty of a worker, default is 1.
+    pub min_share_difficulty: Option<u64>,
+
     #[clap(skip)]
     #[serde(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -37,6 +50,15 @@ impl StratumConfig {
     fn base(&self) -> &BaseConfig {
         self.base.as_ref().expect("Config should init.")
     }
+    pub fn share_rate(&self) -> u32 {
+        self.share_rate.unwrap_or(DEFAULT_SHARE_RATE)
+    }
+
+    pub fn min_share_difficulty(&self) -> u64 {
+        self.min_share_difficulty
+            .unwrap_or(DEFAULT_MIN_SHARE_DIFFICULTY)
+    }
+
     pub fn get_address(&self) -> Option<SocketAddr> {
         if self.disable {
             return None;
@@ -68,6 +90,12 @@ impl ConfigModule for StratumConfig {
         if opt.stratum.port.is_some() {
             self.port = opt.stratum.port;
         }
+        if opt.stratum.share_rate.is_some() {
+            self.share_rate = opt.stratum.share_rate;
+        }
+        if opt.stratum.min_share_difficulty.is_some() {
+            self.min_share_difficulty = opt.stratum.min_share_difficulty;
+        }
         info!(
             "Stratum listen address: {:?}, port:{:?}",
             self.address, self.port
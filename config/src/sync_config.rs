@@ -2,12 +2,53 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use clap::Parser;
 use network_api::PeerStrategy;
 use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
+/// How a node catches up with the network.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncMode {
+    /// Download the state of a recent pivot block, then execute the blocks after it.
+    Fast,
+    /// Execute every block from genesis.
+    Full,
+}
+
+impl Default for SyncMode {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
+impl Display for SyncMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fast => write!(f, "fast"),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl FromStr for SyncMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fast" => Ok(Self::Fast),
+            "full" => Ok(Self::Full),
+            _ => anyhow::bail!("Unknown sync mode: {}, expect fast or full", s),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, Deserialize, PartialEq, Eq, Serialize, Parser)]
 #[serde(deny_unknown_fields)]
 pub struct SyncConfig {
@@ -28,6 +69,34 @@ pub struct SyncConfig {
         help = "max retry times once sync block failed, default 15."
     )]
     max_retry_times: Option<u64>,
+
+    /// sync mode, fast or full
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "sync-mode",
+        long,
+        help = "fast or full, fast mode downloads the state of a recent block instead of executing the history blocks, only works on a new node, default full."
+    )]
+    sync_mode: Option<SyncMode>,
+
+    /// the snapshot dir to bootstrap a new node from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "bootstrap-from-snapshot",
+        long,
+        help = "the dir of a snapshot exported by db-exporter, every chunk of the snapshot is verified before written to storage, only works on a new node.",
+        parse(from_os_str)
+    )]
+    bootstrap_from_snapshot: Option<PathBuf>,
+
+    /// the trusted pivot block hash of the snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "snapshot-trusted-pivot",
+        long,
+        help = "the hash of the pivot block of the snapshot, get it from a trusted source, the snapshot is verified against it, required by bootstrap-from-snapshot."
+    )]
+    snapshot_trusted_pivot: Option<HashValue>,
 }
 
 impl SyncConfig {
@@ -38,6 +107,18 @@ impl SyncConfig {
     pub fn max_retry_times(&self) -> u64 {
         self.max_retry_times.unwrap_or(15)
     }
+
+    pub fn sync_mode(&self) -> SyncMode {
+        self.sync_mode.unwrap_or_default()
+    }
+
+    pub fn bootstrap_from_snapshot(&self) -> Option<&Path> {
+        self.bootstrap_from_snapshot.as_deref()
+    }
+
+    pub fn snapshot_trusted_pivot(&self) -> Option<HashValue> {
+        self.snapshot_trusted_pivot
+    }
 }
 
 impl ConfigModule for SyncConfig {
@@ -50,6 +131,22 @@ impl ConfigModule for SyncConfig {
             self.max_retry_times = opt.sync.max_retry_times;
         }
 
+        if opt.sync.sync_mode.is_some() {
+            self.sync_mode = opt.sync.sync_mode;
+        }
+
+        if opt.sync.bootstrap_from_snapshot.is_some() {
+            self.bootstrap_from_snapshot = opt.sync.bootstrap_from_snapshot.clone();
+        }
+
+        if opt.sync.snapshot_trusted_pivot.is_some() {
+            self.snapshot_trusted_pivot = opt.sync.snapshot_trusted_pivot;
+        }
+        ensure!(
+            self.bootstrap_from_snapshot.is_none() || self.snapshot_trusted_pivot.is_some(),
+            "snapshot-trusted-pivot is required by bootstrap-from-snapshot"
+        );
+
         Ok(())
     }
 }
@@ -184,3 +184,25 @@ fn test_check_method_in_api_sets() {
     assert!(!ApiSet::UnsafeContext.check_rpc_method("unknown"));
     assert!(!ApiSet::UnsafeContext.check_rpc_method(""));
 }
+
+#[test]
+fn test_network_config_validation() -> Result<()> {
+    let load = |args: &[&str]| {
+        let data_dir = temp_dir();
+        let mut all_args = vec!["starcoin", "-n", "test", "-d"];
+        all_args.push(data_dir.path().to_str().unwrap());
+        all_args.extend_from_slice(args);
+        NodeConfig::load_with_opt(&StarcoinOpt::try_parse_from(all_args)?)
+    };
+
+    assert!(load(&["--peers-persist-interval", "0"]).is_err());
+    assert!(load(&["--reserved-only", "true"]).is_err());
+    let config = load(&[
+        "--reserved-only",
+        "true",
+        "--trusted-peer",
+        "/ip4/1.2.3.4/tcp/9840/p2p/12D3KooWCfUex27aoqaKScponiLB4N4FWbgmbHYjVoRebGrQaRYk",
+    ])?;
+    assert!(config.network.reserved_only());
+    Ok(())
+}
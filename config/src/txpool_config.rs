@@ -37,6 +37,26 @@ pub struct TxPoolConfig {
     #[clap(name = "txpool-min-gas-price", long)]
     /// reject transaction whose gas_price is less than the min_gas_price. default to 1.
     min_gas_price: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "txpool-price-bump-percent", long)]
+    /// min gas price bump(%) to replace a transaction with the same sender and sequence number. default to 10.
+    price_bump_percent: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "txpool-disable-journal", long)]
+    /// do not persist the pool transactions across node restarts. default to false.
+    disable_journal: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "txpool-journal-interval", long)]
+    /// interval(s) of the pool transactions snapshot. default to 60.
+    journal_interval: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "txpool-journal-max-age", long)]
+    /// journaled transactions older than max age(s) are dropped on startup. default to 10800.
+    journal_max_age: Option<u64>,
 }
 
 impl TxPoolConfig {
@@ -68,6 +88,18 @@ impl TxPoolConfig {
     pub fn min_gas_price(&self) -> u64 {
         self.min_gas_price.unwrap_or(1)
     }
+    pub fn price_bump_percent(&self) -> u64 {
+        self.price_bump_percent.unwrap_or(10)
+    }
+    pub fn journal_enabled(&self) -> bool {
+        !self.disable_journal.unwrap_or(false)
+    }
+    pub fn journal_interval(&self) -> u64 {
+        self.journal_interval.unwrap_or(60)
+    }
+    pub fn journal_max_age(&self) -> u64 {
+        self.journal_max_age.unwrap_or(3 * 60 * 60)
+    }
 }
 
 impl ConfigModule for TxPoolConfig {
@@ -88,6 +120,18 @@ impl ConfigModule for TxPoolConfig {
         if let Some(m) = txpool_opt.min_gas_price.as_ref() {
             self.min_gas_price = Some(*m);
         }
+        if let Some(m) = txpool_opt.price_bump_percent.as_ref() {
+            self.price_bump_percent = Some(*m);
+        }
+        if let Some(m) = txpool_opt.disable_journal.as_ref() {
+            self.disable_journal = Some(*m);
+        }
+        if let Some(m) = txpool_opt.journal_interval.as_ref() {
+            self.journal_interval = Some(*m);
+        }
+        if let Some(m) = txpool_opt.journal_max_age.as_ref() {
+            self.journal_max_age = Some(*m);
+        }
         Ok(())
     }
 }
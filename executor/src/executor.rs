@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
+use starcoin_gas::CallFrame;
 use starcoin_types::transaction::{SignedUserTransaction, Transaction, TransactionOutput};
 use starcoin_vm_runtime::{metrics::VMMetrics, starcoin_vm::StarcoinVM, VMExecutor};
 use starcoin_vm_types::{
@@ -50,6 +51,17 @@ pub fn validate_transaction<S: StateView>(
     vm.verify_transaction(chain_state, txn)
 }
 
+/// Re-execute a user transaction for debugging, optionally with the gas trace.
+pub fn trace_transaction<S: StateView>(
+    chain_state: &S,
+    txn: SignedUserTransaction,
+    trace: bool,
+    metrics: Option<VMMetrics>,
+) -> Result<(VMStatus, TransactionOutput, Option<CallFrame>)> {
+    let mut vm = StarcoinVM::new(metrics);
+    vm.trace_user_transaction(chain_state, txn, trace)
+}
+
 pub fn execute_readonly_function<S: StateView>(
     chain_state: &S,
     module: &ModuleId,
@@ -3,6 +3,7 @@
 
 use anyhow::anyhow;
 use anyhow::Result;
+use starcoin_config::{BuiltinNetworkID, ChainNetwork};
 use starcoin_executor::validate_transaction;
 use starcoin_logger::prelude::*;
 use starcoin_transaction_builder::{
@@ -809,6 +810,96 @@ fn test_execute_transfer_txn() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_execute_impersonated_txn() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+    let impersonated_accounts = net
+        .impersonated_accounts()
+        .expect("test network can impersonate accounts.");
+    let chain_state = chain_state.with_impersonated_accounts(Some(impersonated_accounts.clone()));
+
+    let account1 = Account::new();
+    let txn1 = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1,
+        0,
+        STCUnit::STC.value_of(100).scaling(),
+        1,
+        &net,
+    ));
+    let output1 = execute_and_apply(&chain_state, txn1);
+    assert_eq!(KeptVMStatus::Executed, output1.status().status().unwrap());
+
+    let account2 = Account::new();
+    let build_txn = |sequence_number| {
+        raw_peer_to_peer_txn(
+            *account1.address(),
+            *account2.address(),
+            1000,
+            sequence_number,
+            1,
+            DEFAULT_MAX_GAS_AMOUNT,
+            G_STC_TOKEN_CODE.clone(),
+            net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+            net.chain_id(),
+        )
+    };
+
+    // account2 try to transfer stc from account1 without impersonating, will discard.
+    let output = execute_and_apply(
+        &chain_state,
+        Transaction::UserTransaction(account2.sign_txn(build_txn(0))),
+    );
+    assert_eq!(
+        StatusCode::INVALID_AUTH_KEY,
+        output.status().status().err().unwrap()
+    );
+
+    let auth_key = chain_state
+        .get_account_resource(*account1.address())?
+        .expect("account resource should exist.")
+        .authentication_key()
+        .to_vec();
+    assert!(impersonated_accounts.impersonate(*account1.address()));
+    assert_eq!(
+        validate_transaction(&chain_state, account2.sign_txn(build_txn(0)), None),
+        None
+    );
+    let output = execute_and_apply(
+        &chain_state,
+        Transaction::UserTransaction(account2.sign_txn(build_txn(0))),
+    );
+    assert_eq!(KeptVMStatus::Executed, output.status().status().unwrap());
+    let account_resource = chain_state
+        .get_account_resource(*account1.address())?
+        .expect("account resource should exist.");
+    // the auth key of the impersonated account is not changed.
+    assert_eq!(account_resource.authentication_key(), auth_key.as_slice());
+    assert_eq!(account_resource.sequence_number(), 1);
+
+    assert!(impersonated_accounts.stop_impersonating(*account1.address()));
+    let output = execute_and_apply(
+        &chain_state,
+        Transaction::UserTransaction(account2.sign_txn(build_txn(1))),
+    );
+    assert_eq!(
+        StatusCode::INVALID_AUTH_KEY,
+        output.status().status().err().unwrap()
+    );
+
+    // the impersonated accounts are kept by the node, the state view of other nodes ignores them.
+    assert!(impersonated_accounts.impersonate(*account1.address()));
+    let other_chain_state = chain_state.fork().with_impersonated_accounts(None);
+    assert_eq!(
+        validate_transaction(&other_chain_state, account2.sign_txn(build_txn(1)), None)
+            .map(|status| status.status_code()),
+        Some(StatusCode::INVALID_AUTH_KEY)
+    );
+    assert!(ChainNetwork::new_builtin(BuiltinNetworkID::Main)
+        .impersonated_accounts()
+        .is_none());
+    Ok(())
+}
+
 #[stest::test]
 fn test_execute_multi_txn_with_same_account() -> Result<()> {
     let (chain_state, net) = prepare_genesis();
@@ -103,6 +103,16 @@ impl Genesis {
         Ok(genesis)
     }
 
+    /// The genesis of a chain forked from a remote node, the block is fetched from the remote node.
+    pub fn new_with_block(block: Block) -> Result<Self> {
+        ensure!(
+            block.header().is_genesis(),
+            "Block {} is not a genesis block",
+            block.id()
+        );
+        Ok(Self { block })
+    }
+
     fn build_genesis_block(net: &ChainNetwork) -> Result<Block> {
         let genesis_config = net.genesis_config();
         if let Some(GenesisBlockParameter {
@@ -173,7 +173,8 @@ impl MinerService {
             && (block_template.body.transactions.is_empty()
             && self.config.miner.is_disable_mint_empty_block()
             //if block time gap > 3600, force create a empty block for fix https://github.com/starcoinorg/starcoin/issues/3036
-            && block_time_gap < MAX_BLOCK_TIME_GAP)
+            //the time of dev instant seal is controlled by user, so do not force create block.
+            && (block_time_gap < MAX_BLOCK_TIME_GAP || self.config.miner.is_dev_instant_seal()))
         {
             debug!("The flag disable_mint_empty_block is true and no txn in pool, so skip mint empty block.");
             Ok(())
@@ -211,6 +212,12 @@ impl MinerService {
             );
         }
         self.current_task = Some(task);
+        if self.config.miner.is_dev_instant_seal() {
+            // The dev network use dummy consensus, any nonce is valid, so seal the block directly.
+            return self
+                .finish_task(0, BlockHeaderExtra::default(), mining_blob, ctx)
+                .map(|_| ());
+        }
         ctx.broadcast(MintBlockEvent::new(
             parent_hash,
             strategy,
@@ -276,7 +283,10 @@ impl EventHandler<Self, GenerateBlockEvent> for MinerService {
             debug!("Miner has mint job so just ignore this event.");
             return;
         }
-        if self.config.miner.disable_miner_client() && self.client_subscribers_num == 0 {
+        if self.config.miner.disable_miner_client()
+            && !self.config.miner.is_dev_instant_seal()
+            && self.client_subscribers_num == 0
+        {
             debug!("No miner client connected, ignore GenerateBlockEvent.");
             // Once Miner client connect, we should dispatch task.
             ctx.run_later(Duration::from_secs(2), |ctx| {
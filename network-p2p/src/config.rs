@@ -4,6 +4,8 @@
 //! Libp2p network configuration.
 
 use crate::business_layer_handle::BusinessLayerHandle;
+use anyhow::format_err;
+use ip_network::IpNetwork;
 use libp2p::{
     core::Multiaddr,
     identity::{ed25519, Keypair},
@@ -17,8 +19,9 @@ use std::{
     fs,
     io::{self, Write},
     iter,
-    net::Ipv4Addr,
+    net::{IpAddr, Ipv4Addr},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 use zeroize::Zeroize;
 
@@ -96,6 +99,8 @@ pub struct NetworkConfiguration {
     pub reserved_nodes: Vec<MultiaddrWithPeerId>,
     /// The non-reserved peer mode.
     pub non_reserved_mode: NonReservedPeerMode,
+    /// The IP networks allowed or denied to connect, enforced by the transport.
+    pub ip_filter: IpFilter,
     /// Client identifier. Sent over the wire for debugging purposes.
     pub client_version: String,
     /// Name of the node. Sent over the wire for debugging purposes.
@@ -143,6 +148,7 @@ impl Default for NetworkConfiguration {
             out_peers: 75,
             reserved_nodes: Vec::new(),
             non_reserved_mode: NonReservedPeerMode::Accept,
+            ip_filter: IpFilter::default(),
             client_version: "unknown".into(),
             node_name: "unknown".into(),
             transport: TransportConfig::Normal {
@@ -173,6 +179,7 @@ impl NetworkConfiguration {
             out_peers: 75,
             reserved_nodes: Vec::new(),
             non_reserved_mode: NonReservedPeerMode::Accept,
+            ip_filter: IpFilter::default(),
             client_version: client_version.into(),
             node_name: node_name.into(),
             transport: TransportConfig::Normal {
@@ -218,6 +225,67 @@ impl NonReservedPeerMode {
     }
 }
 
+/// The allow and deny lists of IP networks for connections.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IpFilter {
+    allow: Vec<IpNetwork>,
+    deny: Vec<IpNetwork>,
+}
+
+impl IpFilter {
+    /// Parse the filter from IP addresses or CIDR networks, such as `10.0.0.1` or `10.0.0.0/8`.
+    pub fn parse<S: AsRef<str>>(allow: &[S], deny: &[S]) -> anyhow::Result<Self> {
+        Ok(Self {
+            allow: Self::parse_networks(allow)?,
+            deny: Self::parse_networks(deny)?,
+        })
+    }
+
+    fn parse_networks<S: AsRef<str>>(networks: &[S]) -> anyhow::Result<Vec<IpNetwork>> {
+        networks
+            .iter()
+            .map(|network| {
+                let network = network.as_ref().trim();
+                if network.contains('/') {
+                    IpNetwork::from_str(network)
+                        .map_err(|e| format_err!("Invalid ip network {}: {:?}", network, e))
+                } else {
+                    IpAddr::from_str(network)
+                        .map(IpNetwork::from)
+                        .map_err(|e| format_err!("Invalid ip address {}: {:?}", network, e))
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Check whether the ip is allowed, the deny list takes precedence over the allow list,
+    /// and an empty allow list allows every ip which is not denied.
+    pub fn is_ip_allowed(&self, ip: IpAddr) -> bool {
+        // A dual stack listener reports ipv4 peers as ipv4-mapped ipv6 addresses.
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            IpAddr::V4(_) => ip,
+        };
+        if self.deny.iter().any(|network| network.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|network| network.contains(ip))
+    }
+
+    /// Check the ip of the address, the address without ip, such as a memory address, is always allowed.
+    pub fn is_allowed(&self, addr: &Multiaddr) -> bool {
+        match addr.iter().next() {
+            Some(Protocol::Ip4(ip)) => self.is_ip_allowed(IpAddr::V4(ip)),
+            Some(Protocol::Ip6(ip)) => self.is_ip_allowed(IpAddr::V6(ip)),
+            _ => true,
+        }
+    }
+}
+
 /// The configuration of a node's secret key, describing the type of key
 /// and how it is obtained. A node's identity keypair is the result of
 /// the evaluation of the node key configuration.
@@ -400,4 +468,25 @@ mod tests {
         let kp2 = NodeKeyConfig::Ed25519(Secret::New).into_keypair().unwrap();
         assert!(secret_bytes(&kp1) != secret_bytes(&kp2));
     }
+
+    #[test]
+    fn test_ip_filter() {
+        let filter = IpFilter::parse(&["10.0.0.0/8", "192.168.1.1"], &["10.1.0.0/16"]).unwrap();
+        let addr = |s: &str| s.parse::<Multiaddr>().unwrap();
+        assert!(filter.is_allowed(&addr("/ip4/10.2.0.1/tcp/9840")));
+        assert!(filter.is_allowed(&addr("/ip4/192.168.1.1/tcp/9840")));
+        assert!(!filter.is_allowed(&addr("/ip4/10.1.0.1/tcp/9840")));
+        assert!(!filter.is_allowed(&addr("/ip4/192.168.1.2/tcp/9840")));
+        assert!(filter.is_allowed(&addr("/memory/1")));
+        assert!(!filter.is_allowed(&addr("/ip6/::ffff:10.1.0.1/tcp/9840")));
+        assert!(filter.is_allowed(&addr("/ip6/::ffff:10.2.0.1/tcp/9840")));
+        assert!(!filter.is_allowed(&addr("/ip6/::ffff:192.168.1.2/tcp/9840")));
+
+        let filter = IpFilter::parse(&[], &["::1"]).unwrap();
+        assert!(!filter.is_allowed(&addr("/ip6/::1/tcp/9840")));
+        assert!(filter.is_allowed(&addr("/ip4/127.0.0.1/tcp/9840")));
+
+        assert!(IpFilter::parse(&["10.0.0.0/33"], &[]).is_err());
+        assert!(IpFilter::parse(&["localhost"], &[]).is_err());
+    }
 }
@@ -118,6 +118,8 @@ pub struct NetworkService {
     /// For each peer and protocol combination, an object that allows sending notifications to
     /// that peer. Updated by the [`NetworkWorker`].
     peers_notifications_sinks: Arc<Mutex<HashMap<(PeerId, Cow<'static, str>), NotificationsSink>>>,
+    /// The peers banned by [`NetworkService::ban_peer`], the temporary bans are not included.
+    banned_peers: Arc<Mutex<HashSet<PeerId>>>,
     /// Channel that sends messages to the actual worker.
     to_worker: mpsc::UnboundedSender<ServiceToWorkerMsg>,
     /// Field extracted from the [`Metrics`] struct and necessary to report the
@@ -174,6 +176,14 @@ impl<T: BusinessLayerHandle + Send> NetworkWorker<T> {
 
         let boot_node_ids = Arc::new(boot_node_ids);
 
+        // The reserved nodes are always connected, so their addresses must be known.
+        for reserved_node in params.network_config.reserved_nodes.iter() {
+            if !known_addresses.contains(&(reserved_node.peer_id, reserved_node.multiaddr.clone()))
+            {
+                known_addresses.push((reserved_node.peer_id, reserved_node.multiaddr.clone()));
+            }
+        }
+
         // Check for duplicate bootnodes.
         known_addresses.iter().try_for_each(|(peer_id, addr)| {
             if let Some(other) = known_addresses
@@ -282,7 +292,11 @@ impl<T: BusinessLayerHandle + Send> NetworkWorker<T> {
                     TransportConfig::MemoryOnly => true,
                     TransportConfig::Normal { .. } => false,
                 };
-                transport::build_transport(local_identity, config_mem)
+                transport::build_transport(
+                    local_identity,
+                    config_mem,
+                    params.network_config.ip_filter.clone(),
+                )
             };
             let builder = {
                 struct SpawnImpl<F>(F);
@@ -339,6 +353,7 @@ impl<T: BusinessLayerHandle + Send> NetworkWorker<T> {
             peerset: peerset_handle,
             local_peer_id,
             peers_notifications_sinks: peers_notifications_sinks.clone(),
+            banned_peers: Arc::new(Mutex::new(HashSet::new())),
             to_worker,
             notifications_sizes_metric: metrics
                 .as_ref()
@@ -878,7 +893,20 @@ impl NetworkService {
         &self.local_peer_id
     }
 
+    /// Returns the peers banned by [`NetworkService::ban_peer`].
+    pub fn banned_peers(&self) -> HashSet<PeerId> {
+        self.banned_peers.lock().clone()
+    }
+
     pub fn ban_peer(&self, peer_id: PeerId, ban: bool) {
+        {
+            let mut banned_peers = self.banned_peers.lock();
+            if ban {
+                banned_peers.insert(peer_id);
+            } else {
+                banned_peers.remove(&peer_id);
+            }
+        }
         let _ = self
             .to_worker
             .unbounded_send(ServiceToWorkerMsg::BanPeer(ban, peer_id));
@@ -698,3 +698,159 @@ async fn test_support_protocol() {
         panic!("Unexpected event type: {:?}", open_event2)
     }
 }
+
+#[stest::test(timeout = 120)]
+async fn test_connect_reserved_only() {
+    let listen_addr = config::build_multiaddr![Memory(rand::random::<u64>())];
+
+    let (node1, _) = build_test_full_node(config::NetworkConfiguration {
+        notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+        listen_addresses: vec![listen_addr.clone()],
+        transport: config::TransportConfig::MemoryOnly,
+        ..config::NetworkConfiguration::new_local()
+    });
+
+    // node2 only knows node1 by the reserved nodes.
+    let (node2, events_stream2) = build_test_full_node(config::NetworkConfiguration {
+        notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+        listen_addresses: vec![],
+        reserved_nodes: vec![config::MultiaddrWithPeerId {
+            multiaddr: listen_addr,
+            peer_id: node1.local_peer_id(),
+        }],
+        non_reserved_mode: config::NonReservedPeerMode::Deny,
+        transport: config::TransportConfig::MemoryOnly,
+        ..config::NetworkConfiguration::new_local()
+    });
+
+    let open_event = events_stream2
+        .filter(|event| future::ready(matches!(event, Event::NotificationStreamOpened { .. })))
+        .take(1)
+        .collect::<Vec<_>>()
+        .await
+        .pop()
+        .unwrap();
+    if let NotificationStreamOpened { remote, .. } = open_event {
+        assert_eq!(remote, node1.local_peer_id());
+    } else {
+        panic!("Unexpected event type: {:?}", open_event)
+    }
+
+    node2.ban_peer(node1.local_peer_id(), true);
+    assert!(node2.banned_peers().contains(&node1.local_peer_id()));
+    node2.ban_peer(node1.local_peer_id(), false);
+    assert!(node2.banned_peers().is_empty());
+}
+
+/// Returns the remote of the first opened notification stream, or `None` if no stream is opened
+/// before the timeout.
+async fn first_opened_stream(
+    events: impl Stream<Item = Event>,
+    timeout: Duration,
+) -> Option<PeerId> {
+    let opened = events
+        .filter_map(|event| {
+            future::ready(match event {
+                NotificationStreamOpened { remote, .. } => Some(remote),
+                _ => None,
+            })
+        })
+        .take(1)
+        .collect::<Vec<_>>();
+    tokio::time::timeout(timeout, opened)
+        .await
+        .ok()
+        .and_then(|mut remotes| remotes.pop())
+}
+
+#[stest::test(timeout = 120)]
+async fn test_reserved_only_deny_non_reserved_peer() {
+    let reserved_addr = config::build_multiaddr![Memory(rand::random::<u64>())];
+    let (reserved_node, _) = build_test_full_node(config::NetworkConfiguration {
+        notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+        listen_addresses: vec![reserved_addr.clone()],
+        transport: config::TransportConfig::MemoryOnly,
+        ..config::NetworkConfiguration::new_local()
+    });
+
+    let listen_addr = config::build_multiaddr![Memory(rand::random::<u64>())];
+    let (node, events_stream) = build_test_full_node(config::NetworkConfiguration {
+        notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+        listen_addresses: vec![listen_addr.clone()],
+        reserved_nodes: vec![config::MultiaddrWithPeerId {
+            multiaddr: reserved_addr,
+            peer_id: reserved_node.local_peer_id(),
+        }],
+        non_reserved_mode: config::NonReservedPeerMode::Deny,
+        transport: config::TransportConfig::MemoryOnly,
+        ..config::NetworkConfiguration::new_local()
+    });
+
+    // the non reserved node dials the node by boot nodes, and should be denied.
+    let (_non_reserved_node, non_reserved_events_stream) =
+        build_test_full_node(config::NetworkConfiguration {
+            notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+            listen_addresses: vec![],
+            boot_nodes: vec![config::MultiaddrWithPeerId {
+                multiaddr: listen_addr,
+                peer_id: node.local_peer_id(),
+            }],
+            transport: config::TransportConfig::MemoryOnly,
+            ..config::NetworkConfiguration::new_local()
+        });
+
+    assert_eq!(
+        first_opened_stream(events_stream, Duration::from_secs(10)).await,
+        Some(reserved_node.local_peer_id())
+    );
+    assert_eq!(
+        first_opened_stream(non_reserved_events_stream, Duration::from_secs(5)).await,
+        None
+    );
+}
+
+#[stest::test(timeout = 120)]
+async fn test_ip_filter_drop_connection() {
+    // the ip filter only applies to tcp connections, so listen on a local tcp port.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let listen_addr = config::build_multiaddr![Ip4([127, 0, 0, 1]), Tcp(port)];
+    let (node, _) = build_test_full_node(config::NetworkConfiguration {
+        notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+        listen_addresses: vec![listen_addr.clone()],
+        ..config::NetworkConfiguration::new_local()
+    });
+    let seed = config::MultiaddrWithPeerId {
+        multiaddr: listen_addr,
+        peer_id: node.local_peer_id(),
+    };
+
+    let (_filtered_node, filtered_events_stream) =
+        build_test_full_node(config::NetworkConfiguration {
+            notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+            listen_addresses: vec![],
+            boot_nodes: vec![seed.clone()],
+            ip_filter: config::IpFilter::parse(&[], &["127.0.0.0/8"]).unwrap(),
+            ..config::NetworkConfiguration::new_local()
+        });
+
+    // a node without ip filter can connect to the same node.
+    let (_node, events_stream) = build_test_full_node(config::NetworkConfiguration {
+        notifications_protocols: vec![From::from(PROTOCOL_NAME)],
+        listen_addresses: vec![],
+        boot_nodes: vec![seed],
+        ..config::NetworkConfiguration::new_local()
+    });
+
+    assert_eq!(
+        first_opened_stream(events_stream, Duration::from_secs(10)).await,
+        Some(node.local_peer_id())
+    );
+    assert_eq!(
+        first_opened_stream(filtered_events_stream, Duration::from_secs(5)).await,
+        None
+    );
+}
@@ -16,6 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::config::IpFilter;
+use futures::future;
 use libp2p::{
     bandwidth,
     core::{
@@ -23,11 +25,12 @@ use libp2p::{
         either::EitherTransport,
         muxing::StreamMuxerBox,
         transport::{Boxed, OptionalTransport},
-        upgrade,
+        upgrade, ConnectedPoint,
     },
     dns, identity, mplex, noise, tcp, websocket, PeerId, Transport,
 };
-use std::{sync::Arc, time::Duration};
+use log::debug;
+use std::{io, sync::Arc, time::Duration};
 
 pub use self::bandwidth::BandwidthSinks;
 
@@ -36,6 +39,9 @@ pub use self::bandwidth::BandwidthSinks;
 /// If `memory_only` is true, then only communication within the same process are allowed. Only
 /// addresses with the format `/memory/...` are allowed.
 ///
+/// The `ip_filter` is checked against the remote ip of every tcp connection, below the DNS
+/// transport, so the resolved addresses are checked too.
+///
 /// `yamux_window_size` is the maximum size of the Yamux receive windows. `None` to leave the
 /// default (256kiB).
 ///
@@ -49,12 +55,15 @@ pub use self::bandwidth::BandwidthSinks;
 pub fn build_transport(
     keypair: identity::Keypair,
     memory_only: bool,
+    ip_filter: IpFilter,
 ) -> (Boxed<(PeerId, StreamMuxerBox)>, Arc<BandwidthSinks>) {
+    let ip_filter = Arc::new(ip_filter);
     // Build the base layer of the transport.
     let transport = if !memory_only {
         // Main transport: DNS(TCP)
         let tcp_config = tcp::Config::new().nodelay(true);
-        let tcp_trans = tcp::tokio::Transport::new(tcp_config.clone());
+        let tcp_trans =
+            tcp::tokio::Transport::new(tcp_config.clone()).and_then(filter_ip(ip_filter.clone()));
         let dns_init = dns::TokioDnsConfig::system(tcp_trans);
 
         EitherTransport::Left(if let Ok(dns) = dns_init {
@@ -63,15 +72,18 @@ pub fn build_transport(
             // Main transport can't be used for `/wss` addresses because WSS transport needs
             // unresolved addresses (BUT WSS transport itself needs an instance of DNS transport to
             // resolve and dial addresses).
-            let tcp_trans = tcp::tokio::Transport::new(tcp_config);
+            let tcp_trans =
+                tcp::tokio::Transport::new(tcp_config).and_then(filter_ip(ip_filter.clone()));
             let dns_for_wss = dns::TokioDnsConfig::system(tcp_trans)
                 .expect("same system_conf & resolver to work");
             EitherTransport::Left(websocket::WsConfig::new(dns_for_wss).or_transport(dns))
         } else {
             // In case DNS can't be constructed, fallback to TCP + WS (WSS won't work)
-            let tcp_trans = tcp::tokio::Transport::new(tcp_config.clone());
-            let desktop_trans = websocket::WsConfig::new(tcp_trans)
-                .or_transport(tcp::tokio::Transport::new(tcp_config));
+            let tcp_trans = tcp::tokio::Transport::new(tcp_config.clone())
+                .and_then(filter_ip(ip_filter.clone()));
+            let desktop_trans = websocket::WsConfig::new(tcp_trans).or_transport(
+                tcp::tokio::Transport::new(tcp_config).and_then(filter_ip(ip_filter)),
+            );
             EitherTransport::Right(desktop_trans)
         })
     } else {
@@ -127,3 +139,21 @@ pub fn build_transport(
 
     (transport, bandwidth)
 }
+
+/// Reject the connection if the remote ip is not allowed by the `ip_filter`.
+fn filter_ip<T>(
+    ip_filter: Arc<IpFilter>,
+) -> impl Fn(T, ConnectedPoint) -> future::Ready<io::Result<T>> + Clone {
+    move |stream, endpoint| {
+        let addr = endpoint.get_remote_address();
+        if ip_filter.is_allowed(addr) {
+            future::ok(stream)
+        } else {
+            debug!(target: "sub-libp2p", "Reject connection with {} by ip filter", addr);
+            future::err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("Address {} is not allowed by ip filter", addr),
+            ))
+        }
+    }
+}
@@ -154,6 +154,36 @@ impl RpcRequest for GetTxnsWithHash {
     }
 }
 
+/// Get the txns of a block by the indexes in the block body, for filling the compact block.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetBlockTxns {
+    pub block_id: HashValue,
+    pub indexes: Vec<u64>,
+}
+
+impl GetBlockTxns {
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl RpcRequest for GetBlockTxns {
+    fn verify(&self) -> Result<()> {
+        if self.indexes.len() as u64 > MAX_TXN_REQUEST_SIZE {
+            return Err(NetRpcError::new(
+                RpcErrorCode::BadRequest,
+                format!("max_size is too big > {}", MAX_TXN_REQUEST_SIZE),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetStateWithProof {
     pub state_root: HashValue,
@@ -225,6 +255,13 @@ pub trait NetworkRpc: Sized + Send + Sync + 'static {
         block_id: HashValue,
     ) -> BoxFuture<Result<Option<Vec<TransactionInfo>>>>;
 
+    ///Get txns of the block by the indexes in the block body
+    fn get_block_txns(
+        &self,
+        peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> BoxFuture<Result<Vec<Option<SignedUserTransaction>>>>;
+
     fn get_headers_by_number(
         &self,
         peer_id: PeerId,
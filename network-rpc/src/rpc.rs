@@ -11,9 +11,9 @@ use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
 use starcoin_crypto::HashValue;
 use starcoin_network_rpc_api::{
     gen_server, BlockBody, GetAccountState, GetAccumulatorNodeByNodeHash, GetBlockHeadersByNumber,
-    GetBlockIds, GetStateWithProof, GetStateWithTableItemProof, GetTableInfo, GetTxnsWithHash,
-    GetTxnsWithSize, Ping, RpcRequest, MAX_BLOCK_HEADER_REQUEST_SIZE, MAX_BLOCK_INFO_REQUEST_SIZE,
-    MAX_BLOCK_REQUEST_SIZE, MAX_TXN_REQUEST_SIZE,
+    GetBlockIds, GetBlockTxns, GetStateWithProof, GetStateWithTableItemProof, GetTableInfo,
+    GetTxnsWithHash, GetTxnsWithSize, Ping, RpcRequest, MAX_BLOCK_HEADER_REQUEST_SIZE,
+    MAX_BLOCK_INFO_REQUEST_SIZE, MAX_BLOCK_REQUEST_SIZE, MAX_TXN_REQUEST_SIZE,
 };
 use starcoin_service_registry::ServiceRef;
 use starcoin_state_api::{ChainStateAsyncService, StateWithProof, StateWithTableItemProof};
@@ -117,6 +117,27 @@ impl gen_server::NetworkRpc for NetworkRpcImpl {
         Box::pin(fut)
     }
 
+    fn get_block_txns(
+        &self,
+        _peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> BoxFuture<Result<Vec<Option<SignedUserTransaction>>>> {
+        let storage = self.storage.clone();
+        let fut = async move {
+            req.verify()?;
+            let txns = match storage.get_body(req.block_id)? {
+                Some(body) => body.transactions,
+                None => vec![],
+            };
+            Ok(req
+                .indexes
+                .into_iter()
+                .map(|index| txns.get(index as usize).cloned())
+                .collect())
+        };
+        Box::pin(fut)
+    }
+
     fn get_headers_by_number(
         &self,
         _peer_id: PeerId,
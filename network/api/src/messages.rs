@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::ServiceRequest;
 use starcoin_types::block::BlockInfo;
-use starcoin_types::compact_block::CompactBlock;
+use starcoin_types::compact_block::{CompactBlock, SaltedCompactBlock};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::SignedUserTransaction;
 use std::borrow::Cow;
@@ -21,6 +21,7 @@ use std::result::Result::Ok;
 pub const TXN_PROTOCOL_NAME: &str = "/starcoin/txn/1";
 pub const BLOCK_PROTOCOL_NAME: &str = "/starcoin/block/1";
 pub const ANNOUNCEMENT_PROTOCOL_NAME: &str = "/starcoin/announcement/1";
+pub const SALTED_COMPACT_BLOCK_PROTOCOL_NAME: &str = "/starcoin/cmpctblock/1";
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TransactionsMessage {
@@ -65,6 +66,34 @@ impl Sample for CompactBlockMessage {
     }
 }
 
+impl CompactBlockMessage {
+    pub fn into_salted(self, nonce: u64) -> SaltedCompactBlockMessage {
+        SaltedCompactBlockMessage::new(self.compact_block.into_salted(nonce), self.block_info)
+    }
+}
+
+/// Message of sending or receive block notification with salted short ids to network
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SaltedCompactBlockMessage {
+    pub compact_block: SaltedCompactBlock,
+    pub block_info: BlockInfo,
+}
+
+impl SaltedCompactBlockMessage {
+    pub fn new(compact_block: SaltedCompactBlock, block_info: BlockInfo) -> Self {
+        Self {
+            compact_block,
+            block_info,
+        }
+    }
+}
+
+impl Sample for SaltedCompactBlockMessage {
+    fn sample() -> Self {
+        Self::new(SaltedCompactBlock::sample(), BlockInfo::sample())
+    }
+}
+
 pub enum AnnouncementType {
     Txn,
 }
@@ -123,6 +152,7 @@ pub enum NotificationMessage {
     Transactions(TransactionsMessage),
     CompactBlock(Box<CompactBlockMessage>),
     Announcement(Announcement),
+    SaltedCompactBlock(Box<SaltedCompactBlockMessage>),
 }
 
 impl NotificationMessage {
@@ -137,6 +167,9 @@ impl NotificationMessage {
             ANNOUNCEMENT_PROTOCOL_NAME => {
                 NotificationMessage::Announcement(Announcement::decode(bytes)?)
             }
+            SALTED_COMPACT_BLOCK_PROTOCOL_NAME => NotificationMessage::SaltedCompactBlock(
+                Box::new(SaltedCompactBlockMessage::decode(bytes)?),
+            ),
             unknown_protocol => bail!(
                 "Unknown protocol {}'s message: {}",
                 unknown_protocol,
@@ -152,6 +185,9 @@ impl NotificationMessage {
             NotificationMessage::Announcement(msg) => {
                 (ANNOUNCEMENT_PROTOCOL_NAME.into(), msg.encode()?)
             }
+            NotificationMessage::SaltedCompactBlock(msg) => {
+                (SALTED_COMPACT_BLOCK_PROTOCOL_NAME.into(), msg.encode()?)
+            }
         })
     }
 
@@ -160,6 +196,7 @@ impl NotificationMessage {
             Self::Transactions(_) => TXN_PROTOCOL_NAME.into(),
             Self::CompactBlock(_) => BLOCK_PROTOCOL_NAME.into(),
             Self::Announcement(_) => ANNOUNCEMENT_PROTOCOL_NAME.into(),
+            Self::SaltedCompactBlock(_) => SALTED_COMPACT_BLOCK_PROTOCOL_NAME.into(),
         }
     }
 
@@ -169,6 +206,7 @@ impl NotificationMessage {
             BLOCK_PROTOCOL_NAME.into(),
             TXN_PROTOCOL_NAME.into(),
             ANNOUNCEMENT_PROTOCOL_NAME.into(),
+            SALTED_COMPACT_BLOCK_PROTOCOL_NAME.into(),
         ]
     }
 
@@ -192,6 +230,13 @@ impl NotificationMessage {
             _ => None,
         }
     }
+
+    pub fn into_salted_compact_block(self) -> Option<SaltedCompactBlockMessage> {
+        match self {
+            NotificationMessage::SaltedCompactBlock(message) => Some(*message),
+            _ => None,
+        }
+    }
 }
 
 /// Message for send or receive from peer
@@ -223,6 +268,16 @@ impl PeerMessage {
         Self::new(peer_id, NotificationMessage::Announcement(announcement))
     }
 
+    pub fn new_salted_compact_block(
+        peer_id: PeerId,
+        compact_block: SaltedCompactBlockMessage,
+    ) -> Self {
+        Self::new(
+            peer_id,
+            NotificationMessage::SaltedCompactBlock(Box::new(compact_block)),
+        )
+    }
+
     pub fn into_transactions(self) -> Option<PeerTransactionsMessage> {
         let peer_id = self.peer_id;
         self.notification
@@ -243,6 +298,13 @@ impl PeerMessage {
             .into_announcement()
             .map(|message| PeerAnnouncementMessage { peer_id, message })
     }
+
+    pub fn into_salted_compact_block(self) -> Option<PeerSaltedCompactBlockMessage> {
+        let peer_id = self.peer_id;
+        self.notification
+            .into_salted_compact_block()
+            .map(|message| PeerSaltedCompactBlockMessage { peer_id, message })
+    }
 }
 
 impl ServiceRequest for PeerMessage {
@@ -289,6 +351,26 @@ impl Into<PeerMessage> for PeerCompactBlockMessage {
     }
 }
 
+/// Message for combine PeerId and SaltedCompactBlockMessage
+#[derive(Clone, Debug)]
+pub struct PeerSaltedCompactBlockMessage {
+    pub peer_id: PeerId,
+    pub message: SaltedCompactBlockMessage,
+}
+
+impl PeerSaltedCompactBlockMessage {
+    pub fn new(peer_id: PeerId, message: SaltedCompactBlockMessage) -> Self {
+        Self { peer_id, message }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<PeerMessage> for PeerSaltedCompactBlockMessage {
+    fn into(self) -> PeerMessage {
+        PeerMessage::new_salted_compact_block(self.peer_id, self.message)
+    }
+}
+
 /// Message for combine PeerId and TransactionsMessage
 #[derive(Clone, Debug)]
 pub struct PeerAnnouncementMessage {
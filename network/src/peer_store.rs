@@ -0,0 +1,217 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Persist the known peers, their reputation and the banned peers across restarts.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use network_p2p::{NetworkService, NetworkWorker};
+use network_p2p_types::{Multiaddr, PeerId};
+use sc_peerset::ReputationChange;
+use serde::{Deserialize, Serialize};
+use starcoin_logger::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::network_p2p_handle::Networkp2pHandle;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PersistedPeer {
+    pub peer_id: network_api::PeerId,
+    pub addresses: Vec<Multiaddr>,
+    pub reputation: i32,
+    pub banned: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct PeerStore {
+    path: PathBuf,
+    /// Serialize the persists, the periodic one and the one triggered by banning a peer may overlap.
+    persist_lock: Arc<Mutex<()>>,
+}
+
+impl PeerStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            persist_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<PersistedPeer>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        Ok(serde_json::from_slice(&fs::read(&self.path)?)?)
+    }
+
+    /// Write to a temp file first, so a crash during saving does not corrupt the previous peers.
+    pub fn save(&self, peers: &[PersistedPeer]) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(peers)?)?;
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Take a snapshot of the known peers and banned peers from the network service.
+    pub async fn snapshot(network_service: Arc<NetworkService>) -> Vec<PersistedPeer> {
+        let reputations: HashMap<PeerId, i32> = network_service
+            .reputations(i32::MIN)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let banned_peers = network_service.banned_peers();
+        let mut peer_ids: HashSet<PeerId> = network_service.known_peers().await;
+        peer_ids.extend(reputations.keys());
+        peer_ids.extend(banned_peers.iter());
+        peer_ids.remove(network_service.peer_id());
+
+        let mut peers = Vec::with_capacity(peer_ids.len());
+        for peer_id in peer_ids {
+            let addresses = network_service.get_address(peer_id).await;
+            let banned = banned_peers.contains(&peer_id);
+            if addresses.is_empty() && !banned {
+                continue;
+            }
+            peers.push(PersistedPeer {
+                peer_id: peer_id.into(),
+                addresses,
+                reputation: reputations.get(&peer_id).copied().unwrap_or_default(),
+                banned,
+            });
+        }
+        peers
+    }
+
+    /// Restore the persisted peers to the network worker before it starts.
+    pub fn restore(worker: &mut NetworkWorker<Networkp2pHandle>, peers: Vec<PersistedPeer>) {
+        let network_service = worker.service().clone();
+        for peer in peers {
+            let peer_id: PeerId = peer.peer_id.into();
+            if &peer_id == network_service.peer_id() {
+                continue;
+            }
+            for addr in peer.addresses {
+                worker.add_known_address(peer_id, addr);
+            }
+            if peer.reputation != 0 {
+                network_service.report_peer(
+                    peer_id,
+                    ReputationChange::new(peer.reputation, "Restored reputation"),
+                );
+            }
+            if peer.banned {
+                network_service.ban_peer(peer_id, true);
+            }
+        }
+    }
+
+    /// Snapshot the peers and save them in background.
+    pub async fn persist(self, network_service: Arc<NetworkService>) {
+        let _guard = self.persist_lock.lock().await;
+        let peers = Self::snapshot(network_service).await;
+        match self.save(&peers) {
+            Ok(()) => debug!("Persist {} peers to {:?}", peers.len(), self.path),
+            Err(e) => error!("Persist peers to {:?} fail: {:?}", self.path, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_network_worker;
+    use starcoin_config::NodeConfig;
+    use starcoin_types::startup_info::ChainInfo;
+    use std::time::Duration;
+    use tokio::task::JoinHandle;
+
+    fn start_worker(
+        config: &NodeConfig,
+        peers: Vec<PersistedPeer>,
+    ) -> (Arc<NetworkService>, JoinHandle<std::io::Result<()>>) {
+        let (_peer_info, mut worker) =
+            build_network_worker(&config.network, ChainInfo::random(), vec![], None, None).unwrap();
+        PeerStore::restore(&mut worker, peers);
+        (worker.service().clone(), tokio::task::spawn(worker))
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let peer_store = PeerStore::new(dir.path().join("peers.json"));
+        assert!(peer_store.load().unwrap().is_empty());
+
+        let peers = vec![
+            PersistedPeer {
+                peer_id: network_api::PeerId::random(),
+                addresses: vec!["/ip4/127.0.0.1/tcp/9840".parse().unwrap()],
+                reputation: -100,
+                banned: false,
+            },
+            PersistedPeer {
+                peer_id: network_api::PeerId::random(),
+                addresses: vec![],
+                reputation: 0,
+                banned: true,
+            },
+        ];
+        peer_store.save(&peers).unwrap();
+        assert_eq!(peer_store.load().unwrap(), peers);
+    }
+
+    #[stest::test]
+    async fn test_restore_after_restart() {
+        let config = NodeConfig::random_for_test();
+        let peer_store = PeerStore::new(config.network.peers_file());
+        let banned_peer = PeerId::random();
+        let bad_peer = PeerId::random();
+        let bad_peer_addr: Multiaddr = "/memory/1".parse().unwrap();
+
+        let (service, worker) = start_worker(
+            &config,
+            vec![
+                PersistedPeer {
+                    peer_id: banned_peer.into(),
+                    addresses: vec![],
+                    reputation: 0,
+                    banned: true,
+                },
+                PersistedPeer {
+                    peer_id: bad_peer.into(),
+                    addresses: vec![bad_peer_addr.clone()],
+                    reputation: -1000,
+                    banned: false,
+                },
+            ],
+        );
+        // wait the worker to apply the restored reputation.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        peer_store
+            .save(&PeerStore::snapshot(service).await)
+            .unwrap();
+        worker.abort();
+        let _ = worker.await;
+
+        // restart the network with the saved peers.
+        let (service, _worker) = start_worker(&config, peer_store.load().unwrap());
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(service.banned_peers().contains(&banned_peer));
+        let peers = PeerStore::snapshot(service).await;
+        let find_peer = |peer_id: PeerId| {
+            let peer_id: network_api::PeerId = peer_id.into();
+            peers
+                .iter()
+                .find(|peer| peer.peer_id == peer_id)
+                .cloned()
+                .unwrap()
+        };
+        assert!(find_peer(banned_peer).banned);
+        let bad = find_peer(bad_peer);
+        assert!(bad.reputation < 0);
+        assert!(bad.addresses.contains(&bad_peer_addr));
+    }
+}
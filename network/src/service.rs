@@ -3,6 +3,7 @@
 
 use crate::network_metrics::NetworkMetrics;
 use crate::network_p2p_handle::Networkp2pHandle;
+use crate::peer_store::PeerStore;
 use crate::{build_network_worker, Announcement};
 use anyhow::{format_err, Result};
 use bcs_ext::BCSCodec;
@@ -16,6 +17,7 @@ use lru::LruCache;
 use network_api::messages::{
     AnnouncementType, BanPeer, GetPeerById, GetPeerSet, GetSelfPeer, NotificationMessage,
     PeerEvent, PeerMessage, PeerReputations, ReportReputation, TransactionsMessage,
+    SALTED_COMPACT_BLOCK_PROTOCOL_NAME,
 };
 use network_api::{
     BroadcastProtocolFilter, NetworkActor, PeerId, PeerInfo, PeerMessageHandler, RpcInfo,
@@ -30,14 +32,16 @@ use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceHandler, ServiceRef, ServiceRequest,
 };
 use starcoin_txpool_api::PropagateTransactions;
+use starcoin_types::block::{BlockHeader, BlockInfo};
 use starcoin_types::startup_info::{ChainInfo, ChainStatus};
 use starcoin_types::sync_status::SyncStatus;
 use starcoin_types::system_events::SyncStatusChangeEvent;
 use std::borrow::Cow;
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
 use std::ops::RangeInclusive;
 use std::sync::Arc;
+use std::time::Duration;
 
 const BARNARD_HARD_FORK_PEER_VERSION_STRING_PREFIX: &str = "barnard_rollback_block_fix";
 const BARNARD_HARD_FORK_VERSION: [i32; 3] = [1, 13, 7];
@@ -47,6 +51,8 @@ pub struct NetworkActorService {
     /// Keep in mind that these two instances must be identical all the time!
     worker: Option<NetworkWorker<Networkp2pHandle>>,
     inner: Inner,
+    /// None if the peer persistence is disabled.
+    peer_store: Option<PeerStore>,
 
     network_worker_handle: Option<AbortHandle>,
 }
@@ -63,19 +69,33 @@ impl NetworkActorService {
     where
         H: PeerMessageHandler + 'static,
     {
-        let (self_info, worker) = build_network_worker(
+        let (self_info, mut worker) = build_network_worker(
             &config.network,
             chain_info,
             config.network.supported_network_protocols(),
             rpc,
             config.metrics.registry().cloned(),
         )?;
+        let peer_store = if config.network.peer_persistence_enabled() {
+            let peer_store = PeerStore::new(config.network.peers_file());
+            match peer_store.load() {
+                Ok(peers) => {
+                    info!("Restore {} persisted peers", peers.len());
+                    PeerStore::restore(&mut worker, peers);
+                }
+                Err(e) => warn!("Load persisted peers fail: {:?}", e),
+            }
+            Some(peer_store)
+        } else {
+            None
+        };
         let service = worker.service().clone();
         //let self_info = PeerInfo::new(config.network.self_peer_id(), chain_info);
         let inner = Inner::new(config, self_info, service, peer_message_handler)?;
         Ok(Self {
             worker: Some(worker),
             inner,
+            peer_store,
             network_worker_handle: None,
         })
     }
@@ -83,6 +103,12 @@ impl NetworkActorService {
     pub fn network_service(&self) -> Arc<network_p2p::NetworkService> {
         self.inner.network_service.clone()
     }
+
+    fn persist_peers(&self, ctx: &mut ServiceContext<Self>) {
+        if let Some(peer_store) = self.peer_store.clone() {
+            ctx.spawn(peer_store.persist(self.network_service()));
+        }
+    }
 }
 
 impl ActorService for NetworkActorService {
@@ -97,13 +123,21 @@ impl ActorService for NetworkActorService {
         ctx.add_stream(event_stream);
         let (fut, abort_handle) = abortable(worker);
         self.network_worker_handle = Some(abort_handle);
-        ctx.spawn(fut.then(|result| async {
+        // Run the worker out of the service context, so it still serves the final peers persist after the service stopped.
+        tokio::task::spawn_local(fut.then(|result| async {
             match result {
                 Err(_abort) => info!("Network worker stopped."),
                 Ok(Err(e)) => error!("Network worker unexpect stopped for : {:?}", e),
                 Ok(Ok(_)) => {}
             }
         }));
+        if let Some(peer_store) = self.peer_store.clone() {
+            let network_service = self.network_service();
+            ctx.run_interval(
+                Duration::from_secs(self.inner.config.network.peers_persist_interval()),
+                move |ctx| ctx.spawn(peer_store.clone().persist(network_service.clone())),
+            );
+        }
         Ok(())
     }
 
@@ -111,7 +145,17 @@ impl ActorService for NetworkActorService {
         ctx.unsubscribe::<SyncStatusChangeEvent>();
         ctx.unsubscribe::<PropagateTransactions>();
         if let Some(abort_handle) = self.network_worker_handle.take() {
-            abort_handle.abort();
+            match self.peer_store.clone() {
+                // Persist the peers once more before stopping the worker, the service context is gone.
+                Some(peer_store) => {
+                    let network_service = self.network_service();
+                    tokio::task::spawn_local(async move {
+                        peer_store.persist(network_service).await;
+                        abort_handle.abort();
+                    });
+                }
+                None => abort_handle.abort(),
+            }
         }
         Ok(())
     }
@@ -251,10 +295,12 @@ impl EventHandler<Self, ReportReputation> for NetworkActorService {
 }
 
 impl EventHandler<Self, BanPeer> for NetworkActorService {
-    fn handle_event(&mut self, msg: BanPeer, _ctx: &mut ServiceContext<NetworkActorService>) {
+    fn handle_event(&mut self, msg: BanPeer, ctx: &mut ServiceContext<NetworkActorService>) {
         self.inner
             .network_service
             .ban_peer(msg.peer_id.into(), msg.ban);
+        // persist the ban immediately, so it is not lost if the node stops before next interval.
+        self.persist_peers(ctx);
     }
 }
 
@@ -432,6 +478,24 @@ impl Peer {
     pub fn get_peer_info(&self) -> &PeerInfo {
         &self.peer_info
     }
+
+    /// Update the chain status of the peer by the new block received from it, return the block id.
+    fn on_new_block(&mut self, block_header: BlockHeader, block_info: BlockInfo) -> HashValue {
+        let block_id = block_header.id();
+        debug!(
+            "Receive new compact block from {:?} with hash {:?}",
+            self.peer_info.peer_id(),
+            block_id
+        );
+        debug!(
+            "total_difficulty is {},peer_info is {:?}",
+            block_info.total_difficulty, self.peer_info
+        );
+        self.known_blocks.put(block_id, ());
+        self.peer_info
+            .update_chain_status(ChainStatus::new(block_header, block_info));
+        block_id
+    }
 }
 
 pub(crate) struct Inner {
@@ -541,23 +605,22 @@ impl Inner {
                     }
                 }
                 NotificationMessage::CompactBlock(compact_block_message) => {
-                    let block_header = compact_block_message.compact_block.header.clone();
-                    let total_difficulty = compact_block_message.block_info.total_difficulty;
-                    let block_id = block_header.id();
-                    debug!(
-                        "Receive new compact block from {:?} with hash {:?}",
-                        peer_id, block_id
-                    );
-                    debug!(
-                        "total_difficulty is {},peer_info is {:?}",
-                        total_difficulty, peer_info
+                    let block_id = peer_info.on_new_block(
+                        compact_block_message.compact_block.header.clone(),
+                        compact_block_message.block_info.clone(),
                     );
-                    peer_info.known_blocks.put(block_id, ());
-                    peer_info.peer_info.update_chain_status(ChainStatus::new(
-                        block_header,
+                    if self.self_peer.known_blocks.contains(&block_id) {
+                        None
+                    } else {
+                        self.self_peer.known_blocks.put(block_id, ());
+                        Some(notification)
+                    }
+                }
+                NotificationMessage::SaltedCompactBlock(compact_block_message) => {
+                    let block_id = peer_info.on_new_block(
+                        compact_block_message.compact_block.header.clone(),
                         compact_block_message.block_info.clone(),
-                    ));
-
+                    );
                     if self.self_peer.known_blocks.contains(&block_id) {
                         None
                     } else {
@@ -664,6 +727,14 @@ impl Inner {
         peer_id: PeerId,
         notification: NotificationMessage,
     ) -> Option<(Cow<'static, str>, Vec<u8>)> {
+        let notification = match notification {
+            NotificationMessage::CompactBlock(msg)
+                if self.is_supported(&peer_id, SALTED_COMPACT_BLOCK_PROTOCOL_NAME.into()) =>
+            {
+                NotificationMessage::SaltedCompactBlock(Box::new(msg.into_salted(rand::random())))
+            }
+            notification => notification,
+        };
         let (protocol_name, data) = notification
             .encode_notification()
             .expect("Encode notification message should ok");
@@ -685,6 +756,11 @@ impl Inner {
                     .known_blocks
                     .put(block.compact_block.header.id(), ());
             }
+            NotificationMessage::SaltedCompactBlock(block) => {
+                self.self_peer
+                    .known_blocks
+                    .put(block.compact_block.header.id(), ());
+            }
             NotificationMessage::Announcement(announcement) => {
                 if announcement.is_txn() {
                     announcement.ids().into_iter().for_each(|txn_id| {
@@ -696,6 +772,21 @@ impl Inner {
         Some((protocol_name, data))
     }
 
+    /// Return at most `count` peers with the highest total difficulty in the given peers.
+    fn best_peers(&self, peer_ids: &[PeerId], count: u32) -> Vec<PeerId> {
+        let mut peers = peer_ids
+            .iter()
+            .filter_map(|peer_id| self.peers.get(peer_id))
+            .map(|peer| &peer.peer_info)
+            .collect::<Vec<_>>();
+        peers.sort_by_key(|peer| Reverse(peer.total_difficulty()));
+        peers
+            .into_iter()
+            .take(count as usize)
+            .map(|peer| peer.peer_id())
+            .collect()
+    }
+
     pub(crate) fn prepare_broadcast(
         &mut self,
         notification: NotificationMessage,
@@ -756,15 +847,36 @@ impl Inner {
                         ..=self.config.network.max_peers_to_propagate().max(peers_len), // use max(max_peers_to_propagate,peers_len) to ensure range [min,max] , max > min.
                     filtered_peer_ids.iter(),
                 );
+                // High bandwidth mode, the best peers always receive the new block, for they are
+                // most likely to mine or relay the next block.
+                let high_bandwidth_peers = self.best_peers(
+                    &filtered_peer_ids,
+                    self.config.network.high_bandwidth_peers(),
+                );
+                let selected_peers = high_bandwidth_peers
+                    .into_iter()
+                    .chain(selected_peers.into_iter())
+                    .collect::<HashSet<_>>();
                 let peers_send_message = selected_peers.len();
+                let mut salted_message = None;
                 for peer_id in &selected_peers {
+                    let (real_protocol_name, data) =
+                        if self.is_supported(peer_id, SALTED_COMPACT_BLOCK_PROTOCOL_NAME.into()) {
+                            salted_message
+                                .get_or_insert_with(|| {
+                                    NotificationMessage::SaltedCompactBlock(Box::new(
+                                        msg.as_ref().clone().into_salted(rand::random()),
+                                    ))
+                                    .encode_notification()
+                                    .expect("Encode notification message should ok")
+                                })
+                                .clone()
+                        } else {
+                            (protocol_name.clone(), message.clone())
+                        };
                     let peer = self.peers.get_mut(peer_id).expect("peer should exists");
                     peer.known_blocks.put(id, ());
-                    prepare_to_broadcast.push((
-                        protocol_name.clone(),
-                        peer_id.clone(),
-                        message.clone(),
-                    ));
+                    prepare_to_broadcast.push((real_protocol_name, peer_id.clone(), data));
                 }
                 debug!(
                     "[network] broadcast new compact block message {:?} to {} peers, total_peers: {}, peers_after_known_hash_filter: {}, peers_after_protocol_filter: {}",
@@ -861,6 +973,9 @@ impl Inner {
             NotificationMessage::Announcement(_msg) => {
                 error!("[network] can not broadcast announcement message directly.");
             }
+            NotificationMessage::SaltedCompactBlock(_msg) => {
+                error!("[network] can not broadcast salted compact block message directly, broadcast the compact block instead.");
+            }
         }
         prepare_to_broadcast
     }
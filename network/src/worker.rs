@@ -7,7 +7,7 @@ use futures::channel::mpsc::channel;
 use futures::prelude::*;
 use log::{debug, error, info};
 use network_api::{PeerInfo, RpcInfo};
-use network_p2p::config::{RequestResponseConfig, TransportConfig};
+use network_p2p::config::{IpFilter, NonReservedPeerMode, RequestResponseConfig, TransportConfig};
 use network_p2p::{
     identity, NetworkConfiguration, NetworkWorker, NodeKeyConfig, Params, ProtocolId, Secret,
 };
@@ -76,6 +76,14 @@ pub fn build_network_worker(
     let boot_nodes = network_config.seeds();
 
     info!("Final bootstrap seeds: {:?}", boot_nodes);
+    let reserved_nodes = network_config.trusted_peers();
+    let non_reserved_mode = if network_config.reserved_only() {
+        info!("Only connect with trusted peers: {:?}", reserved_nodes);
+        NonReservedPeerMode::Deny
+    } else {
+        NonReservedPeerMode::Accept
+    };
+    let ip_filter = IpFilter::parse(&network_config.allow_ips(), &network_config.deny_ips())?;
     let self_info = PeerInfo::new(
         network_config.self_peer_id(),
         chain_info.clone(),
@@ -96,6 +104,9 @@ pub fn build_network_worker(
             .expect("decode network node key should success.");
             NodeKeyConfig::Ed25519(Secret::Input(secret))
         },
+        reserved_nodes,
+        non_reserved_mode,
+        ip_filter,
         in_peers: network_config.max_incoming_peers(),
         out_peers: network_config.max_outgoing_peers(),
         notifications_protocols: protocols,
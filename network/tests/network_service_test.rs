@@ -5,7 +5,8 @@ use futures::stream::StreamExt;
 use futures_timer::Delay;
 use network_api::messages::{
     Announcement, AnnouncementType, CompactBlockMessage, NotificationMessage, PeerMessage,
-    TransactionsMessage, ANNOUNCEMENT_PROTOCOL_NAME, TXN_PROTOCOL_NAME,
+    TransactionsMessage, ANNOUNCEMENT_PROTOCOL_NAME, SALTED_COMPACT_BLOCK_PROTOCOL_NAME,
+    TXN_PROTOCOL_NAME,
 };
 use network_api::{Multiaddr, NetworkService};
 use network_p2p_types::MultiaddrWithPeerId;
@@ -162,7 +163,7 @@ async fn test_event_notify_receive() {
     let mut receiver = network2.message_handler.channel();
     network1.service_ref.send_peer_message(msg_send.clone());
     let msg_receive = receiver.next().await.unwrap();
-    assert_eq!(msg_send.notification, msg_receive.notification);
+    assert_salted_compact_block(&msg_send.notification, msg_receive.notification);
 }
 
 #[stest::test]
@@ -185,7 +186,7 @@ async fn test_event_notify_receive_repeat_block() {
     network1.service_ref.send_peer_message(msg_send1.clone());
     network1.service_ref.send_peer_message(msg_send2.clone());
     let msg_receive1 = receiver.next().await.unwrap();
-    assert_eq!(msg_send1.notification, msg_receive1.notification);
+    assert_salted_compact_block(&msg_send1.notification, msg_receive1.notification);
 
     //repeat message is filter, so expect timeout error.
     let msg_receive2 = async_std::future::timeout(Duration::from_secs(2), receiver.next()).await;
@@ -239,6 +240,13 @@ async fn test_event_notify_receive_repeat_transaction() {
     assert!(msg_receive3.is_err());
 }
 
+/// The compact block is sent as the salted compact block to the peer which supports it.
+fn assert_salted_compact_block(send: &NotificationMessage, receive: NotificationMessage) {
+    let send = send.clone().into_compact_block().unwrap();
+    let receive = receive.into_salted_compact_block().unwrap();
+    assert_eq!(send.into_salted(receive.compact_block.nonce), receive);
+}
+
 fn mock_block_info(total_difficulty: U256) -> BlockInfo {
     BlockInfo::new(
         HashValue::random(),
@@ -268,10 +276,10 @@ async fn test_event_broadcast() {
     node1.service_ref.broadcast(notification.clone());
 
     let msg_receive2 = receiver2.next().await.unwrap();
-    assert_eq!(notification, msg_receive2.notification);
+    assert_salted_compact_block(&notification, msg_receive2.notification);
 
     let msg_receive3 = receiver3.next().await.unwrap();
-    assert_eq!(notification, msg_receive3.notification);
+    assert_salted_compact_block(&notification, msg_receive3.notification);
 
     //repeat broadcast
     node2.service_ref.broadcast(notification.clone());
@@ -368,3 +376,39 @@ async fn test_filter_protocol() {
         msg_3.notification.protocol_name()
     );
 }
+
+#[stest::test]
+async fn test_filter_salted_compact_block_protocol() {
+    let node_config_1 = Arc::new(NodeConfig::random_for_test());
+    let service1 = build_network_with_config(node_config_1.clone(), None)
+        .await
+        .unwrap();
+
+    let nodes = vec![MultiaddrWithPeerId::new(
+        node_config_1.network.listen(),
+        service1.peer_id().into(),
+    )];
+    let mut node_config_2 = NodeConfig::random_for_test();
+    node_config_2.network.seeds = nodes.into();
+    node_config_2.network.unsupported_protocols =
+        Some(vec![SALTED_COMPACT_BLOCK_PROTOCOL_NAME.to_string()]);
+    let service2 = build_network_with_config(Arc::new(node_config_2), None)
+        .await
+        .unwrap();
+    Delay::new(Duration::from_secs(2)).await;
+    assert!(service2.service_ref.is_connected(service1.peer_id()).await);
+    assert!(service1.service_ref.is_connected(service2.peer_id()).await);
+
+    let mut receiver2 = service2.message_handler.channel();
+
+    let block = Block::new(BlockHeader::random(), BlockBody::new_empty());
+    let notification = NotificationMessage::CompactBlock(Box::new(CompactBlockMessage::new(
+        CompactBlock::new(block),
+        mock_block_info(10.into()),
+    )));
+    service1.service_ref.broadcast(notification.clone());
+
+    // the peer does not support the salted compact block receive the legacy compact block.
+    let msg_2 = receiver2.next().await.unwrap();
+    assert_eq!(notification, msg_2.notification);
+}
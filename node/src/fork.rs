@@ -0,0 +1,316 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fork the chain of a remote node at a block, for rehearsing transactions on the state of a live
+//! network with a local dev node.
+//!
+//! Only the blocks around the fork block are fetched when forking, the state nodes and table infos
+//! are fetched from the remote node lazily when missing in local storage, and cached locally.
+//! The fork block is re-sealed locally with the chain id of the local network and the dummy
+//! consensus in its state, so the forked chain can be mined and used by the normal tools of the
+//! local network. The history before the fork block is not available in local storage.
+
+use anyhow::{ensure, format_err, Result};
+use futures::executor::block_on;
+use jsonrpc_client_transports::{transports::http, RpcChannel, RpcError};
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_genesis::{Genesis, GenesisError};
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::chain::ChainApiClient;
+use starcoin_rpc_api::state::StateApiClient;
+use starcoin_state_api::{AccountStateReader, ChainStateWriter};
+use starcoin_state_tree::StateNode;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::block_info::BlockInfoStore;
+use starcoin_storage::state_prune::StatePruneStore;
+use starcoin_storage::{BlockStore, IntoSuper, RemoteStateStore, Storage, Store};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{Block, BlockIdAndNumber, BlockInfo, BlockNumber};
+use starcoin_types::startup_info::{ChainInfo, StartupInfo};
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_config::genesis_address;
+use starcoin_vm_types::genesis_config::{ChainId, ConsensusStrategy};
+use starcoin_vm_types::move_resource::MoveResource;
+use starcoin_vm_types::on_chain_config::{ConsensusConfig, OnChainConfig};
+use starcoin_vm_types::on_chain_resource::Epoch;
+use starcoin_vm_types::state_store::table::TableInfo;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// The rpc client of the remote node a local node forked from.
+pub struct ForkClient {
+    url: String,
+    runtime: Option<Runtime>,
+    chain_client: ChainApiClient,
+    state_client: StateApiClient,
+}
+
+impl ForkClient {
+    pub fn connect(url: &str) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name("fork-client")
+            .enable_all()
+            .build()?;
+        let rpc_url = url.to_string();
+        let channel: RpcChannel =
+            Self::block_on(
+                &runtime,
+                async move { http::connect(rpc_url.as_str()).await },
+            )
+            .map_err(|e| format_err!("Connect to fork url {} error: {}", url, e))?;
+        Ok(Self {
+            url: url.to_string(),
+            runtime: Some(runtime),
+            chain_client: channel.clone().into(),
+            state_client: channel.into(),
+        })
+    }
+
+    /// The request is executed by the runtime of the client, and the current thread is blocked
+    /// until it is done, so the client can be called in both sync and async context, such as the
+    /// state store in the actors.
+    fn block_on<F, T>(runtime: &Runtime, fut: F) -> Result<T, RpcError>
+    where
+        F: Future<Output = Result<T, RpcError>> + Send + 'static,
+        T: Send + 'static,
+    {
+        block_on(runtime.spawn(fut)).map_err(|e| RpcError::Client(e.to_string()))?
+    }
+
+    fn call<F, T>(&self, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T, RpcError>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let runtime = self
+            .runtime
+            .as_ref()
+            .ok_or_else(|| format_err!("The client of fork url {} is closed.", self.url))?;
+        Self::block_on(runtime, fut)
+            .map_err(|e| format_err!("Call fork url {} error: {}", self.url, e))
+    }
+
+    pub fn head_block_number(&self) -> Result<BlockNumber> {
+        let client = self.chain_client.clone();
+        let chain_info = self.call(async move { client.info().await })?;
+        Ok(chain_info.head.number.0)
+    }
+
+    pub fn get_block_by_number(&self, number: BlockNumber) -> Result<Block> {
+        let client = self.chain_client.clone();
+        let block_view = self
+            .call(async move { client.get_block_by_number(number, None).await })?
+            .ok_or_else(|| format_err!("Can not find block {} from {}", number, self.url))?;
+        let block_hash = block_view.header.block_hash;
+        let block = Block::try_from(block_view)?;
+        ensure!(
+            block.id() == block_hash,
+            "Block {} from {} mismatch its hash {}",
+            number,
+            self.url,
+            block_hash
+        );
+        Ok(block)
+    }
+
+    pub fn get_block_info_by_number(&self, number: BlockNumber) -> Result<BlockInfo> {
+        let client = self.chain_client.clone();
+        self.call(async move { client.get_block_info_by_number(number).await })?
+            .map(|view| view.into_info())
+            .ok_or_else(|| format_err!("Can not find block info {} from {}", number, self.url))
+    }
+}
+
+impl Drop for ForkClient {
+    fn drop(&mut self) {
+        // the client may be dropped in async context, where a runtime can not be dropped.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl RemoteStateStore for ForkClient {
+    fn get_state_node(&self, hash: &HashValue) -> Result<Option<StateNode>> {
+        let client = self.state_client.clone();
+        let hash = *hash;
+        let node = self
+            .call(async move { client.get_state_node_by_node_hash(hash).await })?
+            .map(StateNode);
+        if let Some(node) = node.as_ref() {
+            let node_hash = node.node_hash()?;
+            ensure!(
+                node_hash == hash,
+                "State node {} from {} mismatch its hash {}",
+                hash,
+                self.url,
+                node_hash
+            );
+        }
+        Ok(node)
+    }
+
+    fn get_table_info(&self, address: AccountAddress) -> Result<Option<TableInfo>> {
+        let client = self.state_client.clone();
+        let table_info = self.call(async move { client.get_table_info(address).await })?;
+        Ok(table_info.map(Into::into))
+    }
+}
+
+/// Fork the chain of the remote node on a new database, or check the forked database.
+pub fn init_fork_storage(
+    config: &NodeConfig,
+    storage: Arc<Storage>,
+    client: &ForkClient,
+) -> Result<(ChainInfo, Genesis)> {
+    let remote_genesis = client.get_block_by_number(0)?;
+    let (chain_info, genesis) = match storage.get_chain_info()? {
+        Some(chain_info) => {
+            let fork_block = storage.get_fork_block()?.ok_or_else(|| {
+                format_err!(
+                    "The database is not forked from a remote node, fork on a new database."
+                )
+            })?;
+            if let Some(number) = config.storage.fork_block() {
+                ensure!(
+                    number == fork_block.number,
+                    "The database is forked at block {}, mismatch the fork block {}",
+                    fork_block.number,
+                    number
+                );
+            }
+            let genesis = Genesis::load_from_dir(config.data_dir())?
+                .ok_or_else(|| GenesisError::GenesisNotExist("data_dir".to_owned()))?;
+            ensure!(
+                genesis.block().id() == chain_info.genesis_hash()
+                    && genesis.block().id() == remote_genesis.id(),
+                "The database is forked from chain {}, mismatch the chain {} of {}",
+                chain_info.genesis_hash(),
+                remote_genesis.id(),
+                client.url
+            );
+            (chain_info, genesis)
+        }
+        None => {
+            let number = match config.storage.fork_block() {
+                Some(number) => number,
+                None => client.head_block_number()?,
+            };
+            let genesis = Genesis::new_with_block(remote_genesis)?;
+            let chain_info = fork_at(config, storage, client, &genesis, number)?;
+            genesis.save(config.data_dir())?;
+            (chain_info, genesis)
+        }
+    };
+    // the time service of dev network does not start from the current time.
+    config
+        .net()
+        .time_service()
+        .adjust(chain_info.status().head().timestamp());
+    Ok((chain_info, genesis))
+}
+
+fn fork_at(
+    config: &NodeConfig,
+    storage: Arc<Storage>,
+    client: &ForkClient,
+    genesis: &Genesis,
+    number: BlockNumber,
+) -> Result<ChainInfo> {
+    ensure!(number > 0, "Can not fork at the genesis block.");
+    info!("Fork chain from {} at block {}", client.url, number);
+    let block = client.get_block_by_number(number)?;
+    let block_info = client.get_block_info_by_number(number)?;
+    let parent_info = client.get_block_info_by_number(number - 1)?;
+    ensure!(
+        block_info.block_id == block.id()
+            && block.header().parent_hash() == parent_info.block_id
+            && block.header().block_accumulator_root()
+                == parent_info.block_accumulator_info.accumulator_root,
+        "Block info of block {} from {} mismatch its header",
+        number,
+        client.url
+    );
+
+    let (state_root, state_seqs) =
+        reseal_state(config, storage.clone(), block.header().state_root())?;
+    let header = block
+        .header()
+        .as_builder()
+        .with_state_root(state_root)
+        .with_chain_id(config.net().chain_id())
+        .build();
+    let block = Block {
+        header,
+        body: block.body,
+    };
+    let block_accumulator = MerkleAccumulator::new_with_info(
+        parent_info.block_accumulator_info,
+        storage.get_accumulator_store(AccumulatorStoreType::Block),
+    );
+    block_accumulator.append(&[block.id()])?;
+    block_accumulator.flush()?;
+    let block_info = BlockInfo::new(
+        block.id(),
+        block_info.total_difficulty,
+        block_info.txn_accumulator_info,
+        block_accumulator.get_info(),
+    );
+    let fork_block = BlockIdAndNumber::new(block.id(), number);
+    storage.save_block_state_journal(number, fork_block.id, state_seqs)?;
+
+    storage.save_genesis(genesis.block().id())?;
+    storage.commit_block(genesis.block().clone())?;
+    storage.commit_block(block)?;
+    storage.save_block_info(block_info)?;
+    storage.save_fork_block(fork_block)?;
+    storage.save_startup_info(StartupInfo::new(fork_block.id))?;
+    info!(
+        "Forked at block {}, the local fork block is {:?}",
+        number, fork_block.id
+    );
+    storage
+        .get_chain_info()?
+        .ok_or_else(|| format_err!("ChainInfo should exist after fork."))
+}
+
+/// Set the chain id to the local network, and the consensus to dummy, both the current epoch and
+/// the consensus config for the next epochs.
+/// Return the new state root and the journaled state node batches.
+fn reseal_state(
+    config: &NodeConfig,
+    storage: Arc<Storage>,
+    state_root: HashValue,
+) -> Result<(HashValue, Vec<u64>)> {
+    let statedb = ChainStateDB::new(storage.into_super_arc(), Some(state_root));
+    let reader = AccountStateReader::new(&statedb);
+    let mut epoch = reader
+        .get_resource::<Epoch>(genesis_address())?
+        .ok_or_else(|| format_err!("Epoch is none."))?;
+    let mut consensus_config = reader
+        .get_on_chain_config::<ConsensusConfig>()?
+        .ok_or_else(|| format_err!("ConsensusConfig is none."))?;
+    epoch.set_strategy(ConsensusStrategy::Dummy);
+    consensus_config.strategy = ConsensusStrategy::Dummy.value();
+
+    statedb.set(
+        &AccessPath::resource_access_path(genesis_address(), Epoch::struct_tag()),
+        bcs_ext::to_bytes(&epoch)?,
+    )?;
+    statedb.set(
+        &ConsensusConfig::config_id().access_path(),
+        bcs_ext::to_bytes(&consensus_config)?,
+    )?;
+    statedb.set(
+        &AccessPath::resource_access_path(genesis_address(), ChainId::struct_tag()),
+        bcs_ext::to_bytes(&config.net().chain_id())?,
+    )?;
+    let state_root = statedb.commit()?;
+    let state_seqs = statedb.flush()?;
+    Ok((state_root, state_seqs))
+}
@@ -27,6 +27,7 @@ use std::time::Duration;
 use tokio::runtime::Runtime;
 
 pub mod crash_handler;
+pub mod fork;
 mod genesis_parameter_resolve;
 mod metrics;
 pub mod network_service_factory;
@@ -1,13 +1,14 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::fork::{self, ForkClient};
 use crate::metrics::{MetricsPushActorService, MetricsServerActorService};
 use crate::network_service_factory::NetworkServiceFactory;
 use crate::peer_message_handler::NodePeerMessageHandler;
 use crate::rpc_service_factory::RpcServiceFactory;
 use crate::NodeHandle;
 use actix::prelude::*;
-use anyhow::{format_err, Result};
+use anyhow::{ensure, format_err, Result};
 use futures::channel::oneshot;
 use futures::executor::block_on;
 use futures_timer::Delay;
@@ -15,8 +16,8 @@ use network_api::{PeerProvider, PeerSelector, PeerStrategy};
 use starcoin_account_service::{AccountEventService, AccountService, AccountStorage};
 use starcoin_block_relayer::BlockRelayer;
 use starcoin_chain_notify::ChainNotifyHandlerService;
-use starcoin_chain_service::ChainReaderService;
-use starcoin_config::NodeConfig;
+use starcoin_chain_service::{ChainReaderService, StatePruneService};
+use starcoin_config::{NodeConfig, StatePruneMode};
 use starcoin_genesis::{Genesis, GenesisError};
 use starcoin_logger::prelude::*;
 use starcoin_logger::structured_log::init_slog_logger;
@@ -43,7 +44,7 @@ use starcoin_storage::db_storage::DBStorage;
 use starcoin_storage::errors::StorageInitError;
 use starcoin_storage::metrics::StorageMetrics;
 use starcoin_storage::storage::StorageInstance;
-use starcoin_storage::{BlockStore, Storage};
+use starcoin_storage::{BlockStore, RemoteStateStore, Storage};
 use starcoin_stratum::service::{StratumService, StratumServiceFactory};
 use starcoin_stratum::stratum::{Stratum, StratumFactory};
 use starcoin_sync::announcement::AnnouncementService;
@@ -310,10 +311,67 @@ impl NodeService {
         storage_instance.barnard_hard_fork(config.clone())?;
         storage_instance.dragon_hard_fork(config.clone())?;
         let upgrade_time = SystemTime::now().duration_since(start_time)?;
-        let storage = Arc::new(Storage::new(storage_instance)?);
+        let fork_client = config
+            .storage
+            .fork_url()
+            .map(ForkClient::connect)
+            .transpose()?
+            .map(Arc::new);
+        let storage = Arc::new(
+            Storage::new(storage_instance)?
+                .with_account_txn_index(config.storage.enable_account_txn_index())
+                .with_state_pruning(config.storage.state_prune_mode() == StatePruneMode::Pruned)?
+                .with_remote_state_store(
+                    fork_client
+                        .clone()
+                        .map(|client| client as Arc<dyn RemoteStateStore>),
+                )
+                .with_impersonated_accounts(config.net().impersonated_accounts()),
+        );
         registry.put_shared(storage.clone()).await?;
-        let (chain_info, genesis) =
-            Genesis::init_and_check_storage(config.net(), storage.clone(), config.data_dir())?;
+        let (mut chain_info, genesis) = match fork_client.as_ref() {
+            Some(fork_client) => {
+                fork::init_fork_storage(config.as_ref(), storage.clone(), fork_client.as_ref())?
+            }
+            None => {
+                ensure!(
+                    storage.get_fork_block()?.is_none(),
+                    "The database is forked from a remote node, start the node with the fork url."
+                );
+                Genesis::init_and_check_storage(config.net(), storage.clone(), config.data_dir())?
+            }
+        };
+        if let Some(snapshot_dir) = config.sync.bootstrap_from_snapshot() {
+            if chain_info.status().head().number() == 0 {
+                let trusted_pivot = config
+                    .sync
+                    .snapshot_trusted_pivot()
+                    .ok_or_else(|| format_err!("snapshot-trusted-pivot is required"))?;
+                let pivot = starcoin_sync::snapshot::import_snapshot(
+                    storage.clone(),
+                    genesis.block().id(),
+                    trusted_pivot,
+                    snapshot_dir,
+                )?;
+                info!(
+                    "Bootstrap from snapshot {} done, head: ({:?},{})",
+                    snapshot_dir.display(),
+                    pivot.id,
+                    pivot.number
+                );
+                chain_info = Genesis::init_and_check_storage(
+                    config.net(),
+                    storage.clone(),
+                    config.data_dir(),
+                )?
+                .0;
+            } else {
+                info!(
+                    "Skip bootstrap from snapshot {}, the node is not new.",
+                    snapshot_dir.display()
+                );
+            }
+        }
 
         info!(
             "Start node with chain info: {}, number {} upgrade_time cost {} secs, ",
@@ -346,6 +404,10 @@ impl NodeService {
 
         registry.register::<ChainReaderService>().await?;
 
+        if config.storage.state_prune_mode() == StatePruneMode::Pruned {
+            registry.register::<StatePruneService>().await?;
+        }
+
         registry.register::<ChainNotifyHandlerService>().await?;
 
         registry.register::<BlockConnectorService>().await?;
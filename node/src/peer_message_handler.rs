@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use network_api::messages::{
-    NotificationMessage, PeerCompactBlockMessage, PeerMessage, PeerTransactionsMessage,
+    NotificationMessage, PeerCompactBlockMessage, PeerMessage, PeerSaltedCompactBlockMessage,
+    PeerTransactionsMessage,
 };
 use network_api::PeerMessageHandler;
 use starcoin_block_relayer::BlockRelayer;
@@ -66,6 +67,24 @@ impl PeerMessageHandler for NodePeerMessageHandler {
                     }
                 }
             }
+            NotificationMessage::SaltedCompactBlock(message) => {
+                if let Err(e) = self
+                    .block_relayer
+                    .notify(PeerSaltedCompactBlockMessage::new(
+                        peer_message.peer_id,
+                        *message,
+                    ))
+                {
+                    match e {
+                        TrySendError::Full(_) => {
+                            warn!("Handle PeerCmpctBlock error, BlockRelayer is too busy.");
+                        }
+                        TrySendError::Disconnected(_) => {
+                            error!("Handle PeerCmpctBlock error, BlockRelayer is shutdown.");
+                        }
+                    }
+                }
+            }
             NotificationMessage::Announcement(message) => {
                 if let Err(e) = self
                     .announcement_service
@@ -12,8 +12,9 @@ use starcoin_genesis::Genesis;
 use starcoin_logger::LoggerHandle;
 use starcoin_miner::MinerService;
 use starcoin_network::NetworkServiceRef;
+use starcoin_rpc_server::graphql::GraphQLContext;
 use starcoin_rpc_server::module::{
-    AccountRpcImpl, ChainRpcImpl, ContractRpcImpl, DebugRpcImpl, MinerRpcImpl,
+    AccountRpcImpl, ChainRpcImpl, ContractRpcImpl, DebugRpcImpl, DevRpcImpl, MinerRpcImpl,
     NetworkManagerRpcImpl, NodeManagerRpcImpl, NodeRpcImpl, PubSubImpl, PubSubService,
     StateRpcImpl, SyncManagerRpcImpl, TxPoolRpcImpl,
 };
@@ -21,6 +22,7 @@ use starcoin_rpc_server::service::RpcService;
 use starcoin_service_registry::{ServiceContext, ServiceFactory};
 use starcoin_state_service::ChainStateService;
 use starcoin_storage::Storage;
+use starcoin_stratum::stratum::Stratum;
 use starcoin_sync::sync::SyncService;
 use starcoin_txpool::TxPoolService;
 use std::sync::Arc;
@@ -54,11 +56,18 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
                 )
             });
         let txpool_service = ctx.get_shared::<TxPoolService>()?;
-        let txpool_api = Some(TxPoolRpcImpl::new(txpool_service.clone()));
+        let pubsub_service = ctx.service_ref::<PubSubService>()?.clone();
+        let txpool_api = Some(TxPoolRpcImpl::new(
+            txpool_service.clone(),
+            Some(pubsub_service.clone()),
+        ));
 
+        let chain_service = ctx.service_ref_opt::<ChainReaderService>()?.cloned();
         let state_api = ctx
             .service_ref_opt::<ChainStateService>()?
-            .map(|service_ref| StateRpcImpl::new(service_ref.clone(), storage.clone()));
+            .map(|service_ref| {
+                StateRpcImpl::new(service_ref.clone(), chain_service.clone(), storage.clone())
+            });
         let chain_state_service = ctx.service_ref::<ChainStateService>()?.clone();
         let account_service = ctx.service_ref_opt::<AccountService>()?.cloned();
         let account_api = account_service.clone().map(|service_ref| {
@@ -69,31 +78,52 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
                 chain_state_service.clone(),
             )
         });
-        let pubsub_service = ctx.service_ref::<PubSubService>()?.clone();
         let pubsub_api = Some(PubSubImpl::new(pubsub_service));
+        let vm_metrics = ctx.get_shared_opt::<VMMetrics>()?;
         let debug_api = Some(DebugRpcImpl::new(
             config.clone(),
             log_handler,
             ctx.bus_ref().clone(),
+            chain_service.clone(),
+            storage.clone(),
+            vm_metrics.clone(),
         ));
+        let stratum_service = ctx.service_ref_opt::<Stratum>()?.cloned();
         let miner_api = ctx
             .service_ref_opt::<MinerService>()?
-            .map(|service_ref| MinerRpcImpl::new(service_ref.clone()));
+            .map(|service_ref| MinerRpcImpl::new(service_ref.clone(), stratum_service));
 
+        let dev_api = match (
+            chain_service.clone(),
+            ctx.service_ref_opt::<NodeService>()?.cloned(),
+        ) {
+            (Some(chain_service), Some(node_service)) => DevRpcImpl::new(
+                config.clone(),
+                ctx.bus_ref().clone(),
+                chain_service,
+                node_service,
+                txpool_service.clone(),
+            ),
+            _ => None,
+        };
+
+        let graphql_context = chain_service.clone().map(|chain_service| {
+            GraphQLContext::new(chain_service, chain_state_service.clone(), storage.clone())
+        });
         let contract_api = {
-            let vm_metrics = ctx.get_shared_opt::<VMMetrics>()?;
             let dev_playground = PlaygroudService::new(storage.clone(), vm_metrics);
             ContractRpcImpl::new(
                 config.clone(),
                 account_service,
                 txpool_service,
                 chain_state_service,
+                chain_service,
                 dev_playground,
                 storage,
             )
         };
 
-        Ok(RpcService::new_with_api(
+        let service = RpcService::new_with_api(
             config,
             node_api,
             node_manager_api,
@@ -107,6 +137,14 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
             debug_api,
             miner_api,
             Some(contract_api),
-        ))
+        );
+        let service = match dev_api {
+            Some(dev_api) => service.with_dev_api(dev_api),
+            None => service,
+        };
+        Ok(match graphql_context {
+            Some(graphql_context) => service.with_graphql(graphql_context),
+            None => service,
+        })
     }
 }
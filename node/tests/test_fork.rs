@@ -0,0 +1,78 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::executor::block_on;
+use starcoin_chain_service::ChainAsyncService;
+use starcoin_config::{temp_dir, BuiltinNetworkID, NodeConfig, StarcoinOpt};
+use starcoin_node::{run_node, NodeHandle};
+use starcoin_node_api::node_service::NodeAsyncService;
+use starcoin_storage::BlockStore;
+use starcoin_types::block::Block;
+use std::sync::Arc;
+
+fn start_node(config: Arc<NodeConfig>) -> NodeHandle {
+    let handle = run_node(config).unwrap();
+    let node_service = handle.node_service();
+    block_on(async { node_service.stop_pacemaker().await }).unwrap();
+    handle
+}
+
+fn head_block(handle: &NodeHandle) -> Block {
+    let chain_service = handle.chain_service().unwrap();
+    block_on(async { chain_service.main_head_block().await }).unwrap()
+}
+
+#[stest::test]
+fn test_fork_node() {
+    let mut remote_config = NodeConfig::random_for_test();
+    remote_config.network.disable_seed = true;
+    let remote_config = Arc::new(remote_config);
+    let remote = start_node(remote_config.clone());
+    let remote_blocks = (0..3)
+        .map(|_| remote.generate_block().unwrap())
+        .collect::<Vec<_>>();
+    let fork_url = format!(
+        "http://127.0.0.1:{}",
+        remote_config.rpc.get_http_address().unwrap().port
+    );
+
+    let data_dir = temp_dir();
+    let mut opt = StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(data_dir.path().to_path_buf()),
+        ..StarcoinOpt::default()
+    };
+    opt.storage.fork_url = Some(fork_url);
+    opt.storage.fork_block = Some(2);
+    let mut config = NodeConfig::load_with_opt(&opt).unwrap();
+    config.network.disable_seed = true;
+    let config = Arc::new(config);
+
+    // fork at block 2, the fork block is re-sealed with the local chain id.
+    let local = start_node(config.clone());
+    let fork_block = head_block(&local);
+    assert_eq!(fork_block.header().number(), 2);
+    assert_eq!(
+        fork_block.header().parent_hash(),
+        remote_blocks[0].header().id()
+    );
+    assert_eq!(fork_block.header().chain_id(), config.net().chain_id());
+    assert_eq!(
+        local.storage().get_fork_block().unwrap().unwrap().id,
+        fork_block.id()
+    );
+
+    // the missing state is fetched from the remote node when executing the new block.
+    let block = local.generate_block().unwrap();
+    assert_eq!(block.header().number(), 3);
+    assert_eq!(block.header().parent_hash(), fork_block.id());
+    local.stop().unwrap();
+
+    // reopen the forked database, and continue the forked chain.
+    let local = start_node(config);
+    assert_eq!(head_block(&local).id(), block.id());
+    let next_block = local.generate_block().unwrap();
+    assert_eq!(next_block.header().parent_hash(), block.id());
+    local.stop().unwrap();
+    remote.stop().unwrap();
+}
@@ -5,7 +5,7 @@ pub use self::gen_client::Client as AccountClient;
 use crate::types::{SignedMessageView, StrView, TransactionRequest};
 use crate::FutureResult;
 use openrpc_derive::openrpc;
-use starcoin_account_api::AccountInfo;
+use starcoin_account_api::{AccountInfo, MnemonicAccountInfo};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::sign_message::SigningMessage;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
@@ -94,6 +94,21 @@ pub trait AccountApi {
         address: AccountAddress,
         password: Option<String>,
     ) -> FutureResult<AccountInfo>;
+    /// Create a hd wallet with a new mnemonic, return the mnemonic and the first account.
+    #[rpc(name = "account.create_from_mnemonic")]
+    fn create_from_mnemonic(&self, password: String) -> FutureResult<MnemonicAccountInfo>;
+
+    /// Restore the hd wallet from mnemonic, return the first account.
+    #[rpc(name = "account.import_mnemonic")]
+    fn import_mnemonic(&self, mnemonic: String, password: String) -> FutureResult<AccountInfo>;
+
+    /// Derive the account at `index` from the hd wallet.
+    #[rpc(name = "account.derive")]
+    fn derive(&self, index: u32, password: String) -> FutureResult<AccountInfo>;
+
+    /// Return the mnemonic of the hd wallet.
+    #[rpc(name = "account.export_mnemonic")]
+    fn export_mnemonic(&self, password: String) -> FutureResult<String>;
 }
 
 #[test]
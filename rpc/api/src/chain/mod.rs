@@ -13,6 +13,7 @@ use openrpc_derive::openrpc;
 use schemars::{self, JsonSchema};
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::HashValue;
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::block::BlockNumber;
 use starcoin_vm_types::access_path::AccessPath;
 
@@ -101,6 +102,18 @@ pub trait ChainApi {
         max_size: u64,
     ) -> FutureResult<Vec<TransactionInfoView>>;
 
+    /// Get transaction info list of the transactions sent by `sender`, ordered by sequence number.
+    /// `start_seq` is the sender's sequence number, if absent, start from the first transaction, or the last one if `reverse`.
+    /// Require the node to enable the account transaction index by storage config.
+    #[rpc(name = "chain.get_transactions_by_sender")]
+    fn get_transactions_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> FutureResult<Vec<TransactionInfoView>>;
+
     /// Get TransactionInfoWithProof, if the block with `block_hash` or transaction with `transaction_global_index` do not exists, return None.
     /// if `event_index` is some, also return the EventWithProof in current transaction event_root
     /// if `access_path` is some, also return the StateWithProof in current transaction state_root
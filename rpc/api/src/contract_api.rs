@@ -1,4 +1,5 @@
 pub use self::gen_client::Client as ContractClient;
+use crate::state::StateQueryOption;
 use crate::types::{
     AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, DryRunOutputView,
     DryRunTransactionRequest, FunctionIdView, ModuleIdView, StrView, StructTagView,
@@ -14,7 +15,11 @@ use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
 pub trait ContractApi {
     /// get code of module
     #[rpc(name = "contract.get_code")]
-    fn get_code(&self, module_id: StrView<ModuleId>) -> FutureResult<Option<StrView<Vec<u8>>>>;
+    fn get_code(
+        &self,
+        module_id: StrView<ModuleId>,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Option<StrView<Vec<u8>>>>;
 
     /// get resource data of `addr`
     #[rpc(name = "contract.get_resource")]
@@ -22,38 +27,62 @@ pub trait ContractApi {
         &self,
         addr: AccountAddress,
         resource_type: StrView<StructTag>,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<Option<AnnotatedMoveStructView>>;
 
     /// Call a move contract, return returned move values.
     #[rpc(name = "contract.call")]
-    fn call(&self, call: ContractCall) -> FutureResult<Vec<AnnotatedMoveValueView>>;
+    fn call(
+        &self,
+        call: ContractCall,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Vec<AnnotatedMoveValueView>>;
 
     /// Call a move contract, return move values.
     #[rpc(name = "contract.call_v2")]
-    fn call_v2(&self, call: ContractCall) -> FutureResult<Vec<DecodedMoveValue>>;
+    fn call_v2(
+        &self,
+        call: ContractCall,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Vec<DecodedMoveValue>>;
 
     #[rpc(name = "contract.dry_run")]
     fn dry_run(&self, txn: DryRunTransactionRequest) -> FutureResult<DryRunOutputView>;
 
-    /// Dry run RawUserTransaction, the raw_txn parameter is RawUserTransaction's hex
+    /// Dry run RawUserTransaction, the raw_txn parameter is RawUserTransaction's hex,
+    /// return the call tree with its gas charges if trace is true.
     #[rpc(name = "contract.dry_run_raw")]
     fn dry_run_raw(
         &self,
         raw_txn: String,
         sender_public_key: StrView<AccountPublicKey>,
+        trace: Option<bool>,
     ) -> FutureResult<DryRunOutputView>;
     #[rpc(name = "contract.resolve_function")]
-    fn resolve_function(&self, function_id: FunctionIdView) -> FutureResult<FunctionABI>;
+    fn resolve_function(
+        &self,
+        function_id: FunctionIdView,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<FunctionABI>;
     #[rpc(name = "contract.resolve_module_function_index")]
     fn resolve_module_function_index(
         &self,
         module_id: ModuleIdView,
         function_index: u16,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<FunctionABI>;
     #[rpc(name = "contract.resolve_struct")]
-    fn resolve_struct(&self, struct_tag: StructTagView) -> FutureResult<StructInstantiation>;
+    fn resolve_struct(
+        &self,
+        struct_tag: StructTagView,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<StructInstantiation>;
     #[rpc(name = "contract.resolve_module")]
-    fn resolve_module(&self, module_id: ModuleIdView) -> FutureResult<ModuleABI>;
+    fn resolve_module(
+        &self,
+        module_id: ModuleIdView,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<ModuleABI>;
 }
 #[test]
 fn test() {
@@ -3,10 +3,12 @@
 
 use jsonrpc_core::Result;
 use openrpc_derive::openrpc;
+use starcoin_crypto::HashValue;
 use starcoin_logger::LogPattern;
 
 pub use self::gen_client::Client as DebugClient;
-use crate::types::FactoryAction;
+use crate::types::{FactoryAction, TransactionTraceView};
+use crate::FutureResult;
 #[openrpc]
 pub trait DebugApi {
     /// Update log level, if logger_name is none, update global log level.
@@ -36,6 +38,15 @@ pub trait DebugApi {
     /// Get vm concurrency level
     #[rpc(name = "debug.get_concurrency_level")]
     fn get_concurrency_level(&self) -> Result<usize>;
+
+    /// Re-execute the main chain transaction on the state of its parent block, without writing the state.
+    /// Return the write set, events, vm status, and the call tree with its gas charges if trace is true.
+    #[rpc(name = "debug.trace_transaction")]
+    fn trace_transaction(
+        &self,
+        txn_hash: HashValue,
+        trace: Option<bool>,
+    ) -> FutureResult<TransactionTraceView>;
 }
 #[test]
 fn test() {
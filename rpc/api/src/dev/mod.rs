@@ -0,0 +1,54 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+use jsonrpc_core::Result;
+use openrpc_derive::openrpc;
+use starcoin_crypto::HashValue;
+use starcoin_types::account_address::AccountAddress;
+
+pub use self::gen_client::Client as DevClient;
+use crate::FutureResult;
+
+/// The api for control the block time and chain state of dev network.
+#[openrpc]
+pub trait DevApi {
+    /// Set the timestamp(in milliseconds) of the next block, should be greater than the head block timestamp.
+    #[rpc(name = "dev.set_next_block_timestamp")]
+    fn set_next_block_timestamp(&self, timestamp: u64) -> FutureResult<()>;
+
+    /// Increase the node time by `millis` milliseconds, return the node time after increased.
+    #[rpc(name = "dev.increase_time")]
+    fn increase_time(&self, millis: u64) -> Result<u64>;
+
+    /// Mine `n` blocks, the empty block is also mined, return the hashes of the mined blocks.
+    #[rpc(name = "dev.mine")]
+    fn mine(&self, n: u64) -> FutureResult<Vec<HashValue>>;
+
+    /// Take a snapshot of the chain head and node time, return the snapshot id.
+    #[rpc(name = "dev.snapshot")]
+    fn snapshot(&self) -> FutureResult<u64>;
+
+    /// Revert the chain head and node time to the snapshot, the snapshot and the snapshots taken after it are dropped.
+    #[rpc(name = "dev.revert")]
+    fn revert(&self, snapshot_id: u64) -> FutureResult<()>;
+
+    /// Impersonate the account, the transactions of the account are accepted without its private key.
+    /// Return false if the account is already impersonated.
+    #[rpc(name = "dev.impersonate_account")]
+    fn impersonate_account(&self, address: AccountAddress) -> Result<bool>;
+
+    /// Stop impersonating the account, return false if the account is not impersonated.
+    #[rpc(name = "dev.stop_impersonating_account")]
+    fn stop_impersonating_account(&self, address: AccountAddress) -> Result<bool>;
+
+    /// Submit the hex of an unsigned raw transaction whose sender is impersonated, return the transaction hash.
+    #[rpc(name = "dev.submit_impersonated_transaction")]
+    fn submit_impersonated_transaction(&self, raw_txn: String) -> FutureResult<HashValue>;
+}
+
+#[test]
+fn test() {
+    let schema = self::gen_schema();
+    let j = serde_json::to_string_pretty(&schema).unwrap();
+    println!("{}", j);
+}
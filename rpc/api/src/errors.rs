@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use jsonrpc_core::{Error, ErrorCode, Value};
+use starcoin_crypto::HashValue;
 use std::fmt;
 
+pub const REORG_TOO_DEEP_ERROR_CODE: i64 = -40000;
+
 pub fn invalid_params<T: fmt::Debug>(param: &str, details: T) -> Error {
     Error {
         code: ErrorCode::InvalidParams,
@@ -11,3 +14,15 @@ pub fn invalid_params<T: fmt::Debug>(param: &str, details: T) -> Error {
         data: Some(Value::String(format!("{:?}", details))),
     }
 }
+
+/// The main chain reorg is too deep to notify the retracted blocks to the subscription.
+pub fn reorg_too_deep(old_head: HashValue, new_head: HashValue, max_depth: u64) -> Error {
+    Error {
+        code: ErrorCode::ServerError(REORG_TOO_DEEP_ERROR_CODE),
+        message: format!(
+            "Chain reorg from {} to {} is deeper than {} blocks, resync from the new head",
+            old_head, new_head, max_depth
+        ),
+        data: None,
+    }
+}
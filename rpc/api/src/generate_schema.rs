@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
 use starcoin_rpc_api::{
-    account, chain, contract_api, debug, miner, network_manager, node, node_manager, state,
+    account, chain, contract_api, debug, dev, miner, network_manager, node, node_manager, state,
     sync_manager, txpool,
 };
 use std::fs::{create_dir_all, File};
@@ -41,6 +41,7 @@ fn main() {
         chain,
         contract_api,
         debug,
+        dev,
         miner,
         network_manager,
         node,
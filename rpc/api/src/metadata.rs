@@ -12,6 +12,8 @@ pub struct Metadata {
     /// Request PubSub Session
     pub session: Option<Arc<Session>>,
     pub user: Option<String>,
+    /// The bearer token of the request.
+    pub token: Option<String>,
 }
 
 impl Metadata {
@@ -19,6 +21,7 @@ impl Metadata {
         Self {
             session: Some(session),
             user: None,
+            token: None,
         }
     }
 }
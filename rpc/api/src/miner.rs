@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 pub use self::gen_client::Client as MinerClient;
-use crate::types::MintedBlockView;
+use crate::types::{MiningWorkerView, MintedBlockView};
 use crate::FutureResult;
 use openrpc_derive::openrpc;
 use starcoin_types::system_events::MintBlockEvent;
@@ -20,6 +20,9 @@ pub trait MinerApi {
     /// get current mining job
     #[rpc(name = "mining.get_job")]
     fn get_job(&self) -> FutureResult<Option<MintBlockEvent>>;
+    /// get the share statistics of the stratum workers
+    #[rpc(name = "mining.workers")]
+    fn workers(&self) -> FutureResult<Vec<MiningWorkerView>>;
 }
 
 #[test]
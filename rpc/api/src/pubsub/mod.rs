@@ -15,6 +15,11 @@ use crate::types::pubsub;
 /// $ netcat localhost 3030
 /// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["newPendingTransactions"]}
 /// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["events", {}]}
+/// ```
+/// The `newHeads` and `events` subscriptions notify the blocks retracted by a reorg with
+/// `removed`. If the reorg is deeper than 256 blocks, the retracted blocks are not notified,
+/// an error with code -40000 is sent instead, followed by the new head,
+/// the subscriber should resync from the new head.
 #[allow(clippy::needless_return)]
 #[rpc(server)]
 pub trait StarcoinPubSub {
@@ -12,6 +12,7 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use starcoin_crypto::HashValue;
+use starcoin_types::block::BlockNumber;
 use starcoin_types::language_storage::{ModuleId, StructTag};
 use starcoin_types::{
     access_path::AccessPath, account_address::AccountAddress, account_state::AccountState,
@@ -20,7 +21,11 @@ use starcoin_vm_types::state_store::table::TableHandle;
 #[openrpc]
 pub trait StateApi {
     #[rpc(name = "state.get")]
-    fn get(&self, access_path: AccessPath) -> FutureResult<Option<Vec<u8>>>;
+    fn get(
+        &self,
+        access_path: AccessPath,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Option<Vec<u8>>>;
 
     /// Return state from StateTree storage directly by tree node key.
     #[rpc(name = "state.get_state_node_by_node_hash")]
@@ -28,24 +33,38 @@ pub trait StateApi {
 
     /// Return the Resource Or Code at the `access_path`, and provide a State Proof.
     #[rpc(name = "state.get_with_proof")]
-    fn get_with_proof(&self, access_path: AccessPath) -> FutureResult<StateWithProofView>;
+    fn get_with_proof(
+        &self,
+        access_path: AccessPath,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<StateWithProofView>;
 
     /// Same as `state.get_with_proof` but return `StateWithProof` in BCS serialize bytes.
     #[rpc(name = "state.get_with_proof_raw")]
-    fn get_with_proof_raw(&self, access_path: AccessPath) -> FutureResult<StrView<Vec<u8>>>;
+    fn get_with_proof_raw(
+        &self,
+        access_path: AccessPath,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<StrView<Vec<u8>>>;
 
     #[rpc(name = "state.get_account_state")]
-    fn get_account_state(&self, address: AccountAddress) -> FutureResult<Option<AccountState>>;
+    fn get_account_state(
+        &self,
+        address: AccountAddress,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Option<AccountState>>;
 
     #[rpc(name = "state.get_account_state_set")]
     fn get_account_state_set(
         &self,
         address: AccountAddress,
         state_root: Option<HashValue>,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<Option<AccountStateSetView>>;
 
+    /// Return the state root of the latest block, or the block in `option`.
     #[rpc(name = "state.get_state_root")]
-    fn get_state_root(&self) -> FutureResult<HashValue>;
+    fn get_state_root(&self, option: Option<StateQueryOption>) -> FutureResult<HashValue>;
 
     /// Return the Resource Or Code at the `access_path` and provide a State Proof at `state_root`
     #[rpc(name = "state.get_with_proof_by_root")]
@@ -73,6 +92,7 @@ pub trait StateApi {
         &self,
         handle: TableHandle,
         key: Vec<u8>,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<StateWithTableItemProofView>;
 
     /// Return the TableItem value  and provide a State Proof at `state_root`
@@ -118,11 +138,24 @@ pub trait StateApi {
     ) -> FutureResult<ListCodeView>;
 }
 
+/// Read the state at a block of the main chain, default is the latest block.
+/// Only one of `block_number` and `block_hash` can be specified.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct StateQueryOption {
+    pub block_number: Option<BlockNumber>,
+    pub block_hash: Option<HashValue>,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq, JsonSchema)]
 #[serde(default)]
 pub struct GetResourceOption {
     pub decode: bool,
     pub state_root: Option<HashValue>,
+    /// Read the state at the main chain block of the number, conflicts with `state_root`
+    pub block_number: Option<BlockNumber>,
+    /// Read the state at the main chain block of the hash, conflicts with `state_root`
+    pub block_hash: Option<HashValue>,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq, JsonSchema)]
@@ -130,6 +163,10 @@ pub struct GetResourceOption {
 pub struct GetCodeOption {
     pub resolve: bool,
     pub state_root: Option<HashValue>,
+    /// Read the state at the main chain block of the number, conflicts with `state_root`
+    pub block_number: Option<BlockNumber>,
+    /// Read the state at the main chain block of the hash, conflicts with `state_root`
+    pub block_hash: Option<HashValue>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq, JsonSchema)]
@@ -138,6 +175,10 @@ pub struct ListResourceOption {
     pub decode: bool,
     /// The state tree root, default is the latest block state root
     pub state_root: Option<HashValue>,
+    /// Read the state at the main chain block of the number, conflicts with `state_root`
+    pub block_number: Option<BlockNumber>,
+    /// Read the state at the main chain block of the hash, conflicts with `state_root`
+    pub block_hash: Option<HashValue>,
     pub start_index: usize,
     pub max_size: usize,
     pub resource_types: Option<Vec<StructTagView>>,
@@ -148,6 +189,8 @@ impl Default for ListResourceOption {
         ListResourceOption {
             decode: false,
             state_root: None,
+            block_number: None,
+            block_hash: None,
             start_index: 0,
             max_size: std::usize::MAX,
             resource_types: None,
@@ -161,6 +204,10 @@ pub struct ListCodeOption {
     pub resolve: bool,
     /// The state tree root, default is the latest block state root
     pub state_root: Option<HashValue>,
+    /// Read the state at the main chain block of the number, conflicts with `state_root`
+    pub block_number: Option<BlockNumber>,
+    /// Read the state at the main chain block of the hash, conflicts with `state_root`
+    pub block_hash: Option<HashValue>,
     //TODO support filter by type and pagination
 }
 #[test]
@@ -1,4 +1,4 @@
-use crate::types::{ContractCall, TransactionArgumentView, TypeTagView};
+use crate::types::{CallFrameView, ContractCall, TransactionArgumentView, TypeTagView};
 use starcoin_vm_types::token::stc::stc_type_tag;
 use starcoin_vm_types::transaction_argument::TransactionArgument;
 use std::path::PathBuf;
@@ -34,6 +34,38 @@ fn test_deserialize() {
     println!("{:?}", v);
 }
 
+#[test]
+fn test_call_frame_to_folded_stacks() {
+    let frame = |name: &str, gas: &[(&str, u64)], callees: Vec<CallFrameView>| CallFrameView {
+        name: name.to_string(),
+        is_native: false,
+        gas_by_category: gas
+            .iter()
+            .map(|(category, gas)| (category.to_string(), (*gas).into()))
+            .collect(),
+        storage_accesses: vec![],
+        callees,
+    };
+    let root = frame(
+        "0x1::TransferScripts::peer_to_peer_v2",
+        &[("intrinsic", 100), ("call", 2)],
+        vec![
+            frame(
+                "0x1::Account::pay_from",
+                &[],
+                vec![frame("0x1::Account::withdraw", &[("global", 7)], vec![])],
+            ),
+            frame("0x1::Account::deposit", &[("local", 3)], vec![]),
+        ],
+    );
+    assert_eq!(
+        root.to_folded_stacks(),
+        "0x1::TransferScripts::peer_to_peer_v2 102\n\
+         0x1::TransferScripts::peer_to_peer_v2;0x1::Account::pay_from;0x1::Account::withdraw 7\n\
+         0x1::TransferScripts::peer_to_peer_v2;0x1::Account::deposit 3"
+    );
+}
+
 fn assert_that_version_control_has_no_unstaged_changes() {
     let output = Command::new("git")
         .arg("status")
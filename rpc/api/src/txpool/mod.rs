@@ -6,6 +6,7 @@ use openrpc_derive::openrpc;
 use starcoin_types::transaction::SignedUserTransaction;
 
 pub use self::gen_client::Client as TxPoolClient;
+use crate::types::pubsub::TransactionStatusNotification;
 use crate::types::{SignedUserTransactionView, StrView};
 use starcoin_crypto::HashValue;
 use starcoin_txpool_api::TxPoolStatus;
@@ -43,6 +44,16 @@ pub trait TxPoolApi {
     /// or `None` if there are no pending transactions from that sender in txpool.
     #[rpc(name = "txpool.state")]
     fn state(&self) -> FutureResult<TxPoolStatus>;
+
+    /// Wait until the txn is dropped, replaced, expired, or finalized after `confirmations` blocks,
+    /// `timeout` in seconds, default 60, at most 600.
+    #[rpc(name = "txpool.wait_for_transaction")]
+    fn wait_for_transaction(
+        &self,
+        txn_hash: HashValue,
+        timeout: Option<u64>,
+        confirmations: Option<u64>,
+    ) -> FutureResult<TransactionStatusNotification>;
 }
 #[test]
 fn test() {
@@ -20,6 +20,7 @@ use starcoin_abi_decoder::{
 use starcoin_abi_types::ModuleABI;
 use starcoin_accumulator::proof::AccumulatorProof;
 use starcoin_crypto::{CryptoMaterialError, HashValue, ValidCryptoMaterialStringExt};
+use starcoin_gas::{CallFrame, StorageAccess};
 use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue};
 use starcoin_service_registry::ServiceRequest;
 use starcoin_state_api::{StateProof, StateWithProof, StateWithTableItemProof};
@@ -63,6 +64,22 @@ pub struct MintedBlockView {
     pub block_hash: HashValue,
 }
 
+/// The share statistics of a stratum worker.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct MiningWorkerView {
+    pub worker_id: String,
+    pub login: String,
+    pub agent: String,
+    /// The current share difficulty of the worker.
+    #[schemars(with = "String")]
+    pub share_difficulty: U256,
+    pub accepted_shares: u64,
+    pub stale_shares: u64,
+    pub invalid_shares: u64,
+    /// Estimated hashes per second, by the accepted shares.
+    pub hashrate: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceView {
     pub raw: StrView<Vec<u8>>,
@@ -242,6 +259,9 @@ pub struct DryRunTransactionRequest {
     pub transaction: TransactionRequest,
     /// Sender's public key
     pub sender_public_key: StrView<AccountPublicKey>,
+    /// Return the call tree with the gas charged per frame, instruction category and storage access.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<bool>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, JsonSchema)]
@@ -1188,6 +1208,93 @@ pub struct DryRunOutputView {
     pub explained_status: VmStatusExplainView,
     #[serde(flatten)]
     pub txn_output: TransactionOutputView,
+    /// Only returned when the dry run is traced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<CallFrameView>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionTraceView {
+    pub transaction_hash: HashValue,
+    pub block_hash: HashValue,
+    pub block_number: StrView<u64>,
+    /// Transaction index in block, the block metadata transaction is the first one.
+    pub transaction_index: u32,
+    #[serde(flatten)]
+    pub output: DryRunOutputView,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CallFrameView {
+    pub name: String,
+    pub is_native: bool,
+    /// Gas charged by the frame itself excluding the callees, in internal gas units.
+    pub gas_by_category: BTreeMap<String, StrView<u64>>,
+    pub storage_accesses: Vec<StorageAccessView>,
+    pub callees: Vec<CallFrameView>,
+}
+
+impl CallFrameView {
+    pub fn self_gas(&self) -> u64 {
+        self.gas_by_category
+            .values()
+            .fold(0u64, |acc, gas| acc.saturating_add(gas.0))
+    }
+
+    /// Render the call tree as folded stacks, the input format of flamegraph tools.
+    /// Each frame with self gas is a line of `root;caller;callee gas`.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut lines = vec![];
+        self.fold_stacks(None, &mut lines);
+        lines.join("\n")
+    }
+
+    fn fold_stacks(&self, parent: Option<&str>, lines: &mut Vec<String>) {
+        let stack = match parent {
+            Some(parent) => format!("{};{}", parent, self.name),
+            None => self.name.clone(),
+        };
+        let gas = self.self_gas();
+        if gas > 0 {
+            lines.push(format!("{} {}", stack, gas));
+        }
+        for callee in &self.callees {
+            callee.fold_stacks(Some(stack.as_str()), lines);
+        }
+    }
+}
+
+impl From<CallFrame> for CallFrameView {
+    fn from(frame: CallFrame) -> Self {
+        Self {
+            name: frame.name,
+            is_native: frame.is_native,
+            gas_by_category: frame
+                .gas_by_category
+                .into_iter()
+                .map(|(category, gas)| (category.to_string(), gas.into()))
+                .collect(),
+            storage_accesses: frame.storage_accesses.into_iter().map(Into::into).collect(),
+            callees: frame.callees.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StorageAccessView {
+    pub kind: String,
+    pub type_tag: TypeTagView,
+    pub gas: StrView<u64>,
+}
+
+impl From<StorageAccess> for StorageAccessView {
+    fn from(access: StorageAccess) -> Self {
+        Self {
+            kind: access.kind.to_string(),
+            type_tag: access.ty.into(),
+            gas: access.gas.into(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::errors;
-use crate::types::{BlockView, TransactionEventResponse, TypeTagView};
+use crate::types::{
+    BlockView, StrView, TransactionEventResponse, TransactionStatusView, TypeTagView,
+};
 use jsonrpc_core::error::Error as JsonRpcError;
 use schemars::{self, JsonSchema};
 use serde::de::Error;
@@ -10,6 +12,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{from_value, Value};
 use starcoin_crypto::HashValue;
 use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::BlockNumber;
 use starcoin_types::event::EventKey;
 use starcoin_types::filter::Filter;
 use starcoin_types::system_events::MintBlockEvent;
@@ -30,17 +33,123 @@ pub enum Kind {
     NewPendingTransactions,
     /// New block for minting
     NewMintBlock,
+    /// Transaction status subscription.
+    TransactionStatus,
 }
 
 /// Subscription result.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Result {
     /// New block.
-    Block(Box<BlockView>),
+    Block(Box<BlockNotification>),
     /// Transaction hash
     TransactionHash(Vec<HashValue>),
-    Event(Box<TransactionEventResponse>),
+    Event(Box<EventNotification>),
     MintBlock(Box<MintBlockEvent>),
+    TransactionStatus(Box<TransactionStatusNotification>),
+}
+
+/// Block of `newHeads` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockNotification {
+    #[serde(flatten)]
+    pub block: BlockView,
+    /// The block is retracted from the main chain by a reorg.
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// Event of `events` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventNotification {
+    #[serde(flatten)]
+    pub event: TransactionEventResponse,
+    /// The block of the event is retracted from the main chain by a reorg.
+    #[serde(default)]
+    pub removed: bool,
+    /// Pass it back to resume the subscription after the event.
+    pub cursor: EventCursor,
+}
+
+/// The position of an event in the main chain.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct EventCursor {
+    pub block_number: StrView<BlockNumber>,
+    pub transaction_global_index: StrView<u64>,
+    pub event_index: u32,
+}
+
+impl EventCursor {
+    pub fn new(block_number: BlockNumber, transaction_global_index: u64, event_index: u32) -> Self {
+        Self {
+            block_number: block_number.into(),
+            transaction_global_index: transaction_global_index.into(),
+            event_index,
+        }
+    }
+
+    /// Whether the event at `cursor` is after `self` in the main chain.
+    pub fn is_before(&self, cursor: &EventCursor) -> bool {
+        (
+            self.block_number.0,
+            self.transaction_global_index.0,
+            self.event_index,
+        ) < (
+            cursor.block_number.0,
+            cursor.transaction_global_index.0,
+            cursor.event_index,
+        )
+    }
+}
+
+/// Status of `transactionStatus` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionStatusNotification {
+    pub txn_hash: HashValue,
+    #[serde(flatten)]
+    pub status: TransactionLifecycle,
+}
+
+impl TransactionStatusNotification {
+    pub fn new(txn_hash: HashValue, status: TransactionLifecycle) -> Self {
+        Self { txn_hash, status }
+    }
+}
+
+/// The lifecycle of a transaction, from the txpool to the main chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionLifecycle {
+    /// The transaction is waiting in the txpool.
+    Pending,
+    /// The transaction is rejected or removed by the txpool.
+    Dropped { reason: String },
+    /// Replaced by a transaction with the same sender and sequence number but higher gas price.
+    Replaced,
+    /// The transaction is expired before included.
+    Expired,
+    /// The transaction is included in a main chain block.
+    Included {
+        block_hash: HashValue,
+        block_number: StrView<BlockNumber>,
+        vm_status: TransactionStatusView,
+    },
+    /// The block including the transaction is `confirmations` blocks deep in the main chain.
+    Finalized {
+        block_hash: HashValue,
+        block_number: StrView<BlockNumber>,
+        vm_status: TransactionStatusView,
+    },
+}
+
+impl TransactionLifecycle {
+    /// Whether the transaction will not change its status any more, unless resubmitted or reorged.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(
+            self,
+            TransactionLifecycle::Pending | TransactionLifecycle::Included { .. }
+        )
+    }
 }
 
 impl Serialize for Result {
@@ -53,6 +162,7 @@ impl Serialize for Result {
             Result::Event(ref evt) => evt.serialize(serializer),
             Result::TransactionHash(ref hash) => hash.serialize(serializer),
             Result::MintBlock(ref block) => block.serialize(serializer), // Result::SyncState(ref sync) => sync.serialize(serializer),
+            Result::TransactionStatus(ref status) => status.serialize(serializer),
         }
     }
 }
@@ -64,6 +174,10 @@ pub enum Params {
     None,
     /// Log parameters.
     Events(EventParams),
+    /// New block parameters.
+    NewHeads(NewHeadsParams),
+    /// Transaction status parameters.
+    TransactionStatus(TransactionStatusParams),
 }
 
 impl Default for Params {
@@ -82,6 +196,25 @@ impl<'a> Deserialize<'a> for Params {
         if v.is_null() {
             return Ok(Params::None);
         }
+        // only `confirmations` is the parameters of new heads.
+        let is_new_heads = v
+            .as_object()
+            .map(|params| !params.is_empty() && params.keys().all(|key| key == "confirmations"))
+            .unwrap_or(false);
+        if is_new_heads {
+            return from_value(v)
+                .map(Params::NewHeads)
+                .map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)));
+        }
+        let is_txn_status = v
+            .as_object()
+            .map(|params| params.contains_key("txn_hash"))
+            .unwrap_or(false);
+        if is_txn_status {
+            return from_value(v)
+                .map(Params::TransactionStatus)
+                .map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)));
+        }
         // Err(D::Error::custom("Invalid Pub-Sub parameters"));
         from_value(v)
             .map(Params::Events)
@@ -89,16 +222,43 @@ impl<'a> Deserialize<'a> for Params {
     }
 }
 
+/// Parameters of `transactionStatus` subscription.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+pub struct TransactionStatusParams {
+    pub txn_hash: HashValue,
+    /// Notify `finalized` after the including block is `confirmations` blocks deep in the main chain, default 0.
+    #[serde(default)]
+    pub confirmations: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash, Default)]
 pub struct EventParams {
     #[serde(flatten)]
     pub filter: EventFilter,
     #[serde(default)]
     pub decode: bool,
+    /// Notify the events of a block after it is `confirmations` blocks deep in the main chain, default 0.
+    #[serde(default)]
+    pub confirmations: Option<u64>,
+    /// Replay the matching events of the main chain from `from_block` before the new events.
+    #[serde(default)]
+    pub replay: bool,
+    /// Resume the subscription after the event of the cursor, the matching events after it are
+    /// replayed before the new events.
+    #[serde(default)]
+    pub cursor: Option<EventCursor>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NewHeadsParams {
+    /// Notify a block after it is `confirmations` blocks deep in the main chain, default 0.
+    #[serde(default)]
+    pub confirmations: Option<u64>,
 }
 
 /// Filter
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash, JsonSchema)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct EventFilter {
     /// From Block
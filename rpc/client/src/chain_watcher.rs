@@ -9,6 +9,7 @@ use jsonrpc_core_client::RpcError;
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
+use starcoin_rpc_api::types::pubsub::BlockNotification;
 use starcoin_rpc_api::types::{BlockHeaderView, BlockView};
 use starcoin_types::block::BlockNumber;
 use std::collections::HashMap;
@@ -47,7 +48,7 @@ impl ChainWatcher {
 
     fn start_subscribe(&mut self, client: PubSubClient, ctx: &mut Context<Self>) {
         let inner_client = client.clone();
-        async move { inner_client.subscribe_new_block().await }
+        async move { inner_client.subscribe_new_block(None).await }
             .into_actor(self)
             .then(|res, act, ctx| {
                 match res {
@@ -137,12 +138,14 @@ impl Handler<WatchTxn> for ChainWatcher {
     }
 }
 
-type BlockEvent = Result<BlockView, RpcError>;
+type BlockEvent = Result<BlockNotification, RpcError>;
 impl actix::StreamHandler<BlockEvent> for ChainWatcher {
     fn handle(&mut self, item: BlockEvent, _ctx: &mut Self::Context) {
         match item {
+            // the watchers of a retracted block have been notified.
+            Ok(b) if b.removed => {}
             Ok(b) => {
-                let b: ThinHeadBlock = b.into();
+                let b: ThinHeadBlock = b.block.into();
                 if let Some(responders) = self.watched_blocks.remove(&b.header.number.0) {
                     for r in responders {
                         let _ = r.send(Ok(b.clone()));
@@ -19,7 +19,7 @@ use network_types::peer_info::Multiaddr;
 use parking_lot::Mutex;
 use serde_json::Value;
 use starcoin_abi_types::{FunctionABI, ModuleABI, StructInstantiation};
-use starcoin_account_api::AccountInfo;
+use starcoin_account_api::{AccountInfo, MnemonicAccountInfo};
 use starcoin_crypto::HashValue;
 use starcoin_logger::{prelude::*, LogPattern};
 use starcoin_rpc_api::chain::{
@@ -30,21 +30,24 @@ use starcoin_rpc_api::service::RpcAsyncService;
 use starcoin_rpc_api::state::{
     GetCodeOption, GetResourceOption, ListCodeOption, ListResourceOption,
 };
-use starcoin_rpc_api::types::pubsub::EventFilter;
+use starcoin_rpc_api::types::pubsub::{
+    BlockNotification, EventNotification, EventParams, TransactionStatusNotification,
+    TransactionStatusParams,
+};
 use starcoin_rpc_api::types::{
     AccountStateSetView, AnnotatedMoveStructView, BlockHeaderView, BlockInfoView, BlockView,
     ChainId, ChainInfoView, CodeView, ContractCall, DecodedMoveValue, DryRunOutputView,
     DryRunTransactionRequest, FactoryAction, FunctionIdView, ListCodeView, ListResourceView,
-    MintedBlockView, ModuleIdView, PeerInfoView, ResourceView, SignedMessageView,
+    MiningWorkerView, MintedBlockView, ModuleIdView, PeerInfoView, ResourceView, SignedMessageView,
     SignedUserTransactionView, StateWithProofView, StateWithTableItemProofView, StrView,
     StructTagView, TableInfoView, TransactionEventResponse, TransactionInfoView,
-    TransactionInfoWithProofView, TransactionRequest, TransactionView,
+    TransactionInfoWithProofView, TransactionRequest, TransactionTraceView, TransactionView,
 };
 use starcoin_rpc_api::{
     account::AccountClient, chain::ChainClient, contract_api::ContractClient, debug::DebugClient,
-    miner::MinerClient, network_manager::NetworkManagerClient, node::NodeClient,
+    dev::DevClient, miner::MinerClient, network_manager::NetworkManagerClient, node::NodeClient,
     node_manager::NodeManagerClient, state::StateClient, sync_manager::SyncManagerClient,
-    txpool::TxPoolClient, types::TransactionEventView,
+    txpool::TxPoolClient,
 };
 use starcoin_service_registry::{ServiceInfo, ServiceStatus};
 use starcoin_sync_api::{PeerScoreResponse, SyncProgressReport};
@@ -306,6 +309,20 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn wait_for_transaction(
+        &self,
+        txn_hash: HashValue,
+        timeout: Option<u64>,
+        confirmations: Option<u64>,
+    ) -> anyhow::Result<TransactionStatusNotification> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .txpool_client
+                .wait_for_transaction(txn_hash, timeout, confirmations)
+        })
+        .map_err(map_err)
+    }
+
     pub fn get_pending_txn_by_hash(
         &self,
         txn_hash: HashValue,
@@ -473,9 +490,36 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn account_create_from_mnemonic(
+        &self,
+        password: String,
+    ) -> anyhow::Result<MnemonicAccountInfo> {
+        self.call_rpc_blocking(|inner| inner.account_client.create_from_mnemonic(password))
+            .map_err(map_err)
+    }
+
+    pub fn account_import_mnemonic(
+        &self,
+        mnemonic: String,
+        password: String,
+    ) -> anyhow::Result<AccountInfo> {
+        self.call_rpc_blocking(|inner| inner.account_client.import_mnemonic(mnemonic, password))
+            .map_err(map_err)
+    }
+
+    pub fn account_derive(&self, index: u32, password: String) -> anyhow::Result<AccountInfo> {
+        self.call_rpc_blocking(|inner| inner.account_client.derive(index, password))
+            .map_err(map_err)
+    }
+
+    pub fn account_export_mnemonic(&self, password: String) -> anyhow::Result<String> {
+        self.call_rpc_blocking(|inner| inner.account_client.export_mnemonic(password))
+            .map_err(map_err)
+    }
+
     pub fn get_code(&self, module_id: ModuleId) -> anyhow::Result<Option<String>> {
         let result: Option<StrView<Vec<u8>>> = self
-            .call_rpc_blocking(|inner| inner.contract_client.get_code(StrView(module_id)))
+            .call_rpc_blocking(|inner| inner.contract_client.get_code(StrView(module_id), None))
             .map_err(map_err)?;
         Ok(result.map(|s| s.to_string()))
     }
@@ -488,7 +532,7 @@ impl RpcClient {
         self.call_rpc_blocking(|inner| {
             inner
                 .contract_client
-                .get_resource(addr, StrView(resource_type))
+                .get_resource(addr, StrView(resource_type), None)
         })
         .map_err(map_err)
     }
@@ -501,7 +545,7 @@ impl RpcClient {
     }
 
     pub fn state_get(&self, access_path: AccessPath) -> anyhow::Result<Option<Vec<u8>>> {
-        self.call_rpc_blocking(|inner| inner.state_client.get(access_path))
+        self.call_rpc_blocking(|inner| inner.state_client.get(access_path, None))
             .map_err(map_err)
     }
 
@@ -509,7 +553,7 @@ impl RpcClient {
         &self,
         access_path: AccessPath,
     ) -> anyhow::Result<StateWithProofView> {
-        self.call_rpc_blocking(|inner| inner.state_client.get_with_proof(access_path))
+        self.call_rpc_blocking(|inner| inner.state_client.get_with_proof(access_path, None))
             .map_err(map_err)
     }
 
@@ -540,7 +584,7 @@ impl RpcClient {
     }
 
     pub fn state_get_state_root(&self) -> anyhow::Result<HashValue> {
-        self.call_rpc_blocking(|inner| inner.state_client.get_state_root())
+        self.call_rpc_blocking(|inner| inner.state_client.get_state_root(None))
             .map_err(map_err)
     }
 
@@ -548,7 +592,7 @@ impl RpcClient {
         &self,
         address: AccountAddress,
     ) -> anyhow::Result<Option<AccountState>> {
-        self.call_rpc_blocking(|inner| inner.state_client.get_account_state(address))
+        self.call_rpc_blocking(|inner| inner.state_client.get_account_state(address, None))
             .map_err(map_err)
     }
 
@@ -560,7 +604,7 @@ impl RpcClient {
         self.call_rpc_blocking(|inner| {
             inner
                 .state_client
-                .get_account_state_set(address, state_root)
+                .get_account_state_set(address, state_root, None)
         })
         .map_err(map_err)
     }
@@ -576,7 +620,11 @@ impl RpcClient {
             inner.state_client.get_resource(
                 address,
                 StrView(resource_type),
-                Some(GetResourceOption { decode, state_root }),
+                Some(GetResourceOption {
+                    decode,
+                    state_root,
+                    ..Default::default()
+                }),
             )
         })
         .map_err(map_err)
@@ -600,6 +648,7 @@ impl RpcClient {
                     start_index,
                     max_size,
                     resource_types,
+                    ..Default::default()
                 }),
             )
         })
@@ -618,6 +667,7 @@ impl RpcClient {
                 Some(GetCodeOption {
                     resolve,
                     state_root,
+                    ..Default::default()
                 }),
             )
         })
@@ -636,6 +686,7 @@ impl RpcClient {
                 Some(ListCodeOption {
                     resolve,
                     state_root,
+                    ..Default::default()
                 }),
             )
         })
@@ -673,7 +724,7 @@ impl RpcClient {
     }
 
     pub fn contract_call(&self, call: ContractCall) -> anyhow::Result<Vec<DecodedMoveValue>> {
-        self.call_rpc_blocking(|inner| inner.contract_client.call_v2(call))
+        self.call_rpc_blocking(|inner| inner.contract_client.call_v2(call, None))
             .map_err(map_err)
     }
 
@@ -681,7 +732,7 @@ impl RpcClient {
         &self,
         function_id: FunctionIdView,
     ) -> anyhow::Result<FunctionABI> {
-        self.call_rpc_blocking(|inner| inner.contract_client.resolve_function(function_id))
+        self.call_rpc_blocking(|inner| inner.contract_client.resolve_function(function_id, None))
             .map_err(map_err)
     }
 
@@ -689,12 +740,12 @@ impl RpcClient {
         &self,
         struct_tag: StructTagView,
     ) -> anyhow::Result<StructInstantiation> {
-        self.call_rpc_blocking(|inner| inner.contract_client.resolve_struct(struct_tag))
+        self.call_rpc_blocking(|inner| inner.contract_client.resolve_struct(struct_tag, None))
             .map_err(map_err)
     }
 
     pub fn contract_resolve_module(&self, module_id: ModuleIdView) -> anyhow::Result<ModuleABI> {
-        self.call_rpc_blocking(|inner| inner.contract_client.resolve_module(module_id))
+        self.call_rpc_blocking(|inner| inner.contract_client.resolve_module(module_id, None))
             .map_err(map_err)
     }
 
@@ -741,6 +792,62 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn debug_trace_transaction(
+        &self,
+        txn_hash: HashValue,
+        trace: bool,
+    ) -> anyhow::Result<TransactionTraceView> {
+        self.call_rpc_blocking(|inner| inner.debug_client.trace_transaction(txn_hash, Some(trace)))
+            .map_err(map_err)
+    }
+
+    pub fn dev_set_next_block_timestamp(&self, timestamp: u64) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| inner.dev_client.set_next_block_timestamp(timestamp))
+            .map_err(map_err)
+    }
+
+    pub fn dev_increase_time(&self, millis: u64) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(|inner| inner.dev_client.increase_time(millis))
+            .map_err(map_err)
+    }
+
+    pub fn dev_mine(&self, n: u64) -> anyhow::Result<Vec<HashValue>> {
+        self.call_rpc_blocking(|inner| inner.dev_client.mine(n))
+            .map_err(map_err)
+    }
+
+    pub fn dev_snapshot(&self) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(|inner| inner.dev_client.snapshot())
+            .map_err(map_err)
+    }
+
+    pub fn dev_revert(&self, snapshot_id: u64) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| inner.dev_client.revert(snapshot_id))
+            .map_err(map_err)
+    }
+
+    pub fn dev_impersonate_account(&self, address: AccountAddress) -> anyhow::Result<bool> {
+        self.call_rpc_blocking(|inner| inner.dev_client.impersonate_account(address))
+            .map_err(map_err)
+    }
+
+    pub fn dev_stop_impersonating_account(&self, address: AccountAddress) -> anyhow::Result<bool> {
+        self.call_rpc_blocking(|inner| inner.dev_client.stop_impersonating_account(address))
+            .map_err(map_err)
+    }
+
+    pub fn dev_submit_impersonated_transaction(
+        &self,
+        raw_txn: RawUserTransaction,
+    ) -> anyhow::Result<HashValue> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .dev_client
+                .submit_impersonated_transaction(raw_txn.to_hex())
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_id(&self) -> anyhow::Result<ChainId> {
         self.call_rpc_blocking(|inner| inner.chain_client.id())
             .map_err(map_err)
@@ -860,6 +967,21 @@ impl RpcClient {
         .map_err(map_err)
     }
 
+    pub fn chain_get_transactions_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> anyhow::Result<Vec<TransactionInfoView>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .chain_client
+                .get_transactions_by_sender(sender, start_seq, reverse, max_size)
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_get_transaction_proof(
         &self,
         block_hash: HashValue,
@@ -909,7 +1031,7 @@ impl RpcClient {
         self.call_rpc_blocking(|inner| {
             inner
                 .contract_client
-                .dry_run_raw(raw_txn, StrView(public_key))
+                .dry_run_raw(raw_txn, StrView(public_key), None)
         })
         .map_err(map_err)
     }
@@ -933,6 +1055,11 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn mining_workers(&self) -> anyhow::Result<Vec<MiningWorkerView>> {
+        self.call_rpc_blocking(|inner| inner.miner_client.workers())
+            .map_err(map_err)
+    }
+
     pub fn txpool_status(&self) -> anyhow::Result<TxPoolStatus> {
         self.call_rpc_blocking(|inner| inner.txpool_client.state())
             .map_err(map_err)
@@ -940,20 +1067,34 @@ impl RpcClient {
 
     pub fn subscribe_events(
         &self,
-        filter: EventFilter,
-        decode: bool,
-    ) -> anyhow::Result<impl TryStream<Ok = TransactionEventView, Error = anyhow::Error>> {
+        params: EventParams,
+    ) -> anyhow::Result<impl TryStream<Ok = EventNotification, Error = anyhow::Error>> {
+        self.call_rpc_blocking(|inner| async move {
+            let res = inner.pubsub_client.subscribe_events(params).await;
+            res.map(|s| s.map_err(map_err))
+        })
+        .map_err(map_err)
+    }
+    pub fn subscribe_transaction_status(
+        &self,
+        params: TransactionStatusParams,
+    ) -> anyhow::Result<impl TryStream<Ok = TransactionStatusNotification, Error = anyhow::Error>>
+    {
         self.call_rpc_blocking(|inner| async move {
-            let res = inner.pubsub_client.subscribe_events(filter, decode).await;
+            let res = inner
+                .pubsub_client
+                .subscribe_transaction_status(params)
+                .await;
             res.map(|s| s.map_err(map_err))
         })
         .map_err(map_err)
     }
     pub fn subscribe_new_blocks(
         &self,
-    ) -> anyhow::Result<impl TryStream<Ok = BlockView, Error = anyhow::Error>> {
+        confirmations: Option<u64>,
+    ) -> anyhow::Result<impl TryStream<Ok = BlockNotification, Error = anyhow::Error>> {
         self.call_rpc_blocking(|inner| async move {
-            let res = inner.pubsub_client.subscribe_new_block().await;
+            let res = inner.pubsub_client.subscribe_new_block(confirmations).await;
             res.map(|s| s.map_err(map_err))
         })
         .map_err(map_err)
@@ -1140,6 +1281,7 @@ pub(crate) struct RpcClientInner {
     account_client: AccountClient,
     state_client: StateClient,
     debug_client: DebugClient,
+    dev_client: DevClient,
     chain_client: ChainClient,
     pubsub_client: PubSubClient,
     contract_client: ContractClient,
@@ -1158,6 +1300,7 @@ impl RpcClientInner {
             account_client: channel.clone().into(),
             state_client: channel.clone().into(),
             debug_client: channel.clone().into(),
+            dev_client: channel.clone().into(),
             chain_client: channel.clone().into(),
             contract_client: channel.clone().into(),
             pubsub_client: channel.clone().into(),
@@ -3,8 +3,10 @@
 
 use jsonrpc_core_client::*;
 use starcoin_crypto::HashValue;
-use starcoin_rpc_api::types::pubsub::EventParams;
-use starcoin_rpc_api::types::{pubsub::EventFilter, pubsub::Kind, BlockView, TransactionEventView};
+use starcoin_rpc_api::types::pubsub::{
+    BlockNotification, EventNotification, EventParams, Kind, NewHeadsParams,
+    TransactionStatusNotification, TransactionStatusParams,
+};
 use starcoin_types::system_events::MintBlockEvent;
 
 const STARCOIN_SUBSCRIPTION: &str = "starcoin_subscription";
@@ -32,12 +34,11 @@ impl From<RpcChannel> for PubSubClient {
 impl PubSubClient {
     pub async fn subscribe_events(
         &self,
-        filter: EventFilter,
-        decode: bool,
-    ) -> Result<TypedSubscriptionStream<TransactionEventView>, RpcError> {
+        params: EventParams,
+    ) -> Result<TypedSubscriptionStream<EventNotification>, RpcError> {
         self.client.subscribe(
             STARCOIN_SUBSCRIBE,
-            (Kind::Events, EventParams { filter, decode }),
+            (Kind::Events, params),
             STARCOIN_SUBSCRIPTION,
             STARCOIN_UNSUBSCRIBE,
             "Event",
@@ -45,24 +46,51 @@ impl PubSubClient {
     }
     pub async fn subscribe_new_block(
         &self,
-    ) -> Result<TypedSubscriptionStream<BlockView>, RpcError> {
+        confirmations: Option<u64>,
+    ) -> Result<TypedSubscriptionStream<BlockNotification>, RpcError> {
+        match confirmations {
+            Some(confirmations) => self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                (
+                    Kind::NewHeads,
+                    NewHeadsParams {
+                        confirmations: Some(confirmations),
+                    },
+                ),
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "ThinBlock",
+            ),
+            None => self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                vec![Kind::NewHeads],
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "ThinBlock",
+            ),
+        }
+    }
+    pub async fn subscribe_new_transactions(
+        &self,
+    ) -> Result<TypedSubscriptionStream<Vec<HashValue>>, RpcError> {
         self.client.subscribe(
             STARCOIN_SUBSCRIBE,
-            vec![Kind::NewHeads],
+            vec![Kind::NewPendingTransactions],
             STARCOIN_SUBSCRIPTION,
             STARCOIN_UNSUBSCRIBE,
-            "ThinBlock",
+            "Vec<HashValue>",
         )
     }
-    pub async fn subscribe_new_transactions(
+    pub async fn subscribe_transaction_status(
         &self,
-    ) -> Result<TypedSubscriptionStream<Vec<HashValue>>, RpcError> {
+        params: TransactionStatusParams,
+    ) -> Result<TypedSubscriptionStream<TransactionStatusNotification>, RpcError> {
         self.client.subscribe(
             STARCOIN_SUBSCRIBE,
-            vec![Kind::NewPendingTransactions],
+            (Kind::TransactionStatus, params),
             STARCOIN_SUBSCRIPTION,
             STARCOIN_UNSUBSCRIBE,
-            "Vec<HashValue>",
+            "TransactionStatus",
         )
     }
     pub async fn subscribe_new_mint_block(
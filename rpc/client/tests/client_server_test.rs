@@ -5,8 +5,11 @@ use anyhow::Result;
 use futures::{StreamExt, TryStreamExt};
 use starcoin_config::NodeConfig;
 use starcoin_logger::prelude::*;
-use starcoin_rpc_client::RpcClient;
+use starcoin_rpc_api::types::TransactionStatusView;
+use starcoin_rpc_client::{RpcClient, StateRootOption};
+use starcoin_types::account::{peer_to_peer_txn, Account, DEFAULT_EXPIRATION_TIME};
 use starcoin_types::system_events::MintBlockEvent;
+use starcoin_vm_types::state_view::StateReaderExt;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -139,3 +142,92 @@ fn test_client_reconnect_subscribe() -> Result<()> {
     assert_ne!(events2.len(), 0);
     Ok(())
 }
+
+#[stest::test(timeout = 120)]
+fn test_dev_api() -> Result<()> {
+    let mut node_config = NodeConfig::random_for_test();
+    node_config.miner.dev_instant_seal = Some(true);
+    let config = Arc::new(node_config);
+    let node_handle = test_helper::run_node_by_config(config)?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    let head = client.chain_info()?.head;
+    let snapshot_id = client.dev_snapshot()?;
+    let timestamp = head.timestamp.0 + 3600 * 1000;
+    client.dev_set_next_block_timestamp(timestamp)?;
+    let blocks = client.dev_mine(2)?;
+    assert_eq!(blocks.len(), 2);
+    let block = client
+        .chain_get_block_by_hash(blocks[0], None)?
+        .expect("mined block should exist");
+    assert_eq!(block.header.timestamp.0, timestamp);
+    assert_eq!(client.chain_info()?.head.number.0, head.number.0 + 2);
+    assert!(client.dev_increase_time(1000)? >= timestamp + 1000);
+    // the timestamp of next block should be greater than head.
+    assert!(client.dev_set_next_block_timestamp(timestamp).is_err());
+
+    client.dev_revert(snapshot_id)?;
+    assert_eq!(client.chain_info()?.head.block_hash, head.block_hash);
+    // the reverted snapshot is dropped.
+    assert!(client.dev_revert(snapshot_id).is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_dev_submit_impersonated_transaction() -> Result<()> {
+    let mut node_config = NodeConfig::random_for_test();
+    node_config.miner.dev_instant_seal = Some(true);
+    let config = Arc::new(node_config);
+    let node_handle = test_helper::run_node_by_config(config)?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    // the association account of test network is guarded by a multi-sig key,
+    // but the impersonated transaction is signed by the genesis key.
+    let association = Account::new_association();
+    let receiver = Account::new();
+    let sequence_number = client
+        .state_reader(StateRootOption::Latest)?
+        .get_sequence_number(*association.address())?;
+    let raw_txn = peer_to_peer_txn(
+        &association,
+        &receiver,
+        sequence_number,
+        1000,
+        client.node_info()?.now_seconds + DEFAULT_EXPIRATION_TIME,
+        client.chain_id()?,
+    )
+    .into_raw_transaction();
+    assert!(client
+        .dev_submit_impersonated_transaction(raw_txn.clone())
+        .is_err());
+
+    assert!(client.dev_impersonate_account(*association.address())?);
+    assert!(!client.dev_impersonate_account(*association.address())?);
+    let txn_hash = client.dev_submit_impersonated_transaction(raw_txn.clone())?;
+    client.dev_mine(1)?;
+    let txn_info = client
+        .chain_get_transaction_info(txn_hash)?
+        .expect("impersonated transaction should be mined");
+    assert_eq!(txn_info.status, TransactionStatusView::Executed);
+    assert_eq!(
+        client
+            .state_reader(StateRootOption::Latest)?
+            .get_balance(*receiver.address())?,
+        Some(1000)
+    );
+
+    assert!(client.dev_stop_impersonating_account(*association.address())?);
+    assert!(!client.dev_stop_impersonating_account(*association.address())?);
+    assert!(client.dev_submit_impersonated_transaction(raw_txn).is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
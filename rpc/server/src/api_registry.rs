@@ -1,39 +1,62 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+use crate::auth_middleware::AuthMiddleware;
 use crate::rate_limit_middleware::JsonApiRateLimitMiddleware;
 use jsonrpc_core::{MetaIoHandler, RemoteProcedure};
-use starcoin_config::{Api, ApiQuotaConfiguration};
+use starcoin_config::{Api, ApiQuotaConfig, ApiQuotaConfiguration, RpcAuthConfig};
 use starcoin_rpc_api::metadata::Metadata;
 use starcoin_rpc_middleware::{MetricMiddleware, RpcMetrics};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-type Middlewares = (MetricMiddleware, JsonApiRateLimitMiddleware);
+type Middlewares = (MetricMiddleware, AuthMiddleware, JsonApiRateLimitMiddleware);
 
 pub struct ApiRegistry {
     apis: HashMap<Api, MetaIoHandler<Metadata, Middlewares>>,
     quotas: ApiQuotaConfiguration,
+    auth: Option<Arc<RpcAuthConfig>>,
     metrics: Option<RpcMetrics>,
 }
 
 impl ApiRegistry {
-    pub fn new(api_quotas: ApiQuotaConfiguration, metrics: Option<RpcMetrics>) -> ApiRegistry {
+    pub fn new(
+        api_quotas: ApiQuotaConfiguration,
+        auth: Option<Arc<RpcAuthConfig>>,
+        metrics: Option<RpcMetrics>,
+    ) -> ApiRegistry {
         Self {
             apis: Default::default(),
             quotas: api_quotas,
+            auth,
             metrics,
         }
     }
 
+    pub(crate) fn rate_limit_middleware(&self) -> JsonApiRateLimitMiddleware {
+        let user_quotas: HashMap<String, ApiQuotaConfig> = self
+            .auth
+            .as_ref()
+            .map(|auth| {
+                auth.tokens
+                    .iter()
+                    .filter_map(|token| token.quota.clone().map(|quota| (token.id.clone(), quota)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        JsonApiRateLimitMiddleware::from_config(self.quotas.clone(), user_quotas)
+    }
+
     pub fn register<F>(&mut self, api_type: Api, apis: F)
     where
         F: IntoIterator<Item = (String, RemoteProcedure<Metadata>)>,
     {
-        let rate_limit_middleware = JsonApiRateLimitMiddleware::from_config(self.quotas.clone());
+        let rate_limit_middleware = self.rate_limit_middleware();
         let metrics = self.metrics.clone();
         let io_handler = self.apis.entry(api_type).or_insert_with(|| {
             MetaIoHandler::<Metadata, Middlewares>::with_middleware((
                 MetricMiddleware::new(metrics),
+                AuthMiddleware::default(),
                 rate_limit_middleware,
             ))
         });
@@ -44,7 +67,41 @@ impl ApiRegistry {
         &self,
         api_types: impl IntoIterator<Item = Api>,
     ) -> MetaIoHandler<Metadata, Middlewares> {
-        let rate_limit_middleware = JsonApiRateLimitMiddleware::from_config(self.quotas.clone());
+        self.build_apis(api_types, AuthMiddleware::default())
+    }
+
+    /// Get the `api_types`, and the other apis granted by the rpc tokens if auth is configured.
+    pub fn get_apis_with_auth(
+        &self,
+        api_types: impl IntoIterator<Item = Api>,
+    ) -> MetaIoHandler<Metadata, Middlewares> {
+        match self.auth.as_ref() {
+            Some(auth) => {
+                let methods: HashMap<String, Api> = self
+                    .apis
+                    .iter()
+                    .flat_map(|(api_type, apis)| {
+                        apis.iter()
+                            .map(move |(method, _)| (method.clone(), *api_type))
+                    })
+                    .collect();
+                let auth_middleware = AuthMiddleware::new(
+                    auth.clone(),
+                    Arc::new(methods),
+                    api_types.into_iter().collect::<HashSet<_>>(),
+                );
+                self.build_apis(self.apis.keys().cloned(), auth_middleware)
+            }
+            None => self.get_apis(api_types),
+        }
+    }
+
+    fn build_apis(
+        &self,
+        api_types: impl IntoIterator<Item = Api>,
+        auth_middleware: AuthMiddleware,
+    ) -> MetaIoHandler<Metadata, Middlewares> {
+        let rate_limit_middleware = self.rate_limit_middleware();
         let metrics = self.metrics.clone();
         api_types
             .into_iter()
@@ -52,6 +109,7 @@ impl ApiRegistry {
             .fold(
                 MetaIoHandler::<Metadata, Middlewares>::with_middleware((
                     MetricMiddleware::new(metrics),
+                    auth_middleware,
                     rate_limit_middleware,
                 )),
                 |mut init, apis| {
@@ -0,0 +1,250 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use hmac::{Hmac, Mac};
+use jsonrpc_core::futures::future::Either;
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{Call, Error, ErrorCode, Failure, FutureResponse, Id, Middleware, Output};
+use sha2::Sha256;
+use starcoin_config::{Api, RpcAuthConfig, RpcTokenConfig};
+use starcoin_rpc_api::metadata::Metadata;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Generate the token of `id`, signed by `secret`.
+pub fn generate_token(secret: &str, id: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(id.as_bytes());
+    format!("{}.{}", id, hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Verify the signature of `token`, return the config of it.
+pub fn verify_token<'a>(auth: &'a RpcAuthConfig, token: &str) -> Option<&'a RpcTokenConfig> {
+    let (id, signature) = token.rsplit_once('.')?;
+    let signature = hex::decode(signature).ok()?;
+    let mut mac = HmacSha256::new_from_slice(auth.secret.as_bytes()).ok()?;
+    mac.update(id.as_bytes());
+    mac.verify_slice(signature.as_slice()).ok()?;
+    auth.token(id)
+}
+
+/// Check the methods beyond the apiset of the transport against the token of the request,
+/// and identify the user by the token.
+#[derive(Clone, Debug, Default)]
+pub struct AuthMiddleware {
+    auth: Option<Arc<RpcAuthConfig>>,
+    /// The api of every method.
+    methods: Arc<HashMap<String, Api>>,
+    /// The apis allowed without token.
+    apis: HashSet<Api>,
+}
+
+impl AuthMiddleware {
+    pub fn new(
+        auth: Arc<RpcAuthConfig>,
+        methods: Arc<HashMap<String, Api>>,
+        apis: HashSet<Api>,
+    ) -> Self {
+        Self {
+            auth: Some(auth),
+            methods,
+            apis,
+        }
+    }
+}
+
+impl Middleware<Metadata> for AuthMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, mut meta: Metadata, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Metadata) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let auth = match self.auth.as_ref() {
+            Some(auth) => auth,
+            None => return Either::Right(next(call, meta)),
+        };
+        let (method, json_version, id) = match &call {
+            Call::MethodCall(m) => (m.method.clone(), m.jsonrpc, m.id.clone()),
+            Call::Notification(n) => (n.method.clone(), n.jsonrpc, Id::Null),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let api = self.methods.get(&method).cloned();
+        let public = api.map(|api| self.apis.contains(&api)).unwrap_or(false);
+        let token = meta.token.clone();
+        let error = match token.as_deref() {
+            Some(token) => match verify_token(auth, token) {
+                Some(token) if public || token.is_allowed(api, method.as_str()) => {
+                    meta.user = Some(token.id.clone());
+                    return Either::Right(next(call, meta));
+                }
+                Some(_) => Error {
+                    code: ErrorCode::ServerError(-10001),
+                    message: "Permission denied".to_string(),
+                    data: None,
+                },
+                None => Error {
+                    code: ErrorCode::ServerError(-10002),
+                    message: "Invalid rpc token".to_string(),
+                    data: None,
+                },
+            },
+            None if public => return Either::Right(next(call, meta)),
+            None => Error::method_not_found(),
+        };
+        let output = Output::Failure(Failure {
+            jsonrpc: json_version,
+            error,
+            id,
+        });
+        Either::Left(Box::pin(futures::future::ready(Some(output))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_registry::ApiRegistry;
+    use futures::executor::block_on;
+    use jsonrpc_core::{MetaIoHandler, Value};
+    use starcoin_config::{temp_dir, ApiQuotaConfig, ApiQuotaConfiguration};
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    fn test_auth() -> RpcAuthConfig {
+        RpcAuthConfig {
+            secret: SECRET.to_string(),
+            tokens: vec![
+                RpcTokenConfig {
+                    id: "team.a".to_string(),
+                    apis: vec![Api::Debug].into_iter().collect(),
+                    methods: vec![],
+                    quota: Some("2/s".parse::<ApiQuotaConfig>().unwrap()),
+                },
+                RpcTokenConfig {
+                    id: "team.b".to_string(),
+                    apis: HashSet::new(),
+                    methods: vec!["debug.echo".to_string()],
+                    quota: None,
+                },
+                RpcTokenConfig {
+                    id: "team.c".to_string(),
+                    apis: HashSet::new(),
+                    methods: vec![],
+                    quota: None,
+                },
+            ],
+        }
+    }
+
+    /// Call `method` with `token`, return the error code if the call fails.
+    fn call<T>(io: &MetaIoHandler<Metadata, T>, method: &str, token: Option<&str>) -> Option<i64>
+    where
+        T: Middleware<Metadata>,
+    {
+        let meta = Metadata {
+            token: token.map(|token| token.to_string()),
+            ..Default::default()
+        };
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#,
+            method
+        );
+        let response: Value = serde_json::from_str(
+            block_on(io.handle_request(request.as_str(), meta))
+                .expect("response should exist")
+                .as_str(),
+        )
+        .unwrap();
+        response["error"]["code"].as_i64()
+    }
+
+    #[test]
+    fn test_on_call() {
+        let mut registry = ApiRegistry::new(
+            ApiQuotaConfiguration::default(),
+            Some(Arc::new(test_auth())),
+            None,
+        );
+        for (api, method) in [(Api::Node, "node.info"), (Api::Debug, "debug.echo")] {
+            let mut io = MetaIoHandler::<Metadata>::default();
+            io.add_method(method, |_params| async { Ok(Value::Bool(true)) });
+            registry.register(api, io.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        let io = registry.get_apis_with_auth(vec![Api::Node]);
+
+        // the apis of the transport are public.
+        assert_eq!(call(&io, "node.info", None), None);
+        assert_eq!(call(&io, "debug.echo", None), Some(-32601));
+        assert_eq!(call(&io, "debug.echo", Some("team.a.00")), Some(-10002));
+        let forged = generate_token("another secret of 32 bytes length", "team.a");
+        assert_eq!(call(&io, "debug.echo", Some(forged.as_str())), Some(-10002));
+        let token_c = generate_token(SECRET, "team.c");
+        assert_eq!(
+            call(&io, "debug.echo", Some(token_c.as_str())),
+            Some(-10001)
+        );
+        assert_eq!(call(&io, "node.info", Some(token_c.as_str())), None);
+
+        // the quota of team.a is 2/s, team.b is not affected by it.
+        let token_a = generate_token(SECRET, "team.a");
+        let token_b = generate_token(SECRET, "team.b");
+        assert_eq!(call(&io, "debug.echo", Some(token_a.as_str())), None);
+        assert_eq!(call(&io, "debug.echo", Some(token_a.as_str())), None);
+        assert_eq!(
+            call(&io, "debug.echo", Some(token_a.as_str())),
+            Some(-10000)
+        );
+        assert_eq!(call(&io, "debug.echo", Some(token_b.as_str())), None);
+        assert_eq!(call(&io, "debug.echo", Some(token_b.as_str())), None);
+        assert_eq!(call(&io, "debug.echo", Some(token_b.as_str())), None);
+    }
+
+    #[test]
+    fn test_load_short_secret() {
+        let dir = temp_dir();
+        let path = dir.path().join("rpc_auth.toml");
+        let config = |secret: &str| {
+            format!(
+                "secret = \"{}\"\n[[tokens]]\nid = \"team.a\"\nmethods = [\"debug.*\"]\n",
+                secret
+            )
+        };
+        std::fs::write(path.as_path(), config(SECRET)).unwrap();
+        let auth = RpcAuthConfig::load(path.as_path()).unwrap();
+        assert_eq!(auth.secret, SECRET);
+        assert!(auth.token("team.a").is_some());
+
+        std::fs::write(path.as_path(), config("secret")).unwrap();
+        assert!(RpcAuthConfig::load(path.as_path()).is_err());
+    }
+
+    #[test]
+    fn test_verify_token() {
+        let auth = RpcAuthConfig {
+            secret: "secret".to_string(),
+            tokens: vec![RpcTokenConfig {
+                id: "team.a".to_string(),
+                apis: vec![Api::Account].into_iter().collect(),
+                methods: vec!["node_manager.*".to_string(), "debug.panic".to_string()],
+                quota: Some("10/s".parse::<ApiQuotaConfig>().unwrap()),
+            }],
+        };
+        let token = generate_token(auth.secret.as_str(), "team.a");
+        let config = verify_token(&auth, token.as_str()).expect("token should be valid");
+        assert!(config.is_allowed(Some(Api::Account), "account.default"));
+        assert!(config.is_allowed(Some(Api::NodeManager), "node_manager.stop_service"));
+        assert!(config.is_allowed(Some(Api::Debug), "debug.panic"));
+        assert!(!config.is_allowed(Some(Api::Debug), "debug.set_log_level"));
+
+        assert!(verify_token(&auth, generate_token("other", "team.a").as_str()).is_none());
+        assert!(verify_token(&auth, generate_token("secret", "team.b").as_str()).is_none());
+        assert!(verify_token(&auth, "team.a").is_none());
+    }
+}
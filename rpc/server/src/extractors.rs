@@ -27,9 +27,17 @@ impl jsonrpc_http_server::MetaExtractor<Metadata> for RpcExtractor {
             }
         }
 
+        let token = _req
+            .headers()
+            .get(hyper::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string());
+
         Metadata {
             session: None,
             user: client_ip.map(|ip| ip.to_string()),
+            token,
         }
     }
 }
@@ -38,6 +46,7 @@ impl jsonrpc_ipc_server::MetaExtractor<Metadata> for RpcExtractor {
         Metadata {
             session: Some(Arc::new(Session::new(req.sender.clone()))),
             user: None,
+            token: None,
         }
     }
 }
@@ -46,6 +55,7 @@ impl jsonrpc_tcp_server::MetaExtractor<Metadata> for RpcExtractor {
         Metadata {
             session: Some(Arc::new(Session::new(context.sender.clone()))),
             user: Some(context.peer_addr.ip().to_string()),
+            token: None,
         }
     }
 }
@@ -57,6 +67,7 @@ impl jsonrpc_ws_server::MetaExtractor<Metadata> for WsExtractor {
         Metadata {
             session,
             user: None,
+            token: None,
         }
     }
 }
@@ -0,0 +1,692 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An optional GraphQL endpoint over the chain, state and events, every field is resolved lazily,
+//! so one query can fetch a block with its transactions, events and the sender balances.
+
+use crate::auth_middleware::verify_token;
+use crate::rate_limit_middleware::JsonApiRateLimitMiddleware;
+use anyhow::{format_err, Result};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Json, Object, Schema};
+use bcs_ext::BCSCodec;
+use futures::channel::oneshot;
+use hyper::body::HttpBody;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use starcoin_abi_decoder::{decode_move_value, DecodedMoveValue};
+use starcoin_abi_resolver::ABIResolver;
+use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
+use starcoin_config::RpcAuthConfig;
+use starcoin_crypto::HashValue;
+use starcoin_dev::playground::view_resource;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::types::{
+    BlockHeaderView, SignedUserTransactionView, TransactionInfoView, TransactionStatusView,
+};
+use starcoin_service_registry::ServiceRef;
+use starcoin_state_api::{ChainStateAsyncService, StateReaderExt, StateView};
+use starcoin_state_service::ChainStateService;
+use starcoin_state_tree::StateNodeStore;
+use starcoin_statedb::ChainStateDB;
+use starcoin_types::access_path::AccessPath;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{Block, BlockHeader};
+use starcoin_types::contract_event::ContractEventInfo;
+use starcoin_types::transaction::{SignedUserTransaction, Transaction};
+use starcoin_vm_types::language_storage::{struct_tag_match, StructTag};
+use starcoin_vm_types::state_store::state_key::StateKey;
+use starcoin_vm_types::token::token_code::TokenCode;
+use std::convert::{Infallible, TryFrom};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+/// The max depth of a query, to keep a query from walking the whole chain.
+const MAX_QUERY_DEPTH: usize = 16;
+/// The max complexity of a query, every field counts 1, to keep a wide query from fetching too
+/// much in one request.
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+/// The method name of the GraphQL endpoint, for the rpc token permission and the api quota.
+pub const GRAPHQL_METHOD: &str = "graphql";
+
+pub type StarcoinSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The services the GraphQL fields resolve through.
+#[derive(Clone)]
+pub struct GraphQLContext {
+    chain: ServiceRef<ChainReaderService>,
+    state: ServiceRef<ChainStateService>,
+    state_store: Arc<dyn StateNodeStore>,
+}
+
+impl GraphQLContext {
+    pub fn new(
+        chain: ServiceRef<ChainReaderService>,
+        state: ServiceRef<ChainStateService>,
+        state_store: Arc<dyn StateNodeStore>,
+    ) -> Self {
+        Self {
+            chain,
+            state,
+            state_store,
+        }
+    }
+
+    pub fn schema(self) -> StarcoinSchema {
+        Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .data(self)
+            .limit_depth(MAX_QUERY_DEPTH)
+            .limit_complexity(MAX_QUERY_COMPLEXITY)
+            .finish()
+    }
+
+    fn state_db(&self, state_root: HashValue) -> ChainStateDB {
+        ChainStateDB::new(self.state_store.clone(), Some(state_root))
+    }
+
+    async fn state_root(
+        &self,
+        block_hash: Option<String>,
+        block_number: Option<u64>,
+    ) -> Result<HashValue> {
+        let header = match (block_hash, block_number) {
+            (Some(hash), _) => self
+                .chain
+                .get_header_by_hash(&HashValue::from_str(hash.as_str())?)
+                .await?
+                .ok_or_else(|| format_err!("Can not find block {}", hash))?,
+            (None, Some(number)) => self
+                .chain
+                .main_block_header_by_number(number)
+                .await?
+                .ok_or_else(|| format_err!("Can not find block by number {}", number))?,
+            (None, None) => self.chain.main_head_header().await?,
+        };
+        Ok(header.state_root())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Get the block by hash or number, default is the head block of the main chain.
+    async fn block(
+        &self,
+        ctx: &Context<'_>,
+        hash: Option<String>,
+        number: Option<u64>,
+    ) -> async_graphql::Result<Option<GraphQLBlock>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let block = match (hash, number) {
+            (Some(hash), _) => {
+                context
+                    .chain
+                    .get_block_by_hash(HashValue::from_str(hash.as_str())?)
+                    .await?
+            }
+            (None, Some(number)) => context.chain.main_block_by_number(number).await?,
+            (None, None) => Some(context.chain.main_head_block().await?),
+        };
+        Ok(block.map(GraphQLBlock))
+    }
+
+    /// Get the transaction by hash.
+    async fn transaction(
+        &self,
+        ctx: &Context<'_>,
+        hash: String,
+    ) -> async_graphql::Result<Option<GraphQLTransaction>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let txn_hash = HashValue::from_str(hash.as_str())?;
+        let txn = match context.chain.get_transaction(txn_hash).await? {
+            Some(txn) => txn,
+            None => return Ok(None),
+        };
+        let block = context
+            .chain
+            .get_transaction_block(txn_hash)
+            .await?
+            .ok_or_else(|| format_err!("Can not find block of transaction {}", txn_hash))?;
+        Ok(Some(GraphQLTransaction::new(txn, block.header())))
+    }
+
+    /// Get the account at the state of the block, default is the head block of the main chain.
+    async fn account(
+        &self,
+        ctx: &Context<'_>,
+        address: String,
+        block_hash: Option<String>,
+        block_number: Option<u64>,
+    ) -> async_graphql::Result<GraphQLAccount> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let address = AccountAddress::from_str(address.as_str())?;
+        let state_root = context.state_root(block_hash, block_number).await?;
+        Ok(GraphQLAccount {
+            address,
+            state_root,
+        })
+    }
+}
+
+pub struct GraphQLBlock(Block);
+
+#[Object(name = "Block")]
+impl GraphQLBlock {
+    async fn hash(&self) -> String {
+        self.0.id().to_string()
+    }
+
+    async fn number(&self) -> u64 {
+        self.0.header().number()
+    }
+
+    async fn parent_hash(&self) -> String {
+        self.0.header().parent_hash().to_string()
+    }
+
+    async fn timestamp(&self) -> u64 {
+        self.0.header().timestamp()
+    }
+
+    async fn author(&self) -> String {
+        self.0.header().author().to_string()
+    }
+
+    async fn state_root(&self) -> String {
+        self.0.header().state_root().to_string()
+    }
+
+    async fn gas_used(&self) -> u64 {
+        self.0.header().gas_used()
+    }
+
+    async fn header(&self) -> Json<BlockHeaderView> {
+        Json(self.0.header().clone().into())
+    }
+
+    /// The user transactions of the block.
+    async fn transactions(&self) -> Vec<GraphQLTransaction> {
+        self.0
+            .transactions()
+            .iter()
+            .map(|txn| {
+                GraphQLTransaction::new(Transaction::UserTransaction(txn.clone()), self.0.header())
+            })
+            .collect()
+    }
+
+    /// The infos of all the transactions in the block, including the block metadata transaction.
+    async fn transaction_infos(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<GraphQLTransactionInfo>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .chain
+            .get_block_txn_infos(self.0.id())
+            .await?
+            .into_iter()
+            .map(|info| GraphQLTransactionInfo(info.into()))
+            .collect())
+    }
+}
+
+pub struct GraphQLTransaction {
+    txn: Transaction,
+    block_hash: HashValue,
+    block_number: u64,
+    state_root: HashValue,
+}
+
+impl GraphQLTransaction {
+    fn new(txn: Transaction, block_header: &BlockHeader) -> Self {
+        Self {
+            txn,
+            block_hash: block_header.id(),
+            block_number: block_header.number(),
+            state_root: block_header.state_root(),
+        }
+    }
+
+    fn user_txn(&self) -> Option<&SignedUserTransaction> {
+        match &self.txn {
+            Transaction::UserTransaction(txn) => Some(txn),
+            Transaction::BlockMetadata(_) => None,
+        }
+    }
+}
+
+#[Object(name = "Transaction")]
+impl GraphQLTransaction {
+    async fn hash(&self) -> String {
+        self.txn.id().to_string()
+    }
+
+    async fn block_hash(&self) -> String {
+        self.block_hash.to_string()
+    }
+
+    async fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// The signed user transaction, none for the block metadata transaction.
+    async fn user_transaction(
+        &self,
+    ) -> async_graphql::Result<Option<Json<SignedUserTransactionView>>> {
+        Ok(self
+            .user_txn()
+            .map(|txn| SignedUserTransactionView::try_from(txn.clone()))
+            .transpose()?
+            .map(Json))
+    }
+
+    /// The sender at the state of the block, none for the block metadata transaction.
+    async fn sender(&self) -> Option<GraphQLAccount> {
+        self.user_txn().map(|txn| GraphQLAccount {
+            address: txn.sender(),
+            state_root: self.state_root,
+        })
+    }
+
+    async fn info(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Option<GraphQLTransactionInfo>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .chain
+            .get_transaction_info(self.txn.id())
+            .await?
+            .map(|info| GraphQLTransactionInfo(info.into())))
+    }
+
+    async fn events(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<GraphQLEvent>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .chain
+            .get_events_by_txn_hash(self.txn.id())
+            .await?
+            .into_iter()
+            .map(|event| GraphQLEvent {
+                event,
+                state_root: self.state_root,
+            })
+            .collect())
+    }
+}
+
+pub struct GraphQLTransactionInfo(TransactionInfoView);
+
+#[Object(name = "TransactionInfo")]
+impl GraphQLTransactionInfo {
+    async fn transaction_hash(&self) -> String {
+        self.0.transaction_hash.to_string()
+    }
+
+    async fn transaction_index(&self) -> u32 {
+        self.0.transaction_index
+    }
+
+    async fn transaction_global_index(&self) -> u64 {
+        self.0.transaction_global_index.0
+    }
+
+    async fn state_root_hash(&self) -> String {
+        self.0.state_root_hash.to_string()
+    }
+
+    async fn event_root_hash(&self) -> String {
+        self.0.event_root_hash.to_string()
+    }
+
+    async fn gas_used(&self) -> u64 {
+        self.0.gas_used.0
+    }
+
+    async fn status(&self) -> Json<TransactionStatusView> {
+        Json(self.0.status.clone())
+    }
+}
+
+pub struct GraphQLEvent {
+    event: ContractEventInfo,
+    /// The state to decode the event.
+    state_root: HashValue,
+}
+
+#[Object(name = "Event")]
+impl GraphQLEvent {
+    async fn type_tag(&self) -> String {
+        self.event.event.type_tag().to_string()
+    }
+
+    async fn event_key(&self) -> String {
+        self.event.event.key().to_string()
+    }
+
+    async fn sequence_number(&self) -> u64 {
+        self.event.event.sequence_number()
+    }
+
+    async fn event_index(&self) -> u32 {
+        self.event.event_index
+    }
+
+    /// The hex encoded event data.
+    async fn data(&self) -> String {
+        format!("0x{}", hex::encode(self.event.event.event_data()))
+    }
+
+    /// The event data decoded by the abi of the event type.
+    async fn decoded(&self, ctx: &Context<'_>) -> async_graphql::Result<Json<serde_json::Value>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let state = context.state_db(self.state_root);
+        let abi = ABIResolver::new(&state).resolve_type_tag(self.event.event.type_tag())?;
+        let decoded = decode_move_value(&abi, self.event.event.event_data())?;
+        Ok(Json(decoded.into()))
+    }
+}
+
+pub struct GraphQLAccount {
+    address: AccountAddress,
+    state_root: HashValue,
+}
+
+#[Object(name = "Account")]
+impl GraphQLAccount {
+    async fn address(&self) -> String {
+        self.address.to_string()
+    }
+
+    async fn sequence_number(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<u64>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .state_db(self.state_root)
+            .get_account_resource(self.address)?
+            .map(|resource| resource.sequence_number()))
+    }
+
+    /// The balance of the token, default is STC.
+    async fn balance(
+        &self,
+        ctx: &Context<'_>,
+        token: Option<String>,
+    ) -> async_graphql::Result<Option<String>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let state = context.state_db(self.state_root);
+        let balance = match token {
+            Some(token) => {
+                state.get_balance_by_token_code(self.address, TokenCode::from_str(&token)?)?
+            }
+            None => state.get_balance(self.address)?,
+        };
+        Ok(balance.map(|balance| balance.to_string()))
+    }
+
+    async fn resource(
+        &self,
+        ctx: &Context<'_>,
+        type_tag: String,
+    ) -> async_graphql::Result<Option<GraphQLResource>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let struct_tag = StructTag::from_str(type_tag.as_str())?;
+        let data = context
+            .state_db(self.state_root)
+            .get_state_value(&StateKey::AccessPath(AccessPath::resource_access_path(
+                self.address,
+                struct_tag.clone(),
+            )))?;
+        Ok(data.map(|data| GraphQLResource {
+            struct_tag,
+            data,
+            state_root: self.state_root,
+        }))
+    }
+
+    /// The resources of the account, filtered by `type_tags` if present.
+    async fn resources(
+        &self,
+        ctx: &Context<'_>,
+        type_tags: Option<Vec<String>>,
+    ) -> async_graphql::Result<Vec<GraphQLResource>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let filter = type_tags
+            .unwrap_or_default()
+            .iter()
+            .map(|type_tag| StructTag::from_str(type_tag.as_str()))
+            .collect::<Result<Vec<_>>>()?;
+        let state_set = context
+            .state
+            .clone()
+            .get_account_state_set(self.address, Some(self.state_root))
+            .await?;
+        let resources = match state_set.and_then(|state_set| state_set.resource_set().cloned()) {
+            Some(resource_set) => resource_set,
+            None => return Ok(vec![]),
+        };
+        let mut result = vec![];
+        for (key, data) in resources.iter() {
+            let struct_tag = StructTag::decode(key.as_slice())?;
+            if filter.is_empty()
+                || filter
+                    .iter()
+                    .any(|filter| struct_tag_match(filter, &struct_tag))
+            {
+                result.push(GraphQLResource {
+                    struct_tag,
+                    data: data.clone(),
+                    state_root: self.state_root,
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
+pub struct GraphQLResource {
+    struct_tag: StructTag,
+    data: Vec<u8>,
+    state_root: HashValue,
+}
+
+#[Object(name = "Resource")]
+impl GraphQLResource {
+    async fn type_tag(&self) -> String {
+        self.struct_tag.to_string()
+    }
+
+    /// The hex encoded resource.
+    async fn raw(&self) -> String {
+        format!("0x{}", hex::encode(self.data.as_slice()))
+    }
+
+    /// The resource decoded by the resource viewer.
+    async fn json(&self, ctx: &Context<'_>) -> async_graphql::Result<Json<serde_json::Value>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let state = context.state_db(self.state_root);
+        let value = view_resource(&state, self.struct_tag.clone(), self.data.as_slice())?;
+        Ok(Json(DecodedMoveValue::from(value).into()))
+    }
+}
+
+/// The auth and the api quota of the GraphQL endpoint, the same as the json rpc. The endpoint is
+/// not in any apiset, so if the rpc auth is configured, a request must carry a token allowed to
+/// call the `graphql` method.
+pub struct GraphQLGuard {
+    auth: Option<Arc<RpcAuthConfig>>,
+    rate_limiter: JsonApiRateLimitMiddleware,
+}
+
+impl GraphQLGuard {
+    pub fn new(auth: Option<Arc<RpcAuthConfig>>, rate_limiter: JsonApiRateLimitMiddleware) -> Self {
+        Self { auth, rate_limiter }
+    }
+
+    /// Check the request from `client_ip`, return the error response if it is denied.
+    fn check(&self, req: &Request<Body>, client_ip: IpAddr) -> Result<(), Response<Body>> {
+        let mut user = client_ip.to_string();
+        if let Some(auth) = self.auth.as_ref() {
+            let token = req
+                .headers()
+                .get(hyper::header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .map(|token| token.trim())
+                .ok_or_else(|| error_response(StatusCode::UNAUTHORIZED, "Rpc token is required"))?;
+            let token = verify_token(auth, token)
+                .ok_or_else(|| error_response(StatusCode::UNAUTHORIZED, "Invalid rpc token"))?;
+            if !token.is_allowed(None, GRAPHQL_METHOD) {
+                return Err(error_response(StatusCode::FORBIDDEN, "Permission denied"));
+            }
+            user = token.id.clone();
+        }
+        self.rate_limiter
+            .check(GRAPHQL_METHOD, Some(&user))
+            .map_err(|e| error_response(StatusCode::TOO_MANY_REQUESTS, e.to_string()))
+    }
+}
+
+/// Serve the GraphQL requests over http, on its own runtime.
+pub struct GraphQLServer {
+    shutdown: Option<oneshot::Sender<()>>,
+    runtime: Option<tokio::runtime::Runtime>,
+}
+
+impl GraphQLServer {
+    pub fn start(
+        address: SocketAddr,
+        schema: StarcoinSchema,
+        guard: GraphQLGuard,
+        max_request_body_size: usize,
+    ) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name("graphql")
+            .enable_all()
+            .build()?;
+        let builder = {
+            let _guard = runtime.enter();
+            hyper::Server::try_bind(&address)?
+        };
+        let guard = Arc::new(guard);
+        let make_service = make_service_fn(move |conn: &AddrStream| {
+            let schema = schema.clone();
+            let guard = guard.clone();
+            let client_ip = conn.remote_addr().ip();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    handle_request(
+                        schema.clone(),
+                        guard.clone(),
+                        client_ip,
+                        req,
+                        max_request_body_size,
+                    )
+                }))
+            }
+        });
+        let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+        let server = builder
+            .serve(make_service)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_receiver.await;
+            });
+        runtime.spawn(async move {
+            if let Err(e) = server.await {
+                error!("GraphQL server error: {:?}", e);
+            }
+        });
+        Ok(Self {
+            shutdown: Some(shutdown),
+            runtime: Some(runtime),
+        })
+    }
+
+    pub fn close(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl Drop for GraphQLServer {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+async fn handle_request(
+    schema: StarcoinSchema,
+    guard: Arc<GraphQLGuard>,
+    client_ip: IpAddr,
+    req: Request<Body>,
+    max_request_body_size: usize,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::POST {
+        return Ok(error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Only POST is supported",
+        ));
+    }
+    if let Err(response) = guard.check(&req, client_ip) {
+        return Ok(response);
+    }
+    let body = match read_body(req, max_request_body_size).await {
+        Ok(body) => body,
+        Err(response) => return Ok(response),
+    };
+    let request: async_graphql::Request = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, e.to_string())),
+    };
+    let response = schema.execute(request).await;
+    Ok(match serde_json::to_vec(&response) {
+        Ok(body) => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .expect("Build response should success."),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })
+}
+
+/// Read the body up to `max_request_body_size`, the body is rejected by its content length first,
+/// and the reading stops once the size is exceeded.
+async fn read_body(
+    req: Request<Body>,
+    max_request_body_size: usize,
+) -> Result<Vec<u8>, Response<Body>> {
+    let too_large = || error_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large");
+    let content_length = req
+        .headers()
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if matches!(content_length, Some(len) if len > max_request_body_size) {
+        return Err(too_large());
+    }
+    let mut body = req.into_body();
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or_default());
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+        if bytes.len().saturating_add(chunk.len()) > max_request_body_size {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(message.into()))
+        .expect("Build response should success.")
+}
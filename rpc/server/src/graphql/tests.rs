@@ -0,0 +1,152 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::auth_middleware::generate_token;
+use crate::graphql::{read_body, GraphQLContext, GraphQLGuard};
+use crate::rate_limit_middleware::JsonApiRateLimitMiddleware;
+use anyhow::Result;
+use hyper::{Body, Request, StatusCode};
+use starcoin_chain_service::ChainReaderService;
+use starcoin_config::{ApiQuotaConfig, ApiQuotaConfiguration, RpcAuthConfig, RpcTokenConfig};
+use starcoin_state_service::ChainStateService;
+use starcoin_types::account_config::genesis_address;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+
+#[actix_rt::test]
+async fn test_graphql_query() -> Result<()> {
+    let (_txpool_service, storage, _config, _, registry) =
+        test_helper::start_txpool_with_miner(1000, false).await;
+    let chain = registry.register::<ChainReaderService>().await?;
+    let state = registry.register::<ChainStateService>().await?;
+    let schema = GraphQLContext::new(chain, state, storage).schema();
+
+    let query = format!(
+        r#"{{
+            block(number: 0) {{
+                number
+                transactionInfos {{ gasUsed status }}
+            }}
+            account(address: "{}") {{
+                address
+                resources {{ typeTag json }}
+            }}
+        }}"#,
+        genesis_address()
+    );
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json()?;
+    assert_eq!(data["block"]["number"], 0);
+    assert_eq!(
+        data["block"]["transactionInfos"][0]["status"],
+        serde_json::json!("Executed")
+    );
+    let resources = data["account"]["resources"]
+        .as_array()
+        .expect("resources should be array");
+    assert!(!resources.is_empty());
+    assert!(resources
+        .iter()
+        .all(|resource| resource["typeTag"].is_string() && resource["json"].is_object()));
+
+    let response = schema.execute("{ block(number: 1000) { hash } }").await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(response.data.into_json()?["block"].is_null());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_graphql_query_complexity() -> Result<()> {
+    let (_txpool_service, storage, _config, _, registry) =
+        test_helper::start_txpool_with_miner(1000, false).await;
+    let chain = registry.register::<ChainReaderService>().await?;
+    let state = registry.register::<ChainStateService>().await?;
+    let schema = GraphQLContext::new(chain, state, storage).schema();
+
+    let fields = (0..1000)
+        .map(|i| format!("b{}: block(number: 0) {{ number }}", i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let response = schema.execute(format!("{{ {} }}", fields)).await;
+    assert!(!response.errors.is_empty());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_read_body() {
+    let request = |body: Body| Request::post("/").body(body).unwrap();
+    assert_eq!(
+        read_body(request(Body::from(vec![1u8; 10])), 10)
+            .await
+            .unwrap(),
+        vec![1u8; 10]
+    );
+    let response = read_body(request(Body::from(vec![1u8; 11])), 10)
+        .await
+        .unwrap_err();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    // a chunked body without content length is rejected once it exceeds the limit.
+    let chunks = futures::stream::iter((0..3).map(|_| Ok::<_, std::io::Error>(vec![1u8; 6])));
+    let response = read_body(request(Body::wrap_stream(chunks)), 10)
+        .await
+        .unwrap_err();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[test]
+fn test_graphql_guard() {
+    let secret = "0123456789abcdef0123456789abcdef";
+    let auth = RpcAuthConfig {
+        secret: secret.to_string(),
+        tokens: vec![
+            RpcTokenConfig {
+                id: "team.a".to_string(),
+                apis: Default::default(),
+                methods: vec!["graphql".to_string()],
+                quota: Some("1/s".parse::<ApiQuotaConfig>().unwrap()),
+            },
+            RpcTokenConfig {
+                id: "team.b".to_string(),
+                apis: Default::default(),
+                methods: vec![],
+                quota: None,
+            },
+        ],
+    };
+    let user_quotas: HashMap<String, ApiQuotaConfig> = auth
+        .tokens
+        .iter()
+        .filter_map(|token| token.quota.clone().map(|quota| (token.id.clone(), quota)))
+        .collect();
+    let guard = GraphQLGuard::new(
+        Some(Arc::new(auth)),
+        JsonApiRateLimitMiddleware::from_config(ApiQuotaConfiguration::default(), user_quotas),
+    );
+    let client_ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let check = |token: Option<String>| {
+        let mut request = Request::post("/");
+        if let Some(token) = token {
+            request = request.header(hyper::header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        guard
+            .check(&request.body(Body::empty()).unwrap(), client_ip)
+            .map_err(|response| response.status())
+    };
+    assert_eq!(check(None), Err(StatusCode::UNAUTHORIZED));
+    assert_eq!(
+        check(Some(generate_token("another secret", "team.a"))),
+        Err(StatusCode::UNAUTHORIZED)
+    );
+    assert_eq!(
+        check(Some(generate_token(secret, "team.b"))),
+        Err(StatusCode::FORBIDDEN)
+    );
+    assert_eq!(check(Some(generate_token(secret, "team.a"))), Ok(()));
+    assert_eq!(
+        check(Some(generate_token(secret, "team.a"))),
+        Err(StatusCode::TOO_MANY_REQUESTS)
+    );
+}
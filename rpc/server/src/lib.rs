@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2
 
 mod api_registry;
+pub mod auth_middleware;
 mod extractors;
+pub mod graphql;
 pub mod module;
 mod rate_limit_middleware;
 pub mod service;
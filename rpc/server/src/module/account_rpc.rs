@@ -5,7 +5,7 @@ use crate::module::helpers::TransactionRequestFiller;
 use crate::module::map_err;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
-use starcoin_account_api::{AccountAsyncService, AccountInfo};
+use starcoin_account_api::{AccountAsyncService, AccountInfo, MnemonicAccountInfo};
 
 use starcoin_config::NodeConfig;
 use starcoin_rpc_api::types::{SignedMessageView, StrView, TransactionRequest};
@@ -265,4 +265,28 @@ where
         let fut = async move { service.remove_account(address, password).await }.map_err(map_err);
         Box::pin(fut.boxed())
     }
+    fn create_from_mnemonic(&self, password: String) -> FutureResult<MnemonicAccountInfo> {
+        let service = self.account.clone();
+        let fut =
+            async move { service.create_account_from_mnemonic(password).await }.map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn import_mnemonic(&self, mnemonic: String, password: String) -> FutureResult<AccountInfo> {
+        let service = self.account.clone();
+        let fut = async move { service.import_mnemonic(mnemonic, password).await }.map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn derive(&self, index: u32, password: String) -> FutureResult<AccountInfo> {
+        let service = self.account.clone();
+        let fut = async move { service.derive_account(index, password).await }.map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn export_mnemonic(&self, password: String) -> FutureResult<String> {
+        let service = self.account.clone();
+        let fut = async move { service.export_mnemonic(password).await }.map_err(map_err);
+        Box::pin(fut.boxed())
+    }
 }
@@ -23,6 +23,7 @@ use starcoin_state_api::StateView;
 use starcoin_statedb::ChainStateDB;
 use starcoin_storage::Storage;
 use starcoin_types::access_path::AccessPath;
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::block::BlockNumber;
 use starcoin_types::filter::Filter;
 use starcoin_types::startup_info::ChainInfo;
@@ -416,6 +417,29 @@ where
         Box::pin(fut.boxed())
     }
 
+    fn get_transactions_by_sender(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> FutureResult<Vec<TransactionInfoView>> {
+        let service = self.service.clone();
+        let config = self.config.clone();
+        let fut = async move {
+            let max_return_num = max_size.min(config.rpc.txn_info_query_max_range());
+            Ok(service
+                .get_transaction_infos_by_sender(sender, start_seq, reverse, max_return_num)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>())
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+
     fn get_transaction_proof(
         &self,
         block_hash: HashValue,
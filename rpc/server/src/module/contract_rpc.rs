@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::helpers::TransactionRequestFiller;
+use crate::module::helpers::{StateRootResolver, TransactionRequestFiller};
 use crate::module::map_err;
 use anyhow::format_err;
 use futures::future::TryFutureExt;
@@ -10,29 +10,36 @@ use starcoin_abi_decoder::{decode_move_value, DecodedMoveValue};
 use starcoin_abi_resolver::ABIResolver;
 use starcoin_abi_types::{FunctionABI, ModuleABI, StructInstantiation, TypeInstantiation};
 use starcoin_account_api::AccountAsyncService;
+use starcoin_chain_service::ChainReaderService;
 use starcoin_config::NodeConfig;
 use starcoin_dev::playground::{call_contract, PlaygroudService};
 use starcoin_executor::VMMetrics;
 use starcoin_resource_viewer::module_cache::ModuleCache;
 use starcoin_resource_viewer::MoveValueAnnotator;
 use starcoin_rpc_api::contract_api::ContractApi;
+use starcoin_rpc_api::state::StateQueryOption;
 use starcoin_rpc_api::types::{
-    AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, DryRunOutputView,
+    AnnotatedMoveStructView, AnnotatedMoveValueView, CallFrameView, ContractCall, DryRunOutputView,
     DryRunTransactionRequest, FunctionIdView, ModuleIdView, StrView, StructTagView,
     TransactionOutputView, WriteOpValueView,
 };
 use starcoin_rpc_api::FutureResult;
+use starcoin_service_registry::ServiceRef;
 use starcoin_state_api::ChainStateAsyncService;
 use starcoin_statedb::ChainStateDB;
 use starcoin_storage::Storage;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::language_storage::{ModuleId, StructTag};
-use starcoin_types::transaction::{DryRunTransaction, RawUserTransaction, TransactionPayload};
+use starcoin_types::transaction::{
+    DryRunTransaction, RawUserTransaction, TransactionOutput, TransactionPayload,
+};
 use starcoin_vm_types::access_path::AccessPath;
 use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::state_store::state_key::StateKey;
 use starcoin_vm_types::state_view::StateView;
 use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
+use starcoin_vm_types::vm_status::VMStatus;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -41,6 +48,7 @@ pub struct ContractRpcImpl<Account, Pool, State> {
     pub(crate) pool: Pool,
     pub(crate) chain_state: State,
     pub(crate) node_config: Arc<NodeConfig>,
+    chain: Option<ServiceRef<ChainReaderService>>,
     playground: PlaygroudService,
     storage: Arc<Storage>,
 }
@@ -56,6 +64,7 @@ where
         account: Option<Account>,
         pool: Pool,
         chain_state: State,
+        chain: Option<ServiceRef<ChainReaderService>>,
         playground: PlaygroudService,
         storage: Arc<Storage>,
     ) -> Self {
@@ -64,6 +73,7 @@ where
             pool,
             chain_state,
             node_config,
+            chain,
             playground,
             storage,
         }
@@ -76,6 +86,13 @@ where
             node_config: self.node_config.clone(),
         }
     }
+    fn state_root_resolver(&self) -> StateRootResolver<State> {
+        StateRootResolver {
+            chain_state: self.chain_state.clone(),
+            chain: self.chain.clone(),
+            state_store: self.storage.clone(),
+        }
+    }
 }
 
 impl<Account, Pool, State> ContractApi for ContractRpcImpl<Account, Pool, State>
@@ -84,10 +101,20 @@ where
     Pool: TxPoolSyncService + 'static,
     State: ChainStateAsyncService + 'static,
 {
-    fn get_code(&self, module_id: StrView<ModuleId>) -> FutureResult<Option<StrView<Vec<u8>>>> {
-        let service = self.chain_state.clone();
+    fn get_code(
+        &self,
+        module_id: StrView<ModuleId>,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Option<StrView<Vec<u8>>>> {
+        let resolver = self.state_root_resolver();
+        let storage = self.storage.clone();
+        let option = option.unwrap_or_default();
         let f = async move {
-            let code = service.get(AccessPath::from(&module_id.0)).await?;
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let code = ChainStateDB::new(storage, Some(state_root))
+                .get_state_value(&StateKey::AccessPath(AccessPath::from(&module_id.0)))?;
             Ok(code.map(StrView))
         };
         Box::pin(f.map_err(map_err).boxed())
@@ -97,17 +124,22 @@ where
         &self,
         addr: AccountAddress,
         resource_type: StrView<StructTag>,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<Option<AnnotatedMoveStructView>> {
-        let service = self.chain_state.clone();
+        let resolver = self.state_root_resolver();
+        let storage = self.storage.clone();
         let playground = self.playground.clone();
+        let option = option.unwrap_or_default();
         let f = async move {
-            let state_root = service.clone().state_root().await?;
-            let data = service
-                .get(AccessPath::resource_access_path(
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let data = ChainStateDB::new(storage, Some(state_root)).get_state_value(
+                &StateKey::AccessPath(AccessPath::resource_access_path(
                     addr,
                     resource_type.0.clone(),
-                ))
-                .await?;
+                )),
+            )?;
             match data {
                 None => Ok(None),
                 Some(d) => {
@@ -119,16 +151,23 @@ where
         };
         Box::pin(f.map_err(map_err).boxed())
     }
-    fn call(&self, call: ContractCall) -> FutureResult<Vec<AnnotatedMoveValueView>> {
-        let service = self.chain_state.clone();
+    fn call(
+        &self,
+        call: ContractCall,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Vec<AnnotatedMoveValueView>> {
+        let resolver = self.state_root_resolver();
         let playground = self.playground.clone();
+        let option = option.unwrap_or_default();
         let ContractCall {
             function_id,
             type_args,
             args,
         } = call;
         let f = async move {
-            let state_root = service.state_root().await?;
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
             let output = playground.call_contract(
                 state_root,
                 function_id.0.module,
@@ -142,9 +181,14 @@ where
         Box::pin(f.boxed())
     }
 
-    fn call_v2(&self, call: ContractCall) -> FutureResult<Vec<DecodedMoveValue>> {
-        let service = self.chain_state.clone();
+    fn call_v2(
+        &self,
+        call: ContractCall,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Vec<DecodedMoveValue>> {
+        let resolver = self.state_root_resolver();
         let storage = self.storage.clone();
+        let option = option.unwrap_or_default();
         let ContractCall {
             function_id,
             type_args,
@@ -152,7 +196,9 @@ where
         } = call;
         let metrics = self.playground.metrics.clone();
         let f = async move {
-            let state_root = service.state_root().await?;
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
             let state = ChainStateDB::new(storage, Some(state_root));
             let output = call_contract(
                 &state,
@@ -182,6 +228,7 @@ where
             let DryRunTransactionRequest {
                 transaction,
                 sender_public_key,
+                trace,
             } = txn;
 
             let txn = txn_builder.fill_transaction(transaction).await?;
@@ -193,6 +240,7 @@ where
                     public_key: sender_public_key.0,
                 },
                 metrics,
+                trace.unwrap_or(false),
             )
         }
         .map_err(map_err);
@@ -203,6 +251,7 @@ where
         &self,
         raw_txn: String,
         sender_public_key: StrView<AccountPublicKey>,
+        trace: Option<bool>,
     ) -> FutureResult<DryRunOutputView> {
         let service = self.chain_state.clone();
         let storage = self.storage.clone();
@@ -218,17 +267,26 @@ where
                     public_key: sender_public_key.0,
                 },
                 metrics,
+                trace.unwrap_or(false),
             )
         }
         .map_err(map_err);
         Box::pin(f.boxed())
     }
 
-    fn resolve_function(&self, function_id: FunctionIdView) -> FutureResult<FunctionABI> {
-        let service = self.chain_state.clone();
+    fn resolve_function(
+        &self,
+        function_id: FunctionIdView,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<FunctionABI> {
+        let resolver = self.state_root_resolver();
         let storage = self.storage.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
-            let state = ChainStateDB::new(storage, Some(service.state_root().await?));
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let state = ChainStateDB::new(storage, Some(state_root));
             ABIResolver::new(&state)
                 .resolve_function(&function_id.0.module, function_id.0.function.as_ident_str())
         }
@@ -240,33 +298,54 @@ where
         &self,
         module_id: ModuleIdView,
         function_idx: u16,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<FunctionABI> {
-        let service = self.chain_state.clone();
+        let resolver = self.state_root_resolver();
         let storage = self.storage.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
-            let state = ChainStateDB::new(storage, Some(service.state_root().await?));
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let state = ChainStateDB::new(storage, Some(state_root));
             ABIResolver::new(&state).resolve_module_function_index(&module_id.0, function_idx)
         }
         .map_err(map_err);
         Box::pin(fut.boxed())
     }
 
-    fn resolve_struct(&self, struct_tag: StructTagView) -> FutureResult<StructInstantiation> {
-        let service = self.chain_state.clone();
+    fn resolve_struct(
+        &self,
+        struct_tag: StructTagView,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<StructInstantiation> {
+        let resolver = self.state_root_resolver();
         let storage = self.storage.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
-            let state = ChainStateDB::new(storage, Some(service.state_root().await?));
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let state = ChainStateDB::new(storage, Some(state_root));
             ABIResolver::new(&state).resolve_struct_tag(&struct_tag.0)
         }
         .map_err(map_err);
         Box::pin(fut.boxed())
     }
 
-    fn resolve_module(&self, module_id: ModuleIdView) -> FutureResult<ModuleABI> {
-        let service = self.chain_state.clone();
+    fn resolve_module(
+        &self,
+        module_id: ModuleIdView,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<ModuleABI> {
+        let resolver = self.state_root_resolver();
         let storage = self.storage.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
-            let state = ChainStateDB::new(storage, Some(service.state_root().await?));
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let state = ChainStateDB::new(storage, Some(state_root));
             ABIResolver::new(&state).resolve_module(&module_id.0)
         }
         .map_err(map_err);
@@ -278,15 +357,34 @@ pub fn dry_run<S: StateView>(
     state_view: &S,
     txn: DryRunTransaction,
     metrics: Option<VMMetrics>,
+    trace: bool,
+) -> anyhow::Result<DryRunOutputView> {
+    let (vm_status, output, call_frame) =
+        starcoin_dev::playground::dry_run_with_trace(state_view, txn.clone(), metrics, trace)?;
+    explain_txn_output(
+        state_view,
+        txn.raw_txn.into_payload(),
+        vm_status,
+        output,
+        call_frame.map(Into::into),
+    )
+}
+
+/// Explain the vm status, and decode the resources in the write set of the transaction output.
+pub(crate) fn explain_txn_output<S: StateView>(
+    state_view: &S,
+    payload: TransactionPayload,
+    vm_status: VMStatus,
+    output: TransactionOutput,
+    trace: Option<CallFrameView>,
 ) -> anyhow::Result<DryRunOutputView> {
-    let (vm_status, output) = starcoin_dev::playground::dry_run(state_view, txn.clone(), metrics)?;
     let vm_status_explain = vm_status_translator::explain_vm_status(state_view, vm_status)?;
     let mut txn_output: TransactionOutputView = output.into();
 
     let resolver = {
         let module_cache = ModuleCache::new();
         // If the txn is package txn, we need to use modules in the package to resolve transaction output.
-        if let TransactionPayload::Package(p) = txn.raw_txn.into_payload() {
+        if let TransactionPayload::Package(p) = payload {
             let modules = p
                 .modules()
                 .iter()
@@ -321,5 +419,6 @@ pub fn dry_run<S: StateView>(
     Ok(DryRunOutputView {
         explained_status: vm_status_explain,
         txn_output,
+        trace,
     })
 }
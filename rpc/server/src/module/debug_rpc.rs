@@ -1,18 +1,29 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::to_invalid_param_err;
+use crate::module::contract_rpc::explain_txn_output;
 use crate::module::txfactory_rpc::TxFactoryStatusHandle;
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::{bail, ensure, format_err};
+use futures::future::TryFutureExt;
+use futures::FutureExt;
 use jsonrpc_core::Result;
+use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_executor::{block_execute, trace_transaction, VMMetrics};
 use starcoin_logger::prelude::LevelFilter;
 use starcoin_logger::structured_log::set_slog_level;
 use starcoin_logger::{LogPattern, LoggerHandle};
 use starcoin_rpc_api::debug::DebugApi;
-use starcoin_rpc_api::types::FactoryAction;
+use starcoin_rpc_api::types::{FactoryAction, TransactionTraceView};
+use starcoin_rpc_api::FutureResult;
 use starcoin_service_registry::bus::{Bus, BusService};
 use starcoin_service_registry::ServiceRef;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::Storage;
 use starcoin_types::system_events::GenerateBlockEvent;
+use starcoin_types::transaction::Transaction;
 use starcoin_vm_runtime::starcoin_vm::StarcoinVM;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -21,6 +32,9 @@ pub struct DebugRpcImpl {
     config: Arc<NodeConfig>,
     log_handle: Arc<LoggerHandle>,
     bus: ServiceRef<BusService>,
+    chain: Option<ServiceRef<ChainReaderService>>,
+    storage: Arc<Storage>,
+    vm_metrics: Option<VMMetrics>,
 }
 
 impl DebugRpcImpl {
@@ -28,11 +42,17 @@ impl DebugRpcImpl {
         config: Arc<NodeConfig>,
         log_handle: Arc<LoggerHandle>,
         bus: ServiceRef<BusService>,
+        chain: Option<ServiceRef<ChainReaderService>>,
+        storage: Arc<Storage>,
+        vm_metrics: Option<VMMetrics>,
     ) -> Self {
         Self {
             config,
             log_handle,
             bus,
+            chain,
+            storage,
+            vm_metrics,
         }
     }
 }
@@ -92,4 +112,82 @@ impl DebugApi for DebugRpcImpl {
     fn get_concurrency_level(&self) -> Result<usize> {
         Ok(StarcoinVM::get_concurrency_level())
     }
+
+    fn trace_transaction(
+        &self,
+        txn_hash: HashValue,
+        trace: Option<bool>,
+    ) -> FutureResult<TransactionTraceView> {
+        let chain = self.chain.clone();
+        let storage = self.storage.clone();
+        let vm_metrics = self.vm_metrics.clone();
+        let fut = async move {
+            let chain = chain.ok_or_else(|| format_err!("Chain service is not available."))?;
+            let txn_info = chain
+                .get_transaction_info(txn_hash)
+                .await?
+                .ok_or_else(|| format_err!("Can not find transaction info by {}", txn_hash))?;
+            let block = chain
+                .get_block_by_hash(txn_info.block_id)
+                .await?
+                .ok_or_else(|| format_err!("Can not find block by {}", txn_info.block_id))?;
+            if block.header().is_genesis() {
+                bail!("Can not trace the transaction of genesis block.");
+            }
+            let parent = chain
+                .get_header_by_hash(&block.header().parent_hash())
+                .await?
+                .ok_or_else(|| {
+                    format_err!(
+                        "Can not find parent block by {}",
+                        block.header().parent_hash()
+                    )
+                })?;
+            let mut transactions = vec![Transaction::BlockMetadata(
+                block.to_metadata(parent.gas_used()),
+            )];
+            transactions.extend(
+                block
+                    .transactions()
+                    .iter()
+                    .cloned()
+                    .map(Transaction::UserTransaction),
+            );
+            let txn_index = txn_info.transaction_index as usize;
+            ensure!(
+                transactions.get(txn_index).map(|txn| txn.id()) == Some(txn_hash),
+                "Transaction {} is not at index {} of block {}",
+                txn_hash,
+                txn_index,
+                txn_info.block_id
+            );
+            let txn = match transactions.swap_remove(txn_index) {
+                Transaction::UserTransaction(txn) => txn,
+                _ => bail!("Only user transaction can be traced."),
+            };
+            transactions.truncate(txn_index);
+
+            // The statedb is never flushed, the execution results are only kept in memory.
+            let state_view = ChainStateDB::new(storage, Some(parent.state_root()));
+            block_execute(&state_view, transactions, u64::MAX, vm_metrics.clone())?;
+            let (vm_status, output, call_frame) =
+                trace_transaction(&state_view, txn.clone(), trace.unwrap_or(false), vm_metrics)?;
+            let output = explain_txn_output(
+                &state_view,
+                txn.payload().clone(),
+                vm_status,
+                output,
+                call_frame.map(Into::into),
+            )?;
+            Ok(TransactionTraceView {
+                transaction_hash: txn_hash,
+                block_hash: txn_info.block_id,
+                block_number: txn_info.block_number.into(),
+                transaction_index: txn_info.transaction_index,
+                output,
+            })
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
 }
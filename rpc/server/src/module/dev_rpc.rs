@@ -0,0 +1,224 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::module::map_err;
+use anyhow::{ensure, format_err};
+use futures::future::TryFutureExt;
+use futures::{FutureExt, StreamExt};
+use jsonrpc_core::Result;
+use parking_lot::Mutex;
+use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
+use starcoin_config::{genesis_key_pair, NodeConfig};
+use starcoin_crypto::HashValue;
+use starcoin_node_api::node_service::NodeAsyncService;
+use starcoin_rpc_api::dev::DevApi;
+use starcoin_rpc_api::FutureResult;
+use starcoin_service_registry::bus::{Bus, BusService};
+use starcoin_service_registry::ServiceRef;
+use starcoin_time_service::{MockTimeService, TimeService};
+use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::system_events::{GenerateBlockEvent, NewHeadBlock};
+use starcoin_types::transaction::RawUserTransaction;
+use starcoin_vm_types::impersonation::ImpersonatedAccounts;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The max time to wait for a block to be mined.
+const MINE_BLOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+struct DevSnapshot {
+    id: u64,
+    block_hash: HashValue,
+    timestamp: u64,
+}
+
+#[derive(Default)]
+struct DevSnapshots {
+    next_id: u64,
+    snapshots: Vec<DevSnapshot>,
+}
+
+pub struct DevRpcImpl<S, P>
+where
+    S: NodeAsyncService + 'static,
+    P: TxPoolSyncService + 'static,
+{
+    time_service: MockTimeService,
+    bus: ServiceRef<BusService>,
+    chain: ServiceRef<ChainReaderService>,
+    node: S,
+    txpool: P,
+    impersonated_accounts: Option<ImpersonatedAccounts>,
+    snapshots: Arc<Mutex<DevSnapshots>>,
+}
+
+impl<S, P> DevRpcImpl<S, P>
+where
+    S: NodeAsyncService,
+    P: TxPoolSyncService,
+{
+    /// Only the network with mock time service, such as dev, support the dev api.
+    pub fn new(
+        config: Arc<NodeConfig>,
+        bus: ServiceRef<BusService>,
+        chain: ServiceRef<ChainReaderService>,
+        node: S,
+        txpool: P,
+    ) -> Option<Self> {
+        let time_service = config
+            .net()
+            .time_service()
+            .as_any()
+            .downcast_ref::<MockTimeService>()
+            .cloned()?;
+        Some(Self {
+            time_service,
+            bus,
+            chain,
+            node,
+            txpool,
+            impersonated_accounts: config.net().impersonated_accounts(),
+            snapshots: Arc::new(Mutex::new(DevSnapshots::default())),
+        })
+    }
+
+    fn impersonated_accounts(&self) -> anyhow::Result<&ImpersonatedAccounts> {
+        self.impersonated_accounts
+            .as_ref()
+            .ok_or_else(|| format_err!("The network does not support impersonating accounts."))
+    }
+
+    fn add_impersonated_transaction(&self, raw_txn: &str) -> anyhow::Result<HashValue> {
+        let raw_txn = RawUserTransaction::from_hex(raw_txn.strip_prefix("0x").unwrap_or(raw_txn))?;
+        ensure!(
+            self.impersonated_accounts()?.contains(&raw_txn.sender()),
+            "The sender {} is not impersonated.",
+            raw_txn.sender()
+        );
+        // the authenticator of an impersonated transaction is not checked against the sender,
+        // so sign it with the well known genesis key.
+        let (private_key, public_key) = genesis_key_pair();
+        let txn = raw_txn.sign(&private_key, public_key)?.into_inner();
+        let txn_hash = txn.id();
+        self.txpool
+            .add_txns(vec![txn])
+            .pop()
+            .expect("txpool should return result")?;
+        Ok(txn_hash)
+    }
+}
+
+impl<S, P> DevApi for DevRpcImpl<S, P>
+where
+    S: NodeAsyncService,
+    P: TxPoolSyncService,
+{
+    fn set_next_block_timestamp(&self, timestamp: u64) -> FutureResult<()> {
+        let time_service = self.time_service.clone();
+        let chain = self.chain.clone();
+        let fut = async move {
+            let head = chain.main_head_header().await?;
+            ensure!(
+                timestamp > head.timestamp(),
+                "The timestamp {} should be greater than the head block timestamp {}",
+                timestamp,
+                head.timestamp()
+            );
+            time_service.set(timestamp);
+            Ok(())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn increase_time(&self, millis: u64) -> Result<u64> {
+        self.time_service.increment_by(millis);
+        Ok(self.time_service.now_millis())
+    }
+
+    fn mine(&self, n: u64) -> FutureResult<Vec<HashValue>> {
+        let bus = self.bus.clone();
+        let fut = async move {
+            let mut new_heads = bus.channel::<NewHeadBlock>().await?;
+            let mut blocks = vec![];
+            for _ in 0..n {
+                bus.broadcast(GenerateBlockEvent::new(true, true))
+                    .map_err(|e| format_err!("Broadcast generate block event error: {}", e))?;
+                let new_head = tokio::time::timeout(MINE_BLOCK_TIMEOUT, new_heads.next())
+                    .await
+                    .map_err(|_| format_err!("Wait for block mined timeout."))?
+                    .ok_or_else(|| format_err!("Bus service is down."))?;
+                blocks.push(new_head.0.block.id());
+            }
+            Ok(blocks)
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn snapshot(&self) -> FutureResult<u64> {
+        let time_service = self.time_service.clone();
+        let chain = self.chain.clone();
+        let snapshots = self.snapshots.clone();
+        let fut = async move {
+            let head = chain.main_head_header().await?;
+            let mut snapshots = snapshots.lock();
+            let id = snapshots.next_id;
+            snapshots.next_id += 1;
+            snapshots.snapshots.push(DevSnapshot {
+                id,
+                block_hash: head.id(),
+                timestamp: time_service.now_millis(),
+            });
+            Ok(id)
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn revert(&self, snapshot_id: u64) -> FutureResult<()> {
+        let time_service = self.time_service.clone();
+        let node = self.node.clone();
+        let snapshots = self.snapshots.clone();
+        let fut = async move {
+            let snapshot = snapshots
+                .lock()
+                .snapshots
+                .iter()
+                .find(|snapshot| snapshot.id == snapshot_id)
+                .cloned()
+                .ok_or_else(|| format_err!("Can not find snapshot {}", snapshot_id))?;
+            node.reset_node(snapshot.block_hash).await?;
+            time_service.set(snapshot.timestamp);
+            // the reverted snapshot and the snapshots taken after it are invalid now.
+            snapshots
+                .lock()
+                .snapshots
+                .retain(|snapshot| snapshot.id < snapshot_id);
+            Ok(())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn impersonate_account(&self, address: AccountAddress) -> Result<bool> {
+        self.impersonated_accounts()
+            .map(|accounts| accounts.impersonate(address))
+            .map_err(map_err)
+    }
+
+    fn stop_impersonating_account(&self, address: AccountAddress) -> Result<bool> {
+        self.impersonated_accounts()
+            .map(|accounts| accounts.stop_impersonating(address))
+            .map_err(map_err)
+    }
+
+    fn submit_impersonated_transaction(&self, raw_txn: String) -> FutureResult<HashValue> {
+        let result = self
+            .add_impersonated_transaction(raw_txn.as_str())
+            .map_err(map_err);
+        Box::pin(futures::future::ready(result))
+    }
+}
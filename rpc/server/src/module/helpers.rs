@@ -1,9 +1,15 @@
+use anyhow::{bail, ensure, format_err};
 use starcoin_account_api::AccountAsyncService;
+use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
 use starcoin_rpc_api::types::TransactionRequest;
+use starcoin_service_registry::ServiceRef;
 use starcoin_state_api::ChainStateAsyncService;
+use starcoin_state_tree::StateNodeStore;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::account_config::AccountResource;
+use starcoin_types::block::BlockNumber;
 use starcoin_types::transaction::{Module, Package, RawUserTransaction, TransactionPayload};
 use std::sync::Arc;
 
@@ -103,3 +109,132 @@ where
         Ok(raw_txn)
     }
 }
+
+/// Resolve the state root to read at, by the state root or the main chain block of the request.
+#[derive(Clone)]
+pub(crate) struct StateRootResolver<State> {
+    pub(crate) chain_state: State,
+    pub(crate) chain: Option<ServiceRef<ChainReaderService>>,
+    pub(crate) state_store: Arc<dyn StateNodeStore>,
+}
+
+impl<State> StateRootResolver<State>
+where
+    State: ChainStateAsyncService,
+{
+    /// Return the latest state root if none of `state_root`, `block_number` and `block_hash` is given.
+    pub(crate) async fn resolve(
+        self,
+        state_root: Option<HashValue>,
+        block_number: Option<BlockNumber>,
+        block_hash: Option<HashValue>,
+    ) -> anyhow::Result<HashValue> {
+        let header = match (state_root, block_number, block_hash) {
+            (None, None, None) => return self.chain_state.state_root().await,
+            (Some(state_root), None, None) => return Ok(state_root),
+            (None, Some(number), None) => self
+                .chain()?
+                .main_block_header_by_number(number)
+                .await?
+                .ok_or_else(|| format_err!("Can not find block {} on the main chain.", number))?,
+            (None, None, Some(hash)) => {
+                let chain = self.chain()?;
+                let header = chain
+                    .get_header_by_hash(&hash)
+                    .await?
+                    .ok_or_else(|| format_err!("Can not find block {}.", hash))?;
+                let main_header = chain.main_block_header_by_number(header.number()).await?;
+                ensure!(
+                    main_header.map(|h| h.id()) == Some(hash),
+                    "Block {} is not on the main chain.",
+                    hash
+                );
+                header
+            }
+            _ => bail!("Only one of state_root, block_number and block_hash can be specified."),
+        };
+        // The state of the old blocks may be pruned, or not downloaded by fast sync.
+        ensure!(
+            self.state_store.get(&header.state_root())?.is_some(),
+            "The state of block {}(#{}) is not available, state root {} may have been pruned.",
+            header.id(),
+            header.number(),
+            header.state_root()
+        );
+        Ok(header.state_root())
+    }
+
+    fn chain(&self) -> anyhow::Result<&ServiceRef<ChainReaderService>> {
+        self.chain
+            .as_ref()
+            .ok_or_else(|| format_err!("Chain service is not available."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_chain::{BlockChain, ChainWriter};
+    use starcoin_consensus::Consensus;
+    use starcoin_state_service::ChainStateService;
+    use starcoin_state_tree::mock::MockStateNodeStore;
+    use starcoin_storage::BlockStore;
+    use starcoin_types::account_address::AccountAddress;
+
+    #[stest::test]
+    async fn test_resolve_state_root() -> anyhow::Result<()> {
+        let (_txpool_service, storage, config, _, registry) =
+            test_helper::start_txpool_with_miner(1000, false).await;
+        let chain = registry.register::<ChainReaderService>().await?;
+        let state = registry.register::<ChainStateService>().await?;
+        let resolver = |state_store: Arc<dyn StateNodeStore>| StateRootResolver {
+            chain_state: state.clone(),
+            chain: Some(chain.clone()),
+            state_store,
+        };
+        let genesis = chain.main_block_header_by_number(0).await?.unwrap();
+
+        let state_root = resolver(storage.clone())
+            .resolve(None, Some(0), None)
+            .await?;
+        assert_eq!(state_root, genesis.state_root());
+        let state_root = resolver(storage.clone())
+            .resolve(None, None, Some(genesis.id()))
+            .await?;
+        assert_eq!(state_root, genesis.state_root());
+
+        let err = resolver(storage.clone())
+            .resolve(None, Some(0), Some(genesis.id()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Only one of"), "{}", err);
+
+        // Apply a block without updating the startup info, so it is not on the main chain.
+        let mut branch = BlockChain::new(
+            config.net().time_service(),
+            genesis.id(),
+            storage.clone(),
+            None,
+        )?;
+        let (template, _) =
+            branch.create_block_template(AccountAddress::random(), None, vec![], vec![], None)?;
+        let block = branch
+            .consensus()
+            .create_block(template, branch.time_service().as_ref())?;
+        let block_id = block.id();
+        branch.apply(block)?;
+        assert!(storage.get_block_header_by_hash(block_id)?.is_some());
+        let err = resolver(storage.clone())
+            .resolve(None, None, Some(block_id))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not on the main chain"), "{}", err);
+
+        let err = resolver(Arc::new(MockStateNodeStore::new()))
+            .resolve(None, Some(0), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("may have been pruned"), "{}", err);
+        Ok(())
+    }
+}
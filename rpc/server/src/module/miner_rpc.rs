@@ -5,20 +5,29 @@ use crate::module::map_err;
 use futures::{FutureExt, TryFutureExt};
 use starcoin_miner::{MinerService, SubmitSealRequest, UpdateSubscriberNumRequest};
 use starcoin_rpc_api::miner::MinerApi;
-use starcoin_rpc_api::types::MintedBlockView;
+use starcoin_rpc_api::types::{MiningWorkerView, MintedBlockView};
 use starcoin_rpc_api::FutureResult;
 use starcoin_service_registry::ServiceRef;
+use starcoin_stratum::rpc::GetWorkers;
+use starcoin_stratum::stratum::Stratum;
 use starcoin_types::block::BlockHeaderExtra;
 use starcoin_types::system_events::MintBlockEvent;
 use std::convert::TryInto;
 
 pub struct MinerRpcImpl {
     miner_service: ServiceRef<MinerService>,
+    stratum: Option<ServiceRef<Stratum>>,
 }
 
 impl MinerRpcImpl {
-    pub fn new(miner_service: ServiceRef<MinerService>) -> Self {
-        Self { miner_service }
+    pub fn new(
+        miner_service: ServiceRef<MinerService>,
+        stratum: Option<ServiceRef<Stratum>>,
+    ) -> Self {
+        Self {
+            miner_service,
+            stratum,
+        }
     }
 }
 
@@ -61,4 +70,29 @@ impl MinerApi for MinerRpcImpl {
         .map_err(map_err);
         Box::pin(fut.boxed())
     }
+
+    fn workers(&self) -> FutureResult<Vec<MiningWorkerView>> {
+        let stratum = self.stratum.clone();
+        let fut = async move {
+            let workers = match stratum {
+                Some(stratum) => stratum.send(GetWorkers).await?,
+                None => vec![],
+            };
+            Ok(workers
+                .into_iter()
+                .map(|worker| MiningWorkerView {
+                    worker_id: worker.worker_id,
+                    login: worker.login,
+                    agent: worker.agent,
+                    share_difficulty: worker.share_difficulty,
+                    accepted_shares: worker.accepted_shares,
+                    stale_shares: worker.stale_shares,
+                    invalid_shares: worker.invalid_shares,
+                    hashrate: worker.hashrate,
+                })
+                .collect())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
 }
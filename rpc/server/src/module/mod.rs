@@ -5,6 +5,7 @@ mod account_rpc;
 mod chain_rpc;
 mod contract_rpc;
 mod debug_rpc;
+mod dev_rpc;
 mod helpers;
 mod miner_rpc;
 mod network_manager_rpc;
@@ -20,6 +21,7 @@ pub use self::account_rpc::AccountRpcImpl;
 pub use self::chain_rpc::ChainRpcImpl;
 pub use self::contract_rpc::ContractRpcImpl;
 pub use self::debug_rpc::DebugRpcImpl;
+pub use self::dev_rpc::DevRpcImpl;
 pub use self::miner_rpc::MinerRpcImpl;
 pub use self::network_manager_rpc::NetworkManagerRpcImpl;
 pub use self::node_manager_rpc::NodeManagerRpcImpl;
@@ -2,33 +2,46 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::module::map_err;
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use futures::channel::mpsc;
 use futures::future::AbortHandle;
-use futures::StreamExt;
+use futures::stream::{BoxStream, LocalBoxStream};
+use futures::{Stream, StreamExt};
 use jsonrpc_pubsub::typed::Subscriber;
 use jsonrpc_pubsub::SubscriptionId;
 use parking_lot::RwLock;
 use starcoin_abi_decoder::decode_move_value;
 use starcoin_abi_resolver::ABIResolver;
-use starcoin_chain_notify::message::{ContractEventNotification, Notification, ThinBlock};
+use starcoin_chain::{BlockChain, ChainReader};
+use starcoin_chain_notify::load_block_events;
+use starcoin_chain_notify::message::{
+    BlockEvents, ContractEventNotification, Notification, ReorgTooDeep, ReorgTooDeepNotification,
+    ThinBlock,
+};
+use starcoin_config::NodeConfig;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_miner::{MinerService, UpdateSubscriberNumRequest};
 use starcoin_rpc_api::metadata::Metadata;
-use starcoin_rpc_api::types::{BlockView, TransactionEventResponse, TransactionEventView};
+use starcoin_rpc_api::types::{
+    BlockView, TransactionEventResponse, TransactionEventView, TransactionStatusView,
+};
 use starcoin_rpc_api::{errors, pubsub::StarcoinPubSub, types::pubsub};
 use starcoin_service_registry::{
     ActorService, EventHandler as ActorEventHandler, ServiceContext, ServiceFactory,
     ServiceHandler, ServiceRef, ServiceRequest,
 };
 use starcoin_statedb::ChainStateDB;
-use starcoin_storage::Storage;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_time_service::TimeService;
 use starcoin_txpool::TxPoolService;
-use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_txpool_api::{TxPoolSyncService, TxnStatusFullEvent};
+use starcoin_types::block::{BlockHeader, BlockNumber};
+use starcoin_types::contract_event::ContractEvent;
 use starcoin_types::filter::Filter;
 use starcoin_types::system_events::MintBlockEvent;
-use std::collections::HashMap;
+use starcoin_types::transaction::TxStatus;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::fmt::Debug;
 use std::sync::mpsc::TrySendError;
@@ -71,22 +84,16 @@ impl PubSubImpl {
         params: Option<pubsub::Params>,
     ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
         match (kind, params) {
-            (pubsub::Kind::NewHeads, None) => self
-                .service
-                .try_send(SubscribeNewHeads(subscriber))
-                .map_err(|e| {
-                    let msg = map_send_err(&e);
-                    (
-                        match e {
-                            TrySendError::Disconnected(t) => t.0,
-                            TrySendError::Full(t) => t.0,
-                        },
-                        msg,
-                    )
-                }),
+            (pubsub::Kind::NewHeads, None) => self.subscribe_new_heads(subscriber, 0),
+            (pubsub::Kind::NewHeads, Some(pubsub::Params::NewHeads(param))) => {
+                self.subscribe_new_heads(subscriber, param.confirmations.unwrap_or_default())
+            }
             (pubsub::Kind::NewHeads, _) => Err((
                 subscriber,
-                errors::invalid_params("newHeads", "Expected no parameters."),
+                errors::invalid_params(
+                    "newHeads",
+                    "Expected no parameters or a confirmations object.",
+                ),
             )),
             (pubsub::Kind::NewPendingTransactions, None) => self
                 .service
@@ -106,31 +113,41 @@ impl PubSubImpl {
                 errors::invalid_params("newPendingTransactions", "Expected no parameters."),
             )),
             (pubsub::Kind::Events, Some(pubsub::Params::Events(param))) => {
-                match param.filter.try_into() {
-                    Ok(f) => self
-                        .service
-                        .try_send(SubscribeEvents {
-                            subscriber,
-                            filter: f,
-                            decode: param.decode,
-                        })
-                        .map_err(|e| {
-                            let msg = map_send_err(&e);
-                            (
-                                match e {
-                                    TrySendError::Disconnected(t) => t.subscriber,
-                                    TrySendError::Full(t) => t.subscriber,
-                                },
-                                msg,
-                            )
-                        }),
-                    Err(e) => Err((subscriber, e)),
-                }
+                self.subscribe_events(subscriber, param)
             }
+            (pubsub::Kind::Events, Some(pubsub::Params::NewHeads(param))) => self.subscribe_events(
+                subscriber,
+                pubsub::EventParams {
+                    confirmations: param.confirmations,
+                    ..Default::default()
+                },
+            ),
             (pubsub::Kind::Events, _) => Err((
                 subscriber,
                 errors::invalid_params("events", "Expected a filter object."),
             )),
+            (pubsub::Kind::TransactionStatus, Some(pubsub::Params::TransactionStatus(param))) => {
+                self.service
+                    .try_send(SubscribeTxnStatus {
+                        subscriber,
+                        txn_hash: param.txn_hash,
+                        confirmations: param.confirmations.unwrap_or_default(),
+                    })
+                    .map_err(|e| {
+                        let msg = map_send_err(&e);
+                        (
+                            match e {
+                                TrySendError::Disconnected(t) => t.subscriber,
+                                TrySendError::Full(t) => t.subscriber,
+                            },
+                            msg,
+                        )
+                    })
+            }
+            (pubsub::Kind::TransactionStatus, _) => Err((
+                subscriber,
+                errors::invalid_params("transactionStatus", "Expected a txn_hash object."),
+            )),
             (pubsub::Kind::NewMintBlock, _) => self
                 .service
                 .try_send(SubscribeMintBlock(subscriber))
@@ -146,6 +163,58 @@ impl PubSubImpl {
                 }),
         }
     }
+
+    fn subscribe_new_heads(
+        &self,
+        subscriber: Subscriber<pubsub::Result>,
+        confirmations: u64,
+    ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
+        self.service
+            .try_send(SubscribeNewHeads {
+                subscriber,
+                confirmations,
+            })
+            .map_err(|e| {
+                let msg = map_send_err(&e);
+                (
+                    match e {
+                        TrySendError::Disconnected(t) => t.subscriber,
+                        TrySendError::Full(t) => t.subscriber,
+                    },
+                    msg,
+                )
+            })
+    }
+
+    fn subscribe_events(
+        &self,
+        subscriber: Subscriber<pubsub::Result>,
+        param: pubsub::EventParams,
+    ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
+        match param.filter.try_into() {
+            Ok(f) => self
+                .service
+                .try_send(SubscribeEvents {
+                    subscriber,
+                    filter: f,
+                    decode: param.decode,
+                    confirmations: param.confirmations.unwrap_or_default(),
+                    replay: param.replay || param.cursor.is_some(),
+                    cursor: param.cursor,
+                })
+                .map_err(|e| {
+                    let msg = map_send_err(&e);
+                    (
+                        match e {
+                            TrySendError::Disconnected(t) => t.subscriber,
+                            TrySendError::Full(t) => t.subscriber,
+                        },
+                        msg,
+                    )
+                }),
+            Err(e) => Err((subscriber, e)),
+        }
+    }
 }
 
 impl StarcoinPubSub for PubSubImpl {
@@ -189,10 +258,12 @@ impl ServiceFactory<PubSubService> for PubSubServiceFactory {
     fn create(ctx: &mut ServiceContext<PubSubService>) -> Result<PubSubService> {
         let miner_service = ctx.service_ref::<MinerService>()?.clone();
         let storage = ctx.get_shared::<Arc<Storage>>()?;
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
         Ok(PubSubService::new(
             ctx.get_shared::<TxPoolService>()?,
             miner_service,
             storage,
+            config.net().time_service(),
         ))
     }
 }
@@ -202,11 +273,15 @@ pub struct PubSubService {
     txpool: TxPoolService,
     miner_service: ServiceRef<MinerService>,
     storage: Arc<Storage>,
-    new_header_subscribers: HashMap<SubscriptionId, mpsc::UnboundedSender<NewHeadNotification>>,
+    time_service: Arc<dyn TimeService>,
+    new_header_subscribers:
+        HashMap<SubscriptionId, mpsc::UnboundedSender<ChainMessage<NewHeadNotification>>>,
     new_event_subscribers:
-        HashMap<SubscriptionId, mpsc::UnboundedSender<ContractEventNotification>>,
+        HashMap<SubscriptionId, mpsc::UnboundedSender<ChainMessage<ContractEventNotification>>>,
     mint_block_subscribers: HashMap<SubscriptionId, mpsc::UnboundedSender<MintBlockEvent>>,
     new_pending_txn_tasks: Arc<RwLock<HashMap<SubscriptionId, AbortHandle>>>,
+    txn_status_subscribers:
+        HashMap<SubscriptionId, (HashValue, mpsc::UnboundedSender<TxnStatusMessage>)>,
 }
 
 impl PubSubService {
@@ -214,6 +289,7 @@ impl PubSubService {
         txpool: TxPoolService,
         miner_service: ServiceRef<MinerService>,
         storage: Arc<Storage>,
+        time_service: Arc<dyn TimeService>,
     ) -> Self {
         let subscriber_id = Arc::new(atomic::AtomicU64::new(0));
         Self {
@@ -221,27 +297,201 @@ impl PubSubService {
             txpool,
             miner_service,
             storage,
+            time_service,
             new_event_subscribers: Default::default(),
             new_header_subscribers: Default::default(),
             mint_block_subscribers: Default::default(),
             new_pending_txn_tasks: Arc::new(RwLock::new(HashMap::default())),
+            txn_status_subscribers: Default::default(),
         }
     }
     fn next_id(&self) -> SubscriptionId {
         let id = self.subscriber_id.fetch_add(1, atomic::Ordering::SeqCst);
         SubscriptionId::Number(id)
     }
+
+    /// Replay the matching events of the main chain up to the current head, the blocks waiting
+    /// for confirmations are pushed to the handler instead, and the handler skips the new events
+    /// which have been replayed.
+    fn replay_events(
+        &self,
+        handler: &mut ContractEventHandler,
+        cursor: Option<pubsub::EventCursor>,
+    ) -> Result<LocalBoxStream<'static, jsonrpc_core::Result<pubsub::Result>>> {
+        let head_id = self
+            .storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info should exist."))?
+            .main;
+        let chain = BlockChain::new(
+            self.time_service.clone(),
+            head_id,
+            self.storage.clone(),
+            None,
+        )?;
+        let head = chain.current_header();
+        let confirmations = handler.confirmations.confirmations;
+        let confirmed_number = head.number().saturating_sub(confirmations);
+
+        let window_start = head
+            .number()
+            .saturating_sub(confirmations.max(REPLAY_OVERLAP_BLOCKS));
+        let mut known_blocks = HashSet::new();
+        for number in window_start..=head.number() {
+            let block_id = chain
+                .get_hash_by_number(number)?
+                .ok_or_else(|| format_err!("Can not find block by number {}", number))?;
+            known_blocks.insert(block_id);
+            if number > confirmed_number {
+                let block = self
+                    .storage
+                    .get_block_by_hash(block_id)?
+                    .ok_or_else(|| format_err!("Can not find block by id {}", block_id))?;
+                let block_events = load_block_events(self.storage.as_ref(), &block, false)?;
+                handler
+                    .confirmations
+                    .push(block.header(), false, block_events);
+            }
+        }
+        handler.replayed = Some(ReplayedBlocks {
+            head_number: head.number(),
+            window_start,
+            known_blocks,
+        });
+
+        let filter = handler.filter.clone();
+        let replay_end = confirmed_number.min(filter.to_block);
+        let from_block = cursor
+            .map(|cursor| cursor.block_number.0)
+            .unwrap_or_default()
+            .max(filter.from_block);
+        let state = handler
+            .decode
+            .then(|| ChainStateDB::new(self.storage.clone(), Some(head.state_root())));
+        let history = futures::stream::unfold(Some(from_block), move |from_block| {
+            let batch = match from_block {
+                Some(from_block) if from_block <= replay_end => {
+                    let to_block = from_block
+                        .saturating_add(REPLAY_BATCH_BLOCKS.saturating_sub(1))
+                        .min(replay_end);
+                    let events = chain.filter_events(Filter {
+                        from_block,
+                        to_block,
+                        limit: None,
+                        reverse: false,
+                        ..filter.clone()
+                    });
+                    match events {
+                        Ok(events) => Some((
+                            events
+                                .into_iter()
+                                .map(|e| {
+                                    let event_cursor = pubsub::EventCursor::new(
+                                        e.block_number,
+                                        e.transaction_global_index,
+                                        e.event_index,
+                                    );
+                                    (event_cursor, e)
+                                })
+                                .filter(|(event_cursor, _)| match cursor.as_ref() {
+                                    Some(cursor) => cursor.is_before(event_cursor),
+                                    None => true,
+                                })
+                                .map(|(event_cursor, e)| {
+                                    let contract_event = e.event.clone();
+                                    event_notification(
+                                        state.as_ref(),
+                                        &contract_event,
+                                        e.into(),
+                                        event_cursor,
+                                        false,
+                                    )
+                                })
+                                .collect::<Vec<_>>(),
+                            Some(to_block.saturating_add(1)),
+                        )),
+                        Err(e) => Some((vec![Err(map_err(e))], None)),
+                    }
+                }
+                _ => None,
+            };
+            futures::future::ready(batch)
+        })
+        .flat_map(futures::stream::iter);
+        Ok(history.boxed_local())
+    }
+
+    /// Track the status of a transaction, return the handler with the current status, and the
+    /// receiver of the later changes.
+    fn track_txn_status(
+        &mut self,
+        txn_hash: HashValue,
+        confirmations: u64,
+    ) -> Result<(
+        SubscriptionId,
+        mpsc::UnboundedReceiver<TxnStatusMessage>,
+        TxnStatusHandler,
+        Vec<pubsub::TransactionStatusNotification>,
+    )> {
+        let (sender, receiver) = mpsc::unbounded();
+        // track the changes before reading the current status, so there is no gap between them.
+        let subscriber_id = self.next_id();
+        self.txn_status_subscribers
+            .insert(subscriber_id.clone(), (txn_hash, sender));
+        let current = self.storage.get_startup_info().and_then(|startup_info| {
+            let head_id = startup_info
+                .ok_or_else(|| format_err!("Startup info should exist."))?
+                .main;
+            let chain = BlockChain::new(
+                self.time_service.clone(),
+                head_id,
+                self.storage.clone(),
+                None,
+            )?;
+            let mut handler = TxnStatusHandler::new(
+                txn_hash,
+                confirmations,
+                self.storage.clone(),
+                self.time_service.clone(),
+            );
+            let pending = self.txpool.find_txn(&txn_hash).is_some();
+            let notifications = handler.init(&chain, pending)?;
+            Ok((handler, notifications))
+        });
+        match current {
+            Ok((handler, notifications)) => Ok((subscriber_id, receiver, handler, notifications)),
+            Err(e) => {
+                self.txn_status_subscribers.remove(&subscriber_id);
+                Err(e)
+            }
+        }
+    }
 }
 
+/// How many blocks to replay in a batch.
+const REPLAY_BATCH_BLOCKS: u64 = 1000;
+/// How many recent blocks of the replayed chain to check the new events against.
+const REPLAY_OVERLAP_BLOCKS: u64 = 64;
+
 type NewHeadNotification = Notification<ThinBlock>;
 // type NewTxns = Arc<[HashValue]>;
 
+/// The message to the subscriptions of the main chain blocks.
+#[derive(Clone, Debug)]
+enum ChainMessage<T> {
+    Notification(T),
+    /// The blocks retracted by the reorg are not notified, the subscriber should resync.
+    ReorgTooDeep(ReorgTooDeep),
+}
+
 impl ActorService for PubSubService {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.set_mailbox_capacity(1024);
         ctx.subscribe::<NewHeadNotification>();
         ctx.subscribe::<ContractEventNotification>();
+        ctx.subscribe::<ReorgTooDeepNotification>();
         ctx.subscribe::<MintBlockEvent>();
+        ctx.add_stream(self.txpool.subscribe_txns());
 
         Ok(())
     }
@@ -249,7 +499,31 @@ impl ActorService for PubSubService {
 
 impl ActorEventHandler<Self, NewHeadNotification> for PubSubService {
     fn handle_event(&mut self, msg: NewHeadNotification, _ctx: &mut ServiceContext<PubSubService>) {
-        send_to_all(&mut self.new_header_subscribers, msg);
+        self.txn_status_subscribers.retain(|_, (_, sender)| {
+            sender
+                .unbounded_send(TxnStatusMessage::Block(msg.0.clone()))
+                .is_ok()
+        });
+        send_to_all(
+            &mut self.new_header_subscribers,
+            ChainMessage::Notification(msg),
+        );
+    }
+}
+
+impl ActorEventHandler<Self, TxnStatusFullEvent> for PubSubService {
+    fn handle_event(&mut self, msg: TxnStatusFullEvent, _ctx: &mut ServiceContext<PubSubService>) {
+        if self.txn_status_subscribers.is_empty() {
+            return;
+        }
+        for (txn_hash, status) in msg.iter() {
+            self.txn_status_subscribers.retain(|_, (hash, sender)| {
+                hash != txn_hash
+                    || sender
+                        .unbounded_send(TxnStatusMessage::Pool(*status))
+                        .is_ok()
+            });
+        }
     }
 }
 
@@ -259,7 +533,28 @@ impl ActorEventHandler<Self, ContractEventNotification> for PubSubService {
         msg: ContractEventNotification,
         _ctx: &mut ServiceContext<PubSubService>,
     ) {
-        send_to_all(&mut self.new_event_subscribers, msg);
+        send_to_all(
+            &mut self.new_event_subscribers,
+            ChainMessage::Notification(msg),
+        );
+    }
+}
+
+impl ActorEventHandler<Self, ReorgTooDeepNotification> for PubSubService {
+    fn handle_event(
+        &mut self,
+        msg: ReorgTooDeepNotification,
+        _ctx: &mut ServiceContext<PubSubService>,
+    ) {
+        let Notification(reorg) = msg;
+        send_to_all(
+            &mut self.new_header_subscribers,
+            ChainMessage::ReorgTooDeep(reorg.clone()),
+        );
+        send_to_all(
+            &mut self.new_event_subscribers,
+            ChainMessage::ReorgTooDeep(reorg),
+        );
     }
 }
 
@@ -270,7 +565,10 @@ impl ActorEventHandler<Self, MintBlockEvent> for PubSubService {
 }
 
 #[derive(Debug)]
-struct SubscribeNewHeads(Subscriber<pubsub::Result>);
+struct SubscribeNewHeads {
+    subscriber: Subscriber<pubsub::Result>,
+    confirmations: u64,
+}
 
 impl ServiceRequest for SubscribeNewHeads {
     type Response = ();
@@ -278,16 +576,22 @@ impl ServiceRequest for SubscribeNewHeads {
 
 impl ServiceHandler<Self, SubscribeNewHeads> for PubSubService {
     fn handle(&mut self, msg: SubscribeNewHeads, ctx: &mut ServiceContext<Self>) {
-        let SubscribeNewHeads(sink) = msg;
+        let SubscribeNewHeads {
+            subscriber,
+            confirmations,
+        } = msg;
         let (sender, receiver) = mpsc::unbounded();
         let subscriber_id = self.next_id();
         self.new_header_subscribers
             .insert(subscriber_id.clone(), sender);
         ctx.spawn(run_subscription(
+            futures::stream::empty(),
             receiver,
             subscriber_id,
-            sink,
-            NewHeadHandler,
+            subscriber,
+            NewHeadHandler {
+                confirmations: Confirmations::new(confirmations),
+            },
         ));
     }
 }
@@ -309,6 +613,7 @@ impl ServiceHandler<Self, SubscribeMintBlock> for PubSubService {
         let miner_service = self.miner_service.clone();
         let subscribers_num = self.mint_block_subscribers.len() as u32;
         ctx.spawn(run_subscription(
+            futures::stream::empty(),
             receiver,
             subscriber_id,
             subscriber,
@@ -338,6 +643,9 @@ struct SubscribeEvents {
     subscriber: Subscriber<pubsub::Result>,
     filter: Filter,
     decode: bool,
+    confirmations: u64,
+    replay: bool,
+    cursor: Option<pubsub::EventCursor>,
 }
 
 impl ServiceRequest for SubscribeEvents {
@@ -350,20 +658,40 @@ impl ServiceHandler<Self, SubscribeEvents> for PubSubService {
             subscriber,
             filter,
             decode,
+            confirmations,
+            replay,
+            cursor,
         } = msg;
+        let mut handler = ContractEventHandler {
+            storage: self.storage.clone(),
+            filter,
+            decode,
+            confirmations: Confirmations::new(confirmations),
+            replayed: None,
+        };
         let (sender, receiver) = mpsc::unbounded();
+        // subscribe the new events before replay, so there is no gap between them.
         let subscriber_id = self.next_id();
         self.new_event_subscribers
             .insert(subscriber_id.clone(), sender);
+        let history = if replay {
+            match self.replay_events(&mut handler, cursor) {
+                Ok(history) => history,
+                Err(e) => {
+                    self.new_event_subscribers.remove(&subscriber_id);
+                    let _ = subscriber.reject(map_err(e));
+                    return;
+                }
+            }
+        } else {
+            futures::stream::empty().boxed_local()
+        };
         ctx.spawn(run_subscription(
+            history,
             receiver,
             subscriber_id,
             subscriber,
-            ContractEventHandler {
-                storage: self.storage.clone(),
-                filter,
-                decode,
-            },
+            handler,
         ));
     }
 }
@@ -386,6 +714,7 @@ impl ServiceHandler<Self, SubscribeNewPendingTxns> for PubSubService {
         let receiver = self.txpool.subscribe_pending_txn();
         let (f, abort_handle) = futures::future::abortable(async move {
             run_subscription(
+                futures::stream::empty(),
                 receiver,
                 subscriber_id_clone.clone(),
                 subscriber,
@@ -406,6 +735,74 @@ impl ServiceHandler<Self, SubscribeNewPendingTxns> for PubSubService {
     }
 }
 
+#[derive(Debug)]
+struct SubscribeTxnStatus {
+    subscriber: Subscriber<pubsub::Result>,
+    txn_hash: HashValue,
+    confirmations: u64,
+}
+
+impl ServiceRequest for SubscribeTxnStatus {
+    type Response = ();
+}
+
+impl ServiceHandler<Self, SubscribeTxnStatus> for PubSubService {
+    fn handle(&mut self, msg: SubscribeTxnStatus, ctx: &mut ServiceContext<Self>) {
+        let SubscribeTxnStatus {
+            subscriber,
+            txn_hash,
+            confirmations,
+        } = msg;
+        match self.track_txn_status(txn_hash, confirmations) {
+            Ok((subscriber_id, receiver, handler, notifications)) => {
+                let current = notifications
+                    .into_iter()
+                    .map(|n| Ok(pubsub::Result::TransactionStatus(Box::new(n))));
+                ctx.spawn(run_subscription(
+                    futures::stream::iter(current),
+                    receiver,
+                    subscriber_id,
+                    subscriber,
+                    handler,
+                ));
+            }
+            Err(e) => {
+                let _ = subscriber.reject(map_err(e));
+            }
+        }
+    }
+}
+
+/// Watch the status changes of a transaction, the stream ends when the watcher is dropped.
+#[derive(Debug)]
+pub struct WatchTxnStatus {
+    pub txn_hash: HashValue,
+    pub confirmations: u64,
+}
+
+impl ServiceRequest for WatchTxnStatus {
+    type Response = Result<BoxStream<'static, pubsub::TransactionStatusNotification>>;
+}
+
+impl ServiceHandler<Self, WatchTxnStatus> for PubSubService {
+    fn handle(
+        &mut self,
+        msg: WatchTxnStatus,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Result<BoxStream<'static, pubsub::TransactionStatusNotification>> {
+        let (_, receiver, mut handler, notifications) =
+            self.track_txn_status(msg.txn_hash, msg.confirmations)?;
+        let changes = receiver.flat_map(move |msg| {
+            let notifications = handler.handle_message(msg).unwrap_or_else(|e| {
+                warn!(target: "rpc", "fail to track the status of txn {}, err: {}", handler.txn_hash, e);
+                vec![]
+            });
+            futures::stream::iter(notifications)
+        });
+        Ok(futures::stream::iter(notifications).chain(changes).boxed())
+    }
+}
+
 #[derive(Debug)]
 struct Unsubscribe(SubscriptionId);
 
@@ -418,6 +815,7 @@ impl ServiceHandler<Self, Unsubscribe> for PubSubService {
         self.new_header_subscribers.remove(&msg.0);
         self.new_event_subscribers.remove(&msg.0);
         self.mint_block_subscribers.remove(&msg.0);
+        self.txn_status_subscribers.remove(&msg.0);
         self.miner_service.do_send(UpdateSubscriberNumRequest {
             number: Some(self.mint_block_subscribers.len() as u32),
         });
@@ -452,22 +850,25 @@ fn send_to_all<T: Clone>(
     }
 }
 
+/// Forward the `history` notifications, then the notifications of the messages from `msg_channel`.
 async fn run_subscription<M, Handler>(
+    history: impl Stream<Item = jsonrpc_core::Result<pubsub::Result>>,
     msg_channel: mpsc::UnboundedReceiver<M>,
     subscriber_id: SubscriptionId,
     subscriber: Subscriber<pubsub::Result>,
-    event_handler: Handler,
+    mut event_handler: Handler,
 ) where
     M: Send + 'static,
     Handler: EventHandler<M> + Send + 'static,
 {
     // TODO: should we use assgin_id_async?
     if let Ok(sink) = subscriber.assign_id(subscriber_id.clone()) {
-        let forward = msg_channel
-            .flat_map(move |m| {
+        let forward = history
+            .chain(msg_channel.flat_map(move |m| {
                 let r = event_handler.handle(m);
-                futures::stream::iter(r.into_iter().map(Ok::<_, jsonrpc_pubsub::TransportError>))
-            })
+                futures::stream::iter(r)
+            }))
+            .map(Ok::<_, jsonrpc_pubsub::TransportError>)
             .forward(sink)
             .await;
         if let Err(e) = forward {
@@ -477,30 +878,104 @@ async fn run_subscription<M, Handler>(
 }
 
 trait EventHandler<M> {
-    fn handle(&self, msg: M) -> Vec<jsonrpc_core::Result<pubsub::Result>>;
+    fn handle(&mut self, msg: M) -> Vec<jsonrpc_core::Result<pubsub::Result>>;
+}
+
+fn reorg_too_deep_error(reorg: &ReorgTooDeep) -> jsonrpc_core::Error {
+    errors::reorg_too_deep(reorg.old_head.id(), reorg.new_head.id(), reorg.max_depth)
+}
+
+/// Delay the notifications of a block until the block is `confirmations` blocks deep in the
+/// main chain. A retracted block is not notified if it is still waiting for confirmations.
+#[derive(Clone, Debug)]
+struct Confirmations<T> {
+    confirmations: u64,
+    pending: VecDeque<(HashValue, BlockNumber, T)>,
+}
+
+impl<T> Confirmations<T> {
+    fn new(confirmations: u64) -> Self {
+        Self {
+            confirmations,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Drop the notifications waiting for confirmations, the blocks may have been retracted.
+    fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Push the notification of a block, return the notifications to deliver.
+    fn push(&mut self, header: &BlockHeader, removed: bool, msg: T) -> Vec<T> {
+        if self.confirmations == 0 {
+            return vec![msg];
+        }
+        if removed {
+            return match self.pending.iter().position(|(id, ..)| *id == header.id()) {
+                Some(index) => {
+                    self.pending.remove(index);
+                    vec![]
+                }
+                None => vec![msg],
+            };
+        }
+        self.pending.push_back((header.id(), header.number(), msg));
+        let mut confirmed = vec![];
+        while let Some((_, number, _)) = self.pending.front() {
+            if number.saturating_add(self.confirmations) > header.number() {
+                break;
+            }
+            if let Some((.., msg)) = self.pending.pop_front() {
+                confirmed.push(msg);
+            }
+        }
+        confirmed
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct TxnEventHandler;
 
 impl EventHandler<Arc<[HashValue]>> for TxnEventHandler {
-    fn handle(&self, msg: Arc<[HashValue]>) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+    fn handle(&mut self, msg: Arc<[HashValue]>) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
         vec![Ok(pubsub::Result::TransactionHash(msg.to_vec()))]
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct NewHeadHandler;
+#[derive(Clone, Debug)]
+pub struct NewHeadHandler {
+    confirmations: Confirmations<ThinBlock>,
+}
 
-impl EventHandler<Notification<ThinBlock>> for NewHeadHandler {
-    fn handle(&self, msg: Notification<ThinBlock>) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
-        let Notification(block) = msg;
-        vec![Ok(pubsub::Result::Block(Box::new(BlockView {
-            header: block.header.into(),
-            body: block.body.into(),
-            uncles: vec![],
-            raw: None,
-        })))]
+impl EventHandler<ChainMessage<Notification<ThinBlock>>> for NewHeadHandler {
+    fn handle(
+        &mut self,
+        msg: ChainMessage<Notification<ThinBlock>>,
+    ) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+        let Notification(block) = match msg {
+            ChainMessage::Notification(msg) => msg,
+            ChainMessage::ReorgTooDeep(reorg) => {
+                self.confirmations.clear();
+                return vec![Err(reorg_too_deep_error(&reorg))];
+            }
+        };
+        let header = block.header.clone();
+        self.confirmations
+            .push(&header, block.removed, block)
+            .into_iter()
+            .map(|block| {
+                Ok(pubsub::Result::Block(Box::new(pubsub::BlockNotification {
+                    block: BlockView {
+                        header: block.header.into(),
+                        body: block.body.into(),
+                        uncles: vec![],
+                        raw: None,
+                    },
+                    removed: block.removed,
+                })))
+            })
+            .collect()
     }
 }
 
@@ -508,7 +983,7 @@ impl EventHandler<Notification<ThinBlock>> for NewHeadHandler {
 pub struct NewMintBlockHandler;
 
 impl EventHandler<MintBlockEvent> for NewMintBlockHandler {
-    fn handle(&self, msg: MintBlockEvent) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+    fn handle(&mut self, msg: MintBlockEvent) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
         vec![Ok(pubsub::Result::MintBlock(Box::new(msg)))]
     }
 }
@@ -518,11 +993,72 @@ pub struct ContractEventHandler {
     filter: Filter,
     decode: bool,
     storage: Arc<Storage>,
+    confirmations: Confirmations<BlockEvents>,
+    replayed: Option<ReplayedBlocks>,
 }
 
-impl EventHandler<ContractEventNotification> for ContractEventHandler {
-    fn handle(&self, msg: ContractEventNotification) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
-        let Notification((state_root, events)) = msg;
+/// The blocks of the main chain when the events are replayed.
+#[derive(Clone, Debug)]
+struct ReplayedBlocks {
+    head_number: BlockNumber,
+    window_start: BlockNumber,
+    known_blocks: HashSet<HashValue>,
+}
+
+impl ReplayedBlocks {
+    /// Return whether the new events of the block should be handled, the events of a block
+    /// which has been replayed are skipped.
+    fn filter(&mut self, header: &BlockHeader, removed: bool) -> bool {
+        if header.number() > self.head_number {
+            return true;
+        }
+        if removed {
+            if header.number() < self.window_start {
+                return true;
+            }
+            self.known_blocks.remove(&header.id())
+        } else {
+            self.known_blocks.insert(header.id())
+        }
+    }
+}
+
+impl EventHandler<ChainMessage<ContractEventNotification>> for ContractEventHandler {
+    fn handle(
+        &mut self,
+        msg: ChainMessage<ContractEventNotification>,
+    ) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+        let Notification(block_events) = match msg {
+            ChainMessage::Notification(msg) => msg,
+            ChainMessage::ReorgTooDeep(reorg) => {
+                self.confirmations.clear();
+                return vec![Err(reorg_too_deep_error(&reorg))];
+            }
+        };
+        let header = block_events.header.clone();
+        if let Some(replayed) = self.replayed.as_mut() {
+            if !replayed.filter(&header, block_events.removed) {
+                return vec![];
+            }
+        }
+        self.confirmations
+            .push(&header, block_events.removed, block_events)
+            .into_iter()
+            .flat_map(|block_events| self.handle_block_events(block_events))
+            .collect()
+    }
+}
+
+impl ContractEventHandler {
+    fn handle_block_events(
+        &self,
+        block_events: BlockEvents,
+    ) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+        let BlockEvents {
+            header,
+            events,
+            removed,
+        } = block_events;
         let filtered = events
             .as_ref()
             .iter()
@@ -537,23 +1073,25 @@ impl EventHandler<ContractEventNotification> for ContractEventHandler {
         };
 
         let state = if self.decode {
-            Some(ChainStateDB::new(self.storage.clone(), Some(state_root)))
+            Some(ChainStateDB::new(
+                self.storage.clone(),
+                Some(header.state_root()),
+            ))
         } else {
             None
         };
         filtered_events
             .into_iter()
             .map(|e| {
-                let decoded_data = match &state {
-                    Some(s) => {
-                        let abi =
-                            ABIResolver::new(s).resolve_type_tag(e.contract_event.type_tag())?;
-                        Some(decode_move_value(&abi, e.contract_event.event_data())?)
-                    }
-                    None => None,
-                };
-                Ok(TransactionEventResponse {
-                    event: TransactionEventView::new(
+                let cursor = pubsub::EventCursor::new(
+                    e.block_number,
+                    e.transaction_global_index.unwrap_or_default(),
+                    e.event_index.unwrap_or_default(),
+                );
+                event_notification(
+                    state.as_ref(),
+                    &e.contract_event,
+                    TransactionEventView::new(
                         Some(e.block_hash),
                         Some(e.block_number),
                         Some(e.transaction_hash),
@@ -562,13 +1100,258 @@ impl EventHandler<ContractEventNotification> for ContractEventHandler {
                         e.event_index,
                         &e.contract_event,
                     ),
-                    decode_event_data: decoded_data,
-                })
-            })
-            .map(|e| {
-                e.map(|d| pubsub::Result::Event(Box::new(d)))
-                    .map_err(map_err)
+                    cursor,
+                    removed,
+                )
             })
             .collect()
     }
 }
+
+fn event_notification(
+    state: Option<&ChainStateDB>,
+    contract_event: &ContractEvent,
+    event: TransactionEventView,
+    cursor: pubsub::EventCursor,
+    removed: bool,
+) -> jsonrpc_core::Result<pubsub::Result> {
+    let decode_event_data = || -> Result<_> {
+        match state {
+            Some(s) => {
+                let abi = ABIResolver::new(s).resolve_type_tag(contract_event.type_tag())?;
+                Ok(Some(decode_move_value(&abi, contract_event.event_data())?))
+            }
+            None => Ok(None),
+        }
+    };
+    let decode_event_data = decode_event_data().map_err(map_err)?;
+    Ok(pubsub::Result::Event(Box::new(pubsub::EventNotification {
+        event: TransactionEventResponse {
+            event,
+            decode_event_data,
+        },
+        cursor,
+        removed,
+    })))
+}
+
+/// The messages to track the status of a transaction.
+#[derive(Clone, Debug)]
+enum TxnStatusMessage {
+    Pool(TxStatus),
+    Block(ThinBlock),
+}
+
+#[derive(Clone, Debug)]
+pub struct TxnStatusHandler {
+    txn_hash: HashValue,
+    confirmations: u64,
+    storage: Arc<Storage>,
+    time_service: Arc<dyn TimeService>,
+    /// The main chain block including the transaction, and the vm status.
+    included: Option<(HashValue, BlockNumber, TransactionStatusView)>,
+    finalized: bool,
+    last_status: Option<pubsub::TransactionLifecycle>,
+}
+
+impl TxnStatusHandler {
+    fn new(
+        txn_hash: HashValue,
+        confirmations: u64,
+        storage: Arc<Storage>,
+        time_service: Arc<dyn TimeService>,
+    ) -> Self {
+        Self {
+            txn_hash,
+            confirmations,
+            storage,
+            time_service,
+            included: None,
+            finalized: false,
+            last_status: None,
+        }
+    }
+
+    /// Init the status from the main chain, or the txpool if `pending`.
+    fn init(
+        &mut self,
+        chain: &BlockChain,
+        pending: bool,
+    ) -> Result<Vec<pubsub::TransactionStatusNotification>> {
+        let mut notifications = vec![];
+        match chain.get_transaction_info(self.txn_hash)? {
+            Some(txn_info) => {
+                self.include(
+                    &mut notifications,
+                    txn_info.block_id,
+                    txn_info.block_number,
+                    txn_info.status().clone().into(),
+                );
+                self.check_finalized(&mut notifications, chain.current_header().number());
+            }
+            None if pending => {
+                self.notify(&mut notifications, pubsub::TransactionLifecycle::Pending)
+            }
+            None => {}
+        }
+        Ok(notifications)
+    }
+
+    fn handle_message(
+        &mut self,
+        msg: TxnStatusMessage,
+    ) -> Result<Vec<pubsub::TransactionStatusNotification>> {
+        let mut notifications = vec![];
+        match msg {
+            TxnStatusMessage::Pool(status) => match status {
+                TxStatus::Added => {
+                    if self.included.is_none() {
+                        self.notify(&mut notifications, pubsub::TransactionLifecycle::Pending);
+                    }
+                }
+                TxStatus::Replaced => {
+                    self.notify(&mut notifications, pubsub::TransactionLifecycle::Replaced)
+                }
+                // the included transactions are culled from the txpool too, the transaction may be
+                // included by a fork block, so check it on the main chain.
+                TxStatus::Culled => {
+                    if self.included.is_none() {
+                        let chain = self.main_chain()?;
+                        match chain.get_transaction_info(self.txn_hash)? {
+                            Some(txn_info) => {
+                                self.include(
+                                    &mut notifications,
+                                    txn_info.block_id,
+                                    txn_info.block_number,
+                                    txn_info.status().clone().into(),
+                                );
+                                self.check_finalized(
+                                    &mut notifications,
+                                    chain.current_header().number(),
+                                );
+                            }
+                            None => self
+                                .notify(&mut notifications, pubsub::TransactionLifecycle::Expired),
+                        }
+                    }
+                }
+                status => self.notify(
+                    &mut notifications,
+                    pubsub::TransactionLifecycle::Dropped {
+                        reason: status.to_string(),
+                    },
+                ),
+            },
+            TxnStatusMessage::Block(block) if block.removed => {
+                if matches!(self.included, Some((block_id, ..)) if block_id == block.header.id()) {
+                    self.included = None;
+                    self.finalized = false;
+                    self.notify(&mut notifications, pubsub::TransactionLifecycle::Pending);
+                }
+            }
+            TxnStatusMessage::Block(block) => {
+                if block.body.contains(&self.txn_hash) {
+                    for txn_info_id in self
+                        .storage
+                        .get_transaction_info_ids_by_txn_hash(self.txn_hash)?
+                    {
+                        if let Some(txn_info) = self.storage.get_transaction_info(txn_info_id)? {
+                            if txn_info.block_id == block.header.id() {
+                                self.include(
+                                    &mut notifications,
+                                    txn_info.block_id,
+                                    txn_info.block_number,
+                                    txn_info.status().clone().into(),
+                                );
+                                break;
+                            }
+                        }
+                    }
+                }
+                self.check_finalized(&mut notifications, block.header.number());
+            }
+        }
+        Ok(notifications)
+    }
+
+    fn main_chain(&self) -> Result<BlockChain> {
+        let head_id = self
+            .storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info should exist."))?
+            .main;
+        BlockChain::new(
+            self.time_service.clone(),
+            head_id,
+            self.storage.clone(),
+            None,
+        )
+    }
+
+    fn include(
+        &mut self,
+        notifications: &mut Vec<pubsub::TransactionStatusNotification>,
+        block_hash: HashValue,
+        block_number: BlockNumber,
+        vm_status: TransactionStatusView,
+    ) {
+        self.included = Some((block_hash, block_number, vm_status.clone()));
+        self.finalized = false;
+        self.notify(
+            notifications,
+            pubsub::TransactionLifecycle::Included {
+                block_hash,
+                block_number: block_number.into(),
+                vm_status,
+            },
+        );
+    }
+
+    fn check_finalized(
+        &mut self,
+        notifications: &mut Vec<pubsub::TransactionStatusNotification>,
+        head_number: BlockNumber,
+    ) {
+        if let Some((block_hash, block_number, vm_status)) = self.included.clone() {
+            if !self.finalized && block_number.saturating_add(self.confirmations) <= head_number {
+                self.finalized = true;
+                self.notify(
+                    notifications,
+                    pubsub::TransactionLifecycle::Finalized {
+                        block_hash,
+                        block_number: block_number.into(),
+                        vm_status,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Notify the status if it changes.
+    fn notify(
+        &mut self,
+        notifications: &mut Vec<pubsub::TransactionStatusNotification>,
+        status: pubsub::TransactionLifecycle,
+    ) {
+        if self.last_status.as_ref() == Some(&status) {
+            return;
+        }
+        self.last_status = Some(status.clone());
+        notifications.push(pubsub::TransactionStatusNotification::new(
+            self.txn_hash,
+            status,
+        ));
+    }
+}
+
+impl EventHandler<TxnStatusMessage> for TxnStatusHandler {
+    fn handle(&mut self, msg: TxnStatusMessage) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+        match self.handle_message(msg) {
+            Ok(notifications) => notifications
+                .into_iter()
+                .map(|n| Ok(pubsub::Result::TransactionStatus(Box::new(n))))
+                .collect(),
+            Err(e) => vec![Err(map_err(e))],
+        }
+    }
+}
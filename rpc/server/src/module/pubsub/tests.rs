@@ -10,7 +10,8 @@ use serde_json::Value;
 use starcoin_account_api::AccountInfo;
 use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
-use starcoin_chain_notify::ChainNotifyHandlerService;
+use starcoin_chain_notify::message::{Notification, ReorgTooDeep};
+use starcoin_chain_notify::{ChainNotifyHandlerService, MAX_REORG_DEPTH};
 use starcoin_consensus::Consensus;
 use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, HashValue, PrivateKey};
 use starcoin_logger::prelude::*;
@@ -21,6 +22,8 @@ use starcoin_service_registry::RegistryAsyncService;
 use starcoin_state_api::StateReaderExt;
 use starcoin_storage::BlockStore;
 use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::block::BlockHeader;
+use starcoin_types::startup_info::StartupInfo;
 use starcoin_types::system_events::MintBlockEvent;
 use starcoin_types::system_events::NewHeadBlock;
 use starcoin_types::{account_address, U256};
@@ -240,3 +243,313 @@ pub async fn test_subscribe_to_mint_block() -> Result<()> {
     assert_eq!(resp, Some(response.to_owned()));
     Ok(())
 }
+
+#[stest::test]
+pub async fn test_subscribe_to_new_heads_with_reorg() -> Result<()> {
+    let (_txpool_service, storage, config, _, registry) =
+        test_helper::start_txpool_with_miner(1000, true).await;
+    let startup_info = storage.get_startup_info()?.unwrap();
+    let net = config.net();
+    let produce_block = |chain: &mut BlockChain| -> Result<_> {
+        let (block_template, _) = chain.create_block_template(
+            *AccountInfo::random().address(),
+            None,
+            vec![],
+            vec![],
+            None,
+        )?;
+        let block = chain
+            .consensus()
+            .create_block(block_template, net.time_service().as_ref())?;
+        chain.apply(block)
+    };
+    let mut branch_a =
+        BlockChain::new(net.time_service(), startup_info.main, storage.clone(), None)?;
+    let block_a1 = produce_block(&mut branch_a)?;
+    let mut branch_b = BlockChain::new(net.time_service(), startup_info.main, storage, None)?;
+    let block_b1 = produce_block(&mut branch_b)?;
+    let block_b2 = produce_block(&mut branch_b)?;
+
+    let bus = registry.service_ref::<BusService>().await?;
+    registry.register::<ChainNotifyHandlerService>().await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let mut io = MetaIoHandler::default();
+    io.extend_with(PubSubImpl::new(service).to_delegate());
+
+    let mut metadata = Metadata::default();
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newHeads"}], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+    let resp = io.handle_request(request, metadata).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    let mut confirmed_metadata = Metadata::default();
+    let (sender, mut confirmed_receiver) = futures::channel::mpsc::unbounded();
+    confirmed_metadata.session = Some(Arc::new(Session::new(sender)));
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newHeads"}, {"confirmations": 1}], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+    let resp = io.handle_request(request, confirmed_metadata).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    // switch the head from a1 to b2, the parent b1 is not notified by the bus.
+    bus.broadcast(NewHeadBlock(Arc::new(block_a1.clone())))?;
+    bus.broadcast(NewHeadBlock(Arc::new(block_b2.clone())))?;
+
+    let mut notifications = vec![];
+    for _ in 0..4 {
+        let res = timeout(Duration::from_secs(5), receiver.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Empty value"))?;
+        let r: Value = serde_json::from_str(&res)?;
+        let result = r["params"]["result"].clone();
+        notifications.push((
+            serde_json::from_value::<HashValue>(result["header"]["block_hash"].clone())?,
+            result["removed"].as_bool(),
+        ));
+    }
+    assert_eq!(
+        notifications,
+        vec![
+            (block_a1.block().id(), Some(false)),
+            (block_a1.block().id(), Some(true)),
+            (block_b1.block().id(), Some(false)),
+            (block_b2.block().id(), Some(false)),
+        ]
+    );
+
+    // a1 is retracted before confirmed, and only b1 is confirmed by b2.
+    let res = timeout(Duration::from_secs(5), confirmed_receiver.next())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Empty value"))?;
+    let r: Value = serde_json::from_str(&res)?;
+    let result = r["params"]["result"].clone();
+    assert_eq!(
+        serde_json::from_value::<HashValue>(result["header"]["block_hash"].clone())?,
+        block_b1.block().id()
+    );
+    assert_eq!(result["removed"].as_bool(), Some(false));
+    assert!(
+        timeout(Duration::from_millis(500), confirmed_receiver.next())
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[stest::test]
+pub async fn test_subscribe_with_reorg_too_deep() -> Result<()> {
+    let (_txpool_service, _storage, _config, _, registry) =
+        test_helper::start_txpool_with_miner(1000, true).await;
+    let bus = registry.service_ref::<BusService>().await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let mut io = MetaIoHandler::default();
+    io.extend_with(PubSubImpl::new(service).to_delegate());
+
+    let mut receivers = vec![];
+    for (id, params) in [
+        (0, r#"[{"type_name":"newHeads"}]"#),
+        (1, r#"[{"type_name":"events"}, {}]"#),
+    ] {
+        let mut metadata = Metadata::default();
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        metadata.session = Some(Arc::new(Session::new(sender)));
+        let request = format!(
+            r#"{{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": {}, "id": 1}}"#,
+            params
+        );
+        let response = format!(r#"{{"jsonrpc":"2.0","result":{},"id":1}}"#, id);
+        let resp = io.handle_request(request.as_str(), metadata).await;
+        assert_eq!(resp, Some(response));
+        receivers.push(receiver);
+    }
+
+    // the subscribers get an error to resync instead of the missing retracted blocks.
+    bus.broadcast(Notification(ReorgTooDeep {
+        old_head: BlockHeader::random(),
+        new_head: BlockHeader::random(),
+        max_depth: MAX_REORG_DEPTH as u64,
+    }))?;
+    for receiver in receivers.iter_mut() {
+        let res = timeout(Duration::from_secs(5), receiver.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Empty value"))?;
+        let r: Value = serde_json::from_str(&res)?;
+        assert_eq!(
+            r["params"]["error"]["code"].as_i64(),
+            Some(starcoin_rpc_api::errors::REORG_TOO_DEEP_ERROR_CODE)
+        );
+    }
+    Ok(())
+}
+
+async fn collect_notifications(
+    receiver: &mut futures::channel::mpsc::UnboundedReceiver<String>,
+) -> Result<Vec<Value>> {
+    let mut results = vec![];
+    while let Ok(Some(res)) = timeout(Duration::from_millis(500), receiver.next()).await {
+        let r: Value = serde_json::from_str(&res)?;
+        results.push(r["params"]["result"].clone());
+    }
+    Ok(results)
+}
+
+#[stest::test]
+pub async fn test_subscribe_to_events_with_replay() -> Result<()> {
+    let (_txpool_service, storage, config, _, registry) =
+        test_helper::start_txpool_with_miner(1000, true).await;
+    let startup_info = storage.get_startup_info()?.unwrap();
+    let net = config.net();
+    let mut chain = BlockChain::new(net.time_service(), startup_info.main, storage.clone(), None)?;
+    let mut blocks = vec![];
+    for _ in 0..2 {
+        let (block_template, _) = chain.create_block_template(
+            *AccountInfo::random().address(),
+            None,
+            vec![],
+            vec![],
+            None,
+        )?;
+        let block = chain
+            .consensus()
+            .create_block(block_template, net.time_service().as_ref())?;
+        blocks.push(chain.apply(block)?);
+    }
+
+    let bus = registry.service_ref::<BusService>().await?;
+    registry.register::<ChainNotifyHandlerService>().await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let mut io = MetaIoHandler::default();
+    io.extend_with(PubSubImpl::new(service).to_delegate());
+
+    // the first block is on the main chain before subscribing.
+    storage.save_startup_info(StartupInfo::new(blocks[0].block().id()))?;
+
+    let mut metadata = Metadata::default();
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"events"}, {"from_block": 1, "replay": true}], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+    let resp = io.handle_request(request, metadata).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    let replayed = collect_notifications(&mut receiver).await?;
+    assert!(!replayed.is_empty());
+    for event in replayed.iter() {
+        assert_eq!(event["block_number"].as_str(), Some("1"));
+        assert_eq!(event["cursor"]["block_number"].as_str(), Some("1"));
+    }
+
+    // the replayed block is not notified again.
+    for block in blocks.iter() {
+        bus.broadcast(NewHeadBlock(Arc::new(block.clone())))?;
+    }
+    let new_events = collect_notifications(&mut receiver).await?;
+    assert!(!new_events.is_empty());
+    for event in new_events.iter() {
+        assert_eq!(event["block_number"].as_str(), Some("2"));
+    }
+
+    // resume from the cursor of the first replayed event.
+    let mut metadata = Metadata::default();
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+    let request = format!(
+        r#"{{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{{"type_name":"events"}}, {{"from_block": 1, "cursor": {}}}], "id": 1}}"#,
+        replayed[0]["cursor"]
+    );
+    let response = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+    let resp = io.handle_request(&request, metadata).await;
+    assert_eq!(resp, Some(response.to_owned()));
+    let resumed = collect_notifications(&mut receiver).await?;
+    assert_eq!(resumed.as_slice(), &replayed[1..]);
+    Ok(())
+}
+
+#[stest::test]
+pub async fn test_subscribe_to_transaction_status() -> Result<()> {
+    let (txpool_service, storage, config, _, registry) =
+        test_helper::start_txpool_with_miner(1000, true).await;
+    let startup_info = storage.get_startup_info()?.unwrap();
+    let net = config.net();
+    let mut chain = BlockChain::new(net.time_service(), startup_info.main, storage, None)?;
+    let txn = {
+        let txn = starcoin_transaction_builder::build_transfer_from_association(
+            *AccountInfo::random().address(),
+            0,
+            10000,
+            net.time_service().now_secs() + starcoin_transaction_builder::DEFAULT_EXPIRATION_TIME,
+            net,
+        );
+        txn.as_signed_user_txn()?.clone()
+    };
+
+    let bus = registry.service_ref::<BusService>().await?;
+    registry.register::<ChainNotifyHandlerService>().await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let mut io = MetaIoHandler::default();
+    io.extend_with(PubSubImpl::new(service).to_delegate());
+
+    let mut metadata = Metadata::default();
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+    let request = format!(
+        r#"{{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{{"type_name":"transactionStatus"}}, {{"txn_hash": "{}", "confirmations": 1}}], "id": 1}}"#,
+        txn.id()
+    );
+    let response = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+    let resp = io.handle_request(&request, metadata).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    txpool_service
+        .add_txns(vec![txn.clone()])
+        .pop()
+        .unwrap()
+        .unwrap();
+    let mut blocks = vec![];
+    for txns in [vec![txn.clone()], vec![]] {
+        let (block_template, _) = chain.create_block_template(
+            *AccountInfo::random().address(),
+            None,
+            txns,
+            vec![],
+            None,
+        )?;
+        let block = chain
+            .consensus()
+            .create_block(block_template, net.time_service().as_ref())?;
+        blocks.push(chain.apply(block)?);
+    }
+    for block in blocks.iter() {
+        bus.broadcast(NewHeadBlock(Arc::new(block.clone())))?;
+    }
+
+    let statuses = collect_notifications(&mut receiver).await?;
+    assert_eq!(
+        statuses
+            .iter()
+            .map(|status| status["status"].as_str())
+            .collect::<Vec<_>>(),
+        vec![Some("pending"), Some("included"), Some("finalized")]
+    );
+    for status in statuses.iter() {
+        assert_eq!(
+            serde_json::from_value::<HashValue>(status["txn_hash"].clone())?,
+            txn.id()
+        );
+    }
+    assert_eq!(
+        serde_json::from_value::<HashValue>(statuses[2]["block_hash"].clone())?,
+        blocks[0].block().id()
+    );
+    assert_eq!(statuses[2]["vm_status"].as_str(), Some("Executed"));
+    Ok(())
+}
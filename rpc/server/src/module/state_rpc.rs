@@ -1,16 +1,19 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::module::helpers::StateRootResolver;
 use crate::module::map_err;
 use bcs_ext::BCSCodec;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
 use starcoin_abi_resolver::ABIResolver;
+use starcoin_chain_service::ChainReaderService;
 use starcoin_crypto::HashValue;
 use starcoin_dev::playground::view_resource;
 use starcoin_resource_viewer::MoveValueAnnotator;
 use starcoin_rpc_api::state::{
     GetCodeOption, GetResourceOption, ListCodeOption, ListResourceOption, StateApi,
+    StateQueryOption,
 };
 use starcoin_rpc_api::types::{
     AccountStateSetView, AnnotatedMoveStructView, CodeView, ListCodeView, ListResourceView,
@@ -18,6 +21,7 @@ use starcoin_rpc_api::types::{
     TableInfoView,
 };
 use starcoin_rpc_api::FutureResult;
+use starcoin_service_registry::ServiceRef;
 use starcoin_state_api::{ChainStateAsyncService, StateView};
 use starcoin_state_tree::StateNodeStore;
 use starcoin_statedb::{ChainStateDB, ChainStateReader};
@@ -37,6 +41,7 @@ where
     S: ChainStateAsyncService + 'static,
 {
     service: S,
+    chain: Option<ServiceRef<ChainReaderService>>,
     state_store: Arc<dyn StateNodeStore>,
 }
 
@@ -44,21 +49,47 @@ impl<S> StateRpcImpl<S>
 where
     S: ChainStateAsyncService,
 {
-    pub fn new(service: S, state_store: Arc<dyn StateNodeStore>) -> Self {
+    pub fn new(
+        service: S,
+        chain: Option<ServiceRef<ChainReaderService>>,
+        state_store: Arc<dyn StateNodeStore>,
+    ) -> Self {
         Self {
             service,
+            chain,
             state_store,
         }
     }
+
+    fn state_root_resolver(&self) -> StateRootResolver<S> {
+        StateRootResolver {
+            chain_state: self.service.clone(),
+            chain: self.chain.clone(),
+            state_store: self.state_store.clone(),
+        }
+    }
 }
 
 impl<S> StateApi for StateRpcImpl<S>
 where
     S: ChainStateAsyncService,
 {
-    fn get(&self, access_path: AccessPath) -> FutureResult<Option<Vec<u8>>> {
-        let fut = self.service.clone().get(access_path).map_err(map_err);
-        Box::pin(fut)
+    fn get(
+        &self,
+        access_path: AccessPath,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Option<Vec<u8>>> {
+        let resolver = self.state_root_resolver();
+        let state_store = self.state_store.clone();
+        let option = option.unwrap_or_default();
+        let fut = async move {
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            ChainStateDB::new(state_store, Some(state_root))
+                .get_state_value(&StateKey::AccessPath(access_path))
+        };
+        Box::pin(fut.map_err(map_err).boxed())
     }
 
     fn get_state_node_by_node_hash(&self, key_hash: HashValue) -> FutureResult<Option<Vec<u8>>> {
@@ -70,46 +101,78 @@ where
         Box::pin(f.map_err(map_err).boxed())
     }
 
-    fn get_with_proof(&self, access_path: AccessPath) -> FutureResult<StateWithProofView> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_proof(access_path)
-            .map_ok(|p| p.into())
-            .map_err(map_err);
-        Box::pin(fut)
+    fn get_with_proof(
+        &self,
+        access_path: AccessPath,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<StateWithProofView> {
+        let service = self.service.clone();
+        let resolver = self.state_root_resolver();
+        let option = option.unwrap_or_default();
+        let fut = async move {
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            service
+                .get_with_proof_by_root(access_path, state_root)
+                .await
+                .map(Into::into)
+        };
+        Box::pin(fut.map_err(map_err).boxed())
     }
 
-    fn get_with_proof_raw(&self, access_path: AccessPath) -> FutureResult<StrView<Vec<u8>>> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_proof(access_path)
-            .map_ok(|p| {
-                StrView(bcs_ext::to_bytes(&p).expect("Serialize StateWithProof should success."))
-            })
-            .map_err(map_err);
-        Box::pin(fut)
+    fn get_with_proof_raw(
+        &self,
+        access_path: AccessPath,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<StrView<Vec<u8>>> {
+        let service = self.service.clone();
+        let resolver = self.state_root_resolver();
+        let option = option.unwrap_or_default();
+        let fut = async move {
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            let p = service
+                .get_with_proof_by_root(access_path, state_root)
+                .await?;
+            Ok(StrView(
+                bcs_ext::to_bytes(&p).expect("Serialize StateWithProof should success."),
+            ))
+        };
+        Box::pin(fut.map_err(map_err).boxed())
     }
 
-    fn get_account_state(&self, address: AccountAddress) -> FutureResult<Option<AccountState>> {
-        let fut = self
-            .service
-            .clone()
-            .get_account_state(address)
-            .map_err(map_err);
-        Box::pin(fut)
+    fn get_account_state(
+        &self,
+        address: AccountAddress,
+        option: Option<StateQueryOption>,
+    ) -> FutureResult<Option<AccountState>> {
+        let service = self.service.clone();
+        let resolver = self.state_root_resolver();
+        let option = option.unwrap_or_default();
+        let fut = async move {
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            service.get_account_state_by_root(address, state_root).await
+        };
+        Box::pin(fut.map_err(map_err).boxed())
     }
 
     fn get_account_state_set(
         &self,
         address: AccountAddress,
         state_root: Option<HashValue>,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<Option<AccountStateSetView>> {
-        let state_service = self.service.clone();
+        let resolver = self.state_root_resolver();
         let db = self.state_store.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
-            let state_root = state_root.unwrap_or(state_service.state_root().await?);
+            let state_root = resolver
+                .resolve(state_root, option.block_number, option.block_hash)
+                .await?;
             let statedb = ChainStateDB::new(db, Some(state_root));
             let state = statedb.get_account_state_set(&address)?;
             let annotator = MoveValueAnnotator::new(&statedb);
@@ -151,9 +214,13 @@ where
         Box::pin(fut.map_err(map_err).boxed())
     }
 
-    fn get_state_root(&self) -> FutureResult<HashValue> {
-        let fut = self.service.clone().state_root().map_err(map_err);
-        Box::pin(fut)
+    fn get_state_root(&self, option: Option<StateQueryOption>) -> FutureResult<HashValue> {
+        let resolver = self.state_root_resolver();
+        let option = option.unwrap_or_default();
+        let fut = resolver
+            .resolve(None, option.block_number, option.block_hash)
+            .map_err(map_err);
+        Box::pin(fut.boxed())
     }
 
     fn get_with_proof_by_root(
@@ -200,14 +267,21 @@ where
         &self,
         handle: TableHandle,
         key: Vec<u8>,
+        option: Option<StateQueryOption>,
     ) -> FutureResult<StateWithTableItemProofView> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_table_item_proof(handle, key)
-            .map_ok(|p| p.into())
-            .map_err(map_err);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let resolver = self.state_root_resolver();
+        let option = option.unwrap_or_default();
+        let fut = async move {
+            let state_root = resolver
+                .resolve(None, option.block_number, option.block_hash)
+                .await?;
+            service
+                .get_with_table_item_proof_by_root(handle, key, state_root)
+                .await
+                .map(Into::into)
+        };
+        Box::pin(fut.map_err(map_err).boxed())
     }
 
     fn get_with_table_item_proof_by_root(
@@ -230,13 +304,13 @@ where
         module_id: StrView<ModuleId>,
         option: Option<GetCodeOption>,
     ) -> FutureResult<Option<CodeView>> {
-        let service = self.service.clone();
+        let resolver = self.state_root_resolver();
         let state_store = self.state_store.clone();
         let option = option.unwrap_or_default();
         let f = async move {
-            let state_root = option
-                .state_root
-                .unwrap_or(service.clone().state_root().await?);
+            let state_root = resolver
+                .resolve(option.state_root, option.block_number, option.block_hash)
+                .await?;
             let chain_state = ChainStateDB::new(state_store, Some(state_root));
             let code = chain_state
                 .get_state_value(&StateKey::AccessPath(AccessPath::from(&module_id.0)))?;
@@ -265,13 +339,13 @@ where
         resource_type: StrView<StructTag>,
         option: Option<GetResourceOption>,
     ) -> FutureResult<Option<ResourceView>> {
-        let service = self.service.clone();
+        let resolver = self.state_root_resolver();
         let state_store = self.state_store.clone();
         let option = option.unwrap_or_default();
         let f = async move {
-            let state_root = option
-                .state_root
-                .unwrap_or(service.clone().state_root().await?);
+            let state_root = resolver
+                .resolve(option.state_root, option.block_number, option.block_hash)
+                .await?;
             let chain_state = ChainStateDB::new(state_store, Some(state_root));
             let data = chain_state.get_state_value(&StateKey::AccessPath(
                 AccessPath::resource_access_path(addr, resource_type.0.clone()),
@@ -301,13 +375,13 @@ where
         addr: AccountAddress,
         option: Option<ListResourceOption>,
     ) -> FutureResult<ListResourceView> {
-        let state_service = self.service.clone();
+        let resolver = self.state_root_resolver();
         let db = self.state_store.clone();
         let option = option.unwrap_or_default();
         let fut = async move {
-            let state_root = option
-                .state_root
-                .unwrap_or(state_service.state_root().await?);
+            let state_root = resolver
+                .resolve(option.state_root, option.block_number, option.block_hash)
+                .await?;
             let statedb = ChainStateDB::new(db, Some(state_root));
 
             let state = statedb.get_account_state_set(&addr)?;
@@ -373,13 +447,13 @@ where
         addr: AccountAddress,
         option: Option<ListCodeOption>,
     ) -> FutureResult<ListCodeView> {
-        let state_service = self.service.clone();
+        let resolver = self.state_root_resolver();
         let db = self.state_store.clone();
         let option = option.unwrap_or_default();
         let fut = async move {
-            let state_root = option
-                .state_root
-                .unwrap_or(state_service.state_root().await?);
+            let state_root = resolver
+                .resolve(option.state_root, option.block_number, option.block_hash)
+                .await?;
             let statedb = ChainStateDB::new(db, Some(state_root));
             //TODO implement list state by iter, and pagination
             let state = statedb.get_account_state_set(&addr)?;
@@ -1,17 +1,24 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::{convert_to_rpc_error, map_err};
+use crate::module::pubsub::WatchTxnStatus;
+use crate::module::{convert_to_rpc_error, map_err, PubSubService};
+use anyhow::format_err;
 use bcs_ext::BCSCodec;
+use futures::future::{FutureExt, TryFutureExt};
+use futures::StreamExt;
 use starcoin_crypto::HashValue;
 /// Re-export the API
 pub use starcoin_rpc_api::txpool::*;
+use starcoin_rpc_api::types::pubsub::TransactionStatusNotification;
 use starcoin_rpc_api::types::{SignedUserTransactionView, StrView};
 use starcoin_rpc_api::{txpool::TxPoolApi, FutureResult};
+use starcoin_service_registry::ServiceRef;
 use starcoin_txpool_api::{TxPoolStatus, TxPoolSyncService};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::SignedUserTransaction;
 use std::convert::TryInto;
+use std::time::Duration;
 
 /// Re-export the API
 pub use starcoin_rpc_api::txpool::*;
@@ -21,17 +28,26 @@ where
     S: TxPoolSyncService + 'static,
 {
     service: S,
+    pubsub_service: Option<ServiceRef<PubSubService>>,
 }
 
 impl<S> TxPoolRpcImpl<S>
 where
     S: TxPoolSyncService,
 {
-    pub fn new(service: S) -> Self {
-        Self { service }
+    pub fn new(service: S, pubsub_service: Option<ServiceRef<PubSubService>>) -> Self {
+        Self {
+            service,
+            pubsub_service,
+        }
     }
 }
 
+/// Default timeout of `txpool.wait_for_transaction` in seconds.
+const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 60;
+/// Max timeout of `txpool.wait_for_transaction` in seconds, a larger timeout is clamped to it.
+const MAX_WAIT_TIMEOUT_SECS: u64 = 600;
+
 impl<S> TxPoolApi for TxPoolRpcImpl<S>
 where
     S: TxPoolSyncService,
@@ -103,6 +119,41 @@ where
         let state = self.service.status();
         Box::pin(futures::future::ok(state))
     }
+
+    fn wait_for_transaction(
+        &self,
+        txn_hash: HashValue,
+        timeout: Option<u64>,
+        confirmations: Option<u64>,
+    ) -> FutureResult<TransactionStatusNotification> {
+        let pubsub_service = self.pubsub_service.clone();
+        let fut = async move {
+            let pubsub_service =
+                pubsub_service.ok_or_else(|| format_err!("Pubsub service is not available."))?;
+            let mut statuses = pubsub_service
+                .send(WatchTxnStatus {
+                    txn_hash,
+                    confirmations: confirmations.unwrap_or_default(),
+                })
+                .await??;
+            let timeout = Duration::from_secs(
+                timeout
+                    .unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS)
+                    .min(MAX_WAIT_TIMEOUT_SECS),
+            );
+            tokio::time::timeout(timeout, async move {
+                while let Some(notification) = statuses.next().await {
+                    if notification.status.is_terminal() {
+                        return Ok(notification);
+                    }
+                }
+                Err(format_err!("Pubsub service is down."))
+            })
+            .await
+            .map_err(|_| format_err!("Wait for transaction {} timeout.", txn_hash))?
+        };
+        Box::pin(fut.boxed().map_err(map_err))
+    }
 }
 
 #[cfg(test)]
@@ -121,7 +172,7 @@ mod tests {
 
         let mut io = IoHandler::new();
         let txpool_service = MockTxPoolService::new();
-        io.extend_with(TxPoolRpcImpl::new(txpool_service).to_delegate());
+        io.extend_with(TxPoolRpcImpl::new(txpool_service, None).to_delegate());
         let txn = SignedUserTransaction::mock();
         let txn_hash = txn.id();
         let prefix = r#"{"jsonrpc":"2.0","method":"txpool.submit_transaction","params":["#;
@@ -9,6 +9,7 @@ pub use api_limiter::Quota;
 use jsonrpc_core::middleware::NoopCallFuture;
 use starcoin_config::{ApiQuotaConfig, ApiQuotaConfiguration, QuotaDuration};
 use starcoin_rpc_api::metadata::Metadata;
+use std::collections::HashMap;
 
 struct QuotaWrapper(Quota);
 
@@ -29,7 +30,11 @@ pub struct JsonApiRateLimitMiddleware {
 }
 
 impl JsonApiRateLimitMiddleware {
-    pub fn from_config(quotas: ApiQuotaConfiguration) -> Self {
+    /// `user_quotas` override the default user api quota of the given users.
+    pub fn from_config(
+        quotas: ApiQuotaConfiguration,
+        user_quotas: HashMap<String, ApiQuotaConfig>,
+    ) -> Self {
         let limiters = ApiLimiters::new(
             Into::<QuotaWrapper>::into(quotas.default_global_api_quota()).0,
             quotas
@@ -43,9 +48,20 @@ impl JsonApiRateLimitMiddleware {
                 .into_iter()
                 .map(|(k, v)| (k, Into::<QuotaWrapper>::into(v).0))
                 .collect(),
+        )
+        .with_custom_user_quotas(
+            user_quotas
+                .into_iter()
+                .map(|(k, v)| (k, Into::<QuotaWrapper>::into(v).0))
+                .collect(),
         );
         Self { limiters }
     }
+
+    /// Check the quota of `method` for `user`, the quota of anonymous user is the global one.
+    pub fn check(&self, method: &str, user: Option<&String>) -> anyhow::Result<()> {
+        self.limiters.check(&method.to_string(), user)
+    }
 }
 
 impl Middleware<Metadata> for JsonApiRateLimitMiddleware {
@@ -64,7 +80,7 @@ impl Middleware<Metadata> for JsonApiRateLimitMiddleware {
             Call::Invalid { .. } => None,
         };
         if let Some((m, json_version, id)) = method {
-            match self.limiters.check(&m, meta.user.as_ref()) {
+            match self.check(m.as_str(), meta.user.as_ref()) {
                 Ok(_) => Either::Right(next(call, meta)),
                 Err(e) => {
                     let output = Output::Failure(Failure {
@@ -3,6 +3,7 @@
 
 use crate::api_registry::ApiRegistry;
 use crate::extractors::{RpcExtractor, WsExtractor};
+use crate::graphql::{GraphQLContext, GraphQLGuard, GraphQLServer};
 use anyhow::Result;
 use futures::stream::*;
 use futures::{FutureExt, StreamExt};
@@ -18,6 +19,7 @@ use jsonrpc_server_utils::hosts::DomainsValidation;
 use starcoin_config::{Api, ApiSet, NodeConfig};
 use starcoin_logger::prelude::*;
 use starcoin_rpc_api::contract_api::ContractApi;
+use starcoin_rpc_api::dev::DevApi;
 use starcoin_rpc_api::metadata::Metadata;
 use starcoin_rpc_api::network_manager::NetworkManagerApi;
 use starcoin_rpc_api::node_manager::NodeManagerApi;
@@ -40,6 +42,8 @@ pub struct RpcService {
     http: Option<jsonrpc_http_server::Server>,
     tcp: Option<jsonrpc_tcp_server::Server>,
     ws: Option<jsonrpc_ws_server::Server>,
+    graphql_context: Option<GraphQLContext>,
+    graphql: Option<GraphQLServer>,
 }
 
 impl ActorService for RpcService {
@@ -48,6 +52,7 @@ impl ActorService for RpcService {
         self.http = self.start_http()?;
         self.tcp = self.start_tcp()?;
         self.ws = self.start_ws()?;
+        self.graphql = self.start_graphql()?;
         Ok(())
     }
 
@@ -66,9 +71,24 @@ impl RpcService {
             http: None,
             tcp: None,
             ws: None,
+            graphql_context: None,
+            graphql: None,
         }
     }
 
+    /// Register the dev api, it is only available in the network with mock time service.
+    pub fn with_dev_api<D: DevApi>(mut self, dev_api: D) -> Self {
+        self.api_registry
+            .register(Api::Dev, DevApi::to_delegate(dev_api));
+        self
+    }
+
+    /// Serve the GraphQL endpoint with `context` if the graphql port is configured.
+    pub fn with_graphql(mut self, context: GraphQLContext) -> Self {
+        self.graphql_context = Some(context);
+        self
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn new_with_api<C, N, NM, SM, NWM, T, A, S, D, P, M, Contract>(
         config: Arc<NodeConfig>,
@@ -104,7 +124,8 @@ impl RpcService {
             .registry()
             .and_then(|registry| RpcMetrics::register(registry).ok());
 
-        let mut api_registry = ApiRegistry::new(config.rpc.api_quotas.clone(), metrics);
+        let mut api_registry =
+            ApiRegistry::new(config.rpc.api_quotas.clone(), config.rpc.auth(), metrics);
 
         api_registry.register(Api::Node, NodeApi::to_delegate(node_api));
         if let Some(node_manager_api) = node_manager_api {
@@ -157,7 +178,12 @@ impl RpcService {
             None
         } else {
             let ipc_file = self.config.rpc.get_ipc_file();
-            let apis: HashSet<Api> = self.config.rpc.ipc.apis().list_apis();
+            let mut apis: HashSet<Api> = self.config.rpc.ipc.apis().list_apis();
+            // The dev api is only registered in the network with mock time service, serve it
+            // by default on the local ipc for the dev commands.
+            if self.config.rpc.ipc.apis.is_none() {
+                apis.insert(Api::Dev);
+            }
             let io_handler = self.api_registry.get_apis(apis);
 
             info!("Ipc rpc server start at :{:?}", ipc_file);
@@ -173,7 +199,8 @@ impl RpcService {
         Ok(if let Some(addr) = self.config.rpc.get_http_address() {
             let address = addr.into();
             let apis = self.config.rpc.http.apis().list_apis();
-            let io_handler = self.api_registry.get_apis(apis);
+            // only the http requests carry the rpc token.
+            let io_handler = self.api_registry.get_apis_with_auth(apis);
             let http = jsonrpc_http_server::ServerBuilder::new(io_handler)
                 .meta_extractor(RpcExtractor {
                     http_ip_headers: self.config.rpc.http.ip_headers(),
@@ -225,6 +252,32 @@ impl RpcService {
         })
     }
 
+    fn start_graphql(&self) -> Result<Option<GraphQLServer>> {
+        Ok(
+            match (
+                self.config.rpc.get_graphql_address(),
+                self.graphql_context.clone(),
+            ) {
+                (Some(addr), Some(context)) => {
+                    let address = addr.into();
+                    let guard = GraphQLGuard::new(
+                        self.config.rpc.auth(),
+                        self.api_registry.rate_limit_middleware(),
+                    );
+                    let server = GraphQLServer::start(
+                        address,
+                        context.schema(),
+                        guard,
+                        self.config.rpc.http.max_request_body_size(),
+                    )?;
+                    info!("Rpc: graphql server start at: {}", address);
+                    Some(server)
+                }
+                _ => None,
+            },
+        )
+    }
+
     pub fn close(&mut self) {
         if let Some(ipc) = self.ipc.take() {
             ipc.close();
@@ -238,6 +291,9 @@ impl RpcService {
         if let Some(ws) = self.ws.take() {
             ws.close();
         }
+        if let Some(mut graphql) = self.graphql.take() {
+            graphql.close();
+        }
         info!("Rpc Sever is closed.");
     }
 }
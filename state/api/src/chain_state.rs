@@ -153,7 +153,9 @@ pub trait ChainStateWriter {
 
     fn commit(&self) -> Result<HashValue>;
 
-    fn flush(&self) -> Result<()>;
+    /// Flush the committed state to the store, return the sequences of the state node batches
+    /// journaled by the store, empty if the store does not support state pruning.
+    fn flush(&self) -> Result<Vec<u64>>;
 }
 /// `AccountStateReader` is a helper struct for read account state.
 pub struct AccountStateReader<'a, Reader> {
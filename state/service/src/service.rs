@@ -1,8 +1,9 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{format_err, Result};
-use starcoin_config::{NodeConfig, TimeService};
+use anyhow::{ensure, format_err, Result};
+use starcoin_config::{NodeConfig, StatePruneMode, TimeService};
+use starcoin_crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_service_registry::{
@@ -40,6 +41,12 @@ impl ChainStateService {
             service: Inner::new(store, root_hash, time_service),
         }
     }
+
+    /// The state out of the latest `retention` blocks may have been pruned.
+    pub fn with_prune_retention(mut self, retention: u64) -> Self {
+        self.service.prune_retention = Some(retention);
+        self
+    }
 }
 
 impl ServiceFactory<Self> for ChainStateService {
@@ -52,11 +59,17 @@ impl ServiceFactory<Self> for ChainStateService {
         let head_block = storage.get_block(startup_info.main)?.ok_or_else(|| {
             format_err!("Can not find head block by hash:{:?}", startup_info.main)
         })?;
-        Ok(Self::new(
+        let service = Self::new(
             storage,
             Some(head_block.header().state_root()),
             config.net().time_service(),
-        ))
+        );
+        Ok(match config.storage.state_prune_mode() {
+            StatePruneMode::Archive => service,
+            StatePruneMode::Pruned => {
+                service.with_prune_retention(config.storage.state_prune_retention())
+            }
+        })
     }
 }
 
@@ -140,9 +153,11 @@ impl EventHandler<Self, NewHeadBlock> for ChainStateService {
 }
 
 pub struct Inner {
+    store: Arc<dyn StateNodeStore>,
     state_db: ChainStateDB,
     //for adjust local time by on chain time.
     time_service: Arc<dyn TimeService>,
+    prune_retention: Option<u64>,
 }
 
 impl Inner {
@@ -152,9 +167,25 @@ impl Inner {
         time_service: Arc<dyn TimeService>,
     ) -> Self {
         Self {
-            state_db: ChainStateDB::new(store, root_hash),
+            state_db: ChainStateDB::new(store.clone(), root_hash),
+            store,
             time_service,
+            prune_retention: None,
+        }
+    }
+
+    /// On a pruned node, the root node of a state out of the retention window has been deleted.
+    fn check_root(&self, state_root: HashValue) -> Result<()> {
+        if let Some(retention) = self.prune_retention {
+            ensure!(
+                state_root == *SPARSE_MERKLE_PLACEHOLDER_HASH
+                    || self.store.get(&state_root)?.is_some(),
+                "The state of root {} is not available, the node is in pruned mode and only keeps the state of the latest {} blocks.",
+                state_root,
+                retention
+            );
         }
+        Ok(())
     }
 
     pub(crate) fn get_account_state_set_with_root(
@@ -164,6 +195,7 @@ impl Inner {
     ) -> Result<Option<AccountStateSet>> {
         match state_root {
             Some(root) => {
+                self.check_root(root)?;
                 let reader = self.state_db.fork_at(root);
                 reader.get_account_state_set(&address)
             }
@@ -176,6 +208,7 @@ impl Inner {
         access_path: AccessPath,
         state_root: HashValue,
     ) -> Result<StateWithProof> {
+        self.check_root(state_root)?;
         let reader = self.state_db.fork_at(state_root);
         reader.get_with_proof(&access_path)
     }
@@ -186,6 +219,7 @@ impl Inner {
         key: Vec<u8>,
         state_root: HashValue,
     ) -> Result<StateWithTableItemProof> {
+        self.check_root(state_root)?;
         let reader = self.state_db.fork_at(state_root);
         reader.get_with_table_item_proof(&handle, &key)
     }
@@ -195,6 +229,7 @@ impl Inner {
         account: AccountAddress,
         state_root: HashValue,
     ) -> Result<Option<AccountState>> {
+        self.check_root(state_root)?;
         let reader = self.state_db.fork_at(state_root);
         reader.get_account_state(&account)
     }
@@ -261,6 +296,10 @@ impl StateView for Inner {
     fn is_genesis(&self) -> bool {
         false
     }
+
+    fn is_impersonated(&self, address: &AccountAddress) -> bool {
+        self.state_db.is_impersonated(address)
+    }
 }
 
 #[cfg(test)]
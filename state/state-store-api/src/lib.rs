@@ -4,6 +4,7 @@ use forkable_jellyfish_merkle::RawKey;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use starcoin_crypto::hash::HashValue;
 use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::impersonation::ImpersonatedAccounts;
 use starcoin_vm_types::state_store::table::TableInfo;
 use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
@@ -11,6 +12,28 @@ use std::convert::{TryFrom, TryInto};
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StateNode(pub Vec<u8>);
 
+impl StateNode {
+    /// The hash of the node, it does not depend on the raw key type of the tree.
+    pub fn node_hash(&self) -> Result<HashValue> {
+        let node: Node<RawBytesKey> = Node::decode(self.0.as_slice())?;
+        Ok(node.hash())
+    }
+}
+
+/// The encoded raw key of a leaf node, for hashing a node of any tree.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct RawBytesKey(Vec<u8>);
+
+impl RawKey for RawBytesKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+
+    fn decode_key(bytes: &[u8]) -> Result<Self> {
+        Ok(Self(bytes.to_vec()))
+    }
+}
+
 impl<K> TryFrom<Node<K>> for StateNode
 where
     K: RawKey,
@@ -52,9 +75,35 @@ impl Serialize for StateNode {
     }
 }
 
+/// The nodes change produced by a state tree flush.
+#[derive(Clone, Debug, Default)]
+pub struct StateNodeBatch {
+    /// The new nodes to write.
+    pub nodes: BTreeMap<HashValue, StateNode>,
+    /// How many times each new node is created by the tree updates,
+    /// the same node may be created more than once in one flush.
+    pub node_refs: BTreeMap<HashValue, u64>,
+    /// The nodes overwritten by the tree updates, they are unreachable from the new root.
+    pub stale_nodes: Vec<HashValue>,
+}
+
 pub trait StateNodeStore: std::marker::Send + std::marker::Sync {
     fn get(&self, hash: &HashValue) -> Result<Option<StateNode>>;
     fn put(&self, key: HashValue, node: StateNode) -> Result<()>;
     fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<()>;
     fn get_table_info(&self, address: AccountAddress) -> Result<Option<TableInfo>>;
+
+    /// The accounts impersonated by the node of the store, the state views on the store accept
+    /// the transactions of them without their private keys.
+    fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts> {
+        None
+    }
+
+    /// Write the nodes of a state tree flush, the store which supports state pruning
+    /// should also record the node references and stale nodes,
+    /// and return the sequence of the recorded journal.
+    fn write_node_batch(&self, batch: StateNodeBatch) -> Result<Option<u64>> {
+        self.write_nodes(batch.nodes)?;
+        Ok(None)
+    }
 }
@@ -201,8 +201,9 @@ where
         Ok(())
     }
 
-    /// commit the state change into underline storage.
-    pub fn flush(&self) -> Result<()> {
+    /// commit the state change into underline storage,
+    /// return the sequence of the state node batch if the storage journals it.
+    pub fn flush(&self) -> Result<Option<u64>> {
         let change_set_list = {
             let mut cache_guard = self.cache.lock();
             cache_guard.split_off_idx = Some(cache_guard.change_set_list.len());
@@ -213,21 +214,28 @@ where
         // when self::commit call self::updates(&self, updates: Vec<(K, Option<Blob>)>)
         // the param updates is empty cause this situation
         if change_set_list.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
         let mut root_hash = HashValue::default();
-        let mut node_map = BTreeMap::new();
+        let mut node_batch = StateNodeBatch::default();
         for (hash, change_sets) in change_set_list.into_iter() {
             for (nk, n) in change_sets.node_batch.into_iter() {
-                node_map.insert(nk, n.try_into()?);
+                node_batch.nodes.insert(nk, n.try_into()?);
+                *node_batch.node_refs.entry(nk).or_insert(0) += 1;
             }
+            node_batch.stale_nodes.extend(
+                change_sets
+                    .stale_node_index_batch
+                    .into_iter()
+                    .map(|index| index.node_key),
+            );
             root_hash = hash;
         }
-        self.storage.write_nodes(node_map)?;
+        let seq = self.storage.write_node_batch(node_batch)?;
         // and then advance the storage root hash
         *self.storage_root_hash.write() = root_hash;
         self.cache.lock().reset(root_hash);
-        Ok(())
+        Ok(seq)
     }
 
     /// Dump tree to state set.
@@ -27,6 +27,7 @@ use starcoin_vm_types::access_path::{DataPath, ModuleName};
 #[cfg(test)]
 use starcoin_vm_types::account_config::TABLE_ADDRESS_LIST_LEN;
 use starcoin_vm_types::account_config::TABLE_HANDLE_ADDRESS_LIST;
+use starcoin_vm_types::impersonation::ImpersonatedAccounts;
 use starcoin_vm_types::language_storage::StructTag;
 use starcoin_vm_types::state_store::table::TableInfo;
 use starcoin_vm_types::state_store::{state_key::StateKey, table::TableHandle};
@@ -185,13 +186,14 @@ impl AccountStateObject {
         Ok(self.to_state())
     }
 
-    pub fn flush(&self) -> Result<()> {
-        self.resource_tree.lock().flush()?;
+    pub fn flush(&self) -> Result<Vec<u64>> {
+        let mut seqs = vec![];
+        seqs.extend(self.resource_tree.lock().flush()?);
         if let Some(code_tree) = self.code_tree.lock().as_ref() {
-            code_tree.flush()?;
+            seqs.extend(code_tree.flush()?);
         }
 
-        Ok(())
+        Ok(seqs)
     }
 
     fn to_state_set(&self) -> Result<AccountStateSet> {
@@ -225,6 +227,7 @@ pub struct ChainStateDB {
     /// state_tree_table_handles SMT save TableHandle -> TableHandleState.root_hash
     state_tree_table_handles_list: Vec<StateTree<TableHandle>>,
     update_table_handle_idx_list: Mutex<HashSet<usize>>,
+    impersonated_accounts: Option<ImpersonatedAccounts>,
 }
 
 static G_DEFAULT_CACHE_SIZE: usize = 10240;
@@ -244,6 +247,7 @@ impl ChainStateDB {
             cache_table_handle: Mutex::new(LruCache::new(G_DEFAULT_CACHE_SIZE)),
             state_tree_table_handles_list: vec![],
             update_table_handle_idx_list: Mutex::new(HashSet::new()),
+            impersonated_accounts: store.impersonated_accounts(),
         };
         for (handle_address, table_path) in
             TABLE_HANDLE_ADDRESS_LIST.iter().zip(TABLE_PATH_LIST.iter())
@@ -272,12 +276,26 @@ impl ChainStateDB {
 
     /// Fork a new statedb base current statedb
     pub fn fork(&self) -> Self {
-        Self::new(self.store.clone(), Some(self.state_root()))
+        self.fork_at(self.state_root())
     }
 
     /// Fork a new statedb at `root_hash`
     pub fn fork_at(&self, state_root: HashValue) -> Self {
         Self::new(self.store.clone(), Some(state_root))
+            .with_impersonated_accounts(self.impersonated_accounts.clone())
+    }
+
+    /// Override the impersonated accounts of the store.
+    pub fn with_impersonated_accounts(
+        mut self,
+        impersonated_accounts: Option<ImpersonatedAccounts>,
+    ) -> Self {
+        self.impersonated_accounts = impersonated_accounts;
+        self
+    }
+
+    pub fn impersonated_accounts(&self) -> Option<&ImpersonatedAccounts> {
+        self.impersonated_accounts.as_ref()
     }
 
     fn new_state_tree<K: RawKey>(&self, root_hash: HashValue) -> StateTree<K> {
@@ -429,6 +447,13 @@ impl StateView for ChainStateDB {
     fn is_genesis(&self) -> bool {
         self.state_tree.is_genesis()
     }
+
+    fn is_impersonated(&self, address: &AccountAddress) -> bool {
+        self.impersonated_accounts
+            .as_ref()
+            .map(|accounts| accounts.contains(address))
+            .unwrap_or(false)
+    }
 }
 
 impl ChainStateReader for ChainStateDB {
@@ -706,30 +731,32 @@ impl ChainStateWriter for ChainStateDB {
     }
 
     /// flush data to db.
-    fn flush(&self) -> Result<()> {
+    fn flush(&self) -> Result<Vec<u64>> {
+        let mut seqs = vec![];
         //cache flush
         let mut locks_table_handle = self.updates_table_handle.write();
         for h in locks_table_handle.iter() {
             let table_handle_state_object = self.get_table_handle_state_object(h)?;
-            table_handle_state_object.flush()?;
+            seqs.extend(table_handle_state_object.flush()?);
         }
         locks_table_handle.clear();
 
         for idx in self.update_table_handle_idx_list.lock().iter() {
             let state_tree_table_handle = self.get_state_tree_table_handles(*idx)?;
-            state_tree_table_handle.flush()?;
+            seqs.extend(state_tree_table_handle.flush()?);
         }
         self.update_table_handle_idx_list.lock().clear();
 
         let mut locks = self.updates.write();
         for address in locks.iter() {
             let account_state_object = self.get_account_state_object(address, false)?;
-            account_state_object.flush()?;
+            seqs.extend(account_state_object.flush()?);
         }
         locks.clear();
 
         // self tree flush
-        self.state_tree.flush()
+        seqs.extend(self.state_tree.flush()?);
+        Ok(seqs)
     }
 }
 
@@ -765,9 +792,8 @@ impl TableHandleStateObject {
         Ok(())
     }
 
-    pub fn flush(&self) -> Result<()> {
-        self.state_tree.lock().flush()?;
-        Ok(())
+    pub fn flush(&self) -> Result<Option<u64>> {
+        self.state_tree.lock().flush()
     }
 
     pub fn root_hash(&self) -> HashValue {
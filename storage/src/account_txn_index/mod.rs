@@ -0,0 +1,200 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::{CodecKVStore, CodecWriteBatch, KeyCodec, SchemaStorage, ValueCodec};
+use crate::{define_storage, ACCOUNT_TXN_INDEX_PREFIX_NAME};
+use anyhow::{ensure, format_err, Result};
+use bcs_ext::BCSCodec;
+use byteorder::{BigEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{Block, BlockNumber};
+
+define_storage!(
+    AccountTransactionIndexStorage,
+    AccountTxnIndexKey,
+    AccountTxnIndexValue,
+    ACCOUNT_TXN_INDEX_PREFIX_NAME
+);
+
+/// The key of account transaction index.
+/// Keys are ordered by sender, then by sequence number,
+/// the `block_id` suffix keeps the entries of different chain branches apart.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AccountTxnIndexKey {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub block_id: HashValue,
+}
+
+impl AccountTxnIndexKey {
+    const LENGTH: usize = AccountAddress::LENGTH + 8 + HashValue::LENGTH;
+
+    pub fn new(sender: AccountAddress, sequence_number: u64, block_id: HashValue) -> Self {
+        Self {
+            sender,
+            sequence_number,
+            block_id,
+        }
+    }
+}
+
+impl KeyCodec for AccountTxnIndexKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = Vec::with_capacity(Self::LENGTH);
+        key.extend_from_slice(self.sender.as_ref());
+        key.extend_from_slice(&self.sequence_number.to_be_bytes());
+        key.extend_from_slice(self.block_id.as_slice());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == Self::LENGTH,
+            "invalid account txn index key length: {}",
+            data.len()
+        );
+        let (sender, mut rest) = data.split_at(AccountAddress::LENGTH);
+        let sender = AccountAddress::from_bytes(sender)?;
+        let sequence_number = rest.read_u64::<BigEndian>()?;
+        let block_id = HashValue::from_slice(rest)?;
+        Ok(Self {
+            sender,
+            sequence_number,
+            block_id,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct AccountTxnIndexValue {
+    pub transaction_hash: HashValue,
+    pub block_number: BlockNumber,
+}
+
+impl ValueCodec for AccountTxnIndexValue {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+pub trait AccountTransactionIndexStore {
+    /// Whether the account transaction index is maintained by this store.
+    fn account_txn_index_enabled(&self) -> bool;
+
+    /// Save the index entries of the user transactions in `block`,
+    /// do nothing if the index is disabled.
+    fn save_account_txn_index(&self, block: &Block) -> Result<()>;
+
+    /// Delete the index entries of the user transactions in `block`,
+    /// used when the block is retracted from main chain.
+    fn delete_account_txn_index(&self, block: &Block) -> Result<()>;
+
+    /// Get the index entries of `sender` from sequence number `start_seq`,
+    /// ordered by sequence number, or in reverse order if `reverse` is true.
+    /// If `start_seq` is `None`, scan from the first (or the last if `reverse`) entry.
+    /// The entries may come from any chain branch, caller should check them against the main chain.
+    /// The entries of one sequence number are never split, so the result may exceed `max_size`.
+    fn get_account_txn_index(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<(AccountTxnIndexKey, AccountTxnIndexValue)>>;
+}
+
+impl AccountTransactionIndexStorage {
+    fn index_batch(block: &Block) -> CodecWriteBatch<AccountTxnIndexKey, AccountTxnIndexValue> {
+        CodecWriteBatch::new_puts(
+            block
+                .transactions()
+                .iter()
+                .map(|txn| {
+                    (
+                        AccountTxnIndexKey::new(txn.sender(), txn.sequence_number(), block.id()),
+                        AccountTxnIndexValue {
+                            transaction_hash: txn.id(),
+                            block_number: block.header().number(),
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn save(&self, block: &Block) -> Result<()> {
+        if block.transactions().is_empty() {
+            return Ok(());
+        }
+        self.write_batch(Self::index_batch(block))
+    }
+
+    pub(crate) fn delete(&self, block: &Block) -> Result<()> {
+        if block.transactions().is_empty() {
+            return Ok(());
+        }
+        let keys = block
+            .transactions()
+            .iter()
+            .map(|txn| AccountTxnIndexKey::new(txn.sender(), txn.sequence_number(), block.id()))
+            .collect();
+        self.write_batch(CodecWriteBatch::new_deletes(keys))
+    }
+
+    pub(crate) fn scan(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<(AccountTxnIndexKey, AccountTxnIndexValue)>> {
+        let db = self
+            .get_store()
+            .storage()
+            .db()
+            .ok_or_else(|| format_err!("Only support scan on db storage instance"))?;
+        let iter = if reverse {
+            let mut iter = db.rev_iter::<AccountTxnIndexKey, AccountTxnIndexValue>(
+                ACCOUNT_TXN_INDEX_PREFIX_NAME,
+            )?;
+            iter.seek_for_prev(
+                AccountTxnIndexKey::new(
+                    sender,
+                    start_seq.unwrap_or(u64::MAX),
+                    HashValue::new([u8::MAX; HashValue::LENGTH]),
+                )
+                .encode_key()?,
+            )?;
+            iter
+        } else {
+            let mut iter =
+                db.iter::<AccountTxnIndexKey, AccountTxnIndexValue>(ACCOUNT_TXN_INDEX_PREFIX_NAME)?;
+            iter.seek(
+                AccountTxnIndexKey::new(sender, start_seq.unwrap_or(0), HashValue::zero())
+                    .encode_key()?,
+            )?;
+            iter
+        };
+        let mut entries: Vec<(AccountTxnIndexKey, AccountTxnIndexValue)> = vec![];
+        for item in iter {
+            let (key, value) = item?;
+            if key.sender != sender {
+                break;
+            }
+            if entries.len() as u64 >= max_size {
+                match entries.last() {
+                    Some((last, _)) if last.sequence_number == key.sequence_number => {}
+                    _ => break,
+                }
+            }
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::{CodecWriteBatch, KeyCodec, ValueCodec, WriteOp};
+use crate::storage::{CodecWriteBatch, ColumnFamilyName, KeyCodec, ValueCodec, WriteOp};
 use anyhow::Result;
 use std::convert::TryFrom;
 
@@ -54,3 +54,35 @@ where
         Ok(WriteBatch::new_with_rows(rows?))
     }
 }
+
+/// A group of [`WriteBatch`] on different column families, written atomically.
+#[derive(Debug, Default, Clone)]
+pub struct WriteBatchWithColumn {
+    pub data: Vec<(ColumnFamilyName, WriteBatch)>,
+}
+
+impl WriteBatchWithColumn {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `batch` of column family `column` to the batch.
+    pub fn put_batch(&mut self, column: ColumnFamilyName, batch: WriteBatch) {
+        self.data.push((column, batch));
+    }
+
+    /// Adds a typed `batch` of column family `column` to the batch.
+    pub fn put_codec_batch<K, V>(
+        &mut self,
+        column: ColumnFamilyName,
+        batch: CodecWriteBatch<K, V>,
+    ) -> Result<()>
+    where
+        K: KeyCodec,
+        V: ValueCodec,
+    {
+        self.data.push((column, WriteBatch::try_from(batch)?));
+        Ok(())
+    }
+}
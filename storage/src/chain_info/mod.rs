@@ -4,7 +4,9 @@
 use crate::storage::{ColumnFamily, InnerStorage, KVStore};
 use crate::{StorageVersion, CHAIN_INFO_PREFIX_NAME};
 use anyhow::Result;
+use bcs_ext::BCSCodec;
 use starcoin_crypto::HashValue;
+use starcoin_types::block::{BlockIdAndNumber, BlockNumber};
 use starcoin_types::startup_info::{BarnardHardFork, DragonHardFork, SnapshotRange, StartupInfo};
 use std::convert::{TryFrom, TryInto};
 
@@ -29,6 +31,9 @@ impl ChainInfoStorage {
     const SNAPSHOT_RANGE_KEY: &'static str = "snapshot_height";
     const BARNARD_HARD_FORK: &'static str = "barnard_hard_fork";
     const DRAGON_HARD_FORK: &'static str = "dragon_hard_fork";
+    const EVENT_INDEX_START_KEY: &'static str = "event_index_start";
+    const STATE_PRUNED_BLOCK_KEY: &'static str = "state_pruned_block";
+    const FORK_BLOCK_KEY: &'static str = "fork_block";
 
     pub fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
         self.get(Self::STARTUP_INFO_KEY.as_bytes())
@@ -127,4 +132,53 @@ impl ChainInfoStorage {
             dragon_hard_fork.try_into()?,
         )
     }
+
+    pub fn get_event_index_start(&self) -> Result<Option<BlockNumber>> {
+        self.get(Self::EVENT_INDEX_START_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(BlockNumber::from_be_bytes(
+                    bytes.as_slice().try_into()?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_event_index_start(&self, block_number: BlockNumber) -> Result<()> {
+        self.put_sync(
+            Self::EVENT_INDEX_START_KEY.as_bytes().to_vec(),
+            block_number.to_be_bytes().to_vec(),
+        )
+    }
+
+    pub fn get_state_pruned_block(&self) -> Result<Option<BlockNumber>> {
+        self.get(Self::STATE_PRUNED_BLOCK_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(BlockNumber::from_be_bytes(
+                    bytes.as_slice().try_into()?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_state_pruned_block(&self, block_number: BlockNumber) -> Result<()> {
+        self.put_sync(
+            Self::STATE_PRUNED_BLOCK_KEY.as_bytes().to_vec(),
+            block_number.to_be_bytes().to_vec(),
+        )
+    }
+
+    pub fn get_fork_block(&self) -> Result<Option<BlockIdAndNumber>> {
+        self.get(Self::FORK_BLOCK_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(BlockIdAndNumber::decode(bytes.as_slice())?)),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_fork_block(&self, fork_block: BlockIdAndNumber) -> Result<()> {
+        self.put_sync(
+            Self::FORK_BLOCK_KEY.as_bytes().to_vec(),
+            fork_block.encode()?,
+        )
+    }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{WriteBatch, WriteBatchWithColumn};
 use crate::errors::StorageInitError;
 use crate::metrics::{record_metrics, StorageMetrics};
 use crate::storage::{ColumnFamilyName, InnerStore, KeyCodec, ValueCodec, WriteOp};
@@ -459,4 +459,29 @@ impl InnerStore for DBStorage {
             Ok(res)
         })
     }
+
+    /// Writes the batches of different column families in one rocksdb WriteBatch.
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()> {
+        record_metrics(
+            "db",
+            "batch_with_column",
+            "write_batch_with_column",
+            self.metrics.as_ref(),
+        )
+        .call(|| {
+            let mut db_batch = DBWriteBatch::default();
+            for (prefix_name, rows) in &batch.data {
+                let cf_handle = self.get_cf_handle(prefix_name)?;
+                for (key, write_op) in &rows.rows {
+                    match write_op {
+                        WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
+                        WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                    };
+                }
+            }
+            self.db
+                .write_opt(db_batch, &Self::default_write_options())?;
+            Ok(())
+        })
+    }
 }
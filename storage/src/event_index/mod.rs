@@ -0,0 +1,344 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::batch::WriteBatchWithColumn;
+use crate::define_storage;
+use crate::storage::{CodecWriteBatch, ColumnFamilyName, InnerStore, KeyCodec, StorageInstance};
+use crate::{
+    CONTRACT_EVENT_PREFIX_NAME, EVENT_ADDRESS_INDEX_PREFIX_NAME, EVENT_KEY_INDEX_PREFIX_NAME,
+    EVENT_TYPE_TAG_INDEX_PREFIX_NAME,
+};
+use anyhow::{ensure, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use starcoin_crypto::HashValue;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::contract_event::ContractEvent;
+use starcoin_types::event::EventKey;
+use starcoin_types::language_storage::TypeTag;
+use starcoin_types::transaction::RichTransactionInfo;
+
+define_storage!(
+    EventKeyIndexStorage,
+    EventIndexKey,
+    Vec<u8>,
+    EVENT_KEY_INDEX_PREFIX_NAME
+);
+
+define_storage!(
+    EventAddressIndexStorage,
+    EventIndexKey,
+    Vec<u8>,
+    EVENT_ADDRESS_INDEX_PREFIX_NAME
+);
+
+define_storage!(
+    EventTypeTagIndexStorage,
+    EventIndexKey,
+    Vec<u8>,
+    EVENT_TYPE_TAG_INDEX_PREFIX_NAME
+);
+
+/// The dimension of an event index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EventIndexType {
+    /// Index by the `EventKey` of the event.
+    EventKey,
+    /// Index by the creator address of the event key.
+    Address,
+    /// Index by the struct type tag of the event, ignoring type params.
+    TypeTag,
+}
+
+impl EventIndexType {
+    pub fn column_family(&self) -> ColumnFamilyName {
+        match self {
+            EventIndexType::EventKey => EVENT_KEY_INDEX_PREFIX_NAME,
+            EventIndexType::Address => EVENT_ADDRESS_INDEX_PREFIX_NAME,
+            EventIndexType::TypeTag => EVENT_TYPE_TAG_INDEX_PREFIX_NAME,
+        }
+    }
+}
+
+/// Get the index value of an event key.
+pub fn event_key_index(event_key: &EventKey) -> Vec<u8> {
+    event_key.to_vec()
+}
+
+/// Get the index value of an event creator address.
+pub fn address_index(address: &AccountAddress) -> Vec<u8> {
+    address.to_vec()
+}
+
+/// Get the index value of an event type tag.
+/// Struct type tags are indexed by `address::module::name`, so a filter without type params
+/// can find all the instantiations of the struct.
+pub fn type_tag_index(type_tag: &TypeTag) -> Vec<u8> {
+    let name = match type_tag {
+        TypeTag::Struct(struct_tag) => {
+            let mut name = struct_tag.address.to_vec();
+            name.extend_from_slice(
+                format!("::{}::{}", struct_tag.module, struct_tag.name).as_bytes(),
+            );
+            name
+        }
+        type_tag => type_tag.to_string().into_bytes(),
+    };
+    HashValue::sha3_256_of(name.as_slice()).to_vec()
+}
+
+/// The key of event index column families.
+/// Keys are ordered by index value, then by the event position in chain,
+/// the `txn_info_id` suffix keeps the entries of different chain branches apart.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EventIndexKey {
+    pub index: Vec<u8>,
+    pub block_number: BlockNumber,
+    pub transaction_global_index: u64,
+    pub event_index: u32,
+    pub txn_info_id: HashValue,
+}
+
+impl EventIndexKey {
+    const POSITION_LENGTH: usize = 8 + 8 + 4 + HashValue::LENGTH;
+
+    pub fn new(
+        index: Vec<u8>,
+        block_number: BlockNumber,
+        transaction_global_index: u64,
+        event_index: u32,
+        txn_info_id: HashValue,
+    ) -> Self {
+        Self {
+            index,
+            block_number,
+            transaction_global_index,
+            event_index,
+            txn_info_id,
+        }
+    }
+
+    /// The smallest encoded key of `index` at block `block_number`.
+    fn lower_bound(index: &[u8], block_number: BlockNumber) -> Vec<u8> {
+        let mut key = index.to_vec();
+        key.extend_from_slice(&block_number.to_be_bytes());
+        key
+    }
+
+    /// The biggest encoded key of `index` at block `block_number`.
+    fn upper_bound(index: &[u8], block_number: BlockNumber) -> Vec<u8> {
+        let mut key = Self::lower_bound(index, block_number);
+        key.extend_from_slice(&[u8::MAX; Self::POSITION_LENGTH - 8]);
+        key
+    }
+}
+
+impl KeyCodec for EventIndexKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = Vec::with_capacity(self.index.len() + Self::POSITION_LENGTH);
+        key.extend_from_slice(self.index.as_slice());
+        key.extend_from_slice(&self.block_number.to_be_bytes());
+        key.extend_from_slice(&self.transaction_global_index.to_be_bytes());
+        key.extend_from_slice(&self.event_index.to_be_bytes());
+        key.extend_from_slice(self.txn_info_id.as_slice());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() > Self::POSITION_LENGTH,
+            "invalid event index key length: {}",
+            data.len()
+        );
+        let (index, mut position) = data.split_at(data.len() - Self::POSITION_LENGTH);
+        let block_number = position.read_u64::<BigEndian>()?;
+        let transaction_global_index = position.read_u64::<BigEndian>()?;
+        let event_index = position.read_u32::<BigEndian>()?;
+        let txn_info_id = HashValue::from_slice(position)?;
+        Ok(Self {
+            index: index.to_vec(),
+            block_number,
+            transaction_global_index,
+            event_index,
+            txn_info_id,
+        })
+    }
+}
+
+pub trait ContractEventIndexStore {
+    /// Save events of `txn_info` and their index entries in one write batch.
+    fn save_contract_events_with_index(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: Vec<ContractEvent>,
+    ) -> Result<()>;
+
+    /// Save the index entries of `events` produced by `txn_info`.
+    fn save_contract_event_index(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+    ) -> Result<()>;
+
+    /// Delete the index entries of `events` produced by `txn_info`,
+    /// used when the block of `txn_info` is retracted from main chain.
+    fn delete_contract_event_index(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+    ) -> Result<()>;
+
+    /// Get the index entries of `index` between block `from_block` and `to_block` (inclusive),
+    /// ordered by event position, or in reverse order if `reverse` is true.
+    /// The entries may come from any chain branch, caller should check them against the main chain.
+    /// Return `None` if the storage instance does not support scanning the index.
+    fn get_contract_event_index(
+        &self,
+        index_type: EventIndexType,
+        index: &[u8],
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        reverse: bool,
+    ) -> Result<Option<Vec<EventIndexKey>>>;
+
+    /// Get the lowest block number from which the event index is complete.
+    /// `None` means the event index is unavailable.
+    fn get_event_index_start(&self) -> Result<Option<BlockNumber>>;
+
+    fn save_event_index_start(&self, block_number: BlockNumber) -> Result<()>;
+}
+
+/// Write and scan the event index column families.
+/// The index entries are written with a cross column family batch, so they are wrapped here
+/// instead of going through the single column family storages.
+#[derive(Clone)]
+pub struct ContractEventIndexStorage {
+    instance: StorageInstance,
+}
+
+impl ContractEventIndexStorage {
+    pub fn new(instance: StorageInstance) -> Self {
+        Self { instance }
+    }
+
+    fn instance(&self) -> &StorageInstance {
+        &self.instance
+    }
+
+    fn index_batch(
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+        delete: bool,
+    ) -> Result<WriteBatchWithColumn> {
+        let mut event_key_batch = CodecWriteBatch::<EventIndexKey, Vec<u8>>::new();
+        let mut address_batch = CodecWriteBatch::<EventIndexKey, Vec<u8>>::new();
+        let mut type_tag_batch = CodecWriteBatch::<EventIndexKey, Vec<u8>>::new();
+        for (event_index, event) in events.iter().enumerate() {
+            let index_key = |index: Vec<u8>| {
+                EventIndexKey::new(
+                    index,
+                    txn_info.block_number,
+                    txn_info.transaction_global_index,
+                    event_index as u32,
+                    txn_info.id(),
+                )
+            };
+            let keys = [
+                (
+                    &mut event_key_batch,
+                    index_key(event_key_index(event.key())),
+                ),
+                (
+                    &mut address_batch,
+                    index_key(address_index(&event.key().get_creator_address())),
+                ),
+                (
+                    &mut type_tag_batch,
+                    index_key(type_tag_index(event.type_tag())),
+                ),
+            ];
+            for (batch, key) in keys {
+                if delete {
+                    batch.delete(key)?;
+                } else {
+                    batch.put(key, vec![])?;
+                }
+            }
+        }
+        let mut batch = WriteBatchWithColumn::new();
+        batch.put_codec_batch(EVENT_KEY_INDEX_PREFIX_NAME, event_key_batch)?;
+        batch.put_codec_batch(EVENT_ADDRESS_INDEX_PREFIX_NAME, address_batch)?;
+        batch.put_codec_batch(EVENT_TYPE_TAG_INDEX_PREFIX_NAME, type_tag_batch)?;
+        Ok(batch)
+    }
+
+    pub(crate) fn save_with_events(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: Vec<ContractEvent>,
+    ) -> Result<()> {
+        let mut batch = Self::index_batch(txn_info, events.as_slice(), false)?;
+        batch.put_codec_batch(
+            CONTRACT_EVENT_PREFIX_NAME,
+            CodecWriteBatch::new_puts(vec![(txn_info.id(), events)]),
+        )?;
+        self.instance().write_batch_with_column(batch)
+    }
+
+    pub(crate) fn save(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+    ) -> Result<()> {
+        let batch = Self::index_batch(txn_info, events, false)?;
+        self.instance().write_batch_with_column(batch)
+    }
+
+    pub(crate) fn delete(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+    ) -> Result<()> {
+        let batch = Self::index_batch(txn_info, events, true)?;
+        self.instance().write_batch_with_column(batch)
+    }
+
+    pub(crate) fn scan(
+        &self,
+        index_type: EventIndexType,
+        index: &[u8],
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        reverse: bool,
+    ) -> Result<Option<Vec<EventIndexKey>>> {
+        let db = match self.instance().db() {
+            Some(db) => db,
+            None => return Ok(None),
+        };
+        let column = index_type.column_family();
+        let mut keys = vec![];
+        if from_block > to_block {
+            return Ok(Some(keys));
+        }
+        let iter = if reverse {
+            let mut iter = db.rev_iter::<EventIndexKey, Vec<u8>>(column)?;
+            iter.seek_for_prev(EventIndexKey::upper_bound(index, to_block))?;
+            iter
+        } else {
+            let mut iter = db.iter::<EventIndexKey, Vec<u8>>(column)?;
+            iter.seek(EventIndexKey::lower_bound(index, from_block))?;
+            iter
+        };
+        for item in iter {
+            let (key, _) = item?;
+            if key.index.as_slice() != index
+                || key.block_number < from_block
+                || key.block_number > to_block
+            {
+                break;
+            }
+            keys.push(key);
+        }
+        Ok(Some(keys))
+    }
+}
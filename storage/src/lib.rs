@@ -1,6 +1,10 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::account_txn_index::{
+    AccountTransactionIndexStorage, AccountTransactionIndexStore, AccountTxnIndexKey,
+    AccountTxnIndexValue,
+};
 use crate::accumulator::{
     AccumulatorStorage, BlockAccumulatorStorage, TransactionAccumulatorStorage,
 };
@@ -8,19 +12,25 @@ use crate::block::BlockStorage;
 use crate::block_info::{BlockInfoStorage, BlockInfoStore};
 use crate::chain_info::ChainInfoStorage;
 use crate::contract_event::ContractEventStorage;
+use crate::event_index::{
+    ContractEventIndexStorage, ContractEventIndexStore, EventIndexKey, EventIndexType,
+};
 use crate::state_node::StateStorage;
+use crate::state_prune::{StatePruneStorage, StatePruneStore};
 use crate::storage::{CodecKVStore, CodecWriteBatch, ColumnFamilyName, StorageInstance};
 use crate::table_info::{TableInfoStorage, TableInfoStore};
 use crate::transaction::TransactionStorage;
 use crate::transaction_info::{TransactionInfoHashStorage, TransactionInfoStorage};
-use anyhow::{bail, format_err, Error, Result};
+use crate::txpool_journal::{TxPoolJournalEntry, TxPoolJournalStorage, TxPoolJournalStore};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use network_p2p_types::peer_id::PeerId;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use once_cell::sync::Lazy;
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::AccumulatorTreeStore;
 use starcoin_crypto::HashValue;
-use starcoin_state_store_api::{StateNode, StateNodeStore};
+use starcoin_state_store_api::{StateNode, StateNodeBatch, StateNodeStore};
+use starcoin_types::block::{BlockIdAndNumber, BlockNumber};
 use starcoin_types::contract_event::ContractEvent;
 use starcoin_types::startup_info::{ChainInfo, ChainStatus, SnapshotRange};
 use starcoin_types::transaction::{RichTransactionInfo, Transaction};
@@ -30,6 +40,7 @@ use starcoin_types::{
 };
 //use starcoin_vm_types::state_store::table::{TableHandle, TableInfo};
 use starcoin_types::account_address::AccountAddress;
+use starcoin_vm_types::impersonation::ImpersonatedAccounts;
 use starcoin_vm_types::state_store::table::{TableHandle, TableInfo};
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Display, Formatter};
@@ -37,6 +48,7 @@ use std::sync::Arc;
 pub use upgrade::BARNARD_HARD_FORK_HASH;
 pub use upgrade::BARNARD_HARD_FORK_HEIGHT;
 
+pub mod account_txn_index;
 pub mod accumulator;
 pub mod batch;
 pub mod block;
@@ -46,14 +58,17 @@ pub mod chain_info;
 pub mod contract_event;
 pub mod db_storage;
 pub mod errors;
+pub mod event_index;
 pub mod metrics;
 pub mod state_node;
+pub mod state_prune;
 pub mod storage;
 pub mod table_info;
 #[cfg(test)]
 mod tests;
 pub mod transaction;
 pub mod transaction_info;
+pub mod txpool_journal;
 mod upgrade;
 
 #[macro_use]
@@ -78,6 +93,14 @@ pub const TRANSACTION_INFO_HASH_PREFIX_NAME: ColumnFamilyName = "transaction_inf
 pub const CONTRACT_EVENT_PREFIX_NAME: ColumnFamilyName = "contract_event";
 pub const FAILED_BLOCK_PREFIX_NAME: ColumnFamilyName = "failed_block";
 pub const TABLE_INFO_PREFIX_NAME: ColumnFamilyName = "table_info";
+pub const EVENT_KEY_INDEX_PREFIX_NAME: ColumnFamilyName = "event_key_index";
+pub const EVENT_ADDRESS_INDEX_PREFIX_NAME: ColumnFamilyName = "event_address_index";
+pub const EVENT_TYPE_TAG_INDEX_PREFIX_NAME: ColumnFamilyName = "event_type_tag_index";
+pub const ACCOUNT_TXN_INDEX_PREFIX_NAME: ColumnFamilyName = "account_txn_index";
+pub const STATE_NODE_REF_COUNT_PREFIX_NAME: ColumnFamilyName = "state_node_ref_count";
+pub const STATE_NODE_JOURNAL_PREFIX_NAME: ColumnFamilyName = "state_node_journal";
+pub const BLOCK_STATE_JOURNAL_PREFIX_NAME: ColumnFamilyName = "block_state_journal";
+pub const TXPOOL_JOURNAL_PREFIX_NAME: ColumnFamilyName = "txpool_journal";
 
 ///db storage use prefix_name vec to init
 /// Please note that adding a prefix needs to be added in vec simultaneously, remember！！
@@ -143,17 +166,48 @@ static VEC_PREFIX_NAME_V3: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
         TABLE_INFO_PREFIX_NAME,
     ]
 });
+
+static VEC_PREFIX_NAME_V4: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
+    vec![
+        BLOCK_ACCUMULATOR_NODE_PREFIX_NAME,
+        TRANSACTION_ACCUMULATOR_NODE_PREFIX_NAME,
+        BLOCK_PREFIX_NAME,
+        BLOCK_HEADER_PREFIX_NAME,
+        BLOCK_BODY_PREFIX_NAME, // unused column
+        BLOCK_INFO_PREFIX_NAME,
+        BLOCK_TRANSACTIONS_PREFIX_NAME,
+        BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+        STATE_NODE_PREFIX_NAME,
+        CHAIN_INFO_PREFIX_NAME,
+        TRANSACTION_PREFIX_NAME,
+        TRANSACTION_INFO_PREFIX_NAME, // unused column
+        TRANSACTION_INFO_PREFIX_NAME_V2,
+        TRANSACTION_INFO_HASH_PREFIX_NAME,
+        CONTRACT_EVENT_PREFIX_NAME,
+        FAILED_BLOCK_PREFIX_NAME,
+        TABLE_INFO_PREFIX_NAME,
+        EVENT_KEY_INDEX_PREFIX_NAME,
+        EVENT_ADDRESS_INDEX_PREFIX_NAME,
+        EVENT_TYPE_TAG_INDEX_PREFIX_NAME,
+        ACCOUNT_TXN_INDEX_PREFIX_NAME,
+        STATE_NODE_REF_COUNT_PREFIX_NAME,
+        STATE_NODE_JOURNAL_PREFIX_NAME,
+        BLOCK_STATE_JOURNAL_PREFIX_NAME,
+        TXPOOL_JOURNAL_PREFIX_NAME,
+    ]
+});
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
 pub enum StorageVersion {
     V1 = 1,
     V2 = 2,
     V3 = 3,
+    V4 = 4,
 }
 
 impl StorageVersion {
     pub fn current_version() -> StorageVersion {
-        StorageVersion::V3
+        StorageVersion::V4
     }
 
     pub fn get_column_family_names(&self) -> &'static [ColumnFamilyName] {
@@ -161,6 +215,7 @@ impl StorageVersion {
             StorageVersion::V1 => &VEC_PREFIX_NAME_V1,
             StorageVersion::V2 => &VEC_PREFIX_NAME_V2,
             StorageVersion::V3 => &VEC_PREFIX_NAME_V3,
+            StorageVersion::V4 => &VEC_PREFIX_NAME_V4,
         }
     }
 }
@@ -224,6 +279,10 @@ pub trait BlockStore {
 
     fn get_snapshot_range(&self) -> Result<Option<SnapshotRange>>;
     fn save_snapshot_range(&self, snapshot_height: SnapshotRange) -> Result<()>;
+
+    /// The block the chain forked from a remote node at, the history before it is not in local storage.
+    fn get_fork_block(&self) -> Result<Option<BlockIdAndNumber>>;
+    fn save_fork_block(&self, fork_block: BlockIdAndNumber) -> Result<()>;
 }
 
 pub trait BlockTransactionInfoStore {
@@ -263,6 +322,12 @@ pub trait TransactionStore {
     fn get_transactions(&self, txn_hash_vec: Vec<HashValue>) -> Result<Vec<Option<Transaction>>>;
 }
 
+/// The source of the state missing in local storage, such as the remote node a local node forked from.
+pub trait RemoteStateStore: Send + Sync {
+    fn get_state_node(&self, hash: &HashValue) -> Result<Option<StateNode>>;
+    fn get_table_info(&self, address: AccountAddress) -> Result<Option<TableInfo>>;
+}
+
 // TODO: remove Arc<dyn Store>, we can clone Storage directly.
 #[derive(Clone)]
 pub struct Storage {
@@ -271,12 +336,19 @@ pub struct Storage {
     transaction_storage: TransactionStorage,
     block_storage: BlockStorage,
     state_node_storage: StateStorage,
+    state_prune_storage: StatePruneStorage,
     block_accumulator_storage: AccumulatorStorage<BlockAccumulatorStorage>,
     transaction_accumulator_storage: AccumulatorStorage<TransactionAccumulatorStorage>,
     block_info_storage: BlockInfoStorage,
     event_storage: ContractEventStorage,
+    event_index_storage: ContractEventIndexStorage,
+    account_txn_index_storage: AccountTransactionIndexStorage,
+    account_txn_index_enabled: bool,
     chain_info_storage: ChainInfoStorage,
     table_info_storage: TableInfoStorage,
+    txpool_journal_storage: TxPoolJournalStorage,
+    remote_state_store: Option<Arc<dyn RemoteStateStore>>,
+    impersonated_accounts: Option<ImpersonatedAccounts>,
     // instance: StorageInstance,
 }
 
@@ -288,6 +360,7 @@ impl Storage {
             transaction_storage: TransactionStorage::new(instance.clone()),
             block_storage: BlockStorage::new(instance.clone()),
             state_node_storage: StateStorage::new(instance.clone()),
+            state_prune_storage: StatePruneStorage::new(instance.clone()),
             block_accumulator_storage: AccumulatorStorage::new_block_accumulator_storage(
                 instance.clone(),
             ),
@@ -295,13 +368,67 @@ impl Storage {
                 AccumulatorStorage::new_transaction_accumulator_storage(instance.clone()),
             block_info_storage: BlockInfoStorage::new(instance.clone()),
             event_storage: ContractEventStorage::new(instance.clone()),
+            event_index_storage: ContractEventIndexStorage::new(instance.clone()),
+            account_txn_index_storage: AccountTransactionIndexStorage::new(instance.clone()),
+            account_txn_index_enabled: false,
             chain_info_storage: ChainInfoStorage::new(instance.clone()),
-            table_info_storage: TableInfoStorage::new(instance),
+            table_info_storage: TableInfoStorage::new(instance.clone()),
+            txpool_journal_storage: TxPoolJournalStorage::new(instance),
+            remote_state_store: None,
+            impersonated_accounts: None,
             // instance,
         };
         Ok(storage)
     }
 
+    /// Maintain the account transaction index when blocks are connected.
+    pub fn with_account_txn_index(mut self, enabled: bool) -> Self {
+        self.account_txn_index_enabled = enabled;
+        self
+    }
+
+    /// Record the state node references for state pruning.
+    /// Pruning can only be enabled on a new database, because the nodes written before have
+    /// no reference count, and a pruned database can't be switched back to archive mode.
+    pub fn with_state_pruning(mut self, enabled: bool) -> Result<Self> {
+        let pruned_block = self.chain_info_storage.get_state_pruned_block()?;
+        if enabled {
+            if pruned_block.is_none() {
+                ensure!(
+                    self.chain_info_storage.get_startup_info()?.is_none(),
+                    "State pruning can only be enabled on a new database, the existing database is in archive mode."
+                );
+                self.chain_info_storage.save_state_pruned_block(0)?;
+            }
+            self.state_prune_storage.enable()?;
+        } else {
+            ensure!(
+                pruned_block.is_none(),
+                "The database is in pruned mode, it can't be opened in archive mode."
+            );
+        }
+        Ok(self)
+    }
+
+    /// Fetch the state nodes and table infos missing in local storage from the remote store,
+    /// and cache them locally.
+    pub fn with_remote_state_store(
+        mut self,
+        remote_state_store: Option<Arc<dyn RemoteStateStore>>,
+    ) -> Self {
+        self.remote_state_store = remote_state_store;
+        self
+    }
+
+    /// The accounts impersonated by the node, only for dev and test networks.
+    pub fn with_impersonated_accounts(
+        mut self,
+        impersonated_accounts: Option<ImpersonatedAccounts>,
+    ) -> Self {
+        self.impersonated_accounts = impersonated_accounts;
+        self
+    }
+
     pub fn get_block_accumulator_storage(&self) -> AccumulatorStorage<BlockAccumulatorStorage> {
         self.block_accumulator_storage.clone()
     }
@@ -315,21 +442,76 @@ impl Storage {
 
 impl StateNodeStore for Storage {
     fn get(&self, hash: &HashValue) -> Result<Option<StateNode>> {
-        self.state_node_storage.get(*hash)
+        match (
+            self.state_node_storage.get(*hash)?,
+            self.remote_state_store.as_ref(),
+        ) {
+            (None, Some(remote)) => {
+                let node = remote.get_state_node(hash)?;
+                if let Some(node) = node.as_ref() {
+                    // never cache a node which does not match the hash.
+                    let node_hash = node.node_hash()?;
+                    ensure!(
+                        node_hash == *hash,
+                        "Remote state node {} mismatch its hash {}",
+                        hash,
+                        node_hash
+                    );
+                    self.put(*hash, node.clone())?;
+                }
+                Ok(node)
+            }
+            (node, _) => Ok(node),
+        }
     }
 
     fn put(&self, key: HashValue, node: StateNode) -> Result<()> {
-        self.state_node_storage.put(key, node)
+        if self.state_prune_storage.enabled() {
+            let mut nodes = BTreeMap::new();
+            nodes.insert(key, node);
+            self.state_prune_storage.write_nodes(nodes)
+        } else {
+            self.state_node_storage.put(key, node)
+        }
     }
 
     fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<()> {
-        let batch = CodecWriteBatch::new_puts(nodes.into_iter().collect());
-        self.state_node_storage.write_batch(batch)
+        if self.state_prune_storage.enabled() {
+            self.state_prune_storage.write_nodes(nodes)
+        } else {
+            let batch = CodecWriteBatch::new_puts(nodes.into_iter().collect());
+            self.state_node_storage.write_batch(batch)
+        }
+    }
+
+    fn write_node_batch(&self, batch: StateNodeBatch) -> Result<Option<u64>> {
+        if self.state_prune_storage.enabled() {
+            self.state_prune_storage.write_node_batch(batch).map(Some)
+        } else {
+            self.write_nodes(batch.nodes)?;
+            Ok(None)
+        }
     }
 
     fn get_table_info(&self, address: AccountAddress) -> Result<Option<TableInfo>> {
         let handle = TableHandle(address);
-        self.table_info_storage.get(handle)
+        match (
+            self.table_info_storage.get(handle)?,
+            self.remote_state_store.as_ref(),
+        ) {
+            (None, Some(remote)) => {
+                let table_info = remote.get_table_info(address)?;
+                if let Some(table_info) = table_info.as_ref() {
+                    self.table_info_storage.put(handle, table_info.clone())?;
+                }
+                Ok(table_info)
+            }
+            (table_info, _) => Ok(table_info),
+        }
+    }
+
+    fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts> {
+        self.impersonated_accounts.clone()
     }
 }
 
@@ -468,6 +650,14 @@ impl BlockStore for Storage {
     fn save_snapshot_range(&self, snapshot_range: SnapshotRange) -> Result<()> {
         self.chain_info_storage.save_snapshot_range(snapshot_range)
     }
+
+    fn get_fork_block(&self) -> Result<Option<BlockIdAndNumber>> {
+        self.chain_info_storage.get_fork_block()
+    }
+
+    fn save_fork_block(&self, fork_block: BlockIdAndNumber) -> Result<()> {
+        self.chain_info_storage.save_fork_block(fork_block)
+    }
 }
 
 impl BlockInfoStore for Storage {
@@ -550,6 +740,128 @@ impl ContractEventStore for Storage {
     }
 }
 
+impl ContractEventIndexStore for Storage {
+    fn save_contract_events_with_index(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: Vec<ContractEvent>,
+    ) -> Result<()> {
+        self.event_index_storage.save_with_events(txn_info, events)
+    }
+
+    fn save_contract_event_index(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+    ) -> Result<()> {
+        self.event_index_storage.save(txn_info, events)
+    }
+
+    fn delete_contract_event_index(
+        &self,
+        txn_info: &RichTransactionInfo,
+        events: &[ContractEvent],
+    ) -> Result<()> {
+        self.event_index_storage.delete(txn_info, events)
+    }
+
+    fn get_contract_event_index(
+        &self,
+        index_type: EventIndexType,
+        index: &[u8],
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        reverse: bool,
+    ) -> Result<Option<Vec<EventIndexKey>>> {
+        self.event_index_storage
+            .scan(index_type, index, from_block, to_block, reverse)
+    }
+
+    fn get_event_index_start(&self) -> Result<Option<BlockNumber>> {
+        self.chain_info_storage.get_event_index_start()
+    }
+
+    fn save_event_index_start(&self, block_number: BlockNumber) -> Result<()> {
+        self.chain_info_storage.save_event_index_start(block_number)
+    }
+}
+
+impl AccountTransactionIndexStore for Storage {
+    fn account_txn_index_enabled(&self) -> bool {
+        self.account_txn_index_enabled
+    }
+
+    fn save_account_txn_index(&self, block: &Block) -> Result<()> {
+        if !self.account_txn_index_enabled {
+            return Ok(());
+        }
+        self.account_txn_index_storage.save(block)
+    }
+
+    fn delete_account_txn_index(&self, block: &Block) -> Result<()> {
+        if !self.account_txn_index_enabled {
+            return Ok(());
+        }
+        self.account_txn_index_storage.delete(block)
+    }
+
+    fn get_account_txn_index(
+        &self,
+        sender: AccountAddress,
+        start_seq: Option<u64>,
+        reverse: bool,
+        max_size: u64,
+    ) -> Result<Vec<(AccountTxnIndexKey, AccountTxnIndexValue)>> {
+        if !self.account_txn_index_enabled {
+            bail!("Account transaction index is disabled, enable it by storage config `enable-account-txn-index`");
+        }
+        self.account_txn_index_storage
+            .scan(sender, start_seq, reverse, max_size)
+    }
+}
+
+impl StatePruneStore for Storage {
+    fn state_pruning_enabled(&self) -> bool {
+        self.state_prune_storage.enabled()
+    }
+
+    fn save_block_state_journal(
+        &self,
+        number: BlockNumber,
+        block_id: HashValue,
+        seqs: Vec<u64>,
+    ) -> Result<()> {
+        if !self.state_prune_storage.enabled() {
+            return Ok(());
+        }
+        self.state_prune_storage
+            .save_block_journal(number, block_id, seqs)
+    }
+
+    fn prune_block_state(&self, number: BlockNumber, main_block_id: HashValue) -> Result<usize> {
+        self.state_prune_storage.prune_block(number, main_block_id)
+    }
+
+    fn get_state_pruned_block(&self) -> Result<Option<BlockNumber>> {
+        self.chain_info_storage.get_state_pruned_block()
+    }
+
+    fn save_state_pruned_block(&self, block_number: BlockNumber) -> Result<()> {
+        self.chain_info_storage
+            .save_state_pruned_block(block_number)
+    }
+}
+
+impl TxPoolJournalStore for Storage {
+    fn get_txpool_journal(&self) -> Result<Vec<TxPoolJournalEntry>> {
+        self.txpool_journal_storage.get_journal()
+    }
+
+    fn save_txpool_journal(&self, entries: Vec<TxPoolJournalEntry>) -> Result<()> {
+        self.txpool_journal_storage.save_journal(entries)
+    }
+}
+
 impl TransactionStore for Storage {
     fn get_transaction(&self, txn_hash: HashValue) -> Result<Option<Transaction>, Error> {
         self.transaction_storage.get(txn_hash)
@@ -579,6 +891,10 @@ pub trait Store:
     + TransactionStore
     + BlockTransactionInfoStore
     + ContractEventStore
+    + ContractEventIndexStore
+    + AccountTransactionIndexStore
+    + StatePruneStore
+    + TxPoolJournalStore
     + IntoSuper<dyn StateNodeStore>
     + TableInfoStore
 {
@@ -0,0 +1,353 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::batch::WriteBatchWithColumn;
+use crate::define_storage;
+use crate::storage::{
+    CodecKVStore, CodecWriteBatch, InnerStore, KeyCodec, StorageInstance, ValueCodec,
+};
+use crate::{
+    BLOCK_STATE_JOURNAL_PREFIX_NAME, STATE_NODE_JOURNAL_PREFIX_NAME, STATE_NODE_PREFIX_NAME,
+    STATE_NODE_REF_COUNT_PREFIX_NAME,
+};
+use anyhow::{ensure, format_err, Result};
+use bcs_ext::BCSCodec;
+use byteorder::{BigEndian, ReadBytesExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_state_store_api::{StateNode, StateNodeBatch};
+use starcoin_types::block::BlockNumber;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// The reference count of the nodes written without journal, they are never pruned.
+const PINNED_REF_COUNT: u64 = u64::MAX;
+
+define_storage!(
+    StateNodeRefCountStorage,
+    HashValue,
+    u64,
+    STATE_NODE_REF_COUNT_PREFIX_NAME
+);
+
+define_storage!(
+    StateNodeJournalStorage,
+    u64,
+    StateNodeJournal,
+    STATE_NODE_JOURNAL_PREFIX_NAME
+);
+
+define_storage!(
+    BlockStateJournalStorage,
+    BlockStateJournalKey,
+    BlockStateJournal,
+    BLOCK_STATE_JOURNAL_PREFIX_NAME
+);
+
+/// The nodes inserted and made stale by one state node batch,
+/// a node appears once for each time it is inserted or made stale.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateNodeJournal {
+    pub inserted: Vec<HashValue>,
+    pub stale: Vec<HashValue>,
+}
+
+impl ValueCodec for StateNodeJournal {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+/// Keys are ordered by block number, so the journals of all blocks at a height are adjacent.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BlockStateJournalKey {
+    pub number: BlockNumber,
+    pub block_id: HashValue,
+}
+
+impl BlockStateJournalKey {
+    const LENGTH: usize = 8 + HashValue::LENGTH;
+
+    pub fn new(number: BlockNumber, block_id: HashValue) -> Self {
+        Self { number, block_id }
+    }
+}
+
+impl KeyCodec for BlockStateJournalKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = Vec::with_capacity(Self::LENGTH);
+        key.extend_from_slice(&self.number.to_be_bytes());
+        key.extend_from_slice(self.block_id.as_slice());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == Self::LENGTH,
+            "invalid block state journal key length: {}",
+            data.len()
+        );
+        let (mut number, block_id) = data.split_at(8);
+        Ok(Self {
+            number: number.read_u64::<BigEndian>()?,
+            block_id: HashValue::from_slice(block_id)?,
+        })
+    }
+}
+
+/// The sequences of the state node batches written when executing a block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockStateJournal {
+    pub seqs: Vec<u64>,
+}
+
+impl ValueCodec for BlockStateJournal {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+pub trait StatePruneStore {
+    fn state_pruning_enabled(&self) -> bool;
+
+    /// Bind the state node batches `seqs` returned by the state flush to the executed block,
+    /// do nothing if pruning is disabled.
+    fn save_block_state_journal(
+        &self,
+        number: BlockNumber,
+        block_id: HashValue,
+        seqs: Vec<u64>,
+    ) -> Result<()>;
+
+    /// Prune the state journals of the blocks at `number`.
+    /// The stale nodes of the main chain block are released, so the state of its parent is lost,
+    /// and the nodes inserted by the other blocks at the same height are released.
+    /// Return the number of deleted nodes.
+    fn prune_block_state(&self, number: BlockNumber, main_block_id: HashValue) -> Result<usize>;
+
+    /// The state of the blocks before this block number has been pruned.
+    /// `None` means the database keeps all the history state.
+    fn get_state_pruned_block(&self) -> Result<Option<BlockNumber>>;
+
+    fn save_state_pruned_block(&self, block_number: BlockNumber) -> Result<()>;
+}
+
+/// Reference counting of state nodes.
+/// A state node is keyed by its hash, so the same node may be shared by different trees,
+/// or created again after it becomes stale. A node is only deleted after
+/// all of its references are released.
+#[derive(Clone)]
+pub struct StatePruneStorage {
+    instance: StorageInstance,
+    ref_count_storage: StateNodeRefCountStorage,
+    journal_storage: StateNodeJournalStorage,
+    block_journal_storage: BlockStateJournalStorage,
+    enabled: bool,
+    next_seq: Arc<AtomicU64>,
+    // serialize the reference count updates of the writer and the pruner.
+    lock: Arc<Mutex<()>>,
+}
+
+impl StatePruneStorage {
+    pub fn new(instance: StorageInstance) -> Self {
+        Self {
+            ref_count_storage: StateNodeRefCountStorage::new(instance.clone()),
+            journal_storage: StateNodeJournalStorage::new(instance.clone()),
+            block_journal_storage: BlockStateJournalStorage::new(instance.clone()),
+            instance,
+            enabled: false,
+            next_seq: Arc::new(AtomicU64::new(0)),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Start recording the node references and journals,
+    /// the sequence continues from the last journal.
+    pub(crate) fn enable(&mut self) -> Result<()> {
+        let db = self
+            .instance
+            .db()
+            .ok_or_else(|| format_err!("State pruning only support db storage instance"))?;
+        let mut iter = db.rev_iter::<u64, StateNodeJournal>(STATE_NODE_JOURNAL_PREFIX_NAME)?;
+        iter.seek_to_last();
+        let next_seq = match iter.next() {
+            Some(item) => item?.0 + 1,
+            None => 0,
+        };
+        self.next_seq = Arc::new(AtomicU64::new(next_seq));
+        self.enabled = true;
+        Ok(())
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Write the nodes which are not produced by a block execution, such as the nodes of a
+    /// synced or imported state. There is no journal to release their references, and a node may
+    /// be shared by several parents of the written trees, so the nodes are pinned and never pruned.
+    pub(crate) fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<()> {
+        let _guard = self.lock.lock();
+        let ref_counts = nodes
+            .keys()
+            .map(|key| (*key, PINNED_REF_COUNT))
+            .collect::<Vec<_>>();
+
+        let mut write_batch = WriteBatchWithColumn::new();
+        write_batch.put_codec_batch(
+            STATE_NODE_PREFIX_NAME,
+            CodecWriteBatch::<HashValue, StateNode>::new_puts(nodes.into_iter().collect()),
+        )?;
+        write_batch.put_codec_batch(
+            STATE_NODE_REF_COUNT_PREFIX_NAME,
+            CodecWriteBatch::<HashValue, u64>::new_puts(ref_counts),
+        )?;
+        self.instance.write_batch_with_column(write_batch)
+    }
+
+    /// Write the batch with its journal, return the sequence of the journal.
+    pub(crate) fn write_node_batch(&self, batch: StateNodeBatch) -> Result<u64> {
+        let _guard = self.lock.lock();
+        let keys = batch.node_refs.keys().cloned().collect::<Vec<_>>();
+        let ref_counts = self.ref_count_storage.multiple_get(keys.clone())?;
+        let mut inserted = vec![];
+        let ref_counts = keys
+            .into_iter()
+            .zip(ref_counts)
+            .map(|(key, count)| {
+                let refs = batch.node_refs[&key];
+                inserted.extend(std::iter::repeat(key).take(refs as usize));
+                match count {
+                    Some(PINNED_REF_COUNT) => (key, PINNED_REF_COUNT),
+                    count => (key, count.unwrap_or(0) + refs),
+                }
+            })
+            .collect::<Vec<_>>();
+        let journal = StateNodeJournal {
+            inserted,
+            stale: batch.stale_nodes,
+        };
+        let seq = self.next_seq.fetch_add(1, Ordering::SeqCst);
+
+        let mut write_batch = WriteBatchWithColumn::new();
+        write_batch.put_codec_batch(
+            STATE_NODE_PREFIX_NAME,
+            CodecWriteBatch::<HashValue, StateNode>::new_puts(batch.nodes.into_iter().collect()),
+        )?;
+        write_batch.put_codec_batch(
+            STATE_NODE_REF_COUNT_PREFIX_NAME,
+            CodecWriteBatch::<HashValue, u64>::new_puts(ref_counts),
+        )?;
+        write_batch.put_codec_batch(
+            STATE_NODE_JOURNAL_PREFIX_NAME,
+            CodecWriteBatch::<u64, StateNodeJournal>::new_puts(vec![(seq, journal)]),
+        )?;
+        self.instance.write_batch_with_column(write_batch)?;
+        Ok(seq)
+    }
+
+    pub(crate) fn save_block_journal(
+        &self,
+        number: BlockNumber,
+        block_id: HashValue,
+        seqs: Vec<u64>,
+    ) -> Result<()> {
+        if seqs.is_empty() {
+            return Ok(());
+        }
+        self.block_journal_storage.put(
+            BlockStateJournalKey::new(number, block_id),
+            BlockStateJournal { seqs },
+        )
+    }
+
+    pub(crate) fn prune_block(
+        &self,
+        number: BlockNumber,
+        main_block_id: HashValue,
+    ) -> Result<usize> {
+        let db = self
+            .instance
+            .db()
+            .ok_or_else(|| format_err!("State pruning only support db storage instance"))?;
+        let mut block_journals = vec![];
+        let mut iter =
+            db.iter::<BlockStateJournalKey, BlockStateJournal>(BLOCK_STATE_JOURNAL_PREFIX_NAME)?;
+        iter.seek(BlockStateJournalKey::new(number, HashValue::zero()).encode_key()?)?;
+        for item in iter {
+            let (key, journal) = item?;
+            if key.number != number {
+                break;
+            }
+            block_journals.push((key, journal));
+        }
+        if block_journals.is_empty() {
+            return Ok(0);
+        }
+
+        let mut released = BTreeMap::new();
+        let mut journal_keys = vec![];
+        for (key, block_journal) in block_journals.iter() {
+            let seqs = block_journal.seqs.clone();
+            let journals = self.journal_storage.multiple_get(seqs.clone())?;
+            for journal in journals.into_iter().flatten() {
+                let nodes = if key.block_id == main_block_id {
+                    journal.stale
+                } else {
+                    journal.inserted
+                };
+                for node_key in nodes {
+                    *released.entry(node_key).or_insert(0u64) += 1;
+                }
+            }
+            journal_keys.extend(seqs);
+        }
+
+        let _guard = self.lock.lock();
+        let keys = released.keys().cloned().collect::<Vec<_>>();
+        let ref_counts = self.ref_count_storage.multiple_get(keys.clone())?;
+        let mut ref_count_batch = CodecWriteBatch::<HashValue, u64>::new();
+        let mut node_batch = CodecWriteBatch::<HashValue, StateNode>::new();
+        let mut deleted = 0;
+        for (key, ref_count) in keys.into_iter().zip(ref_counts) {
+            // the nodes without reference count or pinned are never deleted.
+            let ref_count = match ref_count {
+                Some(PINNED_REF_COUNT) | None => continue,
+                Some(ref_count) => ref_count.saturating_sub(released[&key]),
+            };
+            if ref_count == 0 {
+                ref_count_batch.delete(key)?;
+                node_batch.delete(key)?;
+                deleted += 1;
+            } else {
+                ref_count_batch.put(key, ref_count)?;
+            }
+        }
+
+        let mut write_batch = WriteBatchWithColumn::new();
+        write_batch.put_codec_batch(STATE_NODE_PREFIX_NAME, node_batch)?;
+        write_batch.put_codec_batch(STATE_NODE_REF_COUNT_PREFIX_NAME, ref_count_batch)?;
+        write_batch.put_codec_batch(
+            STATE_NODE_JOURNAL_PREFIX_NAME,
+            CodecWriteBatch::<u64, StateNodeJournal>::new_deletes(journal_keys),
+        )?;
+        write_batch.put_codec_batch(
+            BLOCK_STATE_JOURNAL_PREFIX_NAME,
+            CodecWriteBatch::<BlockStateJournalKey, BlockStateJournal>::new_deletes(
+                block_journals.into_iter().map(|(key, _)| key).collect(),
+            ),
+        )?;
+        self.instance.write_batch_with_column(write_batch)?;
+        Ok(deleted)
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-pub use crate::batch::WriteBatch;
+pub use crate::batch::{WriteBatch, WriteBatchWithColumn};
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::{DBStorage, SchemaIterator};
 use crate::upgrade::DBUpgrade;
@@ -44,6 +44,7 @@ pub trait InnerStore: Send + Sync {
     fn put_sync(&self, prefix_name: &str, key: Vec<u8>, value: Vec<u8>) -> Result<()>;
     fn write_batch_sync(&self, prefix_name: &str, batch: WriteBatch) -> Result<()>;
     fn multi_get(&self, prefix_name: &str, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>>;
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()>;
 }
 
 ///Storage instance type define
@@ -282,6 +283,19 @@ impl InnerStore for StorageInstance {
             }
         }
     }
+
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.write_batch_with_column(batch),
+            StorageInstance::DB { db } => db.write_batch_with_column(batch),
+            StorageInstance::CacheAndDb { cache, db } => {
+                match db.write_batch_with_column(batch.clone()) {
+                    Ok(_) => cache.write_batch_with_column(batch),
+                    Err(err) => bail!("write batch with column db error: {}", err),
+                }
+            }
+        }
+    }
 }
 
 pub trait ColumnFamily: Send + Sync {
@@ -520,6 +534,17 @@ impl KeyCodec for u64 {
     }
 }
 
+impl ValueCodec for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    #[allow(clippy::redundant_slicing)]
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
 impl KeyCodec for HashValue {
     fn encode_key(&self) -> Result<Vec<u8>> {
         Ok(self.to_vec())
@@ -3,31 +3,49 @@
 
 extern crate chrono;
 
+use crate::account_txn_index::AccountTransactionIndexStore;
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
+use crate::event_index::{
+    address_index, event_key_index, type_tag_index, ContractEventIndexStore, EventIndexType,
+};
+use crate::state_prune::StatePruneStore;
 use crate::storage::{CodecKVStore, InnerStore, StorageInstance, ValueCodec};
 use crate::table_info::TableInfoStore;
 use crate::transaction_info::{BlockTransactionInfo, OldTransactionInfoStorage};
+use crate::txpool_journal::{TxPoolJournalEntry, TxPoolJournalStore};
 use crate::{
-    BlockInfoStore, BlockStore, BlockTransactionInfoStore, Storage,
-    StorageVersion, /*TableInfoStore,*/
+    BlockInfoStore, BlockStore, BlockTransactionInfoStore, ContractEventStore, RemoteStateStore,
+    Storage, StorageVersion, /*TableInfoStore,*/
     TransactionStore, DEFAULT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME,
     TRANSACTION_INFO_PREFIX_NAME_V2,
 };
 use anyhow::Result;
+use forkable_jellyfish_merkle::blob::Blob;
+use forkable_jellyfish_merkle::node_type::Node;
 use starcoin_accumulator::accumulator_info::AccumulatorInfo;
 use starcoin_config::RocksdbConfig;
 use starcoin_crypto::HashValue;
+use starcoin_state_store_api::{StateNode, StateNodeBatch, StateNodeStore};
 use starcoin_types::{
     account_address::AccountAddress,
-    block::{Block, BlockBody, BlockHeader, BlockInfo},
-    language_storage::TypeTag,
+    block::{Block, BlockBody, BlockHeader, BlockHeaderBuilder, BlockInfo},
+    contract_event::ContractEvent,
+    event::EventKey,
+    genesis_config::ChainId,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
     startup_info::SnapshotRange,
-    transaction::{RichTransactionInfo, SignedUserTransaction, Transaction, TransactionInfo},
+    transaction::{
+        RawUserTransaction, RichTransactionInfo, Script, SignedUserTransaction, Transaction,
+        TransactionInfo, TransactionPayload,
+    },
     vm_error::KeptVMStatus,
 };
 use starcoin_vm_types::state_store::table::{TableHandle, TableInfo};
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::sync::Arc;
 
 #[test]
 fn test_reopen() {
@@ -518,3 +536,334 @@ fn test_table_info_storage() -> Result<()> {
     assert_eq!(vals, vals2);
     Ok(())
 }
+
+#[test]
+fn test_event_index_storage() -> Result<()> {
+    let tmpdir = starcoin_config::temp_dir();
+    let instance = StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None)?,
+    );
+    let storage = Storage::new(instance)?;
+    let address = AccountAddress::random();
+    let event_key = EventKey::new_from_address(&address, 0);
+    let type_tag = TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: Identifier::new("Account")?,
+        name: Identifier::new("DepositEvent")?,
+        type_params: vec![TypeTag::U64],
+    }));
+    let other_event = ContractEvent::new(
+        EventKey::new_from_address(&AccountAddress::random(), 0),
+        0,
+        TypeTag::U8,
+        vec![],
+    );
+
+    let mut txn_infos = vec![];
+    for block_number in 1..=3u64 {
+        let txn_info = RichTransactionInfo::new(
+            HashValue::random(),
+            block_number,
+            TransactionInfo::new(
+                HashValue::random(),
+                HashValue::zero(),
+                vec![].as_slice(),
+                0,
+                KeptVMStatus::Executed,
+            ),
+            1,
+            block_number * 2,
+        );
+        let events = vec![
+            other_event.clone(),
+            ContractEvent::new(event_key, block_number, type_tag.clone(), vec![]),
+        ];
+        storage.save_contract_events_with_index(&txn_info, events.clone())?;
+        assert_eq!(storage.get_contract_events(txn_info.id())?, Some(events));
+        txn_infos.push(txn_info);
+    }
+
+    let keys = storage
+        .get_contract_event_index(
+            EventIndexType::EventKey,
+            event_key_index(&event_key).as_slice(),
+            1,
+            2,
+            false,
+        )?
+        .unwrap();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0].block_number, 1);
+    assert_eq!(keys[0].event_index, 1);
+    assert_eq!(keys[0].txn_info_id, txn_infos[0].id());
+    assert_eq!(keys[1].block_number, 2);
+
+    let keys = storage
+        .get_contract_event_index(
+            EventIndexType::Address,
+            address_index(&address).as_slice(),
+            0,
+            10,
+            true,
+        )?
+        .unwrap();
+    assert_eq!(
+        keys.iter().map(|key| key.block_number).collect::<Vec<_>>(),
+        vec![3, 2, 1]
+    );
+
+    // type params are ignored by the type tag index.
+    let filter_type_tag = TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: Identifier::new("Account")?,
+        name: Identifier::new("DepositEvent")?,
+        type_params: vec![],
+    }));
+    let keys = storage
+        .get_contract_event_index(
+            EventIndexType::TypeTag,
+            type_tag_index(&filter_type_tag).as_slice(),
+            2,
+            3,
+            true,
+        )?
+        .unwrap();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0].block_number, 3);
+
+    storage.delete_contract_event_index(
+        &txn_infos[1],
+        storage
+            .get_contract_events(txn_infos[1].id())?
+            .unwrap()
+            .as_slice(),
+    )?;
+    let keys = storage
+        .get_contract_event_index(
+            EventIndexType::EventKey,
+            event_key_index(&event_key).as_slice(),
+            0,
+            10,
+            false,
+        )?
+        .unwrap();
+    assert_eq!(
+        keys.iter().map(|key| key.block_number).collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_account_txn_index_storage() -> Result<()> {
+    let tmpdir = starcoin_config::temp_dir();
+    let instance = StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None)?,
+    );
+    let storage = Storage::new(instance.clone())?;
+    assert!(storage
+        .get_account_txn_index(AccountAddress::random(), None, false, 10)
+        .is_err());
+    let storage = Storage::new(instance)?.with_account_txn_index(true);
+
+    let sender = AccountAddress::random();
+    let authenticator = SignedUserTransaction::mock().authenticator();
+    let new_txn = |sender: AccountAddress, sequence_number: u64| {
+        SignedUserTransaction::new(
+            RawUserTransaction::new_with_default_gas_token(
+                sender,
+                sequence_number,
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                0,
+                0,
+                u64::MAX,
+                ChainId::test(),
+            ),
+            authenticator.clone(),
+        )
+    };
+    let new_block = |number: u64, txns: Vec<SignedUserTransaction>| {
+        Block::new(
+            BlockHeaderBuilder::random().with_number(number).build(),
+            BlockBody::new(txns, None),
+        )
+    };
+    let blocks = vec![
+        new_block(1, vec![new_txn(sender, 0), new_txn(sender, 1)]),
+        new_block(2, vec![new_txn(AccountAddress::random(), 0)]),
+        new_block(3, vec![new_txn(sender, 2)]),
+    ];
+    // a fork block contains the same sequence number with block 3.
+    let fork_block = new_block(3, vec![new_txn(sender, 2)]);
+    for block in blocks.iter().chain(std::iter::once(&fork_block)) {
+        storage.save_account_txn_index(block)?;
+    }
+
+    let entries = storage.get_account_txn_index(sender, None, false, 10)?;
+    assert_eq!(
+        entries
+            .iter()
+            .map(|(key, _)| key.sequence_number)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 2, 2]
+    );
+    assert_eq!(
+        entries[0].1.transaction_hash,
+        blocks[0].transactions()[0].id()
+    );
+    assert_eq!(entries[0].1.block_number, 1);
+
+    // the entries of one sequence number are never split.
+    let entries = storage.get_account_txn_index(sender, None, true, 1)?;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|(key, _)| key.sequence_number == 2));
+
+    let entries = storage.get_account_txn_index(sender, Some(1), true, 10)?;
+    assert_eq!(
+        entries
+            .iter()
+            .map(|(key, _)| key.sequence_number)
+            .collect::<Vec<_>>(),
+        vec![1, 0]
+    );
+
+    storage.delete_account_txn_index(&fork_block)?;
+    let entries = storage.get_account_txn_index(sender, Some(2), false, 10)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].0.block_id, blocks[2].id());
+    Ok(())
+}
+
+#[test]
+fn test_state_prune() -> Result<()> {
+    let tmpdir = starcoin_config::temp_dir();
+    let instance = StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None)?,
+    );
+    let storage = Storage::new(instance.clone())?.with_state_pruning(true)?;
+    assert_eq!(storage.get_state_pruned_block()?, Some(0));
+
+    let node = |n: u8| (HashValue::random(), StateNode(vec![n]));
+    let (a, b, c, d) = (node(1), node(2), node(3), node(4));
+    let write_block = |number: u64,
+                       block_id: HashValue,
+                       nodes: Vec<(HashValue, StateNode)>,
+                       stale_nodes: Vec<HashValue>|
+     -> Result<()> {
+        let seq = storage.write_node_batch(StateNodeBatch {
+            node_refs: nodes.iter().map(|(key, _)| (*key, 1)).collect(),
+            nodes: nodes.into_iter().collect(),
+            stale_nodes,
+        })?;
+        storage.save_block_state_journal(number, block_id, seq.into_iter().collect())
+    };
+    let (block1, block2, fork_block2) = (
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+    );
+    write_block(1, block1, vec![a.clone(), b.clone()], vec![])?;
+    write_block(2, block2, vec![c.clone()], vec![b.0])?;
+    // the fork block replaces `a`, which is still referenced by the main chain.
+    write_block(2, fork_block2, vec![d.clone()], vec![a.0])?;
+
+    assert_eq!(storage.prune_block_state(1, block1)?, 0);
+    // `b` is stale on main chain, and `d` is only referenced by the fork block.
+    assert_eq!(storage.prune_block_state(2, block2)?, 2);
+    assert_eq!(StateNodeStore::get(&storage, &a.0)?, Some(a.1));
+    assert_eq!(StateNodeStore::get(&storage, &b.0)?, None);
+    assert_eq!(StateNodeStore::get(&storage, &c.0)?, Some(c.1));
+    assert_eq!(StateNodeStore::get(&storage, &d.0)?, None);
+    // the journals are removed after pruning.
+    assert_eq!(storage.prune_block_state(2, block2)?, 0);
+
+    // the synced or imported nodes are pinned, so a block creating the same node
+    // again and a later block making it stale does not delete it.
+    let e = node(5);
+    StateNodeStore::write_nodes(&storage, vec![e.clone()].into_iter().collect())?;
+    let (block3, block4) = (HashValue::random(), HashValue::random());
+    write_block(3, block3, vec![e.clone()], vec![])?;
+    write_block(4, block4, vec![], vec![e.0])?;
+    assert_eq!(storage.prune_block_state(3, block3)?, 0);
+    assert_eq!(storage.prune_block_state(4, block4)?, 0);
+    assert_eq!(StateNodeStore::get(&storage, &e.0)?, Some(e.1));
+
+    // the imported trees share the sub tree `shared` and the node `c` written by a block,
+    // a later block replacing one of the trees does not delete the shared nodes.
+    let (root1, root2, shared, f) = (node(6), node(7), node(8), node(9));
+    StateNodeStore::write_nodes(
+        &storage,
+        vec![root1.clone(), root2.clone(), shared.clone(), c.clone()]
+            .into_iter()
+            .collect(),
+    )?;
+    let (block5, block6) = (HashValue::random(), HashValue::random());
+    write_block(5, block5, vec![f.clone()], vec![root1.0, shared.0, c.0])?;
+    write_block(6, block6, vec![], vec![f.0])?;
+    assert_eq!(storage.prune_block_state(5, block5)?, 0);
+    assert_eq!(storage.prune_block_state(6, block6)?, 1);
+    assert_eq!(StateNodeStore::get(&storage, &root2.0)?, Some(root2.1));
+    assert_eq!(StateNodeStore::get(&storage, &shared.0)?, Some(shared.1));
+    assert_eq!(StateNodeStore::get(&storage, &c.0)?, Some(c.1));
+    assert_eq!(StateNodeStore::get(&storage, &f.0)?, None);
+
+    storage.save_state_pruned_block(6)?;
+    assert!(Storage::new(instance)?.with_state_pruning(false).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_txpool_journal() -> Result<()> {
+    let storage = Storage::new(StorageInstance::new_cache_instance())?;
+    assert!(storage.get_txpool_journal()?.is_empty());
+    let entries = (0..3)
+        .map(|i| TxPoolJournalEntry {
+            txn: SignedUserTransaction::mock(),
+            journaled_at: i,
+        })
+        .collect::<Vec<_>>();
+    storage.save_txpool_journal(entries.clone())?;
+    assert_eq!(storage.get_txpool_journal()?, entries);
+    // a new snapshot replaces the previous one.
+    storage.save_txpool_journal(entries[..1].to_vec())?;
+    assert_eq!(storage.get_txpool_journal()?, entries[..1].to_vec());
+    Ok(())
+}
+
+struct MockRemoteStateStore(BTreeMap<HashValue, StateNode>);
+
+impl RemoteStateStore for MockRemoteStateStore {
+    fn get_state_node(&self, hash: &HashValue) -> Result<Option<StateNode>> {
+        Ok(self.0.get(hash).cloned())
+    }
+
+    fn get_table_info(&self, _address: AccountAddress) -> Result<Option<TableInfo>> {
+        Ok(None)
+    }
+}
+
+#[test]
+fn test_remote_state_node() -> Result<()> {
+    let leaf = Node::new_leaf(AccountAddress::random(), Blob::from(vec![1u8, 2, 3]));
+    let node = StateNode(leaf.encode()?);
+    let node_hash = node.node_hash()?;
+    assert_eq!(node_hash, leaf.hash());
+    let forged_hash = HashValue::random();
+    let remote = MockRemoteStateStore(
+        vec![(node_hash, node.clone()), (forged_hash, node.clone())]
+            .into_iter()
+            .collect(),
+    );
+    let storage = Storage::new(StorageInstance::new_cache_instance())?
+        .with_remote_state_store(Some(Arc::new(remote)));
+
+    assert_eq!(StateNodeStore::get(&storage, &node_hash)?, Some(node));
+    assert!(storage.state_node_storage.get(node_hash)?.is_some());
+    // the node mismatch the hash is rejected, and not cached.
+    assert!(StateNodeStore::get(&storage, &forged_hash).is_err());
+    assert!(storage.state_node_storage.get(forged_hash)?.is_none());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::{CodecKVStore, ValueCodec};
+use crate::{define_storage, TXPOOL_JOURNAL_PREFIX_NAME};
+use anyhow::Result;
+use bcs_ext::BCSCodec;
+use serde::{Deserialize, Serialize};
+use starcoin_types::transaction::SignedUserTransaction;
+
+define_storage!(
+    TxPoolJournalStorage,
+    u64,
+    TxPoolJournal,
+    TXPOOL_JOURNAL_PREFIX_NAME
+);
+
+/// A journaled transaction of the txpool.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxPoolJournalEntry {
+    pub txn: SignedUserTransaction,
+    /// The time in seconds when the transaction is journaled for the first time.
+    pub journaled_at: u64,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxPoolJournal {
+    pub entries: Vec<TxPoolJournalEntry>,
+}
+
+impl ValueCodec for TxPoolJournal {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+pub trait TxPoolJournalStore {
+    /// Get the transactions of the last txpool snapshot.
+    fn get_txpool_journal(&self) -> Result<Vec<TxPoolJournalEntry>>;
+
+    /// Replace the journal with a new txpool snapshot.
+    fn save_txpool_journal(&self, entries: Vec<TxPoolJournalEntry>) -> Result<()>;
+}
+
+impl TxPoolJournalStorage {
+    /// The whole journal is kept in one entry, so a snapshot replaces the previous one atomically.
+    const JOURNAL_KEY: u64 = 0;
+
+    pub(crate) fn get_journal(&self) -> Result<Vec<TxPoolJournalEntry>> {
+        Ok(self
+            .get(Self::JOURNAL_KEY)?
+            .map(|journal| journal.entries)
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn save_journal(&self, entries: Vec<TxPoolJournalEntry>) -> Result<()> {
+        self.put(Self::JOURNAL_KEY, TxPoolJournal { entries })
+    }
+}
@@ -171,6 +171,28 @@ impl DBUpgrade {
         Ok(())
     }
 
+    fn db_upgrade_v3_v4(instance: &mut StorageInstance) -> Result<()> {
+        // The event index column families are empty after upgrade,
+        // so the index is only complete for blocks after current head.
+        // Use `db-exporter backfill-event-index` to index the history blocks.
+        let chain_info_storage = ChainInfoStorage::new(instance.clone());
+        let event_index_start = match chain_info_storage.get_startup_info()? {
+            Some(startup_info) => {
+                let block_storage = BlockStorage::new(instance.clone());
+                let head = block_storage
+                    .get_block_header_by_hash(startup_info.main)?
+                    .ok_or_else(|| {
+                        format_err!("Startup block {:?} should exist", startup_info.main)
+                    })?;
+                head.number().saturating_add(1)
+            }
+            None => 0,
+        };
+        chain_info_storage.save_event_index_start(event_index_start)?;
+        info!("event index start at block {}", event_index_start);
+        Ok(())
+    }
+
     pub fn do_upgrade(
         version_in_db: StorageVersion,
         version_in_code: StorageVersion,
@@ -193,6 +215,21 @@ impl DBUpgrade {
             (StorageVersion::V2, StorageVersion::V3) => {
                 Self::db_upgrade_v2_v3(instance)?;
             }
+
+            (StorageVersion::V1, StorageVersion::V4) => {
+                Self::db_upgrade_v1_v2(instance)?;
+                Self::db_upgrade_v2_v3(instance)?;
+                Self::db_upgrade_v3_v4(instance)?;
+            }
+
+            (StorageVersion::V2, StorageVersion::V4) => {
+                Self::db_upgrade_v2_v3(instance)?;
+                Self::db_upgrade_v3_v4(instance)?;
+            }
+
+            (StorageVersion::V3, StorageVersion::V4) => {
+                Self::db_upgrade_v3_v4(instance)?;
+            }
             _ => bail!(
                 "Can not upgrade db from {:?} to {:?}",
                 version_in_db,
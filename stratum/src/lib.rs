@@ -1,8 +1,10 @@
 use starcoin_types::U256;
 
+pub mod metrics;
 pub mod rpc;
 pub mod service;
 pub mod stratum;
+pub mod worker;
 pub use crate::rpc::gen_client::Client as StratumRpcClient;
 pub use anyhow::Result;
 
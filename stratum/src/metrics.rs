@@ -0,0 +1,43 @@
+use starcoin_metrics::{register, Opts, PrometheusError, Registry, UIntCounterVec, UIntGaugeVec};
+
+#[derive(Clone)]
+pub struct StratumMetrics {
+    pub shares_total: UIntCounterVec,
+    pub worker_hashrate: UIntGaugeVec,
+}
+
+impl StratumMetrics {
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        let shares_total = register(
+            UIntCounterVec::new(
+                Opts::new(
+                    "stratum_shares_total",
+                    "Count of shares submitted by stratum workers, by accepted|stale|invalid",
+                ),
+                &["worker", "status"],
+            )?,
+            registry,
+        )?;
+        let worker_hashrate = register(
+            UIntGaugeVec::new(
+                Opts::new(
+                    "stratum_worker_hashrate",
+                    "Estimated hashes per second of stratum workers",
+                ),
+                &["worker"],
+            )?,
+            registry,
+        )?;
+        Ok(Self {
+            shares_total,
+            worker_hashrate,
+        })
+    }
+
+    pub fn remove_worker(&self, worker: &str) {
+        for status in ["accepted", "stale", "invalid"] {
+            let _ = self.shares_total.remove_label_values(&[worker, status]);
+        }
+        let _ = self.worker_hashrate.remove_label_values(&[worker]);
+    }
+}
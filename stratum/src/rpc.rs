@@ -1,5 +1,6 @@
 use crate::difficulty_to_target_hex;
 use crate::stratum::Stratum;
+use crate::worker::WorkerStats;
 use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use futures::FutureExt;
 use futures::TryFutureExt;
@@ -14,6 +15,7 @@ use starcoin_miner::SubmitSealRequest as MinerSubmitSealRequest;
 use starcoin_service_registry::{ServiceRef, ServiceRequest};
 use starcoin_types::block::BlockHeaderExtra;
 use starcoin_types::system_events::MintBlockEvent;
+use starcoin_types::U256;
 use std::borrow::BorrowMut;
 use std::convert::TryInto;
 use std::io::Write;
@@ -135,6 +137,14 @@ impl ServiceRequest for SubmitShareEvent {
     type Response = anyhow::Result<()>;
 }
 
+/// Get the share statistics of the logged in workers.
+#[derive(Debug, Clone)]
+pub struct GetWorkers;
+
+impl ServiceRequest for GetWorkers {
+    type Response = Vec<WorkerStats>;
+}
+
 pub struct StratumRpcImpl {
     service: ServiceRef<Stratum>,
 }
@@ -205,7 +215,13 @@ impl StratumJob {
 }
 
 impl StratumJobResponse {
-    pub fn from(e: &MintBlockEvent, login: Option<LoginRequest>, worker_id: [u8; 4]) -> Self {
+    /// The job of `e` for the worker, with the target of the worker's `share_difficulty`.
+    pub fn from(
+        e: &MintBlockEvent,
+        login: Option<LoginRequest>,
+        worker_id: [u8; 4],
+        share_difficulty: U256,
+    ) -> Self {
         let mut minting_blob = e.minting_blob.clone();
         let _ = minting_blob[35..39].borrow_mut().write_all(&worker_id);
         let worker_id_hex = hex::encode(worker_id);
@@ -217,7 +233,7 @@ impl StratumJobResponse {
             job: StratumJob {
                 height: 0,
                 id: worker_id_hex,
-                target: difficulty_to_target_hex(e.difficulty),
+                target: difficulty_to_target_hex(share_difficulty),
                 job_id,
                 blob: hex::encode(&minting_blob),
             },
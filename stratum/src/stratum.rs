@@ -1,8 +1,12 @@
+use crate::metrics::StratumMetrics;
 use crate::rpc::*;
-use anyhow::Result;
+use crate::worker::{VarDiff, WorkerStats};
+use anyhow::{bail, format_err, Result};
 use futures::channel::mpsc;
 use futures::StreamExt;
 use jsonrpc_pubsub::SubscriptionId;
+use starcoin_config::NodeConfig;
+use starcoin_consensus::{difficult_to_target, Consensus};
 use starcoin_logger::prelude::*;
 use starcoin_miner::{
     MinerService, SubmitSealRequest as MinerSubmitSealRequest, UpdateSubscriberNumRequest,
@@ -11,22 +15,181 @@ use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler, ServiceRef,
 };
 use starcoin_types::system_events::MintBlockEvent;
-use std::collections::HashMap;
+use starcoin_types::U256;
+use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::sync::atomic;
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ShareStatus {
+    Accepted,
+    Stale,
+    Invalid,
+}
+
+impl ShareStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accepted => "accepted",
+            Self::Stale => "stale",
+            Self::Invalid => "invalid",
+        }
+    }
+}
+
+/// The share difficulty and the submitted nonces of the job sent to a worker.
+struct WorkerJob {
+    job_id: String,
+    /// The lowest share difficulty sent with the job, so the in flight shares of the job are
+    /// still valid after the worker is retargeted.
+    share_difficulty: U256,
+    nonces: HashSet<u32>,
+}
+
+struct WorkerSession {
+    sender: mpsc::UnboundedSender<StratumJobResponse>,
+    login: LoginRequest,
+    worker_id: [u8; 4],
+    vardiff: VarDiff,
+    current_job: Option<WorkerJob>,
+    accepted_shares: u64,
+    stale_shares: u64,
+    invalid_shares: u64,
+}
+
+impl WorkerSession {
+    fn new(
+        sender: mpsc::UnboundedSender<StratumJobResponse>,
+        login: LoginRequest,
+        worker_id: [u8; 4],
+        vardiff: VarDiff,
+    ) -> Self {
+        Self {
+            sender,
+            login,
+            worker_id,
+            vardiff,
+            current_job: None,
+            accepted_shares: 0,
+            stale_shares: 0,
+            invalid_shares: 0,
+        }
+    }
+
+    fn worker_id_hex(&self) -> String {
+        hex::encode(self.worker_id)
+    }
+
+    /// The share difficulty never exceeds the block difficulty.
+    fn share_difficulty(&self, event: &MintBlockEvent) -> U256 {
+        self.vardiff.difficulty().min(event.difficulty)
+    }
+
+    fn job(&mut self, event: &MintBlockEvent, login: Option<LoginRequest>) -> StratumJobResponse {
+        let share_difficulty = self.share_difficulty(event);
+        let response = StratumJobResponse::from(event, login, self.worker_id, share_difficulty);
+        match self.current_job.as_mut() {
+            Some(job) if job.job_id == response.job.job_id => {
+                job.share_difficulty = job.share_difficulty.min(share_difficulty);
+            }
+            _ => {
+                self.current_job = Some(WorkerJob {
+                    job_id: response.job.job_id.clone(),
+                    share_difficulty,
+                    nonces: HashSet::new(),
+                })
+            }
+        }
+        response
+    }
+
+    /// Check the share against the share difficulty of its job, a nonce is accepted once.
+    /// Return the seal if the share meets the block difficulty too.
+    fn verify_share(
+        &mut self,
+        share: ShareRequest,
+        event: &MintBlockEvent,
+    ) -> Result<Option<MinerSubmitSealRequest>> {
+        let job = match self.current_job.as_mut() {
+            Some(job) if job.job_id == share.job_id => job,
+            _ => bail!("Job {} is not sent to worker {}", share.job_id, share.id),
+        };
+        let mut seal: MinerSubmitSealRequest = share.try_into()?;
+        if job.nonces.contains(&seal.nonce) {
+            bail!("Duplicate share of nonce {}", seal.nonce);
+        }
+        let pow_hash: U256 = event
+            .strategy
+            .calculate_pow_hash(&event.minting_blob, seal.nonce, &seal.extra)?
+            .into();
+        if pow_hash > difficult_to_target(job.share_difficulty) {
+            bail!(
+                "Share is below the share difficulty {}",
+                job.share_difficulty
+            );
+        }
+        job.nonces.insert(seal.nonce);
+        if pow_hash <= difficult_to_target(event.difficulty) {
+            seal.minting_blob = event.minting_blob.clone();
+            return Ok(Some(seal));
+        }
+        Ok(None)
+    }
+
+    fn record_share(&mut self, status: ShareStatus) {
+        match status {
+            ShareStatus::Accepted => {
+                self.accepted_shares = self.accepted_shares.saturating_add(1);
+                self.vardiff.record_share();
+            }
+            ShareStatus::Stale => {
+                self.stale_shares = self.stale_shares.saturating_add(1);
+            }
+            ShareStatus::Invalid => {
+                self.invalid_shares = self.invalid_shares.saturating_add(1);
+            }
+        }
+    }
+
+    fn stats(&self) -> WorkerStats {
+        WorkerStats {
+            worker_id: self.worker_id_hex(),
+            login: self.login.login.clone(),
+            agent: self.login.agent.clone(),
+            share_difficulty: self.vardiff.difficulty(),
+            accepted_shares: self.accepted_shares,
+            stale_shares: self.stale_shares,
+            invalid_shares: self.invalid_shares,
+            hashrate: self.vardiff.hashrate(),
+        }
+    }
+}
 
 pub struct Stratum {
     uid: atomic::AtomicU32,
-    mint_block_subscribers: HashMap<u32, (mpsc::UnboundedSender<StratumJobResponse>, LoginRequest)>,
+    mint_block_subscribers: HashMap<u32, WorkerSession>,
     miner_service: ServiceRef<MinerService>,
+    share_rate: u32,
+    min_share_difficulty: U256,
+    metrics: Option<StratumMetrics>,
 }
 
 impl Stratum {
-    fn new(miner_service: ServiceRef<MinerService>) -> Self {
+    fn new(
+        miner_service: ServiceRef<MinerService>,
+        share_rate: u32,
+        min_share_difficulty: U256,
+        metrics: Option<StratumMetrics>,
+    ) -> Self {
         Self {
             miner_service,
             uid: atomic::AtomicU32::new(1),
             mint_block_subscribers: Default::default(),
+            share_rate,
+            min_share_difficulty,
+            metrics,
         }
     }
     fn next_id(&self) -> u32 {
@@ -41,10 +204,18 @@ impl Stratum {
     }
     fn send_to_all(&mut self, event: MintBlockEvent) {
         let mut remove_outdated = vec![];
-        for (id, (ch, login)) in self.mint_block_subscribers.iter() {
-            let worker_id = login.get_worker_id(*id);
-            let job = StratumJobResponse::from(&event, None, worker_id);
-            if let Err(err) = ch.unbounded_send(job) {
+        let now = Instant::now();
+        for (id, session) in self.mint_block_subscribers.iter_mut() {
+            // retarget the idle workers too.
+            session.vardiff.retarget(event.difficulty, now);
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics
+                    .worker_hashrate
+                    .with_label_values(&[session.worker_id_hex().as_str()])
+                    .set(session.vardiff.hashrate());
+            }
+            let job = session.job(&event, None);
+            if let Err(err) = session.sender.unbounded_send(job) {
                 if err.is_disconnected() {
                     remove_outdated.push(*id);
                 } else if err.is_full() {
@@ -53,9 +224,61 @@ impl Stratum {
             }
         }
         for id in remove_outdated {
-            self.mint_block_subscribers.remove(&id);
+            self.remove_worker(id);
+        }
+    }
+
+    fn remove_worker(&mut self, id: u32) {
+        if let Some(session) = self.mint_block_subscribers.remove(&id) {
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.remove_worker(session.worker_id_hex().as_str());
+            }
+        }
+    }
+
+    fn record_share(&mut self, id: u32, status: ShareStatus) {
+        if let Some(session) = self.mint_block_subscribers.get_mut(&id) {
+            session.record_share(status);
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics
+                    .shares_total
+                    .with_label_values(&[session.worker_id_hex().as_str(), status.as_str()])
+                    .inc();
+            }
         }
     }
+
+    /// Retarget the share difficulty of the worker, and send it a new job if the difficulty changed.
+    fn retarget(&mut self, id: u32, event: &MintBlockEvent) {
+        if let Some(session) = self.mint_block_subscribers.get_mut(&id) {
+            let changed = session.vardiff.retarget(event.difficulty, Instant::now());
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics
+                    .worker_hashrate
+                    .with_label_values(&[session.worker_id_hex().as_str()])
+                    .set(session.vardiff.hashrate());
+            }
+            if changed.is_some() {
+                debug!(target: "stratum", "retarget share difficulty of worker {} to {}", session.worker_id_hex(), session.share_difficulty(event));
+                let job = session.job(event, None);
+                if let Err(err) = session.sender.unbounded_send(job) {
+                    error!(target: "stratum", "Failed to send retargeted job: {}", err);
+                }
+            }
+        }
+    }
+
+    /// Check the share of the worker, and forward it to the miner if it meets the block target.
+    fn submit_share(&mut self, id: u32, share: ShareRequest, event: &MintBlockEvent) -> Result<()> {
+        let session = self
+            .mint_block_subscribers
+            .get_mut(&id)
+            .ok_or_else(|| format_err!("Worker {} has logged out", share.id))?;
+        if let Some(seal) = session.verify_share(share, event)? {
+            self.miner_service.try_send(seal)?;
+        }
+        Ok(())
+    }
 }
 
 impl ActorService for Stratum {
@@ -81,15 +304,16 @@ impl ServiceHandler<Self, Unsubscribe> for Stratum {
     fn handle(&mut self, msg: Unsubscribe, _ctx: &mut ServiceContext<Self>) {
         if let SubscriptionId::Number(id) = &msg.0 {
             if let Ok(id) = u32::try_from(*id) {
+                // the subscription ids are never reused, so the id of a removed worker can not
+                // overwrite a live worker session.
+                self.remove_worker(id);
                 if self
                     .miner_service
                     .try_send(UpdateSubscriberNumRequest {
-                        number: Some(self.mint_block_subscribers.len() as u32 - 1),
+                        number: Some(self.mint_block_subscribers.len() as u32),
                     })
                     .is_ok()
                 {
-                    self.mint_block_subscribers.remove(&id);
-                    self.uid.fetch_sub(1, atomic::Ordering::SeqCst);
                     return;
                 }
             }
@@ -104,8 +328,13 @@ impl ServiceHandler<Self, SubscribeJobEvent> for Stratum {
         let SubscribeJobEvent(subscriber, login) = msg;
         let (sender, receiver) = mpsc::unbounded();
         let sub_id = self.next_id();
-        self.mint_block_subscribers
-            .insert(sub_id, (sender.clone(), login.clone()));
+        let session = WorkerSession::new(
+            sender.clone(),
+            login.clone(),
+            login.get_worker_id(sub_id),
+            VarDiff::new(self.share_rate, self.min_share_difficulty, Instant::now()),
+        );
+        self.mint_block_subscribers.insert(sub_id, session);
         ctx.spawn(async move {
             if let Ok(sink) = subscriber
                 .assign_id_async(SubscriptionId::Number(sub_id as u64))
@@ -127,10 +356,12 @@ impl ServiceHandler<Self, SubscribeJobEvent> for Stratum {
                 error!(target: "stratum", "Subscriber assign is failed");
             }
         });
-        if let Ok(Some(event)) = self.sync_current_job() {
+        if let (Ok(Some(event)), Some(session)) = (
+            self.sync_current_job(),
+            self.mint_block_subscribers.get_mut(&sub_id),
+        ) {
+            let stratum_result = session.job(&event, Some(login));
             ctx.spawn(async move {
-                let worker_id = login.get_worker_id(sub_id);
-                let stratum_result = StratumJobResponse::from(&event, Some(login), worker_id);
                 if let Err(err) = sender.unbounded_send(stratum_result) {
                     error!(target: "stratum", "Failed to send MintBlockEvent: {}", err);
                 }
@@ -145,26 +376,197 @@ impl ServiceHandler<Self, SubmitShareEvent> for Stratum {
     fn handle(&mut self, msg: SubmitShareEvent, _ctx: &mut ServiceContext<Self>) -> Result<()> {
         info!(target: "stratum", "received submit share event:{:?}", &msg.0);
         if let Some(current_mint_event) = self.sync_current_job()? {
+            let share = msg.0;
+            let id = self
+                .mint_block_subscribers
+                .iter()
+                .find(|(_, session)| session.worker_id_hex() == share.id)
+                .map(|(id, _)| *id)
+                .ok_or_else(|| format_err!("Unknown worker {}", share.id))?;
             let job_id = hex::encode(&current_mint_event.minting_blob[0..8]);
-            let submit_job_id = msg.0.job_id.clone();
-            if submit_job_id != job_id {
-                warn!(target: "stratum", "received job mismatch with current job,{},{}", submit_job_id, job_id);
-                return Ok(());
+            if share.job_id != job_id {
+                warn!(target: "stratum", "received job mismatch with current job,{},{}", share.job_id, job_id);
+                self.record_share(id, ShareStatus::Stale);
+                bail!("Stale share of job {}", share.job_id);
             };
-            let mut seal: MinerSubmitSealRequest = msg.0.try_into()?;
-
-            seal.minting_blob = current_mint_event.minting_blob;
-            self.miner_service.try_send(seal)?;
+            if let Err(e) = self.submit_share(id, share, &current_mint_event) {
+                self.record_share(id, ShareStatus::Invalid);
+                return Err(e);
+            }
+            self.record_share(id, ShareStatus::Accepted);
+            self.retarget(id, &current_mint_event);
         }
         Ok(())
     }
 }
 
+impl ServiceHandler<Self, GetWorkers> for Stratum {
+    fn handle(&mut self, _msg: GetWorkers, _ctx: &mut ServiceContext<Self>) -> Vec<WorkerStats> {
+        self.mint_block_subscribers
+            .values()
+            .map(WorkerSession::stats)
+            .collect()
+    }
+}
+
 pub struct StratumFactory;
 
 impl ServiceFactory<Stratum> for StratumFactory {
     fn create(ctx: &mut ServiceContext<Stratum>) -> Result<Stratum> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
         let miner_service = ctx.service_ref::<MinerService>()?.clone();
-        Ok(Stratum::new(miner_service))
+        let metrics = config
+            .metrics
+            .registry()
+            .and_then(|registry| StratumMetrics::register(registry).ok());
+        Ok(Stratum::new(
+            miner_service,
+            config.stratum.share_rate(),
+            config.stratum.min_share_difficulty().into(),
+            metrics,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::HashValue;
+    use starcoin_types::block::BlockHeaderExtra;
+    use starcoin_types::genesis_config::ConsensusStrategy;
+
+    const WORKER_ID: [u8; 4] = [1, 2, 3, 4];
+
+    fn new_session(min_share_difficulty: U256) -> WorkerSession {
+        let (sender, _receiver) = mpsc::unbounded();
+        let login = LoginRequest {
+            login: "miner".to_string(),
+            pass: "".to_string(),
+            agent: "test".to_string(),
+            algo: None,
+        };
+        WorkerSession::new(
+            sender,
+            login,
+            WORKER_ID,
+            VarDiff::new(20, min_share_difficulty, Instant::now()),
+        )
+    }
+
+    fn new_event(blob_byte: u8, difficulty: U256) -> MintBlockEvent {
+        MintBlockEvent::new(
+            HashValue::zero(),
+            ConsensusStrategy::Keccak,
+            vec![blob_byte; 76],
+            difficulty,
+            1,
+            None,
+        )
+    }
+
+    /// Find the first nonce whose pow hash matches `pred`.
+    fn find_nonce(event: &MintBlockEvent, pred: impl Fn(U256) -> bool) -> u32 {
+        (0u32..)
+            .find(|nonce| {
+                let pow_hash: U256 = event
+                    .strategy
+                    .calculate_pow_hash(
+                        &event.minting_blob,
+                        *nonce,
+                        &BlockHeaderExtra::new(WORKER_ID),
+                    )
+                    .unwrap()
+                    .into();
+                pred(pow_hash)
+            })
+            .unwrap()
+    }
+
+    fn share(event: &MintBlockEvent, nonce: u32) -> ShareRequest {
+        ShareRequest {
+            id: hex::encode(WORKER_ID),
+            job_id: hex::encode(&event.minting_blob[0..8]),
+            // the nonce is hex encoded in little endian by the miners.
+            nonce: format!("{:08x}", nonce.swap_bytes()),
+            result: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_submit_share() {
+        let mut session = new_session(4.into());
+        let event = new_event(0, 1_000_000.into());
+        session.job(&event, None);
+        let share_target = difficult_to_target(4.into());
+        let nonce = find_nonce(&event, |pow_hash| pow_hash <= share_target);
+        let low_nonce = find_nonce(&event, |pow_hash| pow_hash > share_target);
+
+        let submit = |session: &mut WorkerSession, share: ShareRequest| {
+            let result = session.verify_share(share, &event);
+            session.record_share(if result.is_ok() {
+                ShareStatus::Accepted
+            } else {
+                ShareStatus::Invalid
+            });
+            result
+        };
+        assert!(submit(&mut session, share(&event, nonce))
+            .unwrap()
+            .is_none());
+        // the same nonce is accepted once.
+        assert!(submit(&mut session, share(&event, nonce)).is_err());
+        assert!(submit(&mut session, share(&event, low_nonce)).is_err());
+        let mut unknown_job = share(&event, nonce);
+        unknown_job.job_id = "0101010101010101".to_string();
+        assert!(submit(&mut session, unknown_job).is_err());
+
+        let stats = session.stats();
+        assert_eq!(stats.accepted_shares, 1);
+        assert_eq!(stats.invalid_shares, 3);
+        assert_eq!(stats.stale_shares, 0);
+    }
+
+    #[test]
+    fn test_share_difficulty_of_job() {
+        let mut session = new_session(4.into());
+        let event = new_event(0, 1_000_000.into());
+        session.job(&event, None);
+
+        // retarget the worker, the in flight shares of the job are still valid.
+        session.vardiff = VarDiff::new(20, 64.into(), Instant::now());
+        session.job(&event, None);
+        let easy_target = difficult_to_target(4.into());
+        let hard_target = difficult_to_target(64.into());
+        let nonce = find_nonce(&event, |pow_hash| {
+            pow_hash <= easy_target && pow_hash > hard_target
+        });
+        assert!(session.verify_share(share(&event, nonce), &event).is_ok());
+
+        // the new job is checked against the new share difficulty.
+        let event = new_event(1, 1_000_000.into());
+        session.job(&event, None);
+        let nonce = find_nonce(&event, |pow_hash| {
+            pow_hash <= easy_target && pow_hash > hard_target
+        });
+        assert!(session.verify_share(share(&event, nonce), &event).is_err());
+        let nonce = find_nonce(&event, |pow_hash| pow_hash <= hard_target);
+        assert!(session.verify_share(share(&event, nonce), &event).is_ok());
+    }
+
+    #[test]
+    fn test_submit_block_share() {
+        let mut session = new_session(4.into());
+        // the share difficulty never exceeds the block difficulty.
+        let event = new_event(2, 2.into());
+        session.job(&event, None);
+        let block_target = difficult_to_target(2.into());
+        let nonce = find_nonce(&event, |pow_hash| pow_hash <= block_target);
+        let seal = session
+            .verify_share(share(&event, nonce), &event)
+            .unwrap()
+            .expect("share meets the block difficulty");
+        assert_eq!(seal.nonce, nonce);
+        assert_eq!(seal.extra, BlockHeaderExtra::new(WORKER_ID));
+        assert_eq!(seal.minting_blob, event.minting_blob);
     }
 }
@@ -0,0 +1,147 @@
+use starcoin_types::U256;
+use std::time::{Duration, Instant};
+
+/// The interval to retarget the share difficulty of a worker.
+pub const RETARGET_INTERVAL: Duration = Duration::from_secs(30);
+/// The max factor the share difficulty changes by in one retarget.
+const MAX_RETARGET_FACTOR: u64 = 4;
+/// The precision of the retarget scale.
+const SCALE_PRECISION: u64 = 1000;
+
+/// Variable share difficulty of a worker, retargeted toward `share_rate` shares per minute.
+#[derive(Clone, Debug)]
+pub struct VarDiff {
+    share_rate: u32,
+    min_difficulty: U256,
+    difficulty: U256,
+    window_start: Instant,
+    window_shares: u64,
+    hashrate: u64,
+}
+
+impl VarDiff {
+    pub fn new(share_rate: u32, min_difficulty: U256, now: Instant) -> Self {
+        let min_difficulty = min_difficulty.max(U256::one());
+        Self {
+            share_rate: share_rate.max(1),
+            min_difficulty,
+            difficulty: min_difficulty,
+            window_start: now,
+            window_shares: 0,
+            hashrate: 0,
+        }
+    }
+
+    pub fn difficulty(&self) -> U256 {
+        self.difficulty
+    }
+
+    /// Estimated hashes per second of the worker in the last retarget window.
+    pub fn hashrate(&self) -> u64 {
+        self.hashrate
+    }
+
+    pub fn record_share(&mut self) {
+        self.window_shares = self.window_shares.saturating_add(1);
+    }
+
+    /// Retarget when the window is over, or earlier if the worker submits shares too fast.
+    /// The difficulty never exceeds `max_difficulty`, the block difficulty.
+    /// Return the new difficulty if it changed.
+    pub fn retarget(&mut self, max_difficulty: U256, now: Instant) -> Option<U256> {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        let window_expected_shares =
+            (self.share_rate as u64 * RETARGET_INTERVAL.as_secs() / 60).max(1);
+        if elapsed < RETARGET_INTERVAL
+            && self.window_shares < window_expected_shares * MAX_RETARGET_FACTOR
+        {
+            return None;
+        }
+        let elapsed_millis = (elapsed.as_millis() as u64).max(1);
+        let hashrate = self.difficulty * U256::from(self.window_shares) * U256::from(1000u64)
+            / U256::from(elapsed_millis);
+        self.hashrate = if hashrate > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            hashrate.low_u64()
+        };
+
+        // actual shares per minute / expected shares per minute, in SCALE_PRECISION.
+        let scale =
+            self.window_shares * 60_000 * SCALE_PRECISION / elapsed_millis / self.share_rate as u64;
+        let scale = scale.clamp(
+            SCALE_PRECISION / MAX_RETARGET_FACTOR,
+            SCALE_PRECISION * MAX_RETARGET_FACTOR,
+        );
+        let difficulty = (self.difficulty * U256::from(scale) / U256::from(SCALE_PRECISION))
+            .max(self.min_difficulty)
+            .min(max_difficulty.max(U256::one()));
+
+        self.window_start = now;
+        self.window_shares = 0;
+        if difficulty != self.difficulty {
+            self.difficulty = difficulty;
+            Some(difficulty)
+        } else {
+            None
+        }
+    }
+}
+
+/// The share statistics of a worker.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkerStats {
+    pub worker_id: String,
+    pub login: String,
+    pub agent: String,
+    pub share_difficulty: U256,
+    pub accepted_shares: u64,
+    pub stale_shares: u64,
+    pub invalid_shares: u64,
+    /// Estimated hashes per second, by the accepted shares.
+    pub hashrate: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vardiff_retarget() {
+        let start = Instant::now();
+        let mut vardiff = VarDiff::new(20, 100.into(), start);
+        assert_eq!(vardiff.difficulty(), 100.into());
+
+        // too many shares, retarget early and limited by the max factor.
+        for _ in 0..40 {
+            vardiff.record_share();
+        }
+        let now = start + Duration::from_secs(1);
+        assert_eq!(vardiff.retarget(10000.into(), now), Some(400.into()));
+        assert_eq!(vardiff.hashrate(), 4000);
+
+        // 20 shares in 60 seconds is the target rate.
+        assert_eq!(vardiff.retarget(10000.into(), now), None);
+        for _ in 0..20 {
+            vardiff.record_share();
+        }
+        let now = now + Duration::from_secs(60);
+        assert_eq!(vardiff.retarget(10000.into(), now), None);
+        assert_eq!(vardiff.difficulty(), 400.into());
+
+        // 5 shares in 30 seconds, half of the target rate.
+        for _ in 0..5 {
+            vardiff.record_share();
+        }
+        let now = now + Duration::from_secs(30);
+        assert_eq!(vardiff.retarget(10000.into(), now), Some(200.into()));
+
+        // never exceeds the block difficulty, or falls below the min difficulty.
+        for _ in 0..40 {
+            vardiff.record_share();
+        }
+        assert_eq!(vardiff.retarget(300.into(), now), Some(300.into()));
+        let now = now + Duration::from_secs(300);
+        assert_eq!(vardiff.retarget(300.into(), now), Some(100.into()));
+    }
+}
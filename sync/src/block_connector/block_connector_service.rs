@@ -3,7 +3,7 @@
 
 use crate::block_connector::{ExecuteRequest, ResetRequest, WriteBlockChainService};
 use crate::sync::{CheckSyncEvent, SyncService};
-use crate::tasks::{BlockConnectedEvent, BlockDiskCheckEvent};
+use crate::tasks::{BlockConnectedEvent, BlockDiskCheckEvent, PivotEvent};
 use anyhow::{format_err, Result};
 use network_api::PeerProvider;
 use starcoin_chain_api::{ConnectBlockError, WriteableChainService};
@@ -177,6 +177,18 @@ impl EventHandler<Self, BlockConnectedEvent> for BlockConnectorService {
     }
 }
 
+impl EventHandler<Self, PivotEvent> for BlockConnectorService {
+    fn handle_event(&mut self, msg: PivotEvent, _ctx: &mut ServiceContext<BlockConnectorService>) {
+        let pivot = msg.pivot;
+        if let Err(e) = self.chain_service.switch_head(pivot.id) {
+            error!(
+                "Switch head to pivot block ({:?},{}) error: {:?}",
+                pivot.id, pivot.number, e
+            );
+        }
+    }
+}
+
 impl EventHandler<Self, MinedBlock> for BlockConnectorService {
     fn handle_event(&mut self, msg: MinedBlock, _ctx: &mut ServiceContext<Self>) {
         let MinedBlock(new_block) = msg;
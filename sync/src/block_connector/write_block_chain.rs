@@ -213,6 +213,9 @@ where
             if let Some(metrics) = self.metrics.as_ref() {
                 metrics.chain_rollback_block_total.inc_by(retracted_count);
             }
+            if let Err(e) = self.update_index(&enacted_blocks, &retracted_blocks) {
+                error!("update index err : {:?}", e);
+            }
         }
         self.commit_2_txpool(enacted_blocks, retracted_blocks);
         self.config
@@ -277,6 +280,21 @@ where
         Ok(())
     }
 
+    /// Switch the main chain to `block_id` whose state has been synced by fast sync,
+    /// the blocks before it are not executed locally.
+    pub fn switch_head(&mut self, block_id: HashValue) -> Result<()> {
+        let new_branch = BlockChain::new(
+            self.config.net().time_service(),
+            block_id,
+            self.storage.clone(),
+            self.vm_metrics.clone(),
+        )?;
+        let executed_block = new_branch.head_block();
+        self.main = new_branch;
+        let enacted_blocks = vec![executed_block.block.clone()];
+        self.do_new_head(executed_block, 1, enacted_blocks, 0, vec![])
+    }
+
     ///Directly execute the block and save result, do not try to connect.
     pub fn execute(&mut self, block: Block) -> Result<ExecutedBlock> {
         let chain = BlockChain::new(
@@ -304,6 +322,35 @@ where
         }
     }
 
+    /// Roll back the event index and account txn index of retracted blocks,
+    /// and (re)index the enacted blocks, the enacted blocks may have been retracted before.
+    fn update_index(&self, enacted: &[Block], retracted: &[Block]) -> Result<()> {
+        // delete before save, the same txn info may exist in both branches.
+        for block in retracted {
+            for txn_info in self.storage.get_block_transaction_infos(block.id())? {
+                let events = self
+                    .storage
+                    .get_contract_events(txn_info.id())?
+                    .unwrap_or_default();
+                self.storage
+                    .delete_contract_event_index(&txn_info, events.as_slice())?;
+            }
+            self.storage.delete_account_txn_index(block)?;
+        }
+        for block in enacted {
+            for txn_info in self.storage.get_block_transaction_infos(block.id())? {
+                let events = self
+                    .storage
+                    .get_contract_events(txn_info.id())?
+                    .unwrap_or_default();
+                self.storage
+                    .save_contract_event_index(&txn_info, events.as_slice())?;
+            }
+            self.storage.save_account_txn_index(block)?;
+        }
+        Ok(())
+    }
+
     fn find_ancestors_from_accumulator(
         &self,
         new_branch: &BlockChain,
@@ -4,6 +4,7 @@
 #![deny(clippy::integer_arithmetic)]
 pub mod announcement;
 pub mod block_connector;
+pub mod snapshot;
 pub mod sync;
 pub mod sync_metrics;
 pub mod tasks;
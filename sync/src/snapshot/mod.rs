@@ -0,0 +1,779 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The snapshot of the chain at a pivot block, for bootstrapping a node without executing the
+//! history blocks and without trusting the provider of the snapshot.
+//!
+//! A snapshot is a directory of a `manifest.json` and BCS encoded chunk files. The manifest is
+//! anchored to the `BlockInfo` of the pivot block and records the hash of every chunk. The chunks
+//! contain the block accumulator, the recent blocks up to the pivot, the transaction accumulator
+//! and the state trees of the pivot. The nodes of the merkle trees are exported in breadth first
+//! order, so every node is verified against its parent, from the roots in the pivot `BlockInfo`,
+//! before it is written to storage.
+//!
+//! The hash of the pivot block is supplied by the operator from a trusted source. The headers are
+//! verified by the parent hash from the pivot, and the roots in the pivot `BlockInfo` are verified
+//! against the pivot header.
+
+use crate::tasks::{state_node_children, StateTreeType, FAST_SYNC_PIVOT_OFFSET};
+use anyhow::{bail, ensure, format_err, Result};
+use bcs_ext::BCSCodec;
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::accumulator_info::AccumulatorInfo;
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::node_index::{FrozenSubTreeIterator, NodeIndex};
+use starcoin_accumulator::{Accumulator, AccumulatorNode, AccumulatorTreeStore, MerkleAccumulator};
+use starcoin_chain::verifier::StaticVerifier;
+use starcoin_chain::{BlockChain, ChainReader};
+use starcoin_crypto::hash::{ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_state_tree::{StateNode, StateNodeStore};
+use starcoin_storage::Store;
+use starcoin_time_service::TimeService;
+use starcoin_types::block::{Block, BlockHeader, BlockIdAndNumber, BlockInfo, BlockNumber};
+use starcoin_types::startup_info::StartupInfo;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+pub const SNAPSHOT_MANIFEST_FILE: &str = "manifest.json";
+const NODE_CHUNK_SIZE: usize = 10000;
+const BLOCK_CHUNK_SIZE: usize = 100;
+
+/// The kinds of the chunks, in the order of import.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotChunkKind {
+    BlockAccumulator,
+    Blocks,
+    TxnAccumulator,
+    State,
+}
+
+impl SnapshotChunkKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnapshotChunkKind::BlockAccumulator => "block_accumulator",
+            SnapshotChunkKind::Blocks => "blocks",
+            SnapshotChunkKind::TxnAccumulator => "txn_accumulator",
+            SnapshotChunkKind::State => "state",
+        }
+    }
+}
+
+impl Display for SnapshotChunkKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotChunkInfo {
+    pub kind: SnapshotChunkKind,
+    pub file_name: String,
+    /// The sha3 hash of the chunk file.
+    pub hash: HashValue,
+    pub items: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub genesis_id: HashValue,
+    pub pivot: BlockIdAndNumber,
+    pub pivot_info: BlockInfo,
+    /// The chunks in the order of import.
+    pub chunks: Vec<SnapshotChunkInfo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SnapshotChunk {
+    BlockAccumulator(Vec<AccumulatorNode>),
+    /// The blocks before the pivot and the pivot itself, in ascending order of block number.
+    Blocks(Vec<(Block, BlockInfo)>),
+    TxnAccumulator(Vec<AccumulatorNode>),
+    /// The nodes of the global state tree and the account state trees, with the node hash.
+    State(Vec<(HashValue, Vec<u8>)>),
+}
+
+impl SnapshotChunk {
+    pub fn kind(&self) -> SnapshotChunkKind {
+        match self {
+            SnapshotChunk::BlockAccumulator(_) => SnapshotChunkKind::BlockAccumulator,
+            SnapshotChunk::Blocks(_) => SnapshotChunkKind::Blocks,
+            SnapshotChunk::TxnAccumulator(_) => SnapshotChunkKind::TxnAccumulator,
+            SnapshotChunk::State(_) => SnapshotChunkKind::State,
+        }
+    }
+
+    pub fn items(&self) -> usize {
+        match self {
+            SnapshotChunk::BlockAccumulator(nodes) | SnapshotChunk::TxnAccumulator(nodes) => {
+                nodes.len()
+            }
+            SnapshotChunk::Blocks(blocks) => blocks.len(),
+            SnapshotChunk::State(nodes) => nodes.len(),
+        }
+    }
+}
+
+/// Export the snapshot of the pivot block to `output`. The pivot is selected as fast sync does,
+/// the last block of the epoch before the epoch of `checkpoint_id`.
+pub fn export_snapshot(
+    storage: Arc<dyn Store>,
+    time_service: Arc<dyn TimeService>,
+    checkpoint_id: HashValue,
+    output: &Path,
+) -> Result<SnapshotManifest> {
+    let checkpoint = BlockChain::new(time_service.clone(), checkpoint_id, storage.clone(), None)?;
+    let pivot_number = checkpoint.epoch().start_block_number().saturating_sub(1);
+    ensure!(
+        pivot_number > 0,
+        "No pivot block before the epoch of block {}",
+        checkpoint_id
+    );
+    let pivot_id = checkpoint
+        .get_hash_by_number(pivot_number)?
+        .ok_or_else(|| format_err!("Can not find pivot block by number {}", pivot_number))?;
+    let pivot_chain = BlockChain::new(time_service, pivot_id, storage.clone(), None)?;
+    let genesis_id = pivot_chain
+        .get_hash_by_number(0)?
+        .ok_or_else(|| format_err!("Can not find genesis block"))?;
+    let pivot_info = pivot_chain
+        .get_block_info(Some(pivot_id))?
+        .ok_or_else(|| format_err!("Can not find block info of pivot block {}", pivot_id))?;
+    // the blocks of the pivot epoch are required for the uncles and the difficulty.
+    let pivot_epoch = pivot_chain.epoch();
+    let start_number = pivot_epoch
+        .start_block_number()
+        .min(pivot_number.saturating_sub(pivot_epoch.block_difficulty_window()));
+    info!(
+        "Export snapshot of pivot block ({:?},{}), state root: {}",
+        pivot_id,
+        pivot_number,
+        pivot_chain.current_header().state_root()
+    );
+
+    std::fs::create_dir_all(output)?;
+    let mut writer = ChunkWriter {
+        output,
+        chunks: vec![],
+    };
+    export_accumulator(
+        storage.get_accumulator_store(AccumulatorStoreType::Block),
+        &pivot_info.block_accumulator_info,
+        SnapshotChunk::BlockAccumulator,
+        &mut writer,
+    )?;
+    let mut blocks = vec![];
+    for number in start_number..=pivot_number {
+        let block = pivot_chain
+            .get_block_by_number(number)?
+            .ok_or_else(|| format_err!("Can not find block by number {}", number))?;
+        let block_info = pivot_chain
+            .get_block_info(Some(block.id()))?
+            .ok_or_else(|| format_err!("Can not find block info by id {}", block.id()))?;
+        blocks.push((block, block_info));
+        if blocks.len() >= BLOCK_CHUNK_SIZE {
+            writer.write(SnapshotChunk::Blocks(std::mem::take(&mut blocks)))?;
+        }
+    }
+    if !blocks.is_empty() {
+        writer.write(SnapshotChunk::Blocks(blocks))?;
+    }
+    export_accumulator(
+        storage.get_accumulator_store(AccumulatorStoreType::Transaction),
+        &pivot_info.txn_accumulator_info,
+        SnapshotChunk::TxnAccumulator,
+        &mut writer,
+    )?;
+    export_state(
+        storage.as_ref(),
+        pivot_chain.current_header().state_root(),
+        &mut writer,
+    )?;
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_VERSION,
+        genesis_id,
+        pivot: BlockIdAndNumber::new(pivot_id, pivot_number),
+        pivot_info,
+        chunks: writer.chunks,
+    };
+    std::fs::write(
+        output.join(SNAPSHOT_MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// Select the checkpoint block `FAST_SYNC_PIVOT_OFFSET` blocks behind the head, or at `number`.
+pub fn snapshot_checkpoint(chain: &BlockChain, number: Option<BlockNumber>) -> Result<HashValue> {
+    let number = number.unwrap_or_else(|| {
+        chain
+            .current_header()
+            .number()
+            .saturating_sub(FAST_SYNC_PIVOT_OFFSET)
+    });
+    chain
+        .get_hash_by_number(number)?
+        .ok_or_else(|| format_err!("Can not find block by number {}", number))
+}
+
+struct ChunkWriter<'a> {
+    output: &'a Path,
+    chunks: Vec<SnapshotChunkInfo>,
+}
+
+impl<'a> ChunkWriter<'a> {
+    fn write(&mut self, chunk: SnapshotChunk) -> Result<()> {
+        let kind = chunk.kind();
+        let file_name = format!("{:06}-{}.chunk", self.chunks.len(), kind);
+        let bytes = chunk.encode()?;
+        std::fs::write(self.output.join(file_name.as_str()), bytes.as_slice())?;
+        debug!("Export snapshot chunk {}", file_name);
+        self.chunks.push(SnapshotChunkInfo {
+            kind,
+            file_name,
+            hash: HashValue::sha3_256_of(bytes.as_slice()),
+            items: chunk.items() as u64,
+        });
+        Ok(())
+    }
+}
+
+fn export_accumulator(
+    store: Arc<dyn AccumulatorTreeStore>,
+    info: &AccumulatorInfo,
+    to_chunk: fn(Vec<AccumulatorNode>) -> SnapshotChunk,
+    writer: &mut ChunkWriter,
+) -> Result<()> {
+    let mut pending = VecDeque::new();
+    if info.accumulator_root != *ACCUMULATOR_PLACEHOLDER_HASH {
+        pending.push_back(info.accumulator_root);
+    }
+    let mut nodes = vec![];
+    while let Some(node_hash) = pending.pop_front() {
+        let node = store
+            .get_node(node_hash)?
+            .ok_or_else(|| format_err!("Can not find accumulator node {}", node_hash))?;
+        if let AccumulatorNode::Internal(internal) = &node {
+            pending.extend(
+                [internal.left(), internal.right()]
+                    .into_iter()
+                    .filter(|hash| *hash != *ACCUMULATOR_PLACEHOLDER_HASH),
+            );
+        }
+        nodes.push(node);
+        if nodes.len() >= NODE_CHUNK_SIZE {
+            writer.write(to_chunk(std::mem::take(&mut nodes)))?;
+        }
+    }
+    if !nodes.is_empty() {
+        writer.write(to_chunk(nodes))?;
+    }
+    Ok(())
+}
+
+fn export_state(
+    storage: &dyn Store,
+    state_root: HashValue,
+    writer: &mut ChunkWriter,
+) -> Result<()> {
+    let mut pending = VecDeque::new();
+    if state_root != *SPARSE_MERKLE_PLACEHOLDER_HASH {
+        pending.push_back((state_root, StateTreeType::Global));
+    }
+    // the same sub tree may be shared by different accounts.
+    let mut exported = HashSet::new();
+    let mut nodes = vec![];
+    while let Some((node_hash, tree_type)) = pending.pop_front() {
+        if !exported.insert(node_hash) {
+            continue;
+        }
+        let node = StateNodeStore::get(storage, &node_hash)?
+            .ok_or_else(|| format_err!("Can not find state node {}", node_hash))?;
+        pending.extend(state_node_children(node_hash, node.clone(), tree_type)?);
+        nodes.push((node_hash, node.0));
+        if nodes.len() >= NODE_CHUNK_SIZE {
+            writer.write(SnapshotChunk::State(std::mem::take(&mut nodes)))?;
+        }
+    }
+    if !nodes.is_empty() {
+        writer.write(SnapshotChunk::State(nodes))?;
+    }
+    Ok(())
+}
+
+/// Import the snapshot in `input` to the storage of a new node, and make the pivot block the head
+/// of the main chain. The pivot block of the snapshot must be the `trusted_pivot`, every chunk is
+/// verified before it is written to storage, the genesis of the snapshot must be the local genesis.
+/// The total difficulty of the first block in the snapshot can not be verified without the
+/// history blocks, the following ones are accumulated from the headers.
+/// If the import fails, the written blocks and accumulator nodes are deleted. The written state
+/// nodes are kept, they are verified from the trusted state root.
+pub fn import_snapshot(
+    storage: Arc<dyn Store>,
+    genesis_id: HashValue,
+    trusted_pivot: HashValue,
+    input: &Path,
+) -> Result<BlockIdAndNumber> {
+    let mut writes = SnapshotWrites::default();
+    let result = do_import_snapshot(
+        storage.clone(),
+        genesis_id,
+        trusted_pivot,
+        input,
+        &mut writes,
+    );
+    if let Err(e) = &result {
+        warn!(
+            "Import snapshot failed: {:?}, roll back the written data.",
+            e
+        );
+        if let Err(rollback_err) = writes.rollback(storage.as_ref()) {
+            error!("Roll back the snapshot import failed: {:?}", rollback_err);
+        }
+    }
+    result
+}
+
+/// The keys written by a snapshot import, for rolling back a failed import.
+#[derive(Default)]
+struct SnapshotWrites {
+    block_accumulator_nodes: Vec<HashValue>,
+    blocks: Vec<HashValue>,
+    txn_accumulator_nodes: Vec<HashValue>,
+}
+
+impl SnapshotWrites {
+    fn rollback(self, storage: &dyn Store) -> Result<()> {
+        for block_id in self.blocks {
+            storage.delete_block_info(block_id)?;
+            storage.delete_block(block_id)?;
+        }
+        storage
+            .get_accumulator_store(AccumulatorStoreType::Block)
+            .delete_nodes(self.block_accumulator_nodes)?;
+        storage
+            .get_accumulator_store(AccumulatorStoreType::Transaction)
+            .delete_nodes(self.txn_accumulator_nodes)
+    }
+}
+
+fn do_import_snapshot(
+    storage: Arc<dyn Store>,
+    genesis_id: HashValue,
+    trusted_pivot: HashValue,
+    input: &Path,
+    writes: &mut SnapshotWrites,
+) -> Result<BlockIdAndNumber> {
+    let manifest: SnapshotManifest =
+        serde_json::from_slice(std::fs::read(input.join(SNAPSHOT_MANIFEST_FILE))?.as_slice())?;
+    ensure!(
+        manifest.version == SNAPSHOT_VERSION,
+        "Unsupported snapshot version {}, expect {}",
+        manifest.version,
+        SNAPSHOT_VERSION
+    );
+    ensure!(
+        manifest.genesis_id == genesis_id,
+        "Snapshot genesis {} mismatch local genesis {}",
+        manifest.genesis_id,
+        genesis_id
+    );
+    let startup_info = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Startup info should exist."))?;
+    ensure!(
+        startup_info.main == genesis_id,
+        "Bootstrap from snapshot only works on a new node, current head: {}",
+        startup_info.main
+    );
+    let pivot = manifest.pivot;
+    ensure!(
+        pivot.id == trusted_pivot,
+        "Snapshot pivot block {} mismatch the trusted pivot block {}",
+        pivot.id,
+        trusted_pivot
+    );
+    let pivot_info = &manifest.pivot_info;
+    ensure!(
+        pivot_info.block_id == pivot.id
+            && pivot_info.block_accumulator_info.num_leaves == pivot.number.saturating_add(1),
+        "Block info of pivot block ({:?},{}) mismatch",
+        pivot.id,
+        pivot.number
+    );
+    ensure!(
+        manifest.chunks.windows(2).all(|w| w[0].kind <= w[1].kind),
+        "Snapshot chunks are out of order"
+    );
+    info!(
+        "Import snapshot of pivot block ({:?},{}), {} chunks",
+        pivot.id,
+        pivot.number,
+        manifest.chunks.len()
+    );
+
+    let block_store = storage.get_accumulator_store(AccumulatorStoreType::Block);
+    let mut accumulator_importer =
+        AccumulatorImporter::new(block_store.clone(), &pivot_info.block_accumulator_info);
+    for chunk in read_chunks(input, &manifest, SnapshotChunkKind::BlockAccumulator) {
+        match chunk? {
+            SnapshotChunk::BlockAccumulator(nodes) => {
+                accumulator_importer.import(nodes, &mut writes.block_accumulator_nodes)?
+            }
+            chunk => bail!("Unexpected snapshot chunk {}", chunk.kind()),
+        }
+    }
+    accumulator_importer.finish()?;
+    let block_accumulator =
+        MerkleAccumulator::new_with_info(pivot_info.block_accumulator_info.clone(), block_store);
+    ensure!(
+        block_accumulator.get_leaf(0)? == Some(genesis_id),
+        "Snapshot block accumulator does not start from genesis {}",
+        genesis_id
+    );
+    ensure!(
+        block_accumulator.get_leaf(pivot.number)? == Some(pivot.id),
+        "Snapshot block accumulator does not contain pivot block ({:?},{})",
+        pivot.id,
+        pivot.number
+    );
+
+    let mut block_importer = BlockImporter {
+        storage: storage.clone(),
+        accumulator: &block_accumulator,
+        pending: None,
+        parent_info: None,
+        written: &mut writes.blocks,
+    };
+    for chunk in read_chunks(input, &manifest, SnapshotChunkKind::Blocks) {
+        match chunk? {
+            SnapshotChunk::Blocks(blocks) => block_importer.import(blocks)?,
+            chunk => bail!("Unexpected snapshot chunk {}", chunk.kind()),
+        }
+    }
+    let pivot_header = block_importer.finish(pivot_info)?;
+
+    let mut accumulator_importer = AccumulatorImporter::new(
+        storage.get_accumulator_store(AccumulatorStoreType::Transaction),
+        &pivot_info.txn_accumulator_info,
+    );
+    for chunk in read_chunks(input, &manifest, SnapshotChunkKind::TxnAccumulator) {
+        match chunk? {
+            SnapshotChunk::TxnAccumulator(nodes) => {
+                accumulator_importer.import(nodes, &mut writes.txn_accumulator_nodes)?
+            }
+            chunk => bail!("Unexpected snapshot chunk {}", chunk.kind()),
+        }
+    }
+    accumulator_importer.finish()?;
+
+    let mut state_importer = StateImporter::new(storage.clone(), pivot_header.state_root());
+    for chunk in read_chunks(input, &manifest, SnapshotChunkKind::State) {
+        match chunk? {
+            SnapshotChunk::State(nodes) => state_importer.import(nodes)?,
+            chunk => bail!("Unexpected snapshot chunk {}", chunk.kind()),
+        }
+    }
+    state_importer.finish()?;
+
+    storage.save_startup_info(StartupInfo::new(pivot.id))?;
+    Ok(pivot)
+}
+
+fn read_chunks<'a>(
+    input: &'a Path,
+    manifest: &'a SnapshotManifest,
+    kind: SnapshotChunkKind,
+) -> impl Iterator<Item = Result<SnapshotChunk>> + 'a {
+    manifest
+        .chunks
+        .iter()
+        .filter(move |info| info.kind == kind)
+        .map(move |info| read_chunk(input, info))
+}
+
+fn read_chunk(input: &Path, info: &SnapshotChunkInfo) -> Result<SnapshotChunk> {
+    let bytes = std::fs::read(input.join(info.file_name.as_str()))?;
+    let hash = HashValue::sha3_256_of(bytes.as_slice());
+    ensure!(
+        hash == info.hash,
+        "Snapshot chunk {} hash mismatch, expect: {}, got: {}",
+        info.file_name,
+        info.hash,
+        hash
+    );
+    let chunk = SnapshotChunk::decode(bytes.as_slice())?;
+    ensure!(
+        chunk.kind() == info.kind && chunk.items() as u64 == info.items,
+        "Snapshot chunk {} mismatch its manifest",
+        info.file_name
+    );
+    debug!("Import snapshot chunk {}", info.file_name);
+    Ok(chunk)
+}
+
+/// Verify the accumulator nodes from the root in breadth first order. A node is accepted only
+/// if its parent has been accepted, or it exists locally.
+struct AccumulatorImporter {
+    store: Arc<dyn AccumulatorTreeStore>,
+    pending: HashMap<HashValue, NodeIndex>,
+}
+
+impl AccumulatorImporter {
+    fn new(store: Arc<dyn AccumulatorTreeStore>, info: &AccumulatorInfo) -> Self {
+        let mut pending = HashMap::new();
+        if info.accumulator_root != *ACCUMULATOR_PLACEHOLDER_HASH {
+            pending.insert(
+                info.accumulator_root,
+                NodeIndex::root_from_leaf_count(info.num_leaves),
+            );
+        }
+        Self { store, pending }
+    }
+
+    fn import(&mut self, nodes: Vec<AccumulatorNode>, written: &mut Vec<HashValue>) -> Result<()> {
+        let mut verified: HashMap<HashValue, AccumulatorNode> = HashMap::new();
+        for node in nodes {
+            let node_hash = node.hash();
+            match self.pending.remove(&node_hash) {
+                Some(node_index) => {
+                    ensure!(
+                        node.index() == node_index,
+                        "Accumulator node {} index mismatch",
+                        node_hash
+                    );
+                    if let AccumulatorNode::Internal(internal) = &node {
+                        for (child, child_index) in [
+                            (internal.left(), node_index.left_child()),
+                            (internal.right(), node_index.right_child()),
+                        ] {
+                            if child != *ACCUMULATOR_PLACEHOLDER_HASH
+                                && !verified.contains_key(&child)
+                                && self.store.get_node(child)?.is_none()
+                            {
+                                self.pending.insert(child, child_index);
+                            }
+                        }
+                    }
+                    verified.insert(node_hash, node);
+                }
+                None => ensure!(
+                    verified.contains_key(&node_hash) || self.store.get_node(node_hash)?.is_some(),
+                    "Unexpected accumulator node {} in snapshot",
+                    node_hash
+                ),
+            }
+        }
+        written.extend(verified.keys());
+        self.store.save_nodes(verified.into_values().collect())
+    }
+
+    fn finish(self) -> Result<()> {
+        ensure!(
+            self.pending.is_empty(),
+            "Snapshot misses {} accumulator nodes",
+            self.pending.len()
+        );
+        Ok(())
+    }
+}
+
+/// Compute the root of the accumulator from its frozen subtree roots, as the accumulator does on
+/// append, so the frozen subtree roots in a `BlockInfo` are verified against its root.
+fn accumulator_root(info: &AccumulatorInfo) -> Result<HashValue> {
+    let mut subtrees: Vec<(NodeIndex, HashValue)> = FrozenSubTreeIterator::new(info.num_leaves)
+        .zip(info.frozen_subtree_roots.iter().copied())
+        .collect();
+    ensure!(
+        subtrees.len() == info.frozen_subtree_roots.len()
+            && FrozenSubTreeIterator::new(info.num_leaves).count() == subtrees.len(),
+        "Accumulator frozen subtree roots mismatch {} leaves",
+        info.num_leaves
+    );
+    let (mut pos, mut hash) = match subtrees.pop() {
+        Some(subtree) => subtree,
+        None => return Ok(*ACCUMULATOR_PLACEHOLDER_HASH),
+    };
+    for _ in pos.level()..NodeIndex::root_level_from_leaf_count(info.num_leaves) {
+        hash = if pos.is_left_child() {
+            AccumulatorNode::new_internal(pos.parent(), hash, *ACCUMULATOR_PLACEHOLDER_HASH).hash()
+        } else {
+            let (sibling, left_hash) = subtrees
+                .pop()
+                .filter(|(sibling, _)| *sibling == pos.sibling())
+                .ok_or_else(|| format_err!("Accumulator frozen subtree roots are invalid"))?;
+            AccumulatorNode::new_internal(sibling.parent(), left_hash, hash).hash()
+        };
+        pos = pos.parent();
+    }
+    Ok(hash)
+}
+
+/// Verify the blocks against the block accumulator, and the block info of each block against
+/// the header of its child. The pivot block info is verified against the manifest, and its block
+/// accumulator is verified by appending the pivot block to the accumulator of its parent.
+struct BlockImporter<'a> {
+    storage: Arc<dyn Store>,
+    accumulator: &'a MerkleAccumulator,
+    pending: Option<(Block, BlockInfo)>,
+    parent_info: Option<BlockInfo>,
+    written: &'a mut Vec<HashValue>,
+}
+
+impl<'a> BlockImporter<'a> {
+    fn import(&mut self, blocks: Vec<(Block, BlockInfo)>) -> Result<()> {
+        for (block, block_info) in blocks {
+            let header = block.header();
+            StaticVerifier::verify_body_hash(&block)?;
+            ensure!(
+                self.accumulator.get_leaf(header.number())? == Some(block.id()),
+                "Block ({:?},{}) is not on the chain of the pivot block",
+                block.id(),
+                header.number()
+            );
+            ensure!(
+                block_info.block_id == block.id()
+                    && block_info.block_accumulator_info.num_leaves
+                        == header.number().saturating_add(1)
+                    && block_info.txn_accumulator_info.accumulator_root
+                        == header.txn_accumulator_root()
+                    && accumulator_root(&block_info.block_accumulator_info)?
+                        == block_info.block_accumulator_info.accumulator_root
+                    && accumulator_root(&block_info.txn_accumulator_info)?
+                        == block_info.txn_accumulator_info.accumulator_root,
+                "Block info of block {} mismatch its header",
+                block.id()
+            );
+            if let Some((parent, parent_info)) = self.pending.take() {
+                ensure!(
+                    parent.id() == header.parent_hash()
+                        && parent_info.block_accumulator_info.accumulator_root
+                            == header.block_accumulator_root(),
+                    "Block info of block {} mismatch its child",
+                    parent.id()
+                );
+                ensure!(
+                    block_info.total_difficulty
+                        == parent_info
+                            .total_difficulty
+                            .saturating_add(header.difficulty()),
+                    "Total difficulty of block {} mismatch its parent",
+                    block.id()
+                );
+                self.save(parent, parent_info)?;
+            }
+            self.pending = Some((block, block_info));
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, pivot_info: &BlockInfo) -> Result<BlockHeader> {
+        let (pivot, block_info) = self
+            .pending
+            .take()
+            .ok_or_else(|| format_err!("Snapshot misses the pivot block"))?;
+        ensure!(
+            block_info == *pivot_info,
+            "Block info of pivot block {} mismatch the manifest",
+            pivot.id()
+        );
+        let parent_info = self
+            .parent_info
+            .take()
+            .ok_or_else(|| format_err!("Snapshot misses the parent of pivot block"))?;
+        let accumulator = MerkleAccumulator::new_with_info(
+            parent_info.block_accumulator_info,
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Block),
+        );
+        accumulator.append(&[pivot.id()])?;
+        ensure!(
+            accumulator.get_info() == pivot_info.block_accumulator_info,
+            "Block accumulator of pivot block {} mismatch its header",
+            pivot.id()
+        );
+        let header = pivot.header().clone();
+        self.save(pivot, block_info)?;
+        Ok(header)
+    }
+
+    fn save(&mut self, block: Block, block_info: BlockInfo) -> Result<()> {
+        let block_id = block.id();
+        if self.storage.get_block_info(block_id)?.is_none() {
+            self.written.push(block_id);
+        }
+        self.storage.commit_block(block)?;
+        self.storage.save_block_info(block_info.clone())?;
+        self.parent_info = Some(block_info);
+        Ok(())
+    }
+}
+
+/// Verify the state nodes from the state root in breadth first order, same as the accumulator.
+/// The nodes already in the storage are imported again, so every node of the state is pinned in
+/// the pruned state.
+struct StateImporter {
+    storage: Arc<dyn Store>,
+    pending: HashMap<HashValue, StateTreeType>,
+    // the same sub tree may be shared by different accounts, but it is exported only once.
+    imported: HashSet<HashValue>,
+}
+
+impl StateImporter {
+    fn new(storage: Arc<dyn Store>, state_root: HashValue) -> Self {
+        let mut pending = HashMap::new();
+        if state_root != *SPARSE_MERKLE_PLACEHOLDER_HASH {
+            pending.insert(state_root, StateTreeType::Global);
+        }
+        Self {
+            storage,
+            pending,
+            imported: HashSet::new(),
+        }
+    }
+
+    fn import(&mut self, nodes: Vec<(HashValue, Vec<u8>)>) -> Result<()> {
+        let mut verified = BTreeMap::new();
+        for (node_hash, node) in nodes {
+            let node = StateNode(node);
+            match self.pending.remove(&node_hash) {
+                Some(tree_type) => {
+                    for (child, child_type) in
+                        state_node_children(node_hash, node.clone(), tree_type)?
+                    {
+                        if !self.imported.contains(&child) {
+                            self.pending.insert(child, child_type);
+                        }
+                    }
+                    self.imported.insert(node_hash);
+                    verified.insert(node_hash, node);
+                }
+                None => ensure!(
+                    self.imported.contains(&node_hash),
+                    "Unexpected state node {} in snapshot",
+                    node_hash
+                ),
+            }
+        }
+        StateNodeStore::write_nodes(self.storage.as_ref(), verified)
+    }
+
+    fn finish(self) -> Result<()> {
+        ensure!(
+            self.pending.is_empty(),
+            "Snapshot misses {} state nodes",
+            self.pending.len()
+        );
+        Ok(())
+    }
+}
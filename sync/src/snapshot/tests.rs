@@ -0,0 +1,199 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::snapshot::{
+    export_snapshot, import_snapshot, snapshot_checkpoint, SnapshotChunk, SnapshotChunkKind,
+    SnapshotManifest, SNAPSHOT_MANIFEST_FILE,
+};
+use anyhow::{format_err, Result};
+use bcs_ext::BCSCodec;
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_chain_mock::MockChain;
+use starcoin_config::{temp_dir, BuiltinNetworkID, ChainNetwork};
+use starcoin_crypto::HashValue;
+use starcoin_genesis::Genesis;
+use starcoin_statedb::{ChainStateDB, ChainStateReader};
+use starcoin_storage::{BlockInfoStore, BlockStore, Store};
+use starcoin_types::account_config::genesis_address;
+use std::path::Path;
+use std::sync::Arc;
+
+fn export_test_snapshot(output: &Path) -> Result<(MockChain, SnapshotManifest)> {
+    let net = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
+    let mut mock_chain = MockChain::new(net.clone())?;
+    mock_chain.produce_and_apply_times(150)?;
+    let checkpoint_id = snapshot_checkpoint(mock_chain.head(), None)?;
+    let manifest = export_snapshot(
+        mock_chain.head().get_storage(),
+        net.time_service(),
+        checkpoint_id,
+        output,
+    )?;
+    Ok((mock_chain, manifest))
+}
+
+#[stest::test]
+fn test_export_and_import_snapshot() -> Result<()> {
+    let dir = temp_dir();
+    let (mock_chain, manifest) = export_test_snapshot(dir.path())?;
+    assert!(manifest.pivot.number > 0);
+
+    let net = mock_chain.net().clone();
+    let (storage, _chain_info, genesis) = Genesis::init_storage_for_test(&net)?;
+    let storage: Arc<dyn Store> = storage;
+    let pivot = import_snapshot(
+        storage.clone(),
+        genesis.block().id(),
+        manifest.pivot.id,
+        dir.path(),
+    )?;
+    assert_eq!(pivot, manifest.pivot);
+    let startup_info = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Startup info should exist"))?;
+    assert_eq!(startup_info.main, pivot.id);
+
+    let pivot_header = storage
+        .get_block_header_by_hash(pivot.id)?
+        .ok_or_else(|| format_err!("Pivot block should exist"))?;
+    assert_eq!(
+        ChainStateDB::new(storage.into_super_arc(), Some(pivot_header.state_root()))
+            .get_account_state(&genesis_address())?,
+        ChainStateDB::new(
+            mock_chain.head().get_storage().into_super_arc(),
+            Some(pivot_header.state_root()),
+        )
+        .get_account_state(&genesis_address())?
+    );
+
+    // the snapshot only works on a new node.
+    assert!(import_snapshot(
+        mock_chain.head().get_storage(),
+        genesis.block().id(),
+        manifest.pivot.id,
+        dir.path()
+    )
+    .is_err());
+    Ok(())
+}
+
+#[stest::test]
+fn test_import_tampered_snapshot() -> Result<()> {
+    let dir = temp_dir();
+    let (mock_chain, mut manifest) = export_test_snapshot(dir.path())?;
+
+    // alter the last state node and update the manifest, as a malicious provider can do.
+    let chunk_info = manifest
+        .chunks
+        .iter_mut()
+        .rev()
+        .find(|info| info.kind == SnapshotChunkKind::State)
+        .ok_or_else(|| format_err!("State chunk should exist"))?;
+    let chunk_path = dir.path().join(chunk_info.file_name.as_str());
+    let mut nodes = match SnapshotChunk::decode(std::fs::read(chunk_path.as_path())?.as_slice())? {
+        SnapshotChunk::State(nodes) => nodes,
+        chunk => panic!("Unexpected chunk {}", chunk.kind()),
+    };
+    let node = nodes.last_mut().expect("State chunk should not be empty");
+    let last_byte = node.1.last_mut().expect("State node should not be empty");
+    *last_byte = last_byte.wrapping_add(1);
+    let bytes = SnapshotChunk::State(nodes).encode()?;
+    chunk_info.hash = HashValue::sha3_256_of(bytes.as_slice());
+    std::fs::write(chunk_path, bytes)?;
+    std::fs::write(
+        dir.path().join(SNAPSHOT_MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    let (storage, _chain_info, genesis) = Genesis::init_storage_for_test(mock_chain.net())?;
+    assert!(import_snapshot(
+        storage.clone(),
+        genesis.block().id(),
+        manifest.pivot.id,
+        dir.path()
+    )
+    .is_err());
+    let startup_info = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Startup info should exist"))?;
+    assert_eq!(startup_info.main, genesis.block().id());
+
+    // the state chunk is imported last, the blocks and accumulators before it are rolled back.
+    assert!(storage.get_block_by_hash(manifest.pivot.id)?.is_none());
+    assert!(storage.get_block_info(manifest.pivot.id)?.is_none());
+    for (store_type, info) in [
+        (
+            AccumulatorStoreType::Block,
+            &manifest.pivot_info.block_accumulator_info,
+        ),
+        (
+            AccumulatorStoreType::Transaction,
+            &manifest.pivot_info.txn_accumulator_info,
+        ),
+    ] {
+        assert!(storage
+            .get_accumulator_store(store_type)
+            .get_node(info.accumulator_root)?
+            .is_none());
+    }
+    Ok(())
+}
+
+#[stest::test]
+fn test_import_forged_snapshot() -> Result<()> {
+    let trusted_dir = temp_dir();
+    let (mock_chain, trusted_manifest) = export_test_snapshot(trusted_dir.path())?;
+    // a self consistent chain from the same genesis, mined by the provider.
+    let forged_dir = temp_dir();
+    let (_forged_chain, mut forged_manifest) = export_test_snapshot(forged_dir.path())?;
+    assert_ne!(forged_manifest.pivot.id, trusted_manifest.pivot.id);
+    assert_eq!(forged_manifest.pivot.number, trusted_manifest.pivot.number);
+
+    let (storage, _chain_info, genesis) = Genesis::init_storage_for_test(mock_chain.net())?;
+    let storage: Arc<dyn Store> = storage;
+    assert!(import_snapshot(
+        storage.clone(),
+        genesis.block().id(),
+        trusted_manifest.pivot.id,
+        forged_dir.path()
+    )
+    .is_err());
+
+    // the provider claims the trusted pivot in the manifest.
+    forged_manifest.pivot = trusted_manifest.pivot;
+    forged_manifest.pivot_info.block_id = trusted_manifest.pivot.id;
+    std::fs::write(
+        forged_dir.path().join(SNAPSHOT_MANIFEST_FILE),
+        serde_json::to_vec_pretty(&forged_manifest)?,
+    )?;
+    assert!(import_snapshot(
+        storage.clone(),
+        genesis.block().id(),
+        trusted_manifest.pivot.id,
+        forged_dir.path()
+    )
+    .is_err());
+    assert!(storage
+        .get_accumulator_store(AccumulatorStoreType::Block)
+        .get_node(
+            forged_manifest
+                .pivot_info
+                .block_accumulator_info
+                .accumulator_root
+        )?
+        .is_none());
+    let startup_info = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Startup info should exist"))?;
+    assert_eq!(startup_info.main, genesis.block().id());
+
+    // the trusted snapshot still imports after the rollback.
+    let pivot = import_snapshot(
+        storage,
+        genesis.block().id(),
+        trusted_manifest.pivot.id,
+        trusted_dir.path(),
+    )?;
+    assert_eq!(pivot, trusted_manifest.pivot);
+    Ok(())
+}
@@ -3,7 +3,9 @@
 
 use crate::block_connector::BlockConnectorService;
 use crate::sync_metrics::SyncMetrics;
-use crate::tasks::{full_sync_task, AncestorEvent, SyncFetcher};
+use crate::tasks::{
+    fast_sync_task, full_sync_task, AncestorEvent, SyncFetcher, FAST_SYNC_PIVOT_OFFSET,
+};
 use crate::verified_rpc_client::{RpcVerifyError, VerifiedRpcClient};
 use anyhow::{format_err, Result};
 use futures::FutureExt;
@@ -12,7 +14,7 @@ use network_api::peer_score::PeerScoreMetrics;
 use network_api::{PeerId, PeerProvider, PeerSelector, PeerStrategy, ReputationChange};
 use starcoin_chain::BlockChain;
 use starcoin_chain_api::ChainReader;
-use starcoin_config::NodeConfig;
+use starcoin_config::{NodeConfig, SyncMode};
 use starcoin_executor::VMMetrics;
 use starcoin_logger::prelude::*;
 use starcoin_network::NetworkServiceRef;
@@ -21,6 +23,7 @@ use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler,
 };
 use starcoin_storage::block_info::BlockInfoStore;
+use starcoin_storage::state_prune::StatePruneStore;
 use starcoin_storage::{BlockStore, Storage};
 use starcoin_sync_api::{
     PeerScoreRequest, PeerScoreResponse, SyncCancelRequest, SyncProgressReport,
@@ -222,20 +225,46 @@ impl SyncService {
             {
                 info!("[sync] Find target({}), total_difficulty:{}, current head({})'s total_difficulty({})", target.target_id.id(), target.block_info.total_difficulty, current_block_id, current_block_info.total_difficulty);
 
-                let (fut, task_handle, task_event_handle) = full_sync_task(
-                    current_block_id,
-                    target.clone(),
-                    skip_pow_verify,
-                    config.net().time_service(),
-                    storage.clone(),
-                    connector_service.clone(),
-                    rpc_client.clone(),
-                    self_ref.clone(),
-                    network.clone(),
-                    config.sync.max_retry_times(),
-                    sync_metrics.clone(),
-                    vm_metrics.clone(),
-                )?;
+                // fast sync only works on a new node.
+                let fast_sync = config.sync.sync_mode() == SyncMode::Fast
+                    && current_block_info.block_accumulator_info.num_leaves == 1
+                    && target.target_id.number() > FAST_SYNC_PIVOT_OFFSET;
+                if fast_sync && storage.state_pruning_enabled() {
+                    warn!("[sync] Fast sync is not supported when state pruning is enabled, use full sync.");
+                }
+                let (fut, task_handle, task_event_handle) =
+                    if fast_sync && !storage.state_pruning_enabled() {
+                        fast_sync_task(
+                            current_block_id,
+                            target.clone(),
+                            skip_pow_verify,
+                            config.net().time_service(),
+                            storage.clone(),
+                            connector_service.clone(),
+                            rpc_client.clone(),
+                            self_ref.clone(),
+                            connector_service.clone(),
+                            network.clone(),
+                            config.sync.max_retry_times(),
+                            sync_metrics.clone(),
+                            vm_metrics.clone(),
+                        )?
+                    } else {
+                        full_sync_task(
+                            current_block_id,
+                            target.clone(),
+                            skip_pow_verify,
+                            config.net().time_service(),
+                            storage.clone(),
+                            connector_service.clone(),
+                            rpc_client.clone(),
+                            self_ref.clone(),
+                            network.clone(),
+                            config.sync.max_retry_times(),
+                            sync_metrics.clone(),
+                            vm_metrics.clone(),
+                        )?
+                    };
 
                 self_ref.notify(SyncBeginEvent {
                     target,
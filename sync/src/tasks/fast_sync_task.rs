@@ -0,0 +1,792 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sync_metrics::SyncMetrics;
+use crate::tasks::{
+    full_sync_future, AccumulatorCollector, AncestorEventHandle, BlockAccumulatorSyncTask,
+    BlockConnectedEventHandle, BlockFetcher, BlockIdFetcher, BlockInfoFetcher,
+    ExtSyncTaskErrorHandle, PeerOperator, PivotEvent, PivotEventHandle, StateSyncFetcher,
+    SyncFetcher,
+};
+use crate::verified_rpc_client::RpcVerifyError;
+use anyhow::{ensure, format_err, Result};
+use bcs_ext::BCSCodec;
+use forkable_jellyfish_merkle::node_type::Node;
+use forkable_jellyfish_merkle::RawKey;
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use futures_timer::Delay;
+use network_api::{PeerId, PeerProvider};
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::node_index::NodeIndex;
+use starcoin_accumulator::{Accumulator, AccumulatorNode, MerkleAccumulator};
+use starcoin_chain::BlockChain;
+use starcoin_crypto::hash::{ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use starcoin_crypto::HashValue;
+use starcoin_executor::VMMetrics;
+use starcoin_logger::prelude::*;
+use starcoin_state_api::TABLE_PATH_LIST;
+use starcoin_state_tree::{StateNode, StateNodeStore};
+use starcoin_storage::Store;
+use starcoin_sync_api::SyncTarget;
+use starcoin_time_service::TimeService;
+use starcoin_types::access_path::AccessPath;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_config::genesis_address;
+use starcoin_types::account_state::AccountState;
+use starcoin_types::block::{Block, BlockIdAndNumber, BlockInfo, BlockNumber};
+use starcoin_types::U256;
+use starcoin_vm_types::access_path::ModuleName;
+use starcoin_vm_types::account_config::TABLE_HANDLE_ADDRESS_LIST;
+use starcoin_vm_types::language_storage::StructTag;
+use starcoin_vm_types::move_resource::MoveResource;
+use starcoin_vm_types::on_chain_resource::Epoch;
+use starcoin_vm_types::state_store::table::TableHandle;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::TryInto;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use stream_task::{
+    Generator, TaskError, TaskEventCounterHandle, TaskEventHandle, TaskFuture, TaskGenerator,
+    TaskHandle,
+};
+
+/// The pivot is searched from the block `FAST_SYNC_PIVOT_OFFSET` blocks behind the target,
+/// which is unlikely to be rolled back.
+pub const FAST_SYNC_PIVOT_OFFSET: u64 = 64;
+
+/// How many target peers must agree on the pivot block.
+const MAX_PIVOT_PEERS: usize = 3;
+const NODE_FETCH_CONCURRENCY: usize = 16;
+const NODE_SAVE_BATCH_SIZE: usize = 1024;
+const BLOCK_FETCH_BATCH_SIZE: usize = 10;
+const DELAY_MILLISECONDS_ON_ERROR: u64 = 100;
+
+/// Sync to `target` by downloading the state at a pivot block instead of executing the blocks
+/// from genesis, then execute the blocks after the pivot as full sync.
+///
+/// The pivot is the last block of the epoch before the block `FAST_SYNC_PIVOT_OFFSET` blocks
+/// behind the target, so the uncles of the blocks after the pivot never refer to a block
+/// whose state is missing. The block accumulator, the transaction accumulator and the state
+/// tree of the pivot are downloaded and verified node by node against the pivot header,
+/// and the blocks of the pivot epoch are downloaded without execution, their total difficulty
+/// is accumulated from the headers, only the base before the pivot epoch is confirmed by peers.
+/// The transactions, transaction infos and events before the pivot are not available locally.
+///
+/// Fall back to full sync if the pivot is genesis.
+pub fn fast_sync_task<H, A, P, F, N>(
+    current_block_id: HashValue,
+    target: SyncTarget,
+    skip_pow_verify: bool,
+    time_service: Arc<dyn TimeService>,
+    storage: Arc<dyn Store>,
+    block_event_handle: H,
+    fetcher: Arc<F>,
+    ancestor_event_handle: A,
+    pivot_event_handle: P,
+    peer_provider: N,
+    max_retry_times: u64,
+    sync_metrics: Option<SyncMetrics>,
+    vm_metrics: Option<VMMetrics>,
+) -> Result<(
+    BoxFuture<'static, Result<BlockChain, TaskError>>,
+    TaskHandle,
+    Arc<TaskEventCounterHandle>,
+)>
+where
+    H: BlockConnectedEventHandle + Sync + 'static,
+    A: AncestorEventHandle + Sync + 'static,
+    P: PivotEventHandle + Sync + 'static,
+    F: SyncFetcher + StateSyncFetcher + 'static,
+    N: PeerProvider + Clone + 'static,
+{
+    let current_block_info = storage
+        .get_block_info(current_block_id)?
+        .ok_or_else(|| format_err!("Can not find block info by id: {}", current_block_id))?;
+    ensure!(
+        current_block_info.block_accumulator_info.num_leaves == 1,
+        "Fast sync only support start from genesis, current block: {}",
+        current_block_id
+    );
+    ensure!(
+        target.target_id.number() > FAST_SYNC_PIVOT_OFFSET,
+        "Target block number {} is too small for fast sync",
+        target.target_id.number()
+    );
+    let event_handle = Arc::new(TaskEventCounterHandle::new());
+    let event_handle_clone = event_handle.clone();
+    fetcher.peer_selector().retain(target.peers.as_slice());
+
+    let all_fut = async move {
+        let state_sync = StateSyncer {
+            fetcher: fetcher.clone(),
+            storage: storage.clone(),
+            event_handle: event_handle_clone.clone(),
+            max_retry_times,
+        };
+        let pivot = state_sync
+            .sync_to_pivot(current_block_info, &target)
+            .await
+            .map_err(TaskError::BreakError)?;
+        let current_block_id = match pivot {
+            Some(pivot) => {
+                info!(
+                    "[sync] Fast sync state to pivot block ({:?},{}) done.",
+                    pivot.id, pivot.number
+                );
+                let mut pivot_event_handle = pivot_event_handle;
+                pivot_event_handle
+                    .handle(PivotEvent { pivot })
+                    .map_err(TaskError::BreakError)?;
+                pivot.id
+            }
+            None => {
+                info!("[sync] No pivot block for fast sync, fall back to full sync.");
+                current_block_id
+            }
+        };
+        full_sync_future(
+            current_block_id,
+            target,
+            skip_pow_verify,
+            time_service,
+            storage,
+            block_event_handle,
+            fetcher,
+            ancestor_event_handle,
+            peer_provider,
+            max_retry_times,
+            sync_metrics,
+            vm_metrics,
+            event_handle_clone,
+        )
+        .map_err(TaskError::BreakError)?
+        .await
+    };
+    let task = TaskFuture::new(all_fut.boxed());
+    let (fut, handle) = task.with_handle();
+    Ok((fut, handle, event_handle))
+}
+
+/// The kind of the state tree a node belongs to, the key type of each tree is different.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum StateTreeType {
+    Global,
+    Code,
+    Resource(AccountAddress),
+    TableHandles,
+    TableItems,
+}
+
+struct StateSyncer<F>
+where
+    F: SyncFetcher + StateSyncFetcher + 'static,
+{
+    fetcher: Arc<F>,
+    storage: Arc<dyn Store>,
+    event_handle: Arc<TaskEventCounterHandle>,
+    max_retry_times: u64,
+}
+
+impl<F> StateSyncer<F>
+where
+    F: SyncFetcher + StateSyncFetcher + 'static,
+{
+    /// Download the state of the pivot block, return `None` if the pivot is genesis.
+    async fn sync_to_pivot(
+        &self,
+        genesis_block_info: BlockInfo,
+        target: &SyncTarget,
+    ) -> Result<Option<BlockIdAndNumber>> {
+        let checkpoint_number = target
+            .target_id
+            .number()
+            .saturating_sub(FAST_SYNC_PIVOT_OFFSET);
+        let checkpoint_id = self.confirm_block_id(target, checkpoint_number).await?;
+        let checkpoint = self.fetch_block(checkpoint_id).await?;
+        let block_accumulator = self
+            .sync_block_accumulator(genesis_block_info, &checkpoint)
+            .await?;
+
+        let epoch = self.fetch_epoch(checkpoint.header().state_root()).await?;
+        let pivot_number = epoch.start_block_number().saturating_sub(1);
+        if pivot_number == 0 {
+            return Ok(None);
+        }
+        let pivot_id = block_accumulator
+            .get_leaf(pivot_number)?
+            .ok_or_else(|| format_err!("Can not find pivot block by number {}", pivot_number))?;
+        let next_id = block_accumulator
+            .get_leaf(pivot_number.saturating_add(1))?
+            .ok_or_else(|| {
+                format_err!(
+                    "Can not find block by number {}",
+                    pivot_number.saturating_add(1)
+                )
+            })?;
+        let pivot = self.fetch_block(pivot_id).await?;
+        let next = self.fetch_block(next_id).await?;
+        let (peer_id, pivot_info) = self.fetch_block_info(pivot_id).await?;
+        ensure!(
+            pivot_info.block_accumulator_info.num_leaves == pivot_number.saturating_add(1)
+                && pivot_info.block_accumulator_info.accumulator_root
+                    == next.header().block_accumulator_root()
+                && pivot_info.txn_accumulator_info.accumulator_root
+                    == pivot.header().txn_accumulator_root(),
+            RpcVerifyError::new(
+                peer_id.clone(),
+                format!("Block info of pivot block {} mismatch header", pivot_id)
+            )
+        );
+        info!(
+            "[sync] Fast sync select pivot block ({:?},{}), state root: {}",
+            pivot_id,
+            pivot_number,
+            pivot.header().state_root()
+        );
+
+        self.sync_txn_accumulator(&pivot_info).await?;
+        self.sync_state_tree(pivot.header().state_root()).await?;
+
+        // the blocks of the pivot epoch are required for the uncles and the difficulty.
+        let pivot_epoch = self.fetch_epoch(pivot.header().state_root()).await?;
+        let start_number = pivot_epoch
+            .start_block_number()
+            .min(pivot_number.saturating_sub(pivot_epoch.block_difficulty_window()));
+        let parent_total_difficulty = match start_number.checked_sub(1) {
+            Some(parent_number) => {
+                let parent_id = block_accumulator
+                    .get_leaf(parent_number)?
+                    .ok_or_else(|| format_err!("Can not find block by number {}", parent_number))?;
+                self.confirm_total_difficulty(target, parent_id).await?
+            }
+            None => U256::zero(),
+        };
+        let total_difficulty = self
+            .sync_blocks(
+                &block_accumulator,
+                start_number,
+                pivot_number,
+                parent_total_difficulty,
+            )
+            .await?
+            .saturating_add(pivot.header().difficulty());
+        ensure!(
+            pivot_info.total_difficulty == total_difficulty,
+            RpcVerifyError::new(
+                peer_id,
+                format!(
+                    "Total difficulty of pivot block {} mismatch, expect: {}, got: {}",
+                    pivot_id, total_difficulty, pivot_info.total_difficulty
+                )
+            )
+        );
+
+        self.storage.commit_block(pivot)?;
+        self.storage.save_block_info(pivot_info)?;
+        Ok(Some(BlockIdAndNumber::new(pivot_id, pivot_number)))
+    }
+
+    /// Get the block id at `number` from the target peers, they must agree on it.
+    async fn confirm_block_id(
+        &self,
+        target: &SyncTarget,
+        number: BlockNumber,
+    ) -> Result<HashValue> {
+        let mut block_id = None;
+        for peer_id in target.peers.iter().take(MAX_PIVOT_PEERS) {
+            let id = self
+                .fetcher
+                .fetch_block_id(Some(peer_id.clone()), number)
+                .await?
+                .ok_or_else(|| format_err!("Peer {} has no block at {}", peer_id, number))?;
+            match block_id {
+                Some(block_id) if block_id != id => {
+                    return Err(RpcVerifyError::new_with_peers(
+                        target.peers.clone(),
+                        format!("Peers return different block ids at {}", number),
+                    )
+                    .into());
+                }
+                _ => block_id = Some(id),
+            }
+        }
+        block_id.ok_or_else(|| format_err!("No peers to confirm the block at {}", number))
+    }
+
+    /// Get the total difficulty of the block from the target peers, they must agree on it.
+    async fn confirm_total_difficulty(
+        &self,
+        target: &SyncTarget,
+        block_id: HashValue,
+    ) -> Result<U256> {
+        let mut total_difficulty = None;
+        for peer_id in target.peers.iter().take(MAX_PIVOT_PEERS) {
+            let block_info = self
+                .fetcher
+                .fetch_block_info(Some(peer_id.clone()), block_id)
+                .await?
+                .filter(|block_info| block_info.block_id == block_id)
+                .ok_or_else(|| format_err!("Peer {} has no block info of {}", peer_id, block_id))?;
+            match total_difficulty {
+                Some(total_difficulty) if total_difficulty != block_info.total_difficulty => {
+                    return Err(RpcVerifyError::new_with_peers(
+                        target.peers.clone(),
+                        format!("Peers return different total difficulty of {}", block_id),
+                    )
+                    .into());
+                }
+                _ => total_difficulty = Some(block_info.total_difficulty),
+            }
+        }
+        total_difficulty
+            .ok_or_else(|| format_err!("No peers to confirm the block info of {}", block_id))
+    }
+
+    async fn fetch_block(&self, block_id: HashValue) -> Result<Block> {
+        self.fetcher
+            .fetch_blocks(vec![block_id])
+            .await?
+            .pop()
+            .map(|(block, _)| block)
+            .ok_or_else(|| format_err!("Can not fetch block by id: {}", block_id))
+    }
+
+    async fn fetch_block_info(&self, block_id: HashValue) -> Result<(PeerId, BlockInfo)> {
+        let peer_id = self
+            .fetcher
+            .peer_selector()
+            .select_peer()
+            .ok_or_else(|| format_err!("No peers for fetching block info."))?;
+        let block_info = self
+            .fetcher
+            .fetch_block_info(Some(peer_id.clone()), block_id)
+            .await?
+            .ok_or_else(|| format_err!("Can not fetch block info by id: {}", block_id))?;
+        ensure!(
+            block_info.block_id == block_id,
+            RpcVerifyError::new(
+                peer_id,
+                format!(
+                    "Fetch block info by id {}, but got {}",
+                    block_id, block_info.block_id
+                )
+            )
+        );
+        Ok((peer_id, block_info))
+    }
+
+    async fn fetch_epoch(&self, state_root: HashValue) -> Result<Epoch> {
+        let state = self
+            .fetcher
+            .fetch_state_with_proof(
+                state_root,
+                AccessPath::new(genesis_address(), Epoch::resource_path()),
+            )
+            .await?
+            .state
+            .ok_or_else(|| format_err!("Can not find epoch in state {}", state_root))?;
+        Epoch::decode(state.as_slice())
+    }
+
+    /// Sync the block ids from genesis to `checkpoint`, the accumulator of the parent blocks
+    /// is verified by the checkpoint header.
+    async fn sync_block_accumulator(
+        &self,
+        genesis_block_info: BlockInfo,
+        checkpoint: &Block,
+    ) -> Result<MerkleAccumulator> {
+        let parent_id = checkpoint.header().parent_hash();
+        let (peer_id, parent_info) = self.fetch_block_info(parent_id).await?;
+        let target = parent_info.block_accumulator_info;
+        ensure!(
+            target.num_leaves == checkpoint.header().number()
+                && target.accumulator_root == checkpoint.header().block_accumulator_root(),
+            RpcVerifyError::new(
+                peer_id,
+                format!("Block info of block {} mismatch its child", parent_id)
+            )
+        );
+        let store = self
+            .storage
+            .get_accumulator_store(AccumulatorStoreType::Block);
+        let genesis = BlockIdAndNumber::new(genesis_block_info.block_id, 0);
+        let accumulator = if target.num_leaves > 1 {
+            let accumulator_sync_task =
+                BlockAccumulatorSyncTask::new(1, target.clone(), self.fetcher.clone(), 100)?;
+            let (_, accumulator) = TaskGenerator::new(
+                accumulator_sync_task,
+                self.fetcher.peer_selector().len().max(1),
+                self.max_retry_times,
+                DELAY_MILLISECONDS_ON_ERROR,
+                AccumulatorCollector::new(
+                    store,
+                    genesis,
+                    genesis_block_info.block_accumulator_info,
+                    target,
+                ),
+                self.event_handle.clone(),
+                Arc::new(ExtSyncTaskErrorHandle::new(self.fetcher.clone())),
+            )
+            .generate()
+            .await?;
+            accumulator
+        } else {
+            MerkleAccumulator::new_with_info(genesis_block_info.block_accumulator_info, store)
+        };
+        accumulator.append(&[checkpoint.id()])?;
+        accumulator.flush()?;
+        Ok(accumulator)
+    }
+
+    /// Download the transaction accumulator nodes of the pivot block.
+    async fn sync_txn_accumulator(&self, pivot_info: &BlockInfo) -> Result<()> {
+        let info = &pivot_info.txn_accumulator_info;
+        if info.num_leaves == 0 {
+            return Ok(());
+        }
+        let store = self
+            .storage
+            .get_accumulator_store(AccumulatorStoreType::Transaction);
+        let root = (
+            info.accumulator_root,
+            NodeIndex::root_from_leaf_count(info.num_leaves),
+        );
+        let local_store = store.clone();
+        let fetcher = self.fetcher.clone();
+        sync_tree(
+            "TxnAccumulatorSyncTask",
+            vec![root],
+            move |(node_key, node_index): (HashValue, NodeIndex)| {
+                let local_store = local_store.clone();
+                let fetcher = fetcher.clone();
+                async move {
+                    let (node, is_local) = match local_store.get_node(node_key)? {
+                        Some(node) => (node, true),
+                        None => {
+                            let (peer_id, node) = fetcher
+                                .fetch_accumulator_node(node_key, AccumulatorStoreType::Transaction)
+                                .await?;
+                            ensure!(
+                                node.hash() == node_key && node.index() == node_index,
+                                RpcVerifyError::new(
+                                    peer_id,
+                                    format!("Accumulator node {} mismatch", node_key)
+                                )
+                            );
+                            (node, false)
+                        }
+                    };
+                    let children = match &node {
+                        AccumulatorNode::Internal(internal) => vec![
+                            (internal.left(), node_index.left_child()),
+                            (internal.right(), node_index.right_child()),
+                        ]
+                        .into_iter()
+                        .filter(|(hash, _)| *hash != *ACCUMULATOR_PLACEHOLDER_HASH)
+                        .collect(),
+                        _ => vec![],
+                    };
+                    Ok::<_, anyhow::Error>((if is_local { None } else { Some(node) }, children))
+                }
+            },
+            |nodes: Vec<AccumulatorNode>| store.save_nodes(nodes),
+            self.event_handle.clone(),
+            self.max_retry_times,
+        )
+        .await
+    }
+
+    /// Download the global state tree and all the account state trees from `state_root`.
+    async fn sync_state_tree(&self, state_root: HashValue) -> Result<()> {
+        let storage = self.storage.clone();
+        let fetcher = self.fetcher.clone();
+        sync_tree(
+            "StateSyncTask",
+            vec![(state_root, StateTreeType::Global)],
+            move |(node_key, tree_type): (HashValue, StateTreeType)| {
+                let storage = storage.clone();
+                let fetcher = fetcher.clone();
+                async move {
+                    // the local node is written again to pin it in the pruned state, or it may
+                    // be released by the block which makes it stale.
+                    if let Some(node) = StateNodeStore::get(storage.as_ref(), &node_key)? {
+                        let children = state_node_children(node_key, node.clone(), tree_type)?;
+                        return Ok((Some((node_key, node)), children));
+                    }
+                    let (peer_id, node) = fetcher.fetch_state_node(node_key).await?;
+                    let node = node.ok_or_else(|| {
+                        format_err!("Peer {} has no state node {}", peer_id, node_key)
+                    })?;
+                    let children =
+                        state_node_children(node_key, node.clone(), tree_type).map_err(|e| {
+                            RpcVerifyError::new(
+                                peer_id,
+                                format!("Invalid state node {}: {}", node_key, e),
+                            )
+                        })?;
+                    Ok::<_, anyhow::Error>((Some((node_key, node)), children))
+                }
+            },
+            |nodes: Vec<(HashValue, StateNode)>| {
+                StateNodeStore::write_nodes(
+                    self.storage.as_ref(),
+                    nodes.into_iter().collect::<BTreeMap<_, _>>(),
+                )
+            },
+            self.event_handle.clone(),
+            self.max_retry_times,
+        )
+        .await
+    }
+
+    /// Fetch the block info of the block, and verify it against the header and the total
+    /// difficulty accumulated from the headers.
+    async fn fetch_verified_block_info(
+        &self,
+        block: &Block,
+        total_difficulty: U256,
+    ) -> Result<BlockInfo> {
+        let (peer_id, block_info) = self.fetch_block_info(block.id()).await?;
+        ensure!(
+            block_info.total_difficulty == total_difficulty
+                && block_info.txn_accumulator_info.accumulator_root
+                    == block.header().txn_accumulator_root()
+                && block_info.block_accumulator_info.num_leaves
+                    == block.header().number().saturating_add(1),
+            RpcVerifyError::new(
+                peer_id,
+                format!("Block info of block {} mismatch header", block.id())
+            )
+        );
+        Ok(block_info)
+    }
+
+    /// Download the blocks in `[start_number, end_number)` and their block infos without execution,
+    /// return the total difficulty of the last block.
+    async fn sync_blocks(
+        &self,
+        block_accumulator: &MerkleAccumulator,
+        start_number: BlockNumber,
+        end_number: BlockNumber,
+        parent_total_difficulty: U256,
+    ) -> Result<U256> {
+        let mut total_difficulty = parent_total_difficulty;
+        let block_ids = (start_number..end_number)
+            .map(|number| {
+                block_accumulator
+                    .get_leaf(number)?
+                    .ok_or_else(|| format_err!("Can not find block by number {}", number))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.event_handle.on_start(
+            "FastSyncBlockTask".to_string(),
+            Some(block_ids.len() as u64),
+        );
+        for ids in block_ids.chunks(BLOCK_FETCH_BATCH_SIZE) {
+            let ids = ids.to_vec();
+            let blocks = retry(self.max_retry_times, self.event_handle.as_ref(), || {
+                self.fetcher.fetch_blocks(ids.clone())
+            })
+            .await?;
+            for ((block, _), id) in blocks.into_iter().zip(ids) {
+                ensure!(
+                    block.id() == id,
+                    "Fetch block by id {}, but got {}",
+                    id,
+                    block.id()
+                );
+                if let Some(block_info) = self.storage.get_block_info(block.id())? {
+                    total_difficulty = block_info.total_difficulty;
+                    self.event_handle.on_item();
+                    continue;
+                }
+                total_difficulty = total_difficulty.saturating_add(block.header().difficulty());
+                let block_info = retry(self.max_retry_times, self.event_handle.as_ref(), || {
+                    self.fetch_verified_block_info(&block, total_difficulty)
+                })
+                .await?;
+                self.storage.commit_block(block)?;
+                self.storage.save_block_info(block_info)?;
+                self.event_handle.on_item();
+            }
+            self.event_handle.on_ok();
+        }
+        self.event_handle.on_finish("FastSyncBlockTask".to_string());
+        Ok(total_difficulty)
+    }
+}
+
+/// Verify the state node against its key, and return its children.
+/// The leaves of the global tree and the table handle trees link to the roots of other trees.
+pub(crate) fn state_node_children(
+    node_key: HashValue,
+    node: StateNode,
+    tree_type: StateTreeType,
+) -> Result<Vec<(HashValue, StateTreeType)>> {
+    let children = match tree_type {
+        StateTreeType::Global => match decode_state_node::<AccountAddress>(node_key, node)? {
+            Node::Leaf(leaf) => {
+                let account_state = AccountState::decode(leaf.blob().as_ref())?;
+                let mut children = vec![(
+                    account_state.resource_root(),
+                    StateTreeType::Resource(*leaf.raw_key()),
+                )];
+                if let Some(code_root) = account_state.code_root() {
+                    children.push((code_root, StateTreeType::Code));
+                }
+                children
+            }
+            node => internal_children(node, tree_type),
+        },
+        StateTreeType::Code => {
+            internal_children(decode_state_node::<ModuleName>(node_key, node)?, tree_type)
+        }
+        StateTreeType::Resource(address) => match decode_state_node::<StructTag>(node_key, node)? {
+            Node::Leaf(leaf) => {
+                let is_table_root = TABLE_HANDLE_ADDRESS_LIST
+                    .iter()
+                    .zip(TABLE_PATH_LIST.iter())
+                    .any(|(handle_address, table_path)| {
+                        *handle_address == address
+                            && table_path.as_struct_tag() == Some(leaf.raw_key())
+                    });
+                if is_table_root {
+                    vec![(
+                        HashValue::from_slice(leaf.blob().as_ref())?,
+                        StateTreeType::TableHandles,
+                    )]
+                } else {
+                    vec![]
+                }
+            }
+            node => internal_children(node, tree_type),
+        },
+        StateTreeType::TableHandles => match decode_state_node::<TableHandle>(node_key, node)? {
+            Node::Leaf(leaf) => vec![(
+                HashValue::from_slice(leaf.blob().as_ref())?,
+                StateTreeType::TableItems,
+            )],
+            node => internal_children(node, tree_type),
+        },
+        StateTreeType::TableItems => {
+            internal_children(decode_state_node::<Vec<u8>>(node_key, node)?, tree_type)
+        }
+    };
+    Ok(children
+        .into_iter()
+        .filter(|(hash, _)| *hash != *SPARSE_MERKLE_PLACEHOLDER_HASH)
+        .collect())
+}
+
+fn decode_state_node<K: RawKey>(node_key: HashValue, node: StateNode) -> Result<Node<K>> {
+    let node: Node<K> = node.try_into()?;
+    let hash = node.hash();
+    ensure!(
+        hash == node_key,
+        "State node hash mismatch, expect: {}, got: {}",
+        node_key,
+        hash
+    );
+    Ok(node)
+}
+
+fn internal_children<K: RawKey>(
+    node: Node<K>,
+    tree_type: StateTreeType,
+) -> Vec<(HashValue, StateTreeType)> {
+    match node {
+        Node::Internal(internal) => internal
+            .all_child()
+            .into_iter()
+            .map(|hash| (hash, tree_type))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Download the nodes of merkle trees from `roots` in breadth first order.
+/// `fetch_node` returns the verified node, or `None` if the node exists locally,
+/// and the keys of its children.
+async fn sync_tree<K, T, FetchFn, FetchFut, SaveFn>(
+    task_name: &str,
+    roots: Vec<K>,
+    fetch_node: FetchFn,
+    mut save_nodes: SaveFn,
+    event_handle: Arc<TaskEventCounterHandle>,
+    max_retry_times: u64,
+) -> Result<()>
+where
+    K: Clone + Send,
+    FetchFn: Fn(K) -> FetchFut + Send + Sync,
+    FetchFut: Future<Output = Result<(Option<T>, Vec<K>)>> + Send,
+    SaveFn: FnMut(Vec<T>) -> Result<()>,
+{
+    event_handle.on_start(task_name.to_string(), None);
+    let mut pending: VecDeque<K> = roots.into();
+    let mut fetching = FuturesUnordered::new();
+    let mut nodes = vec![];
+    loop {
+        while fetching.len() < NODE_FETCH_CONCURRENCY {
+            match pending.pop_front() {
+                Some(key) => {
+                    let fetch_node = &fetch_node;
+                    let event_handle = event_handle.as_ref();
+                    fetching.push(retry(max_retry_times, event_handle, move || {
+                        fetch_node(key.clone())
+                    }));
+                }
+                None => break,
+            }
+        }
+        match fetching.next().await {
+            Some(result) => {
+                let (node, children) = result?;
+                if let Some(node) = node {
+                    nodes.push(node);
+                    if nodes.len() >= NODE_SAVE_BATCH_SIZE {
+                        save_nodes(std::mem::take(&mut nodes))?;
+                        event_handle.on_ok();
+                    }
+                }
+                pending.extend(children);
+                event_handle.on_item();
+            }
+            None => break,
+        }
+    }
+    if !nodes.is_empty() {
+        save_nodes(nodes)?;
+        event_handle.on_ok();
+    }
+    event_handle.on_finish(task_name.to_string());
+    Ok(())
+}
+
+async fn retry<T, Fut>(
+    max_retry_times: u64,
+    event_handle: &dyn TaskEventHandle,
+    f: impl Fn() -> Fut,
+) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retry_times = 0u64;
+    loop {
+        match f().await {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                event_handle.on_error();
+                if retry_times >= max_retry_times {
+                    return Err(e);
+                }
+                debug!("[sync] Fast sync fetch error, retry: {:?}", e);
+                retry_times = retry_times.saturating_add(1);
+                event_handle.on_retry();
+                Delay::new(Duration::from_millis(DELAY_MILLISECONDS_ON_ERROR)).await;
+            }
+        }
+    }
+}
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::tasks::{
-    BlockConnectedEvent, BlockFetcher, BlockIdFetcher, BlockInfoFetcher, PeerOperator, SyncFetcher,
+    BlockConnectedEvent, BlockFetcher, BlockIdFetcher, BlockInfoFetcher, PeerOperator,
+    StateSyncFetcher, SyncFetcher,
 };
 use anyhow::{format_err, Context, Result};
 use async_std::task::JoinHandle;
@@ -14,14 +15,18 @@ use network_api::messages::NotificationMessage;
 use network_api::{PeerId, PeerInfo, PeerSelector, PeerStrategy};
 use network_p2p_core::{NetRpcError, RpcErrorCode};
 use rand::Rng;
-use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, AccumulatorNode, MerkleAccumulator};
 use starcoin_chain::BlockChain;
 use starcoin_chain_api::ChainReader;
 use starcoin_chain_mock::MockChain;
 use starcoin_config::ChainNetwork;
 use starcoin_crypto::HashValue;
 use starcoin_network_rpc_api::G_RPC_INFO;
+use starcoin_state_tree::{StateNode, StateNodeStore};
+use starcoin_statedb::{ChainStateDB, ChainStateReader, StateWithProof};
 use starcoin_sync_api::SyncTarget;
+use starcoin_types::access_path::AccessPath;
 use starcoin_types::block::{Block, BlockIdAndNumber, BlockInfo, BlockNumber};
 use std::sync::Arc;
 use std::time::Duration;
@@ -348,3 +353,53 @@ impl BlockInfoFetcher for SyncNodeMocker {
 }
 
 impl SyncFetcher for SyncNodeMocker {}
+
+impl StateSyncFetcher for SyncNodeMocker {
+    fn fetch_state_node(
+        &self,
+        node_key: HashValue,
+    ) -> BoxFuture<Result<(PeerId, Option<StateNode>)>> {
+        let result = StateNodeStore::get(self.chain().get_storage().as_ref(), &node_key);
+        async move {
+            self.err_mocker.random_err().await?;
+            Ok((self.peer_id.clone(), result?))
+        }
+        .boxed()
+    }
+
+    fn fetch_accumulator_node(
+        &self,
+        node_key: HashValue,
+        accumulator_type: AccumulatorStoreType,
+    ) -> BoxFuture<Result<(PeerId, AccumulatorNode)>> {
+        let result = self
+            .chain()
+            .get_storage()
+            .get_accumulator_store(accumulator_type)
+            .get_node(node_key);
+        async move {
+            self.err_mocker.random_err().await?;
+            let node =
+                result?.ok_or_else(|| format_err!("Can not find accumulator node {}", node_key))?;
+            Ok((self.peer_id.clone(), node))
+        }
+        .boxed()
+    }
+
+    fn fetch_state_with_proof(
+        &self,
+        state_root: HashValue,
+        access_path: AccessPath,
+    ) -> BoxFuture<Result<StateWithProof>> {
+        let result = ChainStateDB::new(
+            self.chain().get_storage().into_super_arc(),
+            Some(state_root),
+        )
+        .get_with_proof(&access_path);
+        async move {
+            self.err_mocker.random_err().await?;
+            result
+        }
+        .boxed()
+    }
+}
@@ -11,14 +11,17 @@ use futures::{FutureExt, TryFutureExt};
 use network_api::{PeerId, PeerProvider, PeerSelector};
 use network_p2p_core::{NetRpcError, RpcErrorCode};
 use starcoin_accumulator::node::AccumulatorStoreType;
-use starcoin_accumulator::MerkleAccumulator;
+use starcoin_accumulator::{AccumulatorNode, MerkleAccumulator};
 use starcoin_chain::{BlockChain, ChainReader};
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_service_registry::{ActorService, EventHandler, ServiceRef};
+use starcoin_state_api::StateWithProof;
+use starcoin_state_tree::StateNode;
 use starcoin_storage::Store;
 use starcoin_sync_api::SyncTarget;
 use starcoin_time_service::TimeService;
+use starcoin_types::access_path::AccessPath;
 use starcoin_types::block::{Block, BlockIdAndNumber, BlockInfo, BlockNumber};
 use starcoin_types::startup_info::ChainStatus;
 use starcoin_types::U256;
@@ -360,6 +363,87 @@ impl BlockInfoFetcher for VerifiedRpcClient {
 
 impl SyncFetcher for VerifiedRpcClient {}
 
+/// Fetch the state tree and the accumulator nodes for fast sync.
+pub trait StateSyncFetcher: Send + Sync {
+    /// The returned node is not verified, because the hash of a leaf node depends on the key type of the tree.
+    fn fetch_state_node(
+        &self,
+        node_key: HashValue,
+    ) -> BoxFuture<Result<(PeerId, Option<StateNode>)>>;
+
+    fn fetch_accumulator_node(
+        &self,
+        node_key: HashValue,
+        accumulator_type: AccumulatorStoreType,
+    ) -> BoxFuture<Result<(PeerId, AccumulatorNode)>>;
+
+    /// The returned state is verified against `state_root`.
+    fn fetch_state_with_proof(
+        &self,
+        state_root: HashValue,
+        access_path: AccessPath,
+    ) -> BoxFuture<Result<StateWithProof>>;
+}
+
+impl<T> StateSyncFetcher for Arc<T>
+where
+    T: StateSyncFetcher,
+{
+    fn fetch_state_node(
+        &self,
+        node_key: HashValue,
+    ) -> BoxFuture<Result<(PeerId, Option<StateNode>)>> {
+        StateSyncFetcher::fetch_state_node(self.as_ref(), node_key)
+    }
+
+    fn fetch_accumulator_node(
+        &self,
+        node_key: HashValue,
+        accumulator_type: AccumulatorStoreType,
+    ) -> BoxFuture<Result<(PeerId, AccumulatorNode)>> {
+        StateSyncFetcher::fetch_accumulator_node(self.as_ref(), node_key, accumulator_type)
+    }
+
+    fn fetch_state_with_proof(
+        &self,
+        state_root: HashValue,
+        access_path: AccessPath,
+    ) -> BoxFuture<Result<StateWithProof>> {
+        StateSyncFetcher::fetch_state_with_proof(self.as_ref(), state_root, access_path)
+    }
+}
+
+impl StateSyncFetcher for VerifiedRpcClient {
+    fn fetch_state_node(
+        &self,
+        node_key: HashValue,
+    ) -> BoxFuture<Result<(PeerId, Option<StateNode>)>> {
+        self.get_state_node_by_node_hash(node_key)
+            .map_err(fetcher_err_map)
+            .boxed()
+    }
+
+    fn fetch_accumulator_node(
+        &self,
+        node_key: HashValue,
+        accumulator_type: AccumulatorStoreType,
+    ) -> BoxFuture<Result<(PeerId, AccumulatorNode)>> {
+        self.get_accumulator_node_by_node_hash(node_key, accumulator_type)
+            .map_err(fetcher_err_map)
+            .boxed()
+    }
+
+    fn fetch_state_with_proof(
+        &self,
+        state_root: HashValue,
+        access_path: AccessPath,
+    ) -> BoxFuture<Result<StateWithProof>> {
+        self.get_state_with_proof(state_root, access_path)
+            .map_err(fetcher_err_map)
+            .boxed()
+    }
+}
+
 pub trait BlockLocalStore: Send + Sync {
     fn get_block_with_info(&self, block_ids: Vec<HashValue>) -> Result<Vec<Option<SyncBlockData>>>;
 }
@@ -435,6 +519,32 @@ where
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct PivotEvent {
+    pub pivot: BlockIdAndNumber,
+}
+
+pub trait PivotEventHandle: Send + Clone + std::marker::Unpin {
+    fn handle(&mut self, event: PivotEvent) -> Result<()>;
+}
+
+impl PivotEventHandle for UnboundedSender<PivotEvent> {
+    fn handle(&mut self, event: PivotEvent) -> Result<()> {
+        self.start_send(event)?;
+        Ok(())
+    }
+}
+
+impl<S> PivotEventHandle for ServiceRef<S>
+where
+    S: ActorService + EventHandler<S, PivotEvent>,
+{
+    fn handle(&mut self, event: PivotEvent) -> Result<()> {
+        self.notify(event)?;
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct NoOpEventHandle;
 
@@ -504,6 +614,7 @@ where
 
 mod accumulator_sync_task;
 mod block_sync_task;
+mod fast_sync_task;
 mod find_ancestor_task;
 mod inner_sync_task;
 #[cfg(test)]
@@ -514,6 +625,8 @@ mod tests;
 use crate::sync_metrics::SyncMetrics;
 pub use accumulator_sync_task::{AccumulatorCollector, BlockAccumulatorSyncTask};
 pub use block_sync_task::{BlockCollector, BlockSyncTask};
+pub use fast_sync_task::{fast_sync_task, FAST_SYNC_PIVOT_OFFSET};
+pub(crate) use fast_sync_task::{state_node_children, StateTreeType};
 pub use find_ancestor_task::{AncestorCollector, FindAncestorTask};
 use starcoin_executor::VMMetrics;
 
@@ -535,6 +648,50 @@ pub fn full_sync_task<H, A, F, N>(
     TaskHandle,
     Arc<TaskEventCounterHandle>,
 )>
+where
+    H: BlockConnectedEventHandle + Sync + 'static,
+    A: AncestorEventHandle + Sync + 'static,
+    F: SyncFetcher + 'static,
+    N: PeerProvider + Clone + 'static,
+{
+    let event_handle = Arc::new(TaskEventCounterHandle::new());
+    let all_fut = full_sync_future(
+        current_block_id,
+        target,
+        skip_pow_verify,
+        time_service,
+        storage,
+        block_event_handle,
+        fetcher,
+        ancestor_event_handle,
+        peer_provider,
+        max_retry_times,
+        sync_metrics,
+        vm_metrics,
+        event_handle.clone(),
+    )?;
+    let task = TaskFuture::new(all_fut);
+    let (fut, handle) = task.with_handle();
+    Ok((fut, handle, event_handle))
+}
+
+/// Build the future of syncing from `current_block_id` to `target`,
+/// the progress is reported to `event_handle`.
+fn full_sync_future<H, A, F, N>(
+    current_block_id: HashValue,
+    target: SyncTarget,
+    skip_pow_verify: bool,
+    time_service: Arc<dyn TimeService>,
+    storage: Arc<dyn Store>,
+    block_event_handle: H,
+    fetcher: Arc<F>,
+    ancestor_event_handle: A,
+    peer_provider: N,
+    max_retry_times: u64,
+    sync_metrics: Option<SyncMetrics>,
+    vm_metrics: Option<VMMetrics>,
+    event_handle: Arc<TaskEventCounterHandle>,
+) -> Result<BoxFuture<'static, Result<BlockChain, TaskError>>>
 where
     H: BlockConnectedEventHandle + Sync + 'static,
     A: AncestorEventHandle + Sync + 'static,
@@ -550,8 +707,6 @@ where
         .get_block_info(current_block_id)?
         .ok_or_else(|| format_err!("Can not find block info by id: {}", current_block_id))?;
 
-    let event_handle = Arc::new(TaskEventCounterHandle::new());
-
     let target_block_number = target.target_id.number();
 
     let current_block_accumulator_info = current_block_info.block_accumulator_info.clone();
@@ -687,9 +842,7 @@ where
         }
         Ok(latest_block_chain)
     };
-    let task = TaskFuture::new(all_fut.boxed());
-    let (fut, handle) = task.with_handle();
-    Ok((fut, handle, event_handle))
+    Ok(all_fut.boxed())
 }
 
 const MAX_BETTER_PEER_SIZE: u64 = 20;
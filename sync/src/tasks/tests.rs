@@ -5,8 +5,9 @@
 use crate::tasks::block_sync_task::SyncBlockData;
 use crate::tasks::mock::{ErrorStrategy, MockBlockIdFetcher, SyncNodeMocker};
 use crate::tasks::{
-    full_sync_task, AccumulatorCollector, AncestorCollector, BlockAccumulatorSyncTask,
-    BlockCollector, BlockFetcher, BlockLocalStore, BlockSyncTask, FindAncestorTask, SyncFetcher,
+    fast_sync_task, full_sync_task, AccumulatorCollector, AncestorCollector,
+    BlockAccumulatorSyncTask, BlockCollector, BlockFetcher, BlockLocalStore, BlockSyncTask,
+    FindAncestorTask, SyncFetcher,
 };
 use crate::verified_rpc_client::RpcVerifyError;
 use anyhow::Context;
@@ -27,9 +28,11 @@ use starcoin_config::{BuiltinNetworkID, ChainNetwork};
 use starcoin_crypto::HashValue;
 use starcoin_genesis::Genesis;
 use starcoin_logger::prelude::*;
+use starcoin_statedb::{ChainStateDB, ChainStateReader};
 use starcoin_storage::BlockStore;
 use starcoin_sync_api::SyncTarget;
 use starcoin_types::{
+    account_config::genesis_address,
     block::{Block, BlockBody, BlockHeaderBuilder, BlockIdAndNumber, BlockInfo},
     U256,
 };
@@ -119,6 +122,70 @@ pub async fn test_full_sync_new_node() -> Result<()> {
     Ok(())
 }
 
+#[stest::test(timeout = 120)]
+pub async fn test_fast_sync_new_node() -> Result<()> {
+    let net1 = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
+    let mut node1 = SyncNodeMocker::new(net1, 1, 0)?;
+    node1.produce_block(150)?;
+    let arc_node1 = Arc::new(node1);
+
+    let net2 = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
+    let node2 = SyncNodeMocker::new(net2.clone(), 1, 0)?;
+    let target = arc_node1.sync_target();
+    let current_block_header = node2.chain().current_header();
+    let storage = node2.chain().get_storage();
+    let (sender_1, _receiver_1) = unbounded();
+    let (sender_2, _receiver_2) = unbounded();
+    let (sender_3, mut receiver_3) = unbounded();
+    let (sync_task, _task_handle, task_event_counter) = fast_sync_task(
+        current_block_header.id(),
+        target.clone(),
+        false,
+        net2.time_service(),
+        storage.clone(),
+        sender_1,
+        arc_node1.clone(),
+        sender_2,
+        sender_3,
+        DummyNetworkService::default(),
+        15,
+        None,
+        None,
+    )?;
+    let branch = sync_task.await?;
+    assert_eq!(branch.current_header().id(), target.target_id.id());
+
+    let pivot = receiver_3
+        .try_next()?
+        .ok_or_else(|| format_err!("Pivot event should exist"))?
+        .pivot;
+    assert!(pivot.number > 0 && pivot.number < target.target_id.number());
+    let pivot_header = storage
+        .get_block_header_by_hash(pivot.id)?
+        .ok_or_else(|| format_err!("Pivot block should exist"))?;
+    let pivot_state = ChainStateDB::new(
+        storage.clone().into_super_arc(),
+        Some(pivot_header.state_root()),
+    );
+    assert_eq!(
+        pivot_state.get_account_state(&genesis_address())?,
+        ChainStateDB::new(
+            arc_node1.chain().get_storage().into_super_arc(),
+            Some(pivot_header.state_root()),
+        )
+        .get_account_state(&genesis_address())?
+    );
+
+    let reports = task_event_counter.get_reports();
+    assert!(reports
+        .iter()
+        .any(|report| report.task_name == "StateSyncTask" && report.processed_items > 0));
+    reports
+        .iter()
+        .for_each(|report| debug!("reports: {}", report));
+    Ok(())
+}
+
 #[stest::test]
 pub async fn test_sync_invalid_target() -> Result<()> {
     let net1 = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
@@ -12,9 +12,11 @@ use starcoin_crypto::hash::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network_rpc_api::{
     gen_client::NetworkRpcClient, BlockBody, GetAccumulatorNodeByNodeHash, GetBlockHeadersByNumber,
-    GetBlockIds, GetTxnsWithHash, RawRpcClient,
+    GetBlockIds, GetBlockTxns, GetStateWithProof, GetTxnsWithHash, RawRpcClient,
 };
+use starcoin_state_api::StateWithProof;
 use starcoin_state_tree::StateNode;
+use starcoin_types::access_path::AccessPath;
 use starcoin_types::block::Block;
 use starcoin_types::transaction::{SignedUserTransaction, Transaction};
 use starcoin_types::{
@@ -238,6 +240,32 @@ impl VerifiedRpcClient {
         }
     }
 
+    pub async fn get_block_txns(
+        &self,
+        peer_id: Option<PeerId>,
+        req: GetBlockTxns,
+    ) -> Result<Vec<Option<SignedUserTransaction>>> {
+        let peer_id = peer_id.unwrap_or(self.select_a_peer()?);
+        let data = self
+            .client
+            .get_block_txns(peer_id.clone(), req.clone())
+            .await?;
+        if data.len() == req.len() {
+            Ok(data)
+        } else {
+            Err(RpcVerifyError::new(
+                peer_id.clone(),
+                format!(
+                    "Txn len mismatch {:?} : {:?} from peer : {:?}.",
+                    data.len(),
+                    req.len(),
+                    peer_id
+                ),
+            )
+            .into())
+        }
+    }
+
     pub async fn get_txn_infos(
         &self,
         block_id: HashValue,
@@ -358,6 +386,35 @@ impl VerifiedRpcClient {
         }
     }
 
+    pub async fn get_state_with_proof(
+        &self,
+        state_root: HashValue,
+        access_path: AccessPath,
+    ) -> Result<StateWithProof> {
+        let peer_id = self.select_a_peer()?;
+        let state_with_proof = self
+            .client
+            .get_state_with_proof(
+                peer_id.clone(),
+                GetStateWithProof {
+                    state_root,
+                    access_path: access_path.clone(),
+                },
+            )
+            .await?;
+        if let Err(e) = state_with_proof.verify(state_root, access_path.clone()) {
+            return Err(RpcVerifyError::new(
+                peer_id,
+                format!(
+                    "State proof of {} with root {} is invalid: {}",
+                    access_path, state_root, e
+                ),
+            )
+            .into());
+        }
+        Ok(state_with_proof)
+    }
+
     pub async fn get_block_ids(
         &self,
         peer_id: Option<PeerId>,
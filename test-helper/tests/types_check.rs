@@ -3,14 +3,14 @@
 
 use anyhow::{ensure, Result};
 use bcs_ext::Sample;
-use network_api::messages::{CompactBlockMessage, TransactionsMessage};
+use network_api::messages::{CompactBlockMessage, SaltedCompactBlockMessage, TransactionsMessage};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use starcoin_crypto::hash::PlainCryptoHash;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_types::block::{Block, BlockHeader, BlockInfo};
-use starcoin_types::compact_block::CompactBlock;
+use starcoin_types::compact_block::{CompactBlock, SaltedCompactBlock};
 use starcoin_types::startup_info::ChainStatus;
 use starcoin_vm_types::block_metadata::BlockMetadata;
 use starcoin_vm_types::transaction::{
@@ -44,6 +44,8 @@ fn check_types() {
     check_data::<CompactBlock>().unwrap();
     check_data::<TransactionsMessage>().unwrap();
     check_data::<CompactBlockMessage>().unwrap();
+    check_data::<SaltedCompactBlock>().unwrap();
+    check_data::<SaltedCompactBlockMessage>().unwrap();
 }
 
 const DATA_DIR: &str = "data";
@@ -76,6 +76,20 @@ Feature: cmd integration test
       |  |
       |  |
 
+  # offline transaction
+  Scenario Outline: [cmd] offline transaction
+    Then cmd: "account unlock"
+    Then cmd: "dev get-coin"
+    Then cmd: "account show"
+    Then cmd: "account build-txn --offline -s {{$.account[-1].ok.account.address}} --sequence-number {{$.account[-1].ok.sequence_number}} --chain-id 254 --gas-unit-price 1 --expiration-timestamp-secs 4102444800 --function 0x1::TransferScripts::peer_to_peer_v2 -t 0x1::STC::STC --arg 0x991c2f856a1e32985d9793b449c0f9d3 --arg 1000000u128 -o {{$.node_config[0].data_dir}}/offline.raw-txn"
+    Then cmd: "account sign-txn-file {{$.account[-1].ok.output_file}}"
+    Then cmd: "account submit-txn {{$.account[-1].ok.output_file}} -b"
+    Then stop
+
+    Examples:
+      |  |
+      |  |
+
   #dev
   Scenario Outline: [cmd] dev resolve test
     Then cmd: "dev resolve function 0x1::TransferScripts::peer_to_peer_v2"
@@ -5,7 +5,7 @@ use crate::MyWorld;
 use cucumber::{Steps, StepsBuilder};
 use jpst::TemplateContext;
 use scmd::{result_to_json, CmdContext};
-use serde_json::Value;
+use serde_json::{json, Value};
 use starcoin_account_provider::ProviderFactory;
 use starcoin_cmd::add_command;
 use starcoin_cmd::{CliState, StarcoinOpt};
@@ -42,7 +42,14 @@ pub fn steps() -> Steps<MyWorld> {
                     state,
                 );
                 if world.tpl_ctx.is_none() {
-                    world.tpl_ctx = Some(TemplateContext::new());
+                    let mut tpl_ctx = TemplateContext::new();
+                    // the node data dir, for the cmd output files.
+                    if let Some(node_config) = world.node_config.as_ref() {
+                        tpl_ctx
+                            .entry("node_config")
+                            .append(json!({ "data_dir": node_config.data_dir() }));
+                    }
+                    world.tpl_ctx = Some(tpl_ctx);
                 }
                 let tpl_ctx = world.tpl_ctx.as_mut().unwrap();
                 // get last cmd result as current parameter
@@ -108,12 +108,12 @@ impl ServiceFactory<Self> for TxPoolActorService {
                     )
                 })?;
             let best_block_header = best_block.into_inner().0;
-            Ok(TxPoolService::new(
-                node_config,
-                storage,
-                best_block_header,
-                vm_metrics,
-            ))
+            let txpool_service =
+                TxPoolService::new(node_config, storage, best_block_header, vm_metrics);
+            if let Err(e) = txpool_service.get_inner().load_journal() {
+                error!("txpool: fail to load journaled txns, err: {:?}", e);
+            }
+            Ok(txpool_service)
         })?;
         Ok(Self::new(txpool_service.get_inner()))
     }
@@ -153,12 +153,25 @@ impl ActorService for TxPoolActorService {
             myself.try_propagate_txns(ctx)
         });
 
+        let pool_config = &self.inner.node_config.tx_pool;
+        if pool_config.journal_enabled() {
+            let inner = self.inner.clone();
+            ctx.run_interval(
+                Duration::from_secs(pool_config.journal_interval()),
+                move |_ctx| {
+                    if let Err(e) = inner.save_journal() {
+                        error!("txpool: fail to journal txns, err: {:?}", e);
+                    }
+                },
+            );
+        }
+
         Ok(())
     }
 
     fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.unsubscribe::<SyncStatusChangeEvent>();
-        Ok(())
+        self.inner.save_journal()
     }
 }
 
@@ -9,6 +9,13 @@ use starcoin_crypto::hash::HashValue as H256;
 use starcoin_logger::prelude::*;
 use transaction_pool as tx_pool;
 use tx_pool::VerifiedTransaction;
+
+/// Whether `new` replaces `old` with the same sender and sequence number,
+/// otherwise `old` is pushed out of the pool.
+fn is_replacement(old: &Transaction, new: &Transaction) -> bool {
+    old.sender == new.sender && old.signed().sequence_number() == new.signed().sequence_number()
+}
+
 /// Transaction pool logger.
 #[derive(Default, Debug)]
 pub struct Logger;
@@ -26,7 +33,18 @@ impl tx_pool::Listener<Transaction> for Logger {
             action = "txpool_add",
         );
         if let Some(old) = old {
-            debug!(target: "txqueue", "[{:?}] Dropped. Replaced by [{:?}]", old.hash(), tx.hash());
+            if is_replacement(old, tx) {
+                sl_info!(
+                    "{action} {hash} {new_hash} {gas_price} {new_gas_price}",
+                    gas_price = old.signed().gas_unit_price(),
+                    new_gas_price = tx.signed().gas_unit_price(),
+                    new_hash = tx.hash().to_hex(),
+                    hash = old.hash().to_hex(),
+                    action = "txpool_replace",
+                );
+            } else {
+                debug!(target: "txqueue", "[{:?}] Dropped. Replaced by [{:?}]", old.hash(), tx.hash());
+            }
         }
     }
 
@@ -99,7 +117,11 @@ impl tx_pool::Listener<Transaction> for StatusLogger {
     fn added(&mut self, tx: &Arc<Transaction>, old: Option<&Arc<Transaction>>) {
         Self::log_status(tx, TxStatus::Added);
         if let Some(old) = old {
-            Self::log_status(old, TxStatus::Dropped);
+            if is_replacement(old, tx) {
+                Self::log_status(old, TxStatus::Replaced);
+            } else {
+                Self::log_status(old, TxStatus::Dropped);
+            }
         }
     }
 
@@ -179,8 +201,16 @@ impl fmt::Debug for TransactionsPoolNotifier {
 }
 
 impl tx_pool::Listener<Transaction> for TransactionsPoolNotifier {
-    fn added(&mut self, tx: &Arc<Transaction>, _old: Option<&Arc<Transaction>>) {
+    fn added(&mut self, tx: &Arc<Transaction>, old: Option<&Arc<Transaction>>) {
         self.tx_statuses.push((tx.hash, TxStatus::Added));
+        if let Some(old) = old {
+            let status = if is_replacement(old, tx) {
+                TxStatus::Replaced
+            } else {
+                TxStatus::Dropped
+            };
+            self.tx_statuses.push((old.hash, status));
+        }
     }
 
     fn rejected<H: fmt::Debug + fmt::LowerHex>(
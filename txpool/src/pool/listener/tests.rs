@@ -78,13 +78,57 @@ fn test_notify() {
     assert_eq!(full_res, Some(vec![(*tx.hash(), TxStatus::Invalid)].into()));
 }
 
+#[test]
+fn test_notify_replaced() {
+    let (full_sender, mut full_receiver) = mpsc::unbounded();
+    let mut tx_listener = TransactionsPoolNotifier::default();
+    tx_listener.add_full_listener(full_sender);
+
+    let sender = AccountAddress::random();
+    let old = new_tx_with_gas_price(sender, 10);
+    let new = new_tx_with_gas_price(sender, 11);
+    tx_listener.added(&new, Some(&old));
+    tx_listener.notify();
+    let full_res = full_receiver.try_next().unwrap();
+    assert_eq!(
+        full_res,
+        Some(
+            vec![
+                (*new.hash(), TxStatus::Added),
+                (*old.hash(), TxStatus::Replaced)
+            ]
+            .into()
+        )
+    );
+
+    // pushed out by the transaction of another sender.
+    let other = new_tx();
+    tx_listener.added(&other, Some(&new));
+    tx_listener.notify();
+    let full_res = full_receiver.try_next().unwrap();
+    assert_eq!(
+        full_res,
+        Some(
+            vec![
+                (*other.hash(), TxStatus::Added),
+                (*new.hash(), TxStatus::Dropped)
+            ]
+            .into()
+        )
+    );
+}
+
 fn new_tx() -> Arc<Transaction> {
+    new_tx_with_gas_price(AccountAddress::random(), 10)
+}
+
+fn new_tx_with_gas_price(sender: AccountAddress, gas_price: u64) -> Arc<Transaction> {
     let raw = transaction::RawUserTransaction::new_with_default_gas_token(
-        AccountAddress::random(),
+        sender,
         4,
         TransactionPayload::Script(Script::new(vec![1, 2, 3], vec![], vec![])),
         100_000,
-        10,
+        gas_price,
         get_current_timestamp() + 60,
         ChainId::test(),
     );
@@ -5,7 +5,8 @@
 
 use super::{
     client, listener, local_transactions::LocalTransactionsList, ready, replace, scoring, verifier,
-    PendingOrdering, PendingSettings, PrioritizationStrategy, SeqNumber, TxStatus,
+    PendingOrdering, PendingSettings, PrioritizationStrategy, ScoredTransaction, SeqNumber,
+    TxStatus,
 };
 use crate::pool::ready::Expiration;
 use crate::{pool, pool::PoolTransaction};
@@ -231,13 +232,14 @@ impl TransactionQueue {
         limits: tx_pool::Options,
         verification_options: verifier::Options,
         strategy: PrioritizationStrategy,
+        price_bump_percent: u64,
     ) -> Self {
         let max_count = limits.max_count;
         TransactionQueue {
             insertion_id: Default::default(),
             pool: RwLock::new(tx_pool::Pool::new(
                 Default::default(),
-                scoring::SeqNumberAndGasPrice(strategy),
+                scoring::SeqNumberAndGasPrice::new(strategy, price_bump_percent),
                 limits,
             )),
             options: RwLock::new(verification_options),
@@ -321,10 +323,18 @@ impl TransactionQueue {
             let imported = verifier
                 .verify_transaction(transaction)
                 .and_then(|verified| {
-                    self.pool
-                        .write()
-                        .import(verified, &replace)
-                        .map_err(convert_error)
+                    let new_gas_price = verified.gas_price();
+                    let mut pool = self.pool.write();
+                    pool.import(verified, &replace).map_err(|err| match err {
+                        // the transaction with the same sender and seq number is not replaced.
+                        tx_pool::Error::TooCheapToReplace(old_hash, _) => {
+                            transaction::TransactionError::TooCheapToReplace {
+                                prev: pool.find(&old_hash).map(|old| old.gas_price()),
+                                new: Some(new_gas_price),
+                            }
+                        }
+                        err => convert_error(err),
+                    })
                 });
 
             results.push(match imported {
@@ -359,6 +369,13 @@ impl TransactionQueue {
             .collect()
     }
 
+    /// Returns all the transactions in the pool, including the future ones, without ordering.
+    pub fn all_transactions(&self) -> Vec<Arc<pool::VerifiedTransaction>> {
+        // always ready
+        let ready = Expiration::new(0);
+        self.pool.read().unordered_pending(ready).collect()
+    }
+
     /// Returns current pending transactions ordered by priority.
     ///
     /// NOTE: This may return a cached version of pending transaction set.
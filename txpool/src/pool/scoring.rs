@@ -3,18 +3,19 @@
 
 use std::cmp;
 
-use super::{GasPrice, PrioritizationStrategy, ScoredTransaction, VerifiedTransaction};
+use super::{
+    GasPrice, PoolTransaction, PrioritizationStrategy, ScoredTransaction, VerifiedTransaction,
+};
 use tx_pool::{self, scoring};
-/// Transaction with the same (sender, nonce) can be replaced only if
-/// `new_gas_price >= old_gas_price + old_gas_price >> SHIFT`
-const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
 
-/// TODO: re-consider this, after we settle down the unit of gas price.
 /// Calculate minimal gas price requirement.
+/// Transaction with the same (sender, seq_number) can be replaced only if
+/// `new_gas_price >= old_gas_price + old_gas_price * price_bump_percent / 100`
 #[inline]
-fn bump_gas_price(old_gp: GasPrice) -> GasPrice {
-    old_gp
-    // old_gp.saturating_add(old_gp >> GAS_PRICE_BUMP_SHIFT as u64)
+pub fn bump_gas_price(old_gp: GasPrice, price_bump_percent: u64) -> GasPrice {
+    let bump = (old_gp as u128 * price_bump_percent as u128 / 100) as u64;
+    // the replacement always needs a higher gas price.
+    old_gp.saturating_add(cmp::max(bump, 1))
 }
 
 /// Simple, gas-price based scoring for transactions.
@@ -22,31 +23,37 @@ fn bump_gas_price(old_gp: GasPrice) -> GasPrice {
 /// NOTE: Currently penalization does not apply to new transactions that enter the pool.
 /// We might want to store penalization status in some persistent state.
 #[derive(Debug, Clone)]
-pub struct SeqNumberAndGasPrice(pub PrioritizationStrategy);
+pub struct SeqNumberAndGasPrice {
+    strategy: PrioritizationStrategy,
+    price_bump_percent: u64,
+}
 
 impl SeqNumberAndGasPrice {
+    pub fn new(strategy: PrioritizationStrategy, price_bump_percent: u64) -> Self {
+        Self {
+            strategy,
+            price_bump_percent,
+        }
+    }
+
     /// Decide if the transaction should even be considered into the pool (if the pool is full).
     ///
     /// Used by Verifier to quickly reject transactions that don't have any chance to get into the pool later on,
     /// and save time on more expensive checks like sender recovery, etc.
     ///
-    /// NOTE The method is never called for zero-gas-price transactions or local transactions
+    /// NOTE The method is never called for local or retracted transactions
     /// (such transactions are always considered to the pool and potentially rejected later on)
-    pub fn should_reject_early(&self, _old: &VerifiedTransaction) -> bool {
-        todo!()
-    }
+    pub fn should_reject_early(&self, old: &VerifiedTransaction, new: &PoolTransaction) -> bool {
+        // the transactions of the same sender are ordered by sequence number in the pool.
+        if old.sender == new.signed().sender() {
+            return false;
+        }
+        if old.priority().is_local() {
+            return true;
+        }
 
-    //    pub fn should_reject_early(
-    //        &self,
-    //        old: &VerifiedTransaction,
-    //        new: &verifier::Transaction,
-    //    ) -> bool {
-    //        if old.priority().is_local() {
-    //            return true;
-    //        }
-    //
-    //        &old.transaction.gas_price > new.gas_price()
-    //    }
+        old.gas_price() >= new.gas_price()
+    }
 }
 
 impl<P> tx_pool::Scoring<P> for SeqNumberAndGasPrice
@@ -68,7 +75,7 @@ where
         let old_gp = old.gas_price();
         let new_gp = new.gas_price();
 
-        let min_required_gp = bump_gas_price(old_gp);
+        let min_required_gp = bump_gas_price(old_gp, self.price_bump_percent);
 
         match min_required_gp.cmp(&new_gp) {
             cmp::Ordering::Greater => scoring::Choice::RejectNew,
@@ -9,8 +9,8 @@
 //! May have some overlap with `Readiness` since we don't want to keep around
 //! stalled transactions.
 use crate::pool::{
-    client::Client, scoring, PoolTransaction, Priority, UnverifiedUserTransaction,
-    VerifiedTransaction,
+    client::Client, scoring, PoolTransaction, Priority, ScoredTransaction,
+    UnverifiedUserTransaction, VerifiedTransaction,
 };
 use starcoin_types::transaction;
 use std::sync::{atomic::AtomicUsize, Arc};
@@ -71,6 +71,17 @@ impl<C: Client> tx_pool::Verifier<PoolTransaction>
         let hash = tx.hash();
         let is_local_txn = tx.is_local();
         let is_retracted = tx.is_retracted();
+        if !self.options.no_early_reject && !is_local_txn && !is_retracted {
+            if let Some((ref scoring, ref vtx)) = self.transaction_to_replace {
+                if scoring.should_reject_early(vtx, &tx) {
+                    debug!(target: "txqueue", "[{:?}] Rejected tx early, cheaper than the worst tx in the full pool", hash);
+                    return Err(transaction::TransactionError::TooCheapToReplace {
+                        prev: Some(vtx.gas_price()),
+                        new: Some(tx.gas_price()),
+                    });
+                }
+            }
+        }
         let verified_txn = match tx {
             PoolTransaction::Unverified(unverified) | PoolTransaction::Retracted(unverified) => {
                 match self.client.verify_transaction(unverified) {
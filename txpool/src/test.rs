@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pool::AccountSeqNumberClient;
-use crate::TxStatus;
+use crate::{TxPoolService, TxStatus};
 use anyhow::Result;
 use network_api::messages::{PeerTransactionsMessage, TransactionsMessage};
 use network_api::PeerId;
@@ -21,7 +21,7 @@ use starcoin_txpool_api::{TxPoolSyncService, TxnStatusFullEvent};
 use starcoin_types::{
     account_address::{self, AccountAddress},
     account_config,
-    transaction::{SignedUserTransaction, Transaction, TransactionPayload},
+    transaction::{SignedUserTransaction, Transaction, TransactionError, TransactionPayload},
     U256,
 };
 use std::time::Duration;
@@ -295,7 +295,72 @@ async fn test_txpool_actor_service() {
     sleep(Duration::from_millis(300)).await;
 }
 
+#[stest::test]
+async fn test_txpool_journal() -> Result<()> {
+    let (txpool_service, storage, config, _, _) = test_helper::start_txpool().await;
+    // a pending txn and a future txn.
+    let txns = vec![
+        generate_txn(config.clone(), 0),
+        generate_txn(config.clone(), 2),
+    ];
+    for result in txpool_service.add_txns(txns.clone()) {
+        result?;
+    }
+    txpool_service.get_inner().save_journal()?;
+
+    // restart the txpool on the same storage.
+    let startup_info = storage.get_startup_info()?.unwrap();
+    let header = storage
+        .get_block_header_by_hash(startup_info.main)?
+        .unwrap();
+    let new_txpool_service = TxPoolService::new(config, storage, header, None);
+    assert_eq!(new_txpool_service.get_inner().load_journal()?, 2);
+    for txn in txns {
+        assert!(new_txpool_service.find_txn(&txn.id()).is_some());
+    }
+    Ok(())
+}
+
+#[stest::test]
+async fn test_txn_replace() -> Result<()> {
+    let (txpool_service, _storage, config, _, _) = test_helper::start_txpool().await;
+    let txn = generate_txn_with_gas_price(config.clone(), 0, 10);
+    txpool_service.add_txns(vec![txn.clone()]).pop().unwrap()?;
+
+    // the default min gas price bump is 10%.
+    let underpriced_txn = generate_txn_with_gas_price(config.clone(), 0, 10);
+    let result = txpool_service
+        .add_txns(vec![underpriced_txn.clone()])
+        .pop()
+        .unwrap();
+    assert_eq!(
+        result,
+        Err(TransactionError::TooCheapToReplace {
+            prev: Some(10),
+            new: Some(10),
+        })
+    );
+
+    let replacement_txn = generate_txn_with_gas_price(config, 0, 11);
+    txpool_service
+        .add_txns(vec![replacement_txn.clone()])
+        .pop()
+        .unwrap()?;
+    assert!(txpool_service.find_txn(&txn.id()).is_none());
+    assert!(txpool_service.find_txn(&underpriced_txn.id()).is_none());
+    assert!(txpool_service.find_txn(&replacement_txn.id()).is_some());
+    Ok(())
+}
+
 fn generate_txn(config: Arc<NodeConfig>, seq: u64) -> SignedUserTransaction {
+    generate_txn_with_gas_price(config, seq, 1)
+}
+
+fn generate_txn_with_gas_price(
+    config: Arc<NodeConfig>,
+    seq: u64,
+    gas_price: u64,
+) -> SignedUserTransaction {
     let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
     let account_address = account_address::from_public_key(&public_key);
     let txn = starcoin_transaction_builder::create_signed_txn_with_association_account(
@@ -304,7 +369,7 @@ fn generate_txn(config: Arc<NodeConfig>, seq: u64) -> SignedUserTransaction {
         ),
         seq,
         starcoin_transaction_builder::DEFAULT_MAX_GAS_AMOUNT,
-        1,
+        gas_price,
         2,
         config.net(),
     );
@@ -14,11 +14,12 @@ use crate::metrics::TxPoolMetrics;
 use crate::pool::{Client, TransactionQueue};
 use anyhow::Result;
 use futures_channel::mpsc;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use starcoin_config::NodeConfig;
 use starcoin_crypto::hash::HashValue;
 use starcoin_executor::VMMetrics;
 use starcoin_statedb::ChainStateDB;
+use starcoin_storage::txpool_journal::TxPoolJournalEntry;
 use starcoin_storage::Store;
 use starcoin_txpool_api::{TxPoolStatus, TxPoolSyncService, TxnStatusFullEvent};
 use starcoin_types::{
@@ -27,6 +28,7 @@ use starcoin_types::{
     transaction,
     transaction::SignedUserTransaction,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Clone, Debug)]
@@ -58,6 +60,7 @@ impl TxPoolService {
             },
             verifier_options,
             PrioritizationStrategy::GasPriceOnly,
+            pool_config.price_bump_percent(),
         );
         let queue = Arc::new(queue);
         let inner = Inner {
@@ -68,6 +71,7 @@ impl TxPoolService {
             sequence_number_cache: NonceCache::new(128),
             metrics,
             vm_metrics,
+            journal_times: Arc::new(Mutex::new(HashMap::new())),
         };
 
         Self { inner }
@@ -221,6 +225,8 @@ pub(crate) struct Inner {
     sequence_number_cache: NonceCache,
     pub(crate) metrics: Option<TxPoolMetrics>,
     vm_metrics: Option<VMMetrics>,
+    // the time when the pool transactions are journaled for the first time.
+    journal_times: Arc<Mutex<HashMap<HashValue, u64>>>,
 }
 impl std::fmt::Debug for Inner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -301,6 +307,74 @@ impl Inner {
         );
         self.queue.pending(self.get_pool_client(), pending_settings)
     }
+
+    /// Re-import the transactions journaled before the last shutdown,
+    /// the transactions older than `journal_max_age` are dropped,
+    /// and the stale ones are rejected by the verifier.
+    /// Return the number of imported transactions.
+    pub(crate) fn load_journal(&self) -> Result<usize> {
+        let pool_config = &self.node_config.tx_pool;
+        if !pool_config.journal_enabled() {
+            return Ok(0);
+        }
+        let now = self.node_config.net().time_service().now_secs();
+        let max_age = pool_config.journal_max_age();
+        let entries = self
+            .storage
+            .get_txpool_journal()?
+            .into_iter()
+            .filter(|entry| now.saturating_sub(entry.journaled_at) <= max_age)
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        let total = entries.len();
+        let mut txns = Vec::with_capacity(total);
+        {
+            let mut journal_times = self.journal_times.lock();
+            for entry in entries {
+                journal_times.insert(entry.txn.id(), entry.journaled_at);
+                txns.push(entry.txn);
+            }
+        }
+        let imported = self
+            .import_txns(txns)
+            .into_iter()
+            .filter(|result| result.is_ok())
+            .count();
+        info!(
+            "Re-import {} of {} journaled transactions into txpool.",
+            imported, total
+        );
+        Ok(imported)
+    }
+
+    /// Snapshot the pending and future transactions of the pool into storage.
+    pub(crate) fn save_journal(&self) -> Result<()> {
+        if !self.node_config.tx_pool.journal_enabled() {
+            return Ok(());
+        }
+        let now = self.node_config.net().time_service().now_secs();
+        let txns = self.queue.all_transactions();
+        let entries = {
+            let mut journal_times = self.journal_times.lock();
+            let mut new_journal_times = HashMap::with_capacity(txns.len());
+            let entries = txns
+                .iter()
+                .map(|txn| {
+                    let txn = txn.signed().clone();
+                    let journaled_at = journal_times.get(&txn.id()).cloned().unwrap_or(now);
+                    new_journal_times.insert(txn.id(), journaled_at);
+                    TxPoolJournalEntry { txn, journaled_at }
+                })
+                .collect::<Vec<_>>();
+            *journal_times = new_journal_times;
+            entries
+        };
+        debug!("Journal {} txpool transactions.", entries.len());
+        self.storage.save_txpool_journal(entries)
+    }
+
     pub(crate) fn next_sequence_number(&self, address: AccountAddress) -> Option<u64> {
         self.queue
             .next_sequence_number(self.get_pool_client(), &address)
@@ -2,7 +2,11 @@ use crate::block::{Block, BlockHeader};
 use crate::transaction::SignedUserTransaction;
 use bcs_ext::Sample;
 use serde::{Deserialize, Serialize};
+use siphasher::sip::SipHasher24;
 use starcoin_crypto::HashValue;
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::hash::Hasher;
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CompactBlock {
@@ -18,7 +22,7 @@ pub struct PrefilledTxn {
     pub tx: SignedUserTransaction,
 }
 
-// TODO: change to siphash24 of 6bites
+/// The full transaction id, only used by the legacy compact block, see `SaltedShortId`.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ShortId(pub HashValue);
 
@@ -57,3 +61,119 @@ impl Sample for CompactBlock {
         Block::sample().into()
     }
 }
+
+/// Compact block with BIP152 style short ids, the short ids are salted per block by the block id
+/// and a nonce chosen by the sender, so a collision can not be precomputed for every block.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SaltedCompactBlock {
+    pub header: BlockHeader,
+    pub nonce: u64,
+    pub short_ids: Vec<SaltedShortId>,
+    pub prefilled_txn: Vec<PrefilledTxn>,
+    pub uncles: Option<Vec<BlockHeader>>,
+}
+
+/// The first 6 bytes of the SipHash-2-4 of the transaction id.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct SaltedShortId(pub [u8; 6]);
+
+/// The SipHash-2-4 keys of the short ids in a block, derived from sha3(block_id || nonce).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ShortIdKey {
+    k0: u64,
+    k1: u64,
+}
+
+impl ShortIdKey {
+    pub fn new(block_id: HashValue, nonce: u64) -> Self {
+        let mut data = block_id.to_vec();
+        data.extend_from_slice(&nonce.to_le_bytes());
+        let hash = HashValue::sha3_256_of(&data);
+        let bytes = hash.as_slice();
+        Self {
+            k0: u64::from_le_bytes(bytes[0..8].try_into().expect("slice length must be 8")),
+            k1: u64::from_le_bytes(bytes[8..16].try_into().expect("slice length must be 8")),
+        }
+    }
+
+    pub fn short_id(&self, txn_id: &HashValue) -> SaltedShortId {
+        let mut hasher = SipHasher24::new_with_keys(self.k0, self.k1);
+        hasher.write(txn_id.as_slice());
+        let hash = hasher.finish().to_le_bytes();
+        let mut short_id = [0u8; 6];
+        short_id.copy_from_slice(&hash[0..6]);
+        SaltedShortId(short_id)
+    }
+}
+
+impl SaltedCompactBlock {
+    pub fn new(block: Block, nonce: u64) -> Self {
+        CompactBlock::new(block).into_salted(nonce)
+    }
+
+    pub fn short_id_key(&self) -> ShortIdKey {
+        ShortIdKey::new(self.header.id(), self.nonce)
+    }
+
+    pub fn txn_len(&self) -> usize {
+        self.short_ids
+            .len()
+            .saturating_add(self.prefilled_txn.len())
+    }
+}
+
+impl CompactBlock {
+    /// Convert to the salted compact block, the prefilled txns are not counted in the short ids,
+    /// and the index of the prefilled txn is the index in the block.
+    pub fn into_salted(self, nonce: u64) -> SaltedCompactBlock {
+        let key = ShortIdKey::new(self.header.id(), nonce);
+        let prefilled_indexes = self
+            .prefilled_txn
+            .iter()
+            .map(|txn| txn.index)
+            .collect::<HashSet<_>>();
+        let short_ids = self
+            .short_ids
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !prefilled_indexes.contains(&(*index as u64)))
+            .map(|(_, id)| key.short_id(&id.0))
+            .collect();
+        SaltedCompactBlock {
+            header: self.header,
+            nonce,
+            short_ids,
+            prefilled_txn: self.prefilled_txn,
+            uncles: self.uncles,
+        }
+    }
+}
+
+impl Sample for SaltedCompactBlock {
+    fn sample() -> Self {
+        SaltedCompactBlock::new(Block::sample(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockBody;
+
+    #[test]
+    fn test_salted_short_id() {
+        let txn = SignedUserTransaction::sample();
+        let block = Block::new(
+            BlockHeader::random(),
+            BlockBody::new(vec![txn.clone()], None),
+        );
+        let compact_block = SaltedCompactBlock::new(block.clone(), 1);
+        assert_eq!(compact_block.txn_len(), 1);
+        let key = compact_block.short_id_key();
+        assert_eq!(compact_block.short_ids, vec![key.short_id(&txn.id())]);
+        assert_ne!(
+            SaltedCompactBlock::new(block, 2).short_ids,
+            compact_block.short_ids
+        );
+    }
+}
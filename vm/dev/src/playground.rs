@@ -6,6 +6,7 @@ use starcoin_abi_decoder::decode_move_value;
 use starcoin_abi_resolver::ABIResolver;
 use starcoin_abi_types::TypeInstantiation;
 use starcoin_crypto::HashValue;
+use starcoin_gas::CallFrame;
 use starcoin_resource_viewer::module_cache::ModuleCache;
 use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValueAnnotator};
 use starcoin_rpc_api::types::{DryRunOutputView, TransactionOutputView, WriteOpValueView};
@@ -102,6 +103,17 @@ pub fn dry_run<S: StateView>(
     vm.dry_run_transaction(&state_view_cache.as_move_resolver(), txn)
 }
 
+pub fn dry_run_with_trace<S: StateView>(
+    state_view: &S,
+    txn: DryRunTransaction,
+    metrics: Option<VMMetrics>,
+    trace: bool,
+) -> Result<(VMStatus, TransactionOutput, Option<CallFrame>)> {
+    let mut vm = StarcoinVM::new(metrics);
+    let state_view_cache = StateViewCache::new(state_view);
+    vm.dry_run_transaction_with_trace(&state_view_cache.as_move_resolver(), txn, trace)
+}
+
 pub fn dry_run_explain<S: StateView>(
     state_view: &S,
     txn: DryRunTransaction,
@@ -149,6 +161,7 @@ pub fn dry_run_explain<S: StateView>(
     Ok(DryRunOutputView {
         explained_status: vm_status_explain,
         txn_output,
+        trace: None,
     })
 }
 
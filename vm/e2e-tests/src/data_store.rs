@@ -134,7 +134,7 @@ impl ChainStateWriter for FakeDataStore {
         Ok(HashValue::zero())
     }
 
-    fn flush(&self) -> Result<()> {
-        Ok(())
+    fn flush(&self) -> Result<Vec<u64>> {
+        Ok(vec![])
     }
 }
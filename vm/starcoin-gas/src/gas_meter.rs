@@ -23,6 +23,7 @@ use starcoin_gas_algebra_ext::{
 use starcoin_logger::prelude::*;
 use std::collections::BTreeMap;
 
+use crate::gas_tracer::{CallFrame, GasCategory, GasTracer, StorageAccessKind};
 use move_binary_format::file_format_common::Opcodes;
 use starcoin_gas_algebra_ext::InstructionGasParameters;
 use starcoin_gas_algebra_ext::TransactionGasParameters;
@@ -142,6 +143,7 @@ pub struct StarcoinGasMeter {
     gas_params: StarcoinGasParameters,
     balance: InternalGas,
     charge: bool,
+    tracer: Option<GasTracer>,
 }
 
 impl StarcoinGasMeter {
@@ -151,6 +153,7 @@ impl StarcoinGasMeter {
             gas_params,
             balance,
             charge: true,
+            tracer: None,
         }
     }
 
@@ -183,6 +186,48 @@ impl StarcoinGasMeter {
         self.charge
     }
 
+    /// Record the call tree and the gas charges of the metered execution, `root` is the name of
+    /// the entry frame.
+    pub fn enable_trace(&mut self, root: String) {
+        self.tracer = Some(GasTracer::new(root));
+    }
+
+    pub fn take_trace(&mut self) -> Option<CallFrame> {
+        self.tracer.take().map(GasTracer::finish)
+    }
+
+    /// The unmetered execution, such as prologue and epilogue, is not traced.
+    fn tracer(&mut self) -> Option<&mut GasTracer> {
+        if self.charge {
+            self.tracer.as_mut()
+        } else {
+            None
+        }
+    }
+
+    fn charge_with_category(
+        &mut self,
+        category: GasCategory,
+        amount: InternalGas,
+    ) -> PartialVMResult<()> {
+        if let Some(tracer) = self.tracer() {
+            tracer.record(category, amount.into());
+        }
+        self.deduct_gas(amount)
+    }
+
+    fn charge_storage_access(
+        &mut self,
+        kind: StorageAccessKind,
+        ty: impl TypeView,
+        amount: InternalGas,
+    ) -> PartialVMResult<()> {
+        if let Some(tracer) = self.tracer() {
+            tracer.record_storage_access(kind, ty.to_type_tag(), amount.into());
+        }
+        self.deduct_gas(amount)
+    }
+
     pub fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
         let cost = self.gas_params.txn.calculate_intrinsic_gas(txn_size);
         #[cfg(testing)]
@@ -190,13 +235,17 @@ impl StarcoinGasMeter {
             "charge_intrinsic_gas cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Intrinsic, cost)
             .map_err(|e| e.finish(Location::Undefined))
     }
 
     pub fn cal_write_set_gas(&self) -> InternalGas {
         self.gas_params.txn.cal_write_set_gas()
     }
+
+    pub fn charge_write_set_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        self.charge_with_category(GasCategory::WriteSet, amount)
+    }
 }
 
 #[inline]
@@ -297,7 +346,7 @@ impl GasMeter for StarcoinGasMeter {
             cost,
             self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Simple, cost)
     }
 
     fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
@@ -308,14 +357,14 @@ impl GasMeter for StarcoinGasMeter {
             "simple_instr pop cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Stack, cost)
     }
 
     #[inline]
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
@@ -327,14 +376,18 @@ impl GasMeter for StarcoinGasMeter {
         let cost2 = cal_instr_with_arg(params.call_per_arg, NumArgs::new(args.len() as u64));
         #[cfg(testing)]
         info!("CALL cost InternalGasUnits({}) {}", cost2, self.charge);
-        self.deduct_gas(cost1 + cost2)
+        let result = self.charge_with_category(GasCategory::Call, cost1 + cost2);
+        if let Some(tracer) = self.tracer() {
+            tracer.push_frame(format!("{}::{}", module_id.short_str_lossless(), func_name));
+        }
+        result
     }
 
     #[inline]
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
@@ -357,7 +410,11 @@ impl GasMeter for StarcoinGasMeter {
             "CALL_GENERIC cost InternalGasUnits({}) {}",
             cost2, self.charge
         );
-        self.deduct_gas(cost1 + cost2)
+        let result = self.charge_with_category(GasCategory::Call, cost1 + cost2);
+        if let Some(tracer) = self.tracer() {
+            tracer.push_frame(format!("{}::{}", module_id.short_str_lossless(), func_name));
+        }
+        result
     }
 
     #[inline]
@@ -366,7 +423,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = cal_instr_with_byte(instr.ld_const_per_byte, size);
         #[cfg(testing)]
         info!("LD_CONST cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Stack, cost)
     }
 
     fn charge_ld_const_after_deserialization(
@@ -385,7 +442,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("COPY_LOC cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Local, cost)
     }
 
     #[inline]
@@ -397,7 +454,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("MOVE_LOC cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Local, cost)
     }
 
     #[inline]
@@ -409,7 +466,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("ST_LOC cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Local, cost)
     }
 
     #[inline]
@@ -438,7 +495,7 @@ impl GasMeter for StarcoinGasMeter {
                 info!("PACK cost InternalGasUnits({}) {}", cost, self.charge);
             }
         }
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Struct, cost)
     }
 
     #[inline]
@@ -477,7 +534,7 @@ impl GasMeter for StarcoinGasMeter {
             );
             cost += cost2;
         }
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Struct, cost)
     }
 
     #[inline]
@@ -488,7 +545,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("READ_REF cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Reference, cost)
     }
 
     #[inline]
@@ -503,7 +560,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("WRITE_REF cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Reference, cost)
     }
 
     #[inline]
@@ -515,7 +572,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("EQ cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Equality, cost)
     }
 
     #[inline]
@@ -527,7 +584,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("NEQ cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Equality, cost)
     }
 
     #[inline]
@@ -535,7 +592,7 @@ impl GasMeter for StarcoinGasMeter {
         &mut self,
         _is_mut: bool,
         is_generic: bool,
-        _ty: impl TypeView,
+        ty: impl TypeView,
         is_success: bool,
     ) -> PartialVMResult<()> {
         let cost = if !is_success {
@@ -559,14 +616,14 @@ impl GasMeter for StarcoinGasMeter {
             "{:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_storage_access(StorageAccessKind::BorrowGlobal, ty, cost)
     }
 
     #[inline]
     fn charge_exists(
         &mut self,
         is_generic: bool,
-        _ty: impl TypeView,
+        ty: impl TypeView,
         exists: bool,
     ) -> PartialVMResult<()> {
         let params = &self.gas_params.instr;
@@ -589,14 +646,14 @@ impl GasMeter for StarcoinGasMeter {
             "{:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_storage_access(StorageAccessKind::Exists, ty, cost)
     }
 
     #[inline]
     fn charge_move_from(
         &mut self,
         is_generic: bool,
-        _ty: impl TypeView,
+        ty: impl TypeView,
         val: Option<impl ValueView>,
     ) -> PartialVMResult<()> {
         if let Some(val) = val {
@@ -616,7 +673,7 @@ impl GasMeter for StarcoinGasMeter {
                 "MOVE_FROM {:#?} cost InternalGasUnits({}) {}",
                 opcode, cost, self.charge
             );
-            return self.deduct_gas(cost);
+            return self.charge_storage_access(StorageAccessKind::MoveFrom, ty, cost);
         }
         Ok(())
     }
@@ -625,7 +682,7 @@ impl GasMeter for StarcoinGasMeter {
     fn charge_move_to(
         &mut self,
         is_generic: bool,
-        _ty: impl TypeView,
+        ty: impl TypeView,
         val: impl ValueView,
         is_success: bool,
     ) -> PartialVMResult<()> {
@@ -649,7 +706,7 @@ impl GasMeter for StarcoinGasMeter {
             "charge_MOVE_TO {:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_storage_access(StorageAccessKind::MoveTo, ty, cost)
     }
 
     #[inline]
@@ -663,7 +720,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = cal_instr_with_arg(params.vec_pack_per_elem, num_args);
         #[cfg(testing)]
         info!("VEC_PACK cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -671,7 +728,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = self.gas_params.instr.vec_len_base;
         #[cfg(testing)]
         info!("VEC_LEN cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -700,7 +757,7 @@ impl GasMeter for StarcoinGasMeter {
             "{:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -718,7 +775,7 @@ impl GasMeter for StarcoinGasMeter {
             "VEC_PUSH_BACK cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -733,7 +790,7 @@ impl GasMeter for StarcoinGasMeter {
             "VEC_POP_BACK cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -749,7 +806,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("VEC_UNPACK cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -757,7 +814,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = self.gas_params.instr.vec_swap_base;
         #[cfg(testing)]
         info!("VEC_SWAP cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_with_category(GasCategory::Vector, cost)
     }
 
     #[inline]
@@ -779,7 +836,11 @@ impl GasMeter for StarcoinGasMeter {
             "NATIVE_FUNCTION cost InternalGasUnits({}) {}",
             amount, self.charge
         );
-        self.deduct_gas(amount)
+        let result = self.charge_with_category(GasCategory::Native, amount);
+        if let Some(tracer) = self.tracer() {
+            tracer.pop_native_frame();
+        }
+        result
     }
 
     fn charge_native_function_before_execution(
@@ -787,6 +848,9 @@ impl GasMeter for StarcoinGasMeter {
         _ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         _args: impl ExactSizeIterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
+        if let Some(tracer) = self.tracer() {
+            tracer.mark_native();
+        }
         Ok(())
     }
 
@@ -794,6 +858,9 @@ impl GasMeter for StarcoinGasMeter {
         &mut self,
         _locals: impl Iterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
+        if let Some(tracer) = self.tracer() {
+            tracer.pop_frame();
+        }
         Ok(())
     }
 }
@@ -0,0 +1,241 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains an optional tracer of the gas meter, which records the call tree of a
+//! transaction execution with the gas charged per frame, per instruction category and per
+//! global storage access. It's only enabled for dry run, to help profiling the contracts.
+
+use move_core_types::language_storage::TypeTag;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Categories of the gas charges.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum GasCategory {
+    /// The intrinsic gas of the transaction, charged by transaction size.
+    Intrinsic,
+    /// Simple instructions, such as arithmetic, branch and cast.
+    Simple,
+    /// Call and call generic.
+    Call,
+    /// Pop and load constant.
+    Stack,
+    /// Copy, move and store local.
+    Local,
+    /// Pack and unpack struct.
+    Struct,
+    /// Read and write reference.
+    Reference,
+    /// Eq and neq.
+    Equality,
+    /// Borrow global, exists, move from and move to.
+    Global,
+    /// Vector operations.
+    Vector,
+    /// Native function execution.
+    Native,
+    /// Global write set, charged by the number of mutated accounts.
+    WriteSet,
+}
+
+impl GasCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GasCategory::Intrinsic => "intrinsic",
+            GasCategory::Simple => "simple",
+            GasCategory::Call => "call",
+            GasCategory::Stack => "stack",
+            GasCategory::Local => "local",
+            GasCategory::Struct => "struct",
+            GasCategory::Reference => "reference",
+            GasCategory::Equality => "equality",
+            GasCategory::Global => "global",
+            GasCategory::Vector => "vector",
+            GasCategory::Native => "native",
+            GasCategory::WriteSet => "write_set",
+        }
+    }
+}
+
+impl Display for GasCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Kinds of the global storage access.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageAccessKind {
+    BorrowGlobal,
+    Exists,
+    MoveFrom,
+    MoveTo,
+}
+
+impl StorageAccessKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageAccessKind::BorrowGlobal => "borrow_global",
+            StorageAccessKind::Exists => "exists",
+            StorageAccessKind::MoveFrom => "move_from",
+            StorageAccessKind::MoveTo => "move_to",
+        }
+    }
+}
+
+impl Display for StorageAccessKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageAccess {
+    pub kind: StorageAccessKind,
+    pub ty: TypeTag,
+    /// Gas charged for the access, in internal gas units.
+    pub gas: u64,
+}
+
+/// A frame of the call tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallFrame {
+    /// `address::module::function`, or the payload kind for the root frame of script and package.
+    pub name: String,
+    pub is_native: bool,
+    /// Gas charged by the frame itself, excluding the callees, in internal gas units.
+    pub gas_by_category: BTreeMap<GasCategory, u64>,
+    pub storage_accesses: Vec<StorageAccess>,
+    pub callees: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            is_native: false,
+            gas_by_category: BTreeMap::new(),
+            storage_accesses: vec![],
+            callees: vec![],
+        }
+    }
+
+    /// Gas charged by the frame itself, excluding the callees.
+    pub fn self_gas(&self) -> u64 {
+        self.gas_by_category
+            .values()
+            .fold(0u64, |acc, gas| acc.saturating_add(*gas))
+    }
+
+    /// Gas charged by the frame and all its callees.
+    pub fn total_gas(&self) -> u64 {
+        self.callees.iter().fold(self.self_gas(), |acc, callee| {
+            acc.saturating_add(callee.total_gas())
+        })
+    }
+}
+
+/// Maintains the stack of the open frames while the gas meter is charging.
+#[derive(Debug)]
+pub struct GasTracer {
+    stack: Vec<CallFrame>,
+}
+
+impl GasTracer {
+    pub fn new(root: String) -> Self {
+        Self {
+            stack: vec![CallFrame::new(root)],
+        }
+    }
+
+    fn current(&mut self) -> &mut CallFrame {
+        self.stack
+            .last_mut()
+            .expect("the root frame of gas tracer should always exist")
+    }
+
+    pub fn record(&mut self, category: GasCategory, gas: u64) {
+        let entry = self.current().gas_by_category.entry(category).or_insert(0);
+        *entry = entry.saturating_add(gas);
+    }
+
+    pub fn record_storage_access(&mut self, kind: StorageAccessKind, ty: TypeTag, gas: u64) {
+        self.record(GasCategory::Global, gas);
+        self.current()
+            .storage_accesses
+            .push(StorageAccess { kind, ty, gas });
+    }
+
+    pub fn push_frame(&mut self, name: String) {
+        self.stack.push(CallFrame::new(name));
+    }
+
+    pub fn mark_native(&mut self) {
+        if self.stack.len() > 1 {
+            self.current().is_native = true;
+        }
+    }
+
+    /// Native functions have no drop frame charge, so the frame is closed once the native
+    /// function is charged.
+    pub fn pop_native_frame(&mut self) {
+        if self.current().is_native {
+            self.pop_frame();
+        }
+    }
+
+    /// The root frame is never popped, for it is not pushed by a call.
+    pub fn pop_frame(&mut self) {
+        if self.stack.len() > 1 {
+            let frame = self.stack.pop().expect("checked the stack is not empty");
+            self.current().callees.push(frame);
+        }
+    }
+
+    /// Close the frames left open by an abort, and return the root frame.
+    pub fn finish(mut self) -> CallFrame {
+        while self.stack.len() > 1 {
+            self.pop_frame();
+        }
+        self.stack
+            .pop()
+            .expect("the root frame of gas tracer should always exist")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_tracer() {
+        let mut tracer = GasTracer::new("0x1::TransferScripts::peer_to_peer_v2".to_string());
+        tracer.record(GasCategory::Intrinsic, 100);
+        tracer.record(GasCategory::Call, 2);
+        tracer.push_frame("0x1::Account::pay_from".to_string());
+        tracer.record(GasCategory::Local, 3);
+        tracer.push_frame("0x1::Signer::address_of".to_string());
+        tracer.mark_native();
+        tracer.record(GasCategory::Native, 5);
+        tracer.pop_native_frame();
+        tracer.record_storage_access(StorageAccessKind::Exists, TypeTag::Bool, 7);
+        tracer.pop_frame();
+        tracer.record(GasCategory::WriteSet, 11);
+        // The root frame is dropped by the interpreter too, and it must be kept.
+        tracer.pop_frame();
+        tracer.push_frame("0x1::Account::deposit".to_string());
+        tracer.record(GasCategory::Simple, 13);
+
+        let root = tracer.finish();
+        assert_eq!(root.self_gas(), 113);
+        assert_eq!(root.total_gas(), 141);
+        assert_eq!(root.callees.len(), 2);
+        let pay_from = &root.callees[0];
+        assert_eq!(pay_from.self_gas(), 10);
+        assert_eq!(pay_from.storage_accesses.len(), 1);
+        assert_eq!(pay_from.callees.len(), 1);
+        assert!(pay_from.callees[0].is_native);
+        assert_eq!(pay_from.callees[0].total_gas(), 5);
+        assert_eq!(root.callees[1].total_gas(), 13);
+    }
+}
@@ -19,8 +19,10 @@
 //!     in the future.
 
 mod gas_meter;
+mod gas_tracer;
 
 pub use gas_meter::{NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
+pub use gas_tracer::{CallFrame, GasCategory, GasTracer, StorageAccess, StorageAccessKind};
 pub use move_core_types::gas_algebra::{
     Arg, Byte, GasQuantity, InternalGas, InternalGasPerArg, InternalGasPerByte, InternalGasUnit,
     NumArgs, NumBytes, UnitDiv,
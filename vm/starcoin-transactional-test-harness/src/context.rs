@@ -10,6 +10,7 @@ use starcoin_rpc_server::module::StateRpcImpl;
 use starcoin_state_api::{ChainStateReader, ChainStateWriter, StateNodeStore};
 use starcoin_statedb::ChainStateDB;
 use starcoin_types::write_set::WriteSet;
+use starcoin_vm_types::impersonation::ImpersonatedAccounts;
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use tokio::runtime::Runtime;
@@ -76,7 +77,8 @@ impl ForkContext {
             None => Genesis::build_genesis_transaction(&net).unwrap(),
         };
         let data_store = Arc::new(starcoin_state_tree::mock::MockStateNodeStore::new());
-        let state_db = ChainStateDB::new(data_store.clone(), None);
+        let state_db = ChainStateDB::new(data_store.clone(), None)
+            .with_impersonated_accounts(net.impersonated_accounts());
         Genesis::execute_genesis_txn(&state_db, genesis_txn).unwrap();
 
         let state_root = state_db.state_root();
@@ -99,7 +101,9 @@ impl ForkContext {
         let state_api_client = Arc::new(remote_async_client.get_state_client().clone());
         let root_hash = remote_async_client.get_fork_state_root();
         let data_store = Arc::new(MockStateNodeStore::new(state_api_client, rt.clone()));
-        let state_db = ChainStateDB::new(data_store.clone(), Some(root_hash));
+        // the impersonation is gated by the chain id of the forked chain when handling `impersonate`.
+        let state_db = ChainStateDB::new(data_store.clone(), Some(root_hash))
+            .with_impersonated_accounts(Some(ImpersonatedAccounts::default()));
 
         let fork_nubmer = remote_async_client.get_fork_block_number();
         let fork_block_hash = remote_async_client.get_fork_block_hash();
@@ -122,7 +126,7 @@ impl ForkContext {
     ) -> Result<Self> {
         let chain_api = MockChainApi::new(chain.clone());
         let state_svc = MockChainStateAsyncService::new(data_store.clone(), state_root.clone());
-        let state_api = StateRpcImpl::new(state_svc, data_store);
+        let state_api = StateRpcImpl::new(state_svc, None, data_store);
         let (server, client) = MockServer::create_and_start(chain_api, state_api)?;
 
         Ok(Self {
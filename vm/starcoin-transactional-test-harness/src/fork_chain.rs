@@ -26,6 +26,7 @@ use starcoin_storage::storage::StorageInstance;
 use starcoin_storage::{
     BlockStore, BlockTransactionInfoStore, ContractEventStore, Storage, Store, TransactionStore,
 };
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::block::{Block, BlockInfo, BlockNumber};
 use starcoin_types::startup_info::{ChainInfo, ChainStatus};
 use starcoin_types::transaction::{Transaction, TransactionInfo, TransactionOutput};
@@ -472,6 +473,19 @@ impl ChainApi for MockChainApi {
         Box::pin(fut.boxed().map_err(map_err))
     }
 
+    fn get_transactions_by_sender(
+        &self,
+        _sender: AccountAddress,
+        _start_seq: Option<u64>,
+        _reverse: bool,
+        _max_size: u64,
+    ) -> starcoin_rpc_api::FutureResult<Vec<starcoin_rpc_api::types::TransactionInfoView>> {
+        let fut = async move {
+            bail!("not implemented.");
+        };
+        Box::pin(fut.boxed().map_err(map_err))
+    }
+
     fn get_transaction_proof(
         &self,
         _block_hash: HashValue,
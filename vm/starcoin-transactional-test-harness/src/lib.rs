@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::context::ForkContext;
-use anyhow::{bail, format_err, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use clap::{Args, CommandFactory, Parser};
 use move_binary_format::{file_format::CompiledScript, CompiledModule};
 use move_command_line_common::address::ParsedAddress;
@@ -226,6 +226,17 @@ pub struct VarSub {
     var: Vec<(String, String)>,
 }
 
+#[derive(Debug, Parser)]
+#[clap(name = "impersonate")]
+struct ImpersonateSub {
+    #[clap(long="addr", parse(try_from_str=ParsedAddress::parse))]
+    /// the account to impersonate, its transactions are accepted without its private key, only on dev or test chain.
+    address: ParsedAddress,
+    #[clap(long = "stop")]
+    /// stop impersonating the account.
+    stop: bool,
+}
+
 #[derive(Debug, Parser)]
 #[clap(name = "read-json")]
 pub struct ReadJsonSub {
@@ -310,6 +321,13 @@ pub enum StarcoinSubcommands {
         )]
         var: Vec<(String, String)>,
     },
+    #[clap(name = "impersonate")]
+    Impersonate {
+        #[clap(long="addr", parse(try_from_str=ParsedAddress::parse))]
+        address: ParsedAddress,
+        #[clap(long = "stop")]
+        stop: bool,
+    },
     #[clap(name = "read-json")]
     ReadJson {
         /// max gas for transaction.
@@ -406,6 +424,15 @@ impl From<ReadJsonSub> for StarcoinSubcommands {
     }
 }
 
+impl From<ImpersonateSub> for StarcoinSubcommands {
+    fn from(sub: ImpersonateSub) -> Self {
+        Self::Impersonate {
+            address: sub.address,
+            stop: sub.stop,
+        }
+    }
+}
+
 impl From<VarSub> for StarcoinSubcommands {
     fn from(sub: VarSub) -> Self {
         Self::Var { var: sub.var }
@@ -422,6 +449,7 @@ impl clap::Args for StarcoinSubcommands {
         let deploy = DeploySub::augment_args(clap::Command::new("deploy"));
         let var = VarSub::augment_args(clap::Command::new("var"));
         let read_json = ReadJsonSub::augment_args(clap::Command::new("read-json"));
+        let impersonate = ImpersonateSub::augment_args(clap::Command::new("impersonate"));
         cmd.subcommand(faucet)
             .subcommand(block)
             .subcommand(call)
@@ -430,6 +458,7 @@ impl clap::Args for StarcoinSubcommands {
             .subcommand(deploy)
             .subcommand(var)
             .subcommand(read_json)
+            .subcommand(impersonate)
     }
 
     fn augment_args_for_update(_cmd: clap::Command<'_>) -> clap::Command<'_> {
@@ -1033,6 +1062,36 @@ impl<'a> StarcoinTestAdapter<'a> {
         Ok((None, Some(serde_json::to_value(var_dict)?)))
     }
 
+    fn handle_impersonate(
+        &mut self,
+        address: ParsedAddress,
+        stop: bool,
+    ) -> Result<(Option<String>, Option<Value>)> {
+        let chain_id = self.context.storage.get_chain_id()?;
+        ensure!(
+            chain_id.is_dev() || chain_id.is_test(),
+            "Impersonating accounts is only supported on dev or test chain, current chain id: {}",
+            chain_id
+        );
+        let accounts = self
+            .context
+            .storage
+            .impersonated_accounts()
+            .ok_or_else(|| {
+                format_err!("The fork context does not support impersonating accounts.")
+            })?;
+        let address = self.compiled_state.resolve_address(&address);
+        let changed = if stop {
+            accounts.stop_impersonating(address)
+        } else {
+            accounts.impersonate(address)
+        };
+        Ok((
+            Some(serde_json::to_string_pretty(&changed)?),
+            Some(serde_json::to_value(changed)?),
+        ))
+    }
+
     fn handle_read_json(&mut self, file: &Path) -> Result<(Option<String>, Option<Value>)> {
         let content: serde_json::Value = serde_json::from_reader(File::open(file)?)?;
         Ok((None, Some(content)))
@@ -1401,6 +1460,9 @@ impl<'a> MoveTestAdapter<'a> for StarcoinTestAdapter<'a> {
             } => self.handle_deploy(signers, gas_budget, mv_or_package_file.as_path()),
             StarcoinSubcommands::Var { var } => self.handle_var(var),
             StarcoinSubcommands::ReadJson { file } => self.handle_read_json(file.as_path()),
+            StarcoinSubcommands::Impersonate { address, stop } => {
+                self.handle_impersonate(address, stop)
+            }
         }?;
         if self.debug {
             if let Some(cmd_var_ctx) = cmd_var_ctx.as_ref() {
@@ -1453,6 +1515,7 @@ pub fn print_help(task_name: Option<String>) -> Result<()> {
     tasks.insert("deploy", DeploySub::command().name("deploy"));
     tasks.insert("var", VarSub::command().name("var"));
     tasks.insert("read-json", ReadJsonSub::command().name("read-json"));
+    tasks.insert("impersonate", ImpersonateSub::command().name("impersonate"));
 
     match task_name {
         Some(name) => match tasks.get_mut(&name[..]) {
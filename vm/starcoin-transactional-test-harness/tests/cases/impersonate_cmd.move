@@ -0,0 +1,15 @@
+//# init -n dev
+
+//# faucet --addr alice --amount 100000000000
+
+//# impersonate --addr alice
+
+//# impersonate --addr alice
+
+//# run --signers alice -- 0x1::EmptyScripts::empty_script
+
+//# impersonate --addr alice --stop
+
+//# impersonate --addr alice --stop
+
+//# run --signers alice -- 0x1::EmptyScripts::empty_script
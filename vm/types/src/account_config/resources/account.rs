@@ -71,6 +71,11 @@ impl AccountResource {
         &self.authentication_key
     }
 
+    /// Set the authentication_key field for the given AccountResource
+    pub fn set_authentication_key(&mut self, authentication_key: Vec<u8>) {
+        self.authentication_key = authentication_key;
+    }
+
     /// Return the deposit_events handle for the given AccountResource
     pub fn deposit_events(&self) -> &EventHandle {
         &self.deposit_events
@@ -0,0 +1,44 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+
+/// The accounts impersonated by a dev or test node, shared by the components of the node.
+#[derive(Clone, Debug, Default)]
+pub struct ImpersonatedAccounts(Arc<RwLock<BTreeSet<AccountAddress>>>);
+
+impl ImpersonatedAccounts {
+    /// Impersonate the account, return false if the account is already impersonated.
+    pub fn impersonate(&self, address: AccountAddress) -> bool {
+        self.0
+            .write()
+            .expect("impersonated accounts lock should not be poisoned.")
+            .insert(address)
+    }
+
+    /// Stop impersonating the account, return false if the account is not impersonated.
+    pub fn stop_impersonating(&self, address: AccountAddress) -> bool {
+        self.0
+            .write()
+            .expect("impersonated accounts lock should not be poisoned.")
+            .remove(&address)
+    }
+
+    pub fn contains(&self, address: &AccountAddress) -> bool {
+        self.0
+            .read()
+            .expect("impersonated accounts lock should not be poisoned.")
+            .contains(address)
+    }
+
+    pub fn accounts(&self) -> Vec<AccountAddress> {
+        self.0
+            .read()
+            .expect("impersonated accounts lock should not be poisoned.")
+            .iter()
+            .copied()
+            .collect()
+    }
+}
@@ -102,6 +102,10 @@ impl Epoch {
         ConsensusStrategy::try_from(self.strategy).expect("epoch consensus strategy must exist.")
     }
 
+    pub fn set_strategy(&mut self, strategy: ConsensusStrategy) {
+        self.strategy = strategy.value();
+    }
+
     // TODO/XXX: remove this once the MoveResource trait allows type arguments to `struct_tag`.
     pub fn struct_tag_for_epoch() -> StructTag {
         StructTag {
@@ -42,6 +42,12 @@ pub trait StateView: Sync {
     /// VM needs this method to know whether the current state view is for genesis state creation.
     /// Currently TransactionPayload::WriteSet is only valid for genesis state creation.
     fn is_genesis(&self) -> bool;
+
+    /// VM needs this method to know whether the sender of a transaction is impersonated,
+    /// only the state view of a dev or test node may impersonate accounts.
+    fn is_impersonated(&self, _address: &AccountAddress) -> bool {
+        false
+    }
 }
 
 impl<R, S> StateView for R
@@ -56,6 +62,10 @@ where
     fn is_genesis(&self) -> bool {
         self.deref().is_genesis()
     }
+
+    fn is_impersonated(&self, address: &AccountAddress) -> bool {
+        self.deref().is_impersonated(address)
+    }
 }
 
 impl<T: ?Sized> StateReaderExt for T where T: StateView {}
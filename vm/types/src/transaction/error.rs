@@ -75,7 +75,7 @@ impl fmt::Display for Error {
             AlreadyImported => "Already imported".into(),
             Old => "No longer valid".into(),
             TooCheapToReplace { prev, new } => format!(
-                "Gas price too low to replace, previous tx gas: {:?}, new tx gas: {:?}",
+                "Underpriced, gas price too low to replace, previous tx gas price: {:?}, new tx gas price: {:?}",
                 prev, new
             ),
             LimitReached => "Transaction limit reached".into(),
@@ -927,6 +927,8 @@ pub enum TxStatus {
     Canceled,
     /// Culled transaction
     Culled,
+    /// Replaced by a transaction with the same sender and sequence number but higher gas price
+    Replaced,
 }
 
 impl std::fmt::Display for TxStatus {
@@ -938,6 +940,7 @@ impl std::fmt::Display for TxStatus {
             TxStatus::Invalid => "invalid",
             TxStatus::Canceled => "canceled",
             TxStatus::Culled => "culled",
+            TxStatus::Replaced => "replaced",
         };
         write!(f, "{}", s)
     }
@@ -85,6 +85,10 @@ impl<'block, S: StateView> StateView for StateViewCache<'block, S> {
     fn is_genesis(&self) -> bool {
         self.data_view.is_genesis()
     }
+
+    fn is_impersonated(&self, address: &AccountAddress) -> bool {
+        self.data_view.is_impersonated(address)
+    }
 }
 
 impl<'block, S: StateView> ModuleResolver for StateViewCache<'block, S> {
@@ -0,0 +1,110 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Impersonate accounts on dev and test networks, for rehearsing the transactions of the accounts
+//! whose private key is not available, such as a DAO treasury on a forked network.
+//!
+//! The transaction of an impersonated account is still signed, but the authenticator is not bound
+//! to the authentication key of the account. The prologue is run with the authentication key of the
+//! account replaced by the key of the authenticator, and the replaced key is restored in the output.
+//! The impersonated accounts are kept by the node, only the state view of a dev or test node
+//! impersonates accounts, so it is impossible on main, barnard, proxima and halley.
+
+use anyhow::Result;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::account_config::AccountResource;
+use starcoin_vm_types::move_resource::MoveResource;
+use starcoin_vm_types::state_store::state_key::StateKey;
+use starcoin_vm_types::state_view::StateView;
+use starcoin_vm_types::transaction::TransactionOutput;
+use starcoin_vm_types::write_set::{WriteOp, WriteSetMut};
+
+/// The state view of an impersonated transaction, the authentication key of the sender is replaced
+/// by the key of the transaction authenticator.
+pub(crate) struct ImpersonatedStateView<'a, S> {
+    state_view: &'a S,
+    account_key: StateKey,
+    account_blob: Vec<u8>,
+    original_authentication_key: Vec<u8>,
+    authentication_key: Vec<u8>,
+}
+
+impl<'a, S: StateView> ImpersonatedStateView<'a, S> {
+    /// Return None if the sender is not impersonated or does not exist.
+    pub fn new(
+        state_view: &'a S,
+        sender: AccountAddress,
+        authentication_key_preimage: &[u8],
+    ) -> Result<Option<Self>> {
+        if !state_view.is_impersonated(&sender) {
+            return Ok(None);
+        }
+        let account_key = StateKey::AccessPath(AccessPath::resource_access_path(
+            sender,
+            AccountResource::struct_tag(),
+        ));
+        let mut account = match state_view.get_state_value(&account_key)? {
+            Some(blob) => bcs_ext::from_bytes::<AccountResource>(&blob)?,
+            None => return Ok(None),
+        };
+        let original_authentication_key = account.authentication_key().to_vec();
+        let authentication_key = HashValue::sha3_256_of(authentication_key_preimage).to_vec();
+        account.set_authentication_key(authentication_key.clone());
+        debug!("Execute the transaction of impersonated account {}", sender);
+        Ok(Some(Self {
+            state_view,
+            account_key,
+            account_blob: bcs_ext::to_bytes(&account)?,
+            original_authentication_key,
+            authentication_key,
+        }))
+    }
+
+    /// Restore the authentication key of the sender replaced in the output, the key is kept if the
+    /// transaction rotates it.
+    pub fn restore_output(&self, output: TransactionOutput) -> Result<TransactionOutput> {
+        let (table_infos, write_set, events, gas_used, status) = output.into_inner();
+        let mut write_set_mut = WriteSetMut::default();
+        for (key, op) in write_set {
+            let op = match op {
+                WriteOp::Value(blob) if key == self.account_key => {
+                    let mut account = bcs_ext::from_bytes::<AccountResource>(&blob)?;
+                    if account.authentication_key() == self.authentication_key.as_slice() {
+                        account.set_authentication_key(self.original_authentication_key.clone());
+                    }
+                    WriteOp::Value(bcs_ext::to_bytes(&account)?)
+                }
+                op => op,
+            };
+            write_set_mut.push((key, op));
+        }
+        Ok(TransactionOutput::new(
+            table_infos,
+            write_set_mut.freeze()?,
+            events,
+            gas_used,
+            status,
+        ))
+    }
+}
+
+impl<'a, S: StateView> StateView for ImpersonatedStateView<'a, S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        if state_key == &self.account_key {
+            Ok(Some(self.account_blob.clone()))
+        } else {
+            self.state_view.get_state_value(state_key)
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.state_view.is_genesis()
+    }
+
+    fn is_impersonated(&self, address: &AccountAddress) -> bool {
+        self.state_view.is_impersonated(address)
+    }
+}
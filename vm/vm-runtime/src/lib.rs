@@ -3,6 +3,7 @@
 
 mod adapter_common;
 pub mod data_cache;
+pub mod impersonation;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod natives;
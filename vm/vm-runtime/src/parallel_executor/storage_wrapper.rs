@@ -4,7 +4,8 @@
 use crate::data_cache::{IntoMoveResolver, RemoteStorageOwned};
 use starcoin_parallel_executor::executor::MVHashMapView;
 use starcoin_vm_types::{
-    state_store::state_key::StateKey, state_view::StateView, write_set::WriteOp,
+    account_address::AccountAddress, state_store::state_key::StateKey, state_view::StateView,
+    write_set::WriteOp,
 };
 
 pub(crate) struct VersionedView<'a, S: StateView> {
@@ -40,4 +41,8 @@ impl<'a, S: StateView> StateView for VersionedView<'a, S> {
     fn is_genesis(&self) -> bool {
         self.base_view.is_genesis()
     }
+
+    fn is_impersonated(&self, address: &AccountAddress) -> bool {
+        self.base_view.is_impersonated(address)
+    }
 }
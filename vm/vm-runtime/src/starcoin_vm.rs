@@ -9,6 +9,7 @@ use crate::data_cache::{AsMoveResolver, RemoteStorage, StateViewCache};
 use crate::errors::{
     convert_normal_success_epilogue_error, convert_prologue_runtime_error, error_split,
 };
+use crate::impersonation::ImpersonatedStateView;
 use crate::move_vm_ext::{MoveResolverExt, MoveVmExt, SessionId, SessionOutput};
 use anyhow::{bail, format_err, Error, Result};
 use move_core_types::gas_algebra::{InternalGasPerByte, NumBytes};
@@ -19,7 +20,7 @@ use num_cpus;
 use once_cell::sync::OnceCell;
 use starcoin_config::genesis_config::G_LATEST_GAS_PARAMS;
 use starcoin_crypto::HashValue;
-use starcoin_gas::{NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
+use starcoin_gas::{CallFrame, NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
 use starcoin_gas_algebra_ext::{
     CostTable, FromOnChainGasSchedule, Gas, GasConstants, GasCost, InitialGasSchedule,
 };
@@ -476,7 +477,6 @@ impl StarcoinVM {
                 .with_label_values(&["verify_transaction"])
                 .start_timer()
         });
-        let data_cache = StateViewCache::new(state_view);
         let signature_verified_txn = match txn.check_signature() {
             Ok(t) => t,
             Err(_) => return Some(VMStatus::Error(StatusCode::INVALID_SIGNATURE)),
@@ -485,7 +485,29 @@ impl StarcoinVM {
             warn!("Load config error at verify_transaction: {}", err);
             return Some(VMStatus::Error(StatusCode::VM_STARTUP_FAILURE));
         }
-        match self.verify_transaction_impl(&signature_verified_txn, &data_cache) {
+        let result = match ImpersonatedStateView::new(
+            state_view,
+            signature_verified_txn.sender(),
+            &signature_verified_txn
+                .authenticator()
+                .authentication_key_preimage()
+                .into_vec(),
+        ) {
+            Ok(Some(impersonated_view)) => self.verify_transaction_impl(
+                &signature_verified_txn,
+                &StateViewCache::new(&impersonated_view),
+            ),
+            Ok(None) => self
+                .verify_transaction_impl(&signature_verified_txn, &StateViewCache::new(state_view)),
+            Err(err) => {
+                warn!(
+                    "Load impersonated account error at verify_transaction: {}",
+                    err
+                );
+                return Some(VMStatus::Error(StatusCode::STORAGE_ERROR));
+            }
+        };
+        match result {
             Ok(_) => None,
             Err(err) => {
                 if err.status_code() == StatusCode::SEQUENCE_NUMBER_TOO_NEW {
@@ -914,10 +936,60 @@ impl StarcoinVM {
         storage: &S,
         txn: SignedUserTransaction,
     ) -> (VMStatus, TransactionOutput) {
+        let (status, output, _trace) =
+            self.execute_user_transaction_with_trace(storage, txn, false);
+        (status, output)
+    }
+
+    fn execute_user_transaction_with_trace<S: MoveResolverExt + StateView>(
+        &self,
+        storage: &S,
+        txn: SignedUserTransaction,
+        trace: bool,
+    ) -> (VMStatus, TransactionOutput, Option<CallFrame>) {
+        let authentication_key_preimage =
+            txn.authenticator().authentication_key_preimage().into_vec();
+        match ImpersonatedStateView::new(storage, txn.sender(), &authentication_key_preimage) {
+            Ok(Some(impersonated_view)) => {
+                let (status, output, trace) = self.execute_user_transaction_impl(
+                    &impersonated_view.as_move_resolver(),
+                    txn,
+                    trace,
+                );
+                match impersonated_view.restore_output(output) {
+                    Ok(output) => (status, output, trace),
+                    Err(e) => {
+                        warn!(
+                            "Restore the output of impersonated transaction error: {}",
+                            e
+                        );
+                        let (status, output) =
+                            discard_error_vm_status(VMStatus::Error(StatusCode::STORAGE_ERROR));
+                        (status, output, None)
+                    }
+                }
+            }
+            Ok(None) => self.execute_user_transaction_impl(storage, txn, trace),
+            Err(e) => {
+                warn!("Load impersonated account error: {}", e);
+                let (status, output) =
+                    discard_error_vm_status(VMStatus::Error(StatusCode::STORAGE_ERROR));
+                (status, output, None)
+            }
+        }
+    }
+
+    fn execute_user_transaction_impl<S: MoveResolverExt + StateView>(
+        &self,
+        storage: &S,
+        txn: SignedUserTransaction,
+        trace: bool,
+    ) -> (VMStatus, TransactionOutput, Option<CallFrame>) {
         let txn_data = match TransactionMetadata::new(&txn) {
             Ok(txn_data) => txn_data,
             Err(e) => {
-                return discard_error_vm_status(e);
+                let (status, output) = discard_error_vm_status(e);
+                return (status, output, None);
             }
         };
         let gas_params = match self.get_gas_parameters() {
@@ -926,7 +998,8 @@ impl StarcoinVM {
                 if storage.is_genesis() {
                     &G_LATEST_GAS_PARAMS
                 } else {
-                    return discard_error_vm_status(e);
+                    let (status, output) = discard_error_vm_status(e);
+                    return (status, output, None);
                 }
             }
         };
@@ -937,13 +1010,16 @@ impl StarcoinVM {
             .into();
         let mut gas_meter = StarcoinGasMeter::new(gas_params.clone(), txn_data.max_gas_amount());
         gas_meter.set_metering(false);
+        if trace {
+            gas_meter.enable_trace(trace_root_name(txn.payload()));
+        }
         // check signature
         let signature_checked_txn = match txn.check_signature() {
             Ok(t) => Ok(t),
             Err(_) => Err(VMStatus::Error(StatusCode::INVALID_SIGNATURE)),
         };
 
-        match signature_checked_txn {
+        let (status, output) = match signature_checked_txn {
             Ok(txn) => {
                 let result = match txn.payload() {
                     payload @ TransactionPayload::Script(_)
@@ -980,7 +1056,22 @@ impl StarcoinVM {
                 }
             }
             Err(e) => discard_error_vm_status(e),
-        }
+        };
+        (status, output, gas_meter.take_trace())
+    }
+
+    /// Execute a single user transaction on the state, the output is not applied to the state.
+    /// Record the call tree with its gas charges if `trace` is true.
+    pub fn trace_user_transaction<S: StateView>(
+        &mut self,
+        state_view: &S,
+        txn: SignedUserTransaction,
+        trace: bool,
+    ) -> Result<(VMStatus, TransactionOutput, Option<CallFrame>)> {
+        let data_cache = StateViewCache::new(state_view);
+        // TODO load config by config change event.
+        self.load_configs(&data_cache)?;
+        Ok(self.execute_user_transaction_with_trace(&data_cache.as_move_resolver(), txn, trace))
     }
 
     pub fn dry_run_transaction<S: MoveResolverExt + StateView>(
@@ -988,16 +1079,52 @@ impl StarcoinVM {
         storage: &S,
         txn: DryRunTransaction,
     ) -> Result<(VMStatus, TransactionOutput)> {
+        self.dry_run_transaction_with_trace(storage, txn, false)
+            .map(|(status, output, _trace)| (status, output))
+    }
+
+    /// Dry run the transaction, and record the call tree with its gas charges if `trace` is true.
+    pub fn dry_run_transaction_with_trace<S: MoveResolverExt + StateView>(
+        &mut self,
+        storage: &S,
+        txn: DryRunTransaction,
+        trace: bool,
+    ) -> Result<(VMStatus, TransactionOutput, Option<CallFrame>)> {
         // TODO load config by config change event.
         self.load_configs(&storage)?;
 
+        let authentication_key_preimage = txn.public_key.authentication_key_preimage().into_vec();
+        match ImpersonatedStateView::new(
+            storage,
+            txn.raw_txn.sender(),
+            &authentication_key_preimage,
+        )? {
+            Some(impersonated_view) => {
+                let (status, output, trace) = self.dry_run_transaction_impl(
+                    &impersonated_view.as_move_resolver(),
+                    txn,
+                    trace,
+                )?;
+                Ok((status, impersonated_view.restore_output(output)?, trace))
+            }
+            None => self.dry_run_transaction_impl(storage, txn, trace),
+        }
+    }
+
+    fn dry_run_transaction_impl<S: MoveResolverExt + StateView>(
+        &self,
+        storage: &S,
+        txn: DryRunTransaction,
+        trace: bool,
+    ) -> Result<(VMStatus, TransactionOutput, Option<CallFrame>)> {
         let gas_params = match self.get_gas_parameters() {
             Ok(gas_params) => gas_params,
             Err(e) => {
                 if storage.is_genesis() {
                     &G_LATEST_GAS_PARAMS
                 } else {
-                    return Ok(discard_error_vm_status(e));
+                    let (status, output) = discard_error_vm_status(e);
+                    return Ok((status, output, None));
                 }
             }
         };
@@ -1006,7 +1133,10 @@ impl StarcoinVM {
             txn.public_key.authentication_key_preimage(),
         ) {
             Ok(txn_data) => txn_data,
-            Err(e) => return Ok(discard_error_vm_status(e)),
+            Err(e) => {
+                let (status, output) = discard_error_vm_status(e);
+                return Ok((status, output, None));
+            }
         };
         let session = self
             .move_vm
@@ -1014,6 +1144,9 @@ impl StarcoinVM {
             .into();
         let mut gas_meter = StarcoinGasMeter::new(gas_params.clone(), txn_data.max_gas_amount());
         gas_meter.set_metering(false);
+        if trace {
+            gas_meter.enable_trace(trace_root_name(txn.raw_txn.payload()));
+        }
         let result = match txn.raw_txn.payload() {
             payload @ TransactionPayload::Script(_)
             | payload @ TransactionPayload::ScriptFunction(_) => {
@@ -1023,7 +1156,7 @@ impl StarcoinVM {
                 self.execute_package(session, &mut gas_meter, &txn_data, p, storage)
             }
         };
-        Ok(match result {
+        let (status, output) = match result {
             Ok(status_and_output) => status_and_output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
@@ -1033,7 +1166,8 @@ impl StarcoinVM {
                     self.failed_transaction_cleanup(err, &mut gas_meter, &txn_data, storage)
                 }
             }
-        })
+        };
+        Ok((status, output, gas_meter.take_trace()))
     }
 
     fn check_reconfigure<S: StateView>(
@@ -1414,6 +1548,19 @@ pub fn chunk_block_transactions(txns: Vec<Transaction>) -> Vec<TransactionBlock>
     blocks
 }
 
+/// The name of the root frame of the gas trace, it's the entry function for script function.
+fn trace_root_name(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::Script(_) => "script".to_string(),
+        TransactionPayload::ScriptFunction(script_function) => format!(
+            "{}::{}",
+            script_function.module().short_str_lossless(),
+            script_function.function()
+        ),
+        TransactionPayload::Package(_) => "package".to_string(),
+    }
+}
+
 pub(crate) fn charge_global_write_gas_usage<R: MoveResolverExt>(
     gas_meter: &mut StarcoinGasMeter,
     session: &SessionAdapter<R>,
@@ -1429,7 +1576,7 @@ pub(crate) fn charge_global_write_gas_usage<R: MoveResolverExt>(
         gas_meter.get_metering()
     );
     gas_meter
-        .deduct_gas(total_cost)
+        .charge_write_set_gas(total_cost)
         .map_err(|p_err| p_err.finish(Location::Undefined).into_vm_status())
 }
 